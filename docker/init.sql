@@ -1,8 +1,405 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE TABLE IF NOT EXISTS folders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL,
+    parent_id UUID REFERENCES folders (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT NOW() NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS folders_parent_id_idx ON folders (parent_id);
+
 CREATE TABLE IF NOT EXISTS passwords (
     id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
     service TEXT NOT NULL,
     nonce TEXT NOT NULL,
     cipher TEXT NOT NULL,
     created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL,
-    updated_at TIMESTAMPTZ DEFAULT NOW() NOT NULL
+    updated_at TIMESTAMPTZ DEFAULT NOW() NOT NULL,
+    fingerprint TEXT,
+    strength SMALLINT CHECK (strength BETWEEN 0 AND 4),
+    username TEXT,
+    uris TEXT[] DEFAULT '{}' NOT NULL,
+    uri_match_strategies TEXT[] DEFAULT '{}' NOT NULL,
+    notes_nonce TEXT,
+    notes_cipher TEXT,
+    totp_nonce TEXT,
+    totp_cipher TEXT,
+    custom_fields JSONB DEFAULT '[]' NOT NULL,
+    item_type TEXT DEFAULT 'login' NOT NULL
+        CHECK (item_type IN ('login', 'secure_note', 'card', 'identity', 'ssh_key', 'api_key')),
+    item_data JSONB DEFAULT '{"item_type": "login"}' NOT NULL,
+    folder_id UUID REFERENCES folders (id) ON DELETE SET NULL,
+    favorite BOOLEAN DEFAULT FALSE NOT NULL,
+    last_used_at TIMESTAMPTZ,
+    rotate_by TIMESTAMPTZ,
+    rotation_interval_days INTEGER CHECK (rotation_interval_days > 0),
+    -- Kept up to date by `stamp_password_rotation_due_at`
+    rotation_due_at TIMESTAMPTZ,
+    -- Id of the transaction that last changed the entry, see `stamp_password_sync_version`
+    sync_version BIGINT DEFAULT 0 NOT NULL
+);
+
+-- Columns added since the table was first created, so that existing databases pick them up
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS fingerprint TEXT;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS strength SMALLINT CHECK (strength BETWEEN 0 AND 4);
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS username TEXT;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS uris TEXT[] DEFAULT '{}' NOT NULL;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS notes_nonce TEXT;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS notes_cipher TEXT;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS custom_fields JSONB DEFAULT '[]' NOT NULL;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS item_type TEXT DEFAULT 'login' NOT NULL
+    CHECK (item_type IN ('login', 'secure_note', 'card', 'identity', 'ssh_key', 'api_key'));
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS item_data JSONB DEFAULT '{"item_type": "login"}' NOT NULL;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS folder_id UUID REFERENCES folders (id) ON DELETE SET NULL;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS favorite BOOLEAN DEFAULT FALSE NOT NULL;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS last_used_at TIMESTAMPTZ;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS uri_match_strategies TEXT[] DEFAULT '{}' NOT NULL;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS totp_nonce TEXT;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS totp_cipher TEXT;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS rotate_by TIMESTAMPTZ;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS rotation_interval_days INTEGER CHECK (rotation_interval_days > 0);
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS sync_version BIGINT DEFAULT 0 NOT NULL;
+ALTER TABLE passwords ADD COLUMN IF NOT EXISTS rotation_due_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS passwords_fingerprint_idx ON passwords (fingerprint);
+CREATE INDEX IF NOT EXISTS passwords_item_type_idx ON passwords (item_type);
+CREATE INDEX IF NOT EXISTS passwords_folder_id_idx ON passwords (folder_id);
+CREATE INDEX IF NOT EXISTS passwords_last_used_at_idx ON passwords (last_used_at DESC NULLS LAST);
+CREATE INDEX IF NOT EXISTS passwords_service_trgm_idx ON passwords USING GIN (service gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS passwords_sync_version_idx ON passwords (sync_version);
+CREATE INDEX IF NOT EXISTS passwords_rotation_due_at_idx ON passwords (rotation_due_at) WHERE rotation_due_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS tags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS password_tags (
+    password_id UUID NOT NULL REFERENCES passwords (id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    PRIMARY KEY (password_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS password_tags_tag_id_idx ON password_tags (tag_id);
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id UUID PRIMARY KEY,
+    password_id UUID NOT NULL REFERENCES passwords (id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    content_type TEXT,
+    size BIGINT NOT NULL,
+    storage TEXT NOT NULL CHECK (storage IN ('postgres', 'filesystem')),
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS attachments_password_id_idx ON attachments (password_id);
+
+CREATE TABLE IF NOT EXISTS attachment_chunks (
+    attachment_id UUID NOT NULL REFERENCES attachments (id) ON DELETE CASCADE,
+    seq INTEGER NOT NULL,
+    data BYTEA NOT NULL,
+    PRIMARY KEY (attachment_id, seq)
+);
+
+-- Blobs used to be stored whole in `attachment_blobs`; they become a single chunk
+DO $$
+BEGIN
+    IF to_regclass('attachment_blobs') IS NOT NULL THEN
+        INSERT INTO attachment_chunks (attachment_id, seq, data)
+        SELECT attachment_id, 0, data FROM attachment_blobs
+        ON CONFLICT DO NOTHING;
+        DROP TABLE attachment_blobs;
+    END IF;
+END
+$$;
+
+CREATE TABLE IF NOT EXISTS shares (
+    id_hash TEXT PRIMARY KEY,
+    nonce TEXT NOT NULL,
+    cipher TEXT NOT NULL,
+    password_hash TEXT,
+    max_views INTEGER NOT NULL CHECK (max_views > 0),
+    view_count INTEGER DEFAULT 0 NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS shares_expires_at_idx ON shares (expires_at);
+
+CREATE TABLE IF NOT EXISTS emergency_access (
+    id UUID PRIMARY KEY,
+    contact TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    status TEXT NOT NULL CHECK (status IN ('invited', 'accepted', 'requested', 'granted', 'revoked')),
+    wait_hours INTEGER NOT NULL CHECK (wait_hours >= 0),
+    requested_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS emergency_access_events (
+    id BIGSERIAL PRIMARY KEY,
+    emergency_access_id UUID NOT NULL REFERENCES emergency_access (id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS emergency_access_events_access_id_idx ON emergency_access_events (emergency_access_id, occurred_at);
+
+CREATE TABLE IF NOT EXISTS rotation_notifications (
+    password_id UUID PRIMARY KEY REFERENCES passwords (id) ON DELETE CASCADE,
+    due_at TIMESTAMPTZ NOT NULL,
+    notified_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    seq BIGSERIAL PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    request_id TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    entry_id UUID,
+    ip TEXT,
+    user_agent TEXT,
+    status INTEGER NOT NULL,
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'failure')),
+    prev_hash TEXT NOT NULL,
+    hash TEXT NOT NULL UNIQUE
+);
+
+CREATE INDEX IF NOT EXISTS audit_events_entry_id_idx ON audit_events (entry_id);
+CREATE INDEX IF NOT EXISTS audit_events_occurred_at_idx ON audit_events (occurred_at);
+
+-- Links an event to the newest one and inserts it. The chain lock is taken here so that it is only
+-- held for the insert and the commit that follows. The hash is the one of `NewAuditEvent::chain_hash`.
+CREATE OR REPLACE FUNCTION append_audit_event(
+    lock_key BIGINT,
+    genesis_hash TEXT,
+    chain_fields BYTEA,
+    event_occurred_at TIMESTAMPTZ,
+    event_request_id TEXT,
+    event_actor TEXT,
+    event_action TEXT,
+    event_entry_id UUID,
+    event_ip TEXT,
+    event_user_agent TEXT,
+    event_status INTEGER,
+    event_outcome TEXT
+) RETURNS SETOF audit_events AS $$
+DECLARE
+    last_hash TEXT;
+BEGIN
+    PERFORM pg_advisory_xact_lock(lock_key);
+
+    SELECT hash INTO last_hash FROM audit_events ORDER BY seq DESC LIMIT 1;
+    last_hash := COALESCE(last_hash, genesis_hash);
+
+    RETURN QUERY
+    INSERT INTO audit_events (occurred_at, request_id, actor, action, entry_id, ip, user_agent, status, outcome, prev_hash, hash)
+    VALUES (
+        event_occurred_at, event_request_id, event_actor, event_action, event_entry_id, event_ip, event_user_agent,
+        event_status, event_outcome, last_hash,
+        encode(sha256(convert_to(last_hash || E'\n', 'UTF8') || chain_fields), 'hex')
+    )
+    RETURNING *;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION audit_events_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_events_append_only ON audit_events;
+CREATE TRIGGER audit_events_append_only
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_append_only();
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id UUID PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL CHECK (cardinality(events) > 0),
+    active BOOLEAN DEFAULT TRUE NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY,
+    webhook_id UUID NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    event TEXT NOT NULL CHECK (event IN ('created', 'updated', 'deleted', 'expired')),
+    event_key TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT DEFAULT 'pending' NOT NULL CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER DEFAULT 0 NOT NULL,
+    next_attempt_at TIMESTAMPTZ NOT NULL,
+    last_attempt_at TIMESTAMPTZ,
+    last_response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL,
+    delivered_at TIMESTAMPTZ,
+    UNIQUE (webhook_id, event_key)
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_pending_idx ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS webhook_deliveries_created_at_idx ON webhook_deliveries (webhook_id, created_at DESC);
+
+-- Queues entry changes for webhooks in the same transaction as the change, and announces them on the
+-- `password_changes` channel once it commits. Only ids and the service name are published; updates
+-- that just record usage are left out.
+CREATE OR REPLACE FUNCTION publish_password_change() RETURNS TRIGGER AS $$
+DECLARE
+    change TEXT;
+    entry_id UUID;
+    entry_service TEXT;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        change := 'created';
+        entry_id := NEW.id;
+        entry_service := NEW.service;
+    ELSIF TG_OP = 'UPDATE' THEN
+        IF (to_jsonb(NEW) - 'updated_at' - 'last_used_at' - 'sync_version' - 'rotation_due_at')
+            = (to_jsonb(OLD) - 'updated_at' - 'last_used_at' - 'sync_version' - 'rotation_due_at') THEN
+            RETURN NULL;
+        END IF;
+        change := 'updated';
+        entry_id := NEW.id;
+        entry_service := NEW.service;
+    ELSE
+        change := 'deleted';
+        entry_id := OLD.id;
+        entry_service := OLD.service;
+    END IF;
+
+    INSERT INTO webhook_deliveries (id, webhook_id, event, event_key, payload, next_attempt_at, created_at)
+    SELECT gen_random_uuid(), webhooks.id, change, gen_random_uuid()::TEXT,
+        jsonb_build_object('password_id', entry_id, 'service', entry_service), NOW(), NOW()
+    FROM webhooks
+    WHERE webhooks.active AND change = ANY (webhooks.events);
+
+    PERFORM pg_notify('password_changes', json_build_object('event', change, 'password_id', entry_id, 'occurred_at', NOW())::TEXT);
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Replaced by `passwords_changes`; left in place it would queue every change twice
+DROP TRIGGER IF EXISTS passwords_webhooks ON passwords;
+DROP FUNCTION IF EXISTS enqueue_password_webhooks();
+
+DROP TRIGGER IF EXISTS passwords_changes ON passwords;
+CREATE TRIGGER passwords_changes
+    AFTER INSERT OR UPDATE OR DELETE ON passwords
+    FOR EACH ROW EXECUTE FUNCTION publish_password_change();
+
+CREATE TABLE IF NOT EXISTS password_tombstones (
+    id UUID PRIMARY KEY,
+    deleted_at TIMESTAMPTZ NOT NULL,
+    sync_version BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS password_tombstones_sync_version_idx ON password_tombstones (sync_version);
+
+-- Stamps entries with the id of the transaction changing them. Sync tokens are the oldest transaction
+-- still running when a client synced, so changes committed later by older transactions are not missed.
+CREATE OR REPLACE FUNCTION stamp_password_sync_version() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.sync_version := pg_current_xact_id()::TEXT::BIGINT;
+
+    IF TG_OP = 'INSERT' THEN
+        DELETE FROM password_tombstones WHERE id = NEW.id;
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS passwords_sync_version ON passwords;
+CREATE TRIGGER passwords_sync_version
+    BEFORE INSERT OR UPDATE ON passwords
+    FOR EACH ROW EXECUTE FUNCTION stamp_password_sync_version();
+
+CREATE OR REPLACE FUNCTION record_password_tombstone() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO password_tombstones (id, deleted_at, sync_version)
+    VALUES (OLD.id, NOW(), pg_current_xact_id()::TEXT::BIGINT)
+    ON CONFLICT (id) DO UPDATE SET deleted_at = EXCLUDED.deleted_at, sync_version = EXCLUDED.sync_version;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS passwords_tombstone ON passwords;
+CREATE TRIGGER passwords_tombstone
+    AFTER DELETE ON passwords
+    FOR EACH ROW EXECUTE FUNCTION record_password_tombstone();
+
+-- Tags are part of the entry clients sync, so tagging changes the entry's sync version. The value set
+-- here is replaced by `stamp_password_sync_version`.
+CREATE OR REPLACE FUNCTION touch_tagged_password() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        UPDATE passwords SET sync_version = 0 WHERE id = NEW.password_id;
+    ELSE
+        UPDATE passwords SET sync_version = 0 WHERE id = OLD.password_id;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS password_tags_sync_version ON password_tags;
+CREATE TRIGGER password_tags_sync_version
+    AFTER INSERT OR DELETE ON password_tags
+    FOR EACH ROW EXECUTE FUNCTION touch_tagged_password();
+
+-- The earliest of the entry's rotation dates, see `Password::rotation_due_at`. Stored on the entry so
+-- the webhook dispatcher can find newly overdue entries through an index.
+CREATE OR REPLACE FUNCTION password_rotation_due_at(entry passwords) RETURNS TIMESTAMPTZ AS $$
+    SELECT LEAST(
+        entry.rotate_by,
+        entry.updated_at + make_interval(days => entry.rotation_interval_days),
+        CASE WHEN entry.item_data->>'item_type' = 'api_key' THEN (entry.item_data->>'expires_at')::TIMESTAMPTZ END
+    );
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION stamp_password_rotation_due_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.rotation_due_at := password_rotation_due_at(NEW);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS passwords_rotation_due_at ON passwords;
+CREATE TRIGGER passwords_rotation_due_at
+    BEFORE INSERT OR UPDATE ON passwords
+    FOR EACH ROW EXECUTE FUNCTION stamp_password_rotation_due_at();
+
+-- Entries saved before the column existed
+UPDATE passwords SET rotation_due_at = password_rotation_due_at(passwords)
+WHERE rotation_due_at IS NULL AND password_rotation_due_at(passwords) IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS users (
+    id UUID PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+-- Only the hash of a token is stored, see `utility::token::hash_token`
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    name TEXT,
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL
 );
+
+CREATE INDEX IF NOT EXISTS api_tokens_user_id_idx ON api_tokens (user_id);
@@ -0,0 +1,256 @@
+use rust_password_server::bounded_context::domain::backup::{BackupDb, MIN_PASSPHRASE_LENGTH, RestoreMode, VaultBackup};
+use rust_password_server::bounded_context::domain::key_rotation::KeyRotationDb;
+use rust_password_server::bounded_context::domain::stats::StatsDb;
+use rust_password_server::bounded_context::domain::user::{ApiToken, User, UserDb};
+use rust_password_server::bounded_context::infrastructure::{config::app_config, db::postgres_db::Database};
+use rust_password_server::bounded_context::utility::backup::BackupEnvelope;
+use rust_password_server::bounded_context::utility::encryption::{generate_key, is_valid_masterkey};
+use rust_password_server::bounded_context::utility::token::generate_token;
+use chrono::{Duration, SubsecRound, Utc};
+use sqlx::Executor;
+use std::path::Path;
+
+const USAGE: &str = "Usage: password-admin <command>
+
+Commands:
+  generate-key                                   Print a new hex encoded master key
+  migrate                                        Create or update the database schema
+  check-config                                   Check the configuration in the environment
+  create-user <username>                         Add a user who can be issued API tokens
+  mint-token <username> [--name <name>] [--expires-in-days <days>]
+                                                 Issue an API token, printed only once
+  rotate-key                                     Re-encrypt every entry from ROTATE_OLD_KEY to ROTATE_NEW_KEY
+  backup <file>                                  Write an encrypted backup sealed with BACKUP_PASSPHRASE
+  restore <file> [--mode merge|preserve_ids]     Restore a backup sealed with BACKUP_PASSPHRASE
+  stats                                          Print counts of what the server stores";
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        usage();
+    };
+
+    match command.as_str() {
+        "generate-key" => println!("{}", generate_key()),
+        "migrate" => migrate().await,
+        "check-config" => check_config().await,
+        "create-user" => create_user(args).await,
+        "mint-token" => mint_token(args).await,
+        "rotate-key" => rotate_key().await,
+        "backup" => backup(args).await,
+        "restore" => restore(args).await,
+        "stats" => stats().await,
+        _ => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+async fn connect() -> Database {
+    let config = app_config::load_config();
+    Database::new(&config.db_url, 1, config.clone()).await.unwrap_or_else(|err| fail(format!("Failed to connect to db: {}", err)))
+}
+
+/// Splits `args` into positional arguments and the values of `--name value` options
+fn parse_args<'a>(args: &'a [String], options: &[&str]) -> (Vec<&'a str>, Vec<(&'a str, &'a str)>) {
+    let mut positional = Vec::new();
+    let mut values = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) if options.contains(&name) => match args.next() {
+                Some(value) => values.push((name, value.as_str())),
+                None => usage(),
+            },
+            Some(_) => usage(),
+            None => positional.push(arg.as_str()),
+        }
+    }
+
+    (positional, values)
+}
+
+fn option<'a>(values: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    values.iter().rev().find(|(option, _)| *option == name).map(|(_, value)| *value)
+}
+
+/// Applies `docker/init.sql`, which only creates what is missing and adds the columns, triggers and
+/// data changes later versions need to databases created by earlier ones
+async fn migrate() {
+    let database = connect().await;
+    let mut conn = database.get_connection().await.unwrap_or_else(|err| fail(err));
+    conn.execute(include_str!("../../docker/init.sql")).await.unwrap_or_else(|err| fail(format!("Migration failed: {}", err)));
+
+    println!("Database schema is up to date.");
+}
+
+async fn check_config() {
+    let config = app_config::load_config();
+    let mut problems = Vec::new();
+
+    if config.pagination_default_size > config.pagination_max_size {
+        problems.push("PAGINATION_DEFAULT_SIZE is larger than PAGINATION_MAX_SIZE".to_string());
+    }
+    if !(0..=4).contains(&config.weak_below_strength) {
+        problems.push("WEAK_BELOW_STRENGTH must be between 0 and 4".to_string());
+    }
+    if let Some(dir) = &config.attachment_dir {
+        if !Path::new(dir).is_dir() {
+            problems.push(format!("ATTACHMENT_DIR {} is not a directory", dir));
+        }
+    }
+    if let Some(path) = &config.breach_dataset_path {
+        if !Path::new(path).is_file() {
+            problems.push(format!("BREACH_DATASET_PATH {} is not a file", path));
+        }
+    }
+    if let Some(url) = &config.rotation_webhook_url {
+        if url::Url::parse(url).is_err() {
+            problems.push(format!("ROTATION_WEBHOOK_URL {} is not a valid URL", url));
+        }
+    }
+
+    match Database::new(&config.db_url, 1, config.clone()).await {
+        Ok(database) => {
+            let schema: Result<Option<String>, _> = sqlx::query_scalar("SELECT to_regclass('public.api_tokens')::TEXT")
+                .fetch_one(database.get_pool())
+                .await;
+            match schema {
+                Ok(Some(_)) => {}
+                Ok(None) => problems.push("The database schema is missing or outdated, run password-admin migrate".to_string()),
+                Err(err) => problems.push(format!("Failed to query the database: {}", err)),
+            }
+        }
+        Err(err) => problems.push(format!("Failed to connect to DATABASE_URL: {}", err)),
+    }
+
+    if problems.is_empty() {
+        println!("Configuration OK.");
+        return;
+    }
+    for problem in &problems {
+        eprintln!("{}", problem);
+    }
+    std::process::exit(1);
+}
+
+async fn create_user(args: &[String]) {
+    let (positional, _) = parse_args(args, &[]);
+    let [username] = positional.as_slice() else {
+        usage();
+    };
+    let user = User::new(username, Utc::now().trunc_subsecs(6)).unwrap_or_else(|err| fail(err));
+
+    let mut database = connect().await;
+    database.create_user(user.clone()).await.unwrap_or_else(|err| fail(err));
+
+    println!("Created user {} ({}).", user.username, user.id);
+}
+
+async fn mint_token(args: &[String]) {
+    let (positional, values) = parse_args(args, &["name", "expires-in-days"]);
+    let [username] = positional.as_slice() else {
+        usage();
+    };
+    let now = Utc::now().trunc_subsecs(6);
+    let expires_at = option(&values, "expires-in-days").map(|days| match days.parse::<i64>() {
+        Ok(days) if days > 0 => now + Duration::days(days),
+        _ => fail("--expires-in-days must be a positive number of days"),
+    });
+
+    let mut database = connect().await;
+    let user = database.get_user(username).await.unwrap_or_else(|err| fail(err));
+    let token = generate_token();
+    database
+        .create_token(ApiToken::new(user.id, &token, option(&values, "name").map(str::to_string), expires_at, now))
+        .await
+        .unwrap_or_else(|err| fail(err));
+
+    // The token is only stored hashed, so this is the one time it can be shown
+    println!("{}", token);
+}
+
+/// Keys are read from the environment so they stay out of the shell history
+async fn rotate_key() {
+    let old_key = std::env::var("ROTATE_OLD_KEY").unwrap_or_default();
+    let new_key = std::env::var("ROTATE_NEW_KEY").unwrap_or_default();
+    if !is_valid_masterkey(&old_key) || !is_valid_masterkey(&new_key) {
+        fail("ROTATE_OLD_KEY and ROTATE_NEW_KEY must hold hex encoded 32 byte master keys.");
+    }
+    if old_key == new_key {
+        fail("ROTATE_NEW_KEY must differ from ROTATE_OLD_KEY.");
+    }
+
+    let mut database = connect().await;
+    let rotated = database.rotate_master_key(&old_key, &new_key).await.unwrap_or_else(|err| fail(err));
+
+    println!("Re-encrypted {} entries. Attachments are encrypted by clients and were left as they are.", rotated);
+}
+
+fn backup_passphrase() -> String {
+    let passphrase = std::env::var("BACKUP_PASSPHRASE").unwrap_or_default();
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        fail(format!("BACKUP_PASSPHRASE must be at least {} characters.", MIN_PASSPHRASE_LENGTH));
+    }
+    passphrase
+}
+
+async fn backup(args: &[String]) {
+    let (positional, _) = parse_args(args, &[]);
+    let [path] = positional.as_slice() else {
+        usage();
+    };
+    let passphrase = backup_passphrase();
+
+    let mut database = connect().await;
+    let backup = database.export_vault(Utc::now().trunc_subsecs(6)).await.unwrap_or_else(|err| fail(err));
+    let envelope = serde_json::to_vec_pretty(&backup.seal(&passphrase)).expect("Failed to serialize backup");
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    let mut file = options.open(path).unwrap_or_else(|err| fail(format!("Failed to create {}: {}", path, err)));
+    std::io::Write::write_all(&mut file, &envelope).unwrap_or_else(|err| fail(err));
+
+    println!("Backed up {} entries and {} folders to {}.", backup.entries.len(), backup.folders.len(), path);
+}
+
+async fn restore(args: &[String]) {
+    let (positional, values) = parse_args(args, &["mode"]);
+    let [path] = positional.as_slice() else {
+        usage();
+    };
+    let mode = match option(&values, "mode") {
+        None | Some("merge") => RestoreMode::Merge,
+        Some("preserve_ids") => RestoreMode::PreserveIds,
+        Some(_) => usage(),
+    };
+    let passphrase = backup_passphrase();
+
+    let data = std::fs::read(path).unwrap_or_else(|err| fail(format!("Failed to read {}: {}", path, err)));
+    let envelope: BackupEnvelope = serde_json::from_slice(&data).unwrap_or_else(|err| fail(format!("Invalid backup file: {}", err)));
+    let backup = VaultBackup::open(&envelope, &passphrase).unwrap_or_else(|err| fail(err));
+
+    let mut database = connect().await;
+    let report = database.restore_vault(&backup, mode).await.unwrap_or_else(|err| fail(err));
+
+    println!(
+        "Created {} folders and {} entries, updated {} entries, left {} entries unchanged.",
+        report.folders_created, report.entries_created, report.entries_updated, report.entries_unchanged
+    );
+}
+
+async fn stats() {
+    let mut database = connect().await;
+    let stats = database.vault_stats(Utc::now()).await.unwrap_or_else(|err| fail(err));
+
+    println!("{}", serde_json::to_string_pretty(&stats).expect("Failed to serialize stats"));
+}
@@ -0,0 +1,392 @@
+use rust_password_server::bounded_context::domain::password::Password;
+use rust_password_server::bounded_context::domain::password_db::SortBy;
+use rust_password_server::bounded_context::infrastructure::client::api_client::{ApiClient, NewEntry};
+use rust_password_server::bounded_context::infrastructure::client::session::{ClientConfig, KeySession, DEFAULT_IDLE_TIMEOUT_MINUTES};
+use rust_password_server::bounded_context::utility::backup::KdfParams;
+use rust_password_server::bounded_context::utility::encryption::{derive_master_key, encrypt, try_decrypt};
+use chrono::{SubsecRound, Utc};
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use uuid::Uuid;
+
+const USAGE: &str = "Usage: pw <command>
+
+Commands:
+  login [--server <url>] [--token <token>] [--salt <hex>] [--idle-timeout <minutes>]
+                                     Save the server and unlock the vault with the master password
+  lock                               Forget the cached key
+  add <service> [--username <name>]  Prompt for a secret, encrypt it locally and store it
+  get <service|id> [--copy]          Print or copy the decrypted secret
+  search <term>                      List entries whose service, username or a URI contains <term>
+  ls [--sort <order>]                List every entry, by service unless --sort names a sort option
+  rm <service|id> [--yes]            Delete an entry
+
+The server URL includes the /api prefix, and the API token can also be given in PW_TOKEN.";
+
+/// Clipboard commands tried in order, the first one installed wins
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        usage();
+    };
+
+    match command.as_str() {
+        "login" => login(args).await,
+        "lock" => lock(args),
+        "add" => add(args).await,
+        "get" => get(args).await,
+        "search" => search(args).await,
+        "ls" => list(args).await,
+        "rm" => remove(args).await,
+        _ => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+struct Args<'a> {
+    positional: Vec<&'a str>,
+    values: Vec<(&'a str, &'a str)>,
+    flags: Vec<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    /// Splits `args` into positional arguments, the values of `--name value` options and `--flag`s
+    fn parse(args: &'a [String], options: &[&str], flags: &[&str]) -> Args<'a> {
+        let mut parsed = Args { positional: Vec::new(), values: Vec::new(), flags: Vec::new() };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if options.contains(&name) => match args.next() {
+                    Some(value) => parsed.values.push((name, value.as_str())),
+                    None => usage(),
+                },
+                Some(name) if flags.contains(&name) => parsed.flags.push(name),
+                Some(_) => usage(),
+                None => parsed.positional.push(arg.as_str()),
+            }
+        }
+
+        parsed
+    }
+
+    fn option(&self, name: &str) -> Option<&'a str> {
+        self.values.iter().rev().find(|(option, _)| *option == name).map(|(_, value)| *value)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).filter(|dir| !dir.is_empty()).map(PathBuf::from)
+}
+
+fn home_dir() -> PathBuf {
+    env_dir("HOME").unwrap_or_else(|| fail("HOME is not set."))
+}
+
+fn config_path() -> PathBuf {
+    env_dir("XDG_CONFIG_HOME").unwrap_or_else(|| home_dir().join(".config")).join("pw").join("config.json")
+}
+
+/// The runtime directory is cleared on logout, so a cached key does not outlive the login session
+fn session_path() -> PathBuf {
+    env_dir("XDG_RUNTIME_DIR")
+        .or_else(|| env_dir("XDG_CACHE_HOME"))
+        .unwrap_or_else(|| home_dir().join(".cache"))
+        .join("pw")
+        .join("session.json")
+}
+
+fn load_config() -> ClientConfig {
+    match ClientConfig::load(&config_path()) {
+        Ok(Some(config)) => config,
+        Ok(None) => fail("Not logged in, run pw login first."),
+        Err(err) => fail(err),
+    }
+}
+
+/// Reads a line from stdin, without echoing it when stdin is a terminal
+fn prompt_hidden(prompt: &str) -> String {
+    eprint!("{}", prompt);
+    let stdin = std::io::stdin();
+    let terminal = stdin.is_terminal();
+    if terminal {
+        set_echo(false);
+    }
+    let mut line = String::new();
+    let read = stdin.read_line(&mut line);
+    if terminal {
+        set_echo(true);
+        eprintln!();
+    }
+    match read {
+        Ok(0) => fail("No input."),
+        Ok(_) => line.trim_end_matches(['\r', '\n']).to_string(),
+        Err(err) => fail(format!("Failed to read input: {}", err)),
+    }
+}
+
+fn set_echo(enabled: bool) {
+    let _ = Command::new("stty").arg(if enabled { "echo" } else { "-echo" }).stdin(Stdio::inherit()).status();
+}
+
+/// Asks for a value twice when a person is typing it, so a typo is not stored
+fn prompt_new(prompt: &str) -> String {
+    let value = prompt_hidden(&format!("{}: ", prompt));
+    if std::io::stdin().is_terminal() && prompt_hidden(&format!("Repeat {}: ", prompt.to_lowercase())) != value {
+        fail("The values did not match.");
+    }
+    if value.is_empty() {
+        fail("The value cannot be empty.");
+    }
+    value
+}
+
+/// Returns the cached master key, pushing back its expiry, or asks for the master password
+fn master_key(config: &ClientConfig) -> String {
+    let path = session_path();
+    let now = Utc::now();
+    let mut session = match KeySession::load(&path, now) {
+        Some(session) => session,
+        None => {
+            let password = prompt_hidden("Master password: ");
+            let master_key = config.unlock(&password).unwrap_or_else(|| fail("Wrong master password."));
+            KeySession::new(master_key, now, config.idle_timeout())
+        }
+    };
+    session.touch(now, config.idle_timeout());
+    session.save(&path).unwrap_or_else(|err| fail(format!("Failed to cache the key in {}: {}", path.display(), err)));
+
+    session.master_key
+}
+
+fn client(config: &ClientConfig) -> ApiClient {
+    ApiClient::new(&config.server_url, config.token.clone())
+}
+
+async fn login(args: &[String]) {
+    let args = Args::parse(args, &["server", "token", "salt", "idle-timeout"], &[]);
+    if !args.positional.is_empty() {
+        usage();
+    }
+    let idle_timeout = args.option("idle-timeout").map(|minutes| match minutes.parse::<i64>() {
+        Ok(minutes) if minutes > 0 => minutes,
+        _ => fail("--idle-timeout must be a positive number of minutes"),
+    });
+    let token = args.option("token").map(str::to_string).or_else(|| std::env::var("PW_TOKEN").ok().filter(|token| !token.is_empty()));
+    let path = config_path();
+
+    let (config, master_key) = match ClientConfig::load(&path).unwrap_or_else(|err| fail(err)) {
+        Some(mut config) => {
+            if args.option("salt").is_some_and(|salt| !salt.eq_ignore_ascii_case(&config.kdf.salt)) {
+                fail(format!("Already set up with salt {}, delete {} to use another.", config.kdf.salt, path.display()));
+            }
+            let master_key = config.unlock(&prompt_hidden("Master password: ")).unwrap_or_else(|| fail("Wrong master password."));
+            if let Some(server) = args.option("server") {
+                config.server_url = server.trim_end_matches('/').to_string();
+            }
+            config.token = token.or(config.token);
+            config.idle_timeout_minutes = idle_timeout.unwrap_or(config.idle_timeout_minutes);
+            (config, master_key)
+        }
+        None => {
+            let server = args.option("server").unwrap_or_else(|| fail("--server is required the first time."));
+            let mut kdf = KdfParams::generate();
+            if let Some(salt) = args.option("salt") {
+                if hex::decode(salt).map_or(true, |salt| salt.len() < 8) {
+                    fail("--salt must be the hex encoded salt printed by pw login on another device.");
+                }
+                kdf.salt = salt.to_lowercase();
+            }
+            let password = prompt_new("Master password");
+            let master_key = derive_master_key(&password, &kdf).unwrap_or_else(|| fail("Failed to derive the master key."));
+            if args.option("salt").is_none() {
+                eprintln!("Key salt: {}", kdf.salt);
+                eprintln!("Pass --salt {} to pw login on other devices so they derive the same key.", kdf.salt);
+            }
+            let config = ClientConfig::new(server, token, kdf, &master_key, idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT_MINUTES));
+            (config, master_key)
+        }
+    };
+
+    client(&config).status().await.unwrap_or_else(|err| fail(format!("Failed to reach {}: {}", config.server_url, err)));
+    config.save(&path).unwrap_or_else(|err| fail(format!("Failed to save {}: {}", path.display(), err)));
+    let session = KeySession::new(master_key, Utc::now(), config.idle_timeout());
+    session.save(&session_path()).unwrap_or_else(|err| fail(format!("Failed to cache the key: {}", err)));
+
+    println!("Logged in to {}. The key is cached until {} minutes pass without use.", config.server_url, config.idle_timeout_minutes);
+}
+
+fn lock(args: &[String]) {
+    if !args.is_empty() {
+        usage();
+    }
+    KeySession::clear(&session_path());
+
+    println!("Locked.");
+}
+
+async fn add(args: &[String]) {
+    let args = Args::parse(args, &["username"], &[]);
+    let [service] = args.positional.as_slice() else {
+        usage();
+    };
+    let service = service.trim();
+    if service.is_empty() {
+        usage();
+    }
+    let config = load_config();
+    let master_key = master_key(&config);
+    let secret = prompt_new(&format!("Secret for {}", service));
+
+    let (nonce, cipher) = encrypt(&master_key, secret);
+    let now = Utc::now().trunc_subsecs(6);
+    let entry = NewEntry {
+        service: service.to_string(),
+        username: args.option("username").map(str::to_string),
+        nonce,
+        cipher,
+        created_at: now,
+        updated_at: now,
+    };
+    client(&config).create_password(&entry).await.unwrap_or_else(|err| fail(err));
+
+    println!("Added {}.", service);
+}
+
+/// Finds the entry with the id or exact service name `name`
+async fn find_entry(client: &ApiClient, name: &str) -> Password {
+    if let Ok(id) = Uuid::parse_str(name) {
+        return client.get_password(id).await.unwrap_or_else(|err| fail(err));
+    }
+
+    let mut matches: Vec<Password> = client
+        .search_passwords(name)
+        .await
+        .unwrap_or_else(|err| fail(err))
+        .into_iter()
+        .filter(|password| password.service.eq_ignore_ascii_case(name))
+        .collect();
+    match matches.len() {
+        0 => fail(format!("No entry named {}.", name)),
+        1 => matches.remove(0),
+        _ => {
+            eprintln!("Several entries are named {}, pass the id of one:", name);
+            print_entries(&matches);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_entries(passwords: &[Password]) {
+    for password in passwords {
+        println!("{}\t{}\t{}", password.service, password.username.as_deref().unwrap_or("-"), password.id);
+    }
+}
+
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    for (program, args) in CLIPBOARD_COMMANDS {
+        let Ok(mut child) = Command::new(program).args(*args).stdin(Stdio::piped()).stdout(Stdio::null()).spawn() else {
+            continue;
+        };
+        let written = child.stdin.take().map(|mut stdin| stdin.write_all(text.as_bytes()));
+        let status = child.wait().map_err(|err| err.to_string())?;
+        return match written {
+            Some(Ok(())) if status.success() => Ok(()),
+            _ => Err(format!("{} failed", program)),
+        };
+    }
+
+    Err("No clipboard command found, install one of pbcopy, wl-copy, xclip or xsel".to_string())
+}
+
+async fn get(args: &[String]) {
+    let args = Args::parse(args, &[], &["copy"]);
+    let [name] = args.positional.as_slice() else {
+        usage();
+    };
+    let config = load_config();
+    let master_key = master_key(&config);
+    let password = find_entry(&client(&config), name).await;
+
+    let secret = try_decrypt(&master_key, &password.nonce, &password.cipher)
+        .unwrap_or_else(|| fail(format!("Failed to decrypt {}, it was encrypted with a different key.", password.service)));
+    if !args.flag("copy") {
+        println!("{}", secret);
+        return;
+    }
+    copy_to_clipboard(&secret).unwrap_or_else(|err| fail(format!("Failed to copy: {}", err)));
+
+    println!("Copied the secret for {} to the clipboard.", password.service);
+}
+
+async fn search(args: &[String]) {
+    let args = Args::parse(args, &[], &[]);
+    let [term] = args.positional.as_slice() else {
+        usage();
+    };
+    let config = load_config();
+    let passwords = client(&config).search_passwords(term).await.unwrap_or_else(|err| fail(err));
+
+    print_entries(&passwords);
+}
+
+async fn list(args: &[String]) {
+    let args = Args::parse(args, &["sort"], &[]);
+    if !args.positional.is_empty() {
+        usage();
+    }
+    let sort = args.option("sort").unwrap_or("service_asc");
+    SortBy::from_str(sort).unwrap_or_else(|err| fail(err));
+    let config = load_config();
+    let passwords = client(&config).list_passwords(sort).await.unwrap_or_else(|err| fail(err));
+
+    print_entries(&passwords);
+}
+
+async fn remove(args: &[String]) {
+    let args = Args::parse(args, &[], &["yes"]);
+    let [name] = args.positional.as_slice() else {
+        usage();
+    };
+    let config = load_config();
+    let client = client(&config);
+    let password = find_entry(&client, name).await;
+
+    if !args.flag("yes") {
+        if !std::io::stdin().is_terminal() {
+            fail("Pass --yes to delete without a prompt.");
+        }
+        eprint!("Delete {} ({})? [y/N] ", password.service, password.id);
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).unwrap_or_else(|err| fail(err));
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            fail("Cancelled.");
+        }
+    }
+    client.delete_password(password.id).await.unwrap_or_else(|err| fail(err));
+
+    println!("Deleted {} ({}).", password.service, password.id);
+}
@@ -0,0 +1,143 @@
+use axum::{Json, body::Body, extract::{Query, State}, http::{HeaderMap, header}, response::Response};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::attachment::{Attachment, AttachmentDb, AttachmentError, BlobStream};
+use futures_util::TryStreamExt;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct UploadAttachmentParams {
+    password_id: Uuid,
+    file_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct AttachmentParams {
+    id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct ListAttachmentsParams {
+    password_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteAttachmentInput {
+    id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct ResponseMessage {
+    message: String,
+}
+
+fn attachment_error(err: Box<dyn Error>) -> (axum::http::StatusCode, String) {
+    match err.downcast_ref::<AttachmentError>() {
+        Some(AttachmentError::NotFound(_)) | Some(AttachmentError::PasswordNotFound(_)) => {
+            (axum::http::StatusCode::NOT_FOUND, err.to_string())
+        }
+        Some(AttachmentError::TooLarge(_)) | Some(AttachmentError::QuotaExceeded(_)) => {
+            (axum::http::StatusCode::PAYLOAD_TOO_LARGE, err.to_string())
+        }
+        _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+/// Builds a Content-Disposition value with an ASCII fallback and the RFC 5987 encoded original name.
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| if c == ' ' || (c.is_ascii_graphic() && c != '"' && c != '\\') { c } else { '_' })
+        .collect();
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback,
+        utf8_percent_encode(file_name, NON_ALPHANUMERIC)
+    )
+}
+
+/// Receives the already encrypted file as the raw request body.
+pub async fn upload_attachment(
+    State(database): State<Database>,
+    Query(params): Query<UploadAttachmentParams>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Json<Attachment>, (axum::http::StatusCode, String)> {
+    let file_name = params.file_name.trim();
+    if file_name.is_empty() || file_name.chars().any(|c| c.is_control()) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid file name.".to_string()));
+    }
+
+    let max_size = database.config.attachment_max_size;
+    let declared_size = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if declared_size.is_some_and(|size| size > max_size) {
+        return Err(attachment_error(Box::new(AttachmentError::TooLarge(max_size))));
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    let attachment = Attachment::new(Uuid::new_v4(), params.password_id, file_name.to_string(), content_type);
+    let stream: BlobStream = Box::pin(body.into_data_stream().map_err(std::io::Error::other));
+
+    let mut db = database;
+
+    match db.save_attachment(attachment, stream).await {
+        Ok(attachment) => Ok(Json(attachment)),
+        Err(err) => Err(attachment_error(err)),
+    }
+}
+
+/// Streams the stored blob back exactly as it was uploaded.
+pub async fn download_attachment(
+    State(database): State<Database>,
+    Query(params): Query<AttachmentParams>,
+) -> Result<Response, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    let (attachment, stream) = match db.open_attachment(params.id).await {
+        Ok(opened) => opened,
+        Err(err) => return Err(attachment_error(err)),
+    };
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .header(header::CONTENT_LENGTH, attachment.size)
+        .header(header::CONTENT_DISPOSITION, content_disposition(&attachment.file_name))
+        .body(Body::from_stream(stream))
+        .map_err(|err| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+}
+
+pub async fn list_attachments(
+    State(database): State<Database>,
+    Query(params): Query<ListAttachmentsParams>,
+) -> Result<Json<Vec<Attachment>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.list_attachments(params.password_id).await {
+        Ok(attachments) => Ok(Json(attachments)),
+        Err(err) => Err(attachment_error(err)),
+    }
+}
+
+pub async fn delete_attachment(
+    State(database): State<Database>,
+    Json(payload): Json<DeleteAttachmentInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.delete_attachment(payload.id).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Attachment deleted successfully".to_string(),
+        })),
+        Err(err) => Err(attachment_error(err)),
+    }
+}
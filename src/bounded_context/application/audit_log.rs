@@ -0,0 +1,56 @@
+use axum::{Json, extract::State, extract::Query};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::audit::{AuditDb, AuditEvent, AuditFilter, AuditOutcome};
+use crate::bounded_context::domain::pagination::Page;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct AuditLogInput {
+    actor: Option<String>,
+    action: Option<String>,
+    entry_id: Option<Uuid>,
+    outcome: Option<AuditOutcome>,
+    request_id: Option<String>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+}
+
+pub async fn audit_log(
+    State(database): State<Database>,
+    Query(payload): Query<AuditLogInput>,
+) -> Result<Json<Page<AuditEvent>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    let page = payload.page.unwrap_or(1);
+    if page == 0 {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid page number.".to_string()));
+    }
+
+    let page_size = payload.page_size.unwrap_or(db.config.pagination_default_size);
+    if page_size >= db.config.pagination_max_size {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Max Pagination Size Exceeded".to_string()));
+    }
+
+    let filter = AuditFilter {
+        actor: payload.actor,
+        action: payload.action,
+        entry_id: payload.entry_id,
+        outcome: payload.outcome,
+        request_id: payload.request_id,
+        after: payload.after,
+        before: payload.before,
+    };
+
+    if filter.after.zip(filter.before).is_some_and(|(after, before)| after >= before) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid date range.".to_string()));
+    }
+
+    match db.query_audit_events(&filter, page, page_size).await {
+        Ok(events) => Ok(Json(events)),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
@@ -0,0 +1,91 @@
+use axum::{Json, extract::State, http::header};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::backup::{BackupDb, BackupError, MIN_PASSPHRASE_LENGTH, RestoreMode, RestoreReport, VaultBackup};
+use crate::bounded_context::domain::folder::FolderError;
+use crate::bounded_context::utility::backup::BackupEnvelope;
+use chrono::{SubsecRound, Utc};
+use serde::Deserialize;
+use std::error::Error;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Backups hold the whole vault in memory and derive their keys with Argon2, so only a few run at once
+const MAX_CONCURRENT_BACKUPS: usize = 2;
+
+static BACKUP_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_BACKUPS);
+
+#[derive(Deserialize)]
+pub struct ExportBackupInput {
+    passphrase: String,
+}
+
+#[derive(Deserialize)]
+pub struct RestoreBackupInput {
+    passphrase: String,
+    #[serde(default)]
+    mode: RestoreMode,
+    backup: BackupEnvelope,
+}
+
+fn backup_error(err: Box<dyn Error>) -> (axum::http::StatusCode, String) {
+    if err.is::<FolderError>() {
+        return (axum::http::StatusCode::BAD_REQUEST, err.to_string());
+    }
+
+    match err.downcast_ref::<BackupError>() {
+        Some(BackupError::InvalidFile(_)) | Some(BackupError::UnsupportedVersion(_)) | Some(BackupError::IntegrityCheckFailed) => {
+            (axum::http::StatusCode::BAD_REQUEST, err.to_string())
+        }
+        Some(BackupError::EntryExists(_)) | Some(BackupError::FolderExists(_)) => (axum::http::StatusCode::CONFLICT, err.to_string()),
+        None => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+fn backup_permit() -> Result<SemaphorePermit<'static>, (axum::http::StatusCode, String)> {
+    BACKUP_PERMITS.try_acquire().map_err(|_| {
+        (axum::http::StatusCode::TOO_MANY_REQUESTS, "Too many backups are in progress, try again later.".to_string())
+    })
+}
+
+pub async fn export_backup(
+    State(database): State<Database>,
+    Json(payload): Json<ExportBackupInput>,
+) -> Result<([(header::HeaderName, String); 1], Json<BackupEnvelope>), (axum::http::StatusCode, String)> {
+    if payload.passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Passphrase must be at least {} characters.", MIN_PASSPHRASE_LENGTH),
+        ));
+    }
+
+    let _permit = backup_permit()?;
+    let mut db = database;
+
+    let now = Utc::now().trunc_subsecs(6);
+    let backup = db.export_vault(now).await.map_err(backup_error)?;
+    let disposition = format!("attachment; filename=\"vault-backup-{}.json\"", now.format("%Y%m%dT%H%M%SZ"));
+
+    // Argon2 takes long enough to stall other requests on the async workers
+    let envelope = tokio::task::spawn_blocking(move || backup.seal(&payload.passphrase))
+        .await
+        .map_err(|err| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(envelope)))
+}
+
+pub async fn restore_backup(
+    State(database): State<Database>,
+    Json(payload): Json<RestoreBackupInput>,
+) -> Result<Json<RestoreReport>, (axum::http::StatusCode, String)> {
+    let _permit = backup_permit()?;
+    let backup = tokio::task::spawn_blocking(move || VaultBackup::open(&payload.backup, &payload.passphrase))
+        .await
+        .map_err(|err| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .map_err(|err| backup_error(Box::new(err)))?;
+
+    let mut db = database;
+
+    match db.restore_vault(&backup, payload.mode).await {
+        Ok(report) => Ok(Json(report)),
+        Err(err) => Err(backup_error(err)),
+    }
+}
@@ -0,0 +1,127 @@
+use axum::{Json, extract::State};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::{BatchMode, BatchResult, PasswordDb};
+use super::create_password::NewPassword;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Items accepted in one batch
+const MAX_BATCH_ITEMS: usize = 1000;
+
+#[derive(Deserialize)]
+pub struct BatchInput<T> {
+    #[serde(default)]
+    mode: BatchMode,
+    items: Vec<T>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdatedPassword {
+    id: Uuid,
+    #[serde(flatten)]
+    entry: NewPassword,
+}
+
+fn check_batch_size(len: usize) -> Result<(), (axum::http::StatusCode, String)> {
+    if len == 0 {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "No items provided.".to_string()));
+    }
+    if len > MAX_BATCH_ITEMS {
+        return Err((axum::http::StatusCode::BAD_REQUEST, format!("At most {} items can be sent at once.", MAX_BATCH_ITEMS)));
+    }
+
+    Ok(())
+}
+
+/// Splits validated items into those to send to the database and the outcome of each item so far.
+/// Sends none when `mode` would roll the batch back anyway.
+fn partition<T>(mode: BatchMode, validated: Vec<Result<T, String>>) -> (Vec<T>, Vec<Result<(), String>>) {
+    let mut items = Vec::new();
+    let mut outcomes = Vec::with_capacity(validated.len());
+    for item in validated {
+        match item {
+            Ok(item) => {
+                items.push(item);
+                outcomes.push(Ok(()));
+            }
+            Err(message) => outcomes.push(Err(message)),
+        }
+    }
+    if !mode.commits(&outcomes) {
+        items.clear();
+    }
+
+    (items, outcomes)
+}
+
+/// Replaces the outcome of every valid item with the one the database reported, in order
+fn merge_outcomes(outcomes: Vec<Result<(), String>>, applied: Vec<Result<(), String>>) -> Vec<Result<(), String>> {
+    let mut applied = applied.into_iter();
+    outcomes
+        .into_iter()
+        .map(|outcome| match outcome {
+            Ok(()) => applied.next().unwrap_or(Ok(())),
+            Err(message) => Err(message),
+        })
+        .collect()
+}
+
+pub async fn batch_create(
+    State(database): State<Database>,
+    Json(payload): Json<BatchInput<NewPassword>>,
+) -> Result<Json<BatchResult>, (axum::http::StatusCode, String)> {
+    check_batch_size(payload.items.len())?;
+
+    let validated: Vec<_> = payload.items.into_iter().map(|item| item.into_password(Uuid::new_v4())).collect();
+    let ids: Vec<_> = validated.iter().map(|item| item.as_ref().ok().map(|password| password.id)).collect();
+    let (passwords, outcomes) = partition(payload.mode, validated);
+
+    let mut db = database;
+
+    let applied = if passwords.is_empty() {
+        Vec::new()
+    } else {
+        db.save_batch(passwords, payload.mode)
+            .await
+            .map_err(|err| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+    };
+
+    Ok(Json(BatchResult::new(payload.mode, ids.into_iter().zip(merge_outcomes(outcomes, applied)).collect())))
+}
+
+pub async fn batch_update(
+    State(database): State<Database>,
+    Json(payload): Json<BatchInput<UpdatedPassword>>,
+) -> Result<Json<BatchResult>, (axum::http::StatusCode, String)> {
+    check_batch_size(payload.items.len())?;
+
+    let ids: Vec<_> = payload.items.iter().map(|item| Some(item.id)).collect();
+    let validated = payload.items.into_iter().map(|item| item.entry.into_password(item.id)).collect();
+    let (passwords, outcomes) = partition(payload.mode, validated);
+
+    let mut db = database;
+
+    let applied = if passwords.is_empty() {
+        Vec::new()
+    } else {
+        db.update_batch(passwords, payload.mode)
+            .await
+            .map_err(|err| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+    };
+
+    Ok(Json(BatchResult::new(payload.mode, ids.into_iter().zip(merge_outcomes(outcomes, applied)).collect())))
+}
+
+pub async fn batch_delete(
+    State(database): State<Database>,
+    Json(payload): Json<BatchInput<Uuid>>,
+) -> Result<Json<BatchResult>, (axum::http::StatusCode, String)> {
+    check_batch_size(payload.items.len())?;
+
+    let mut db = database;
+
+    match db.delete_batch(payload.items.clone(), payload.mode).await {
+        Ok(outcomes) => Ok(Json(BatchResult::new(payload.mode, payload.items.into_iter().map(Some).zip(outcomes).collect()))),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{Json, extract::State, extract::Path};
+use crate::bounded_context::infrastructure::db::hibp_index::HibpIndex;
+use crate::bounded_context::domain::breach_db::BreachDb;
+use crate::bounded_context::domain::breach::BreachedHash;
+use crate::bounded_context::utility::breach::is_valid_hash_prefix;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct BreachRangeResponse {
+    prefix: String,
+    hashes: Vec<BreachedHash>,
+}
+
+pub async fn breach_range(
+    State(index): State<Option<HibpIndex>>,
+    Path(prefix): Path<String>,
+) -> Result<Json<BreachRangeResponse>, (axum::http::StatusCode, String)> {
+    let index = match index {
+        Some(index) => index,
+        None => return Err((axum::http::StatusCode::SERVICE_UNAVAILABLE, "Breach dataset not configured.".to_string())),
+    };
+
+    if !is_valid_hash_prefix(&prefix) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid hash prefix.".to_string()));
+    }
+
+    let prefix = prefix.to_uppercase();
+
+    // The dataset is read from disk, so the lookup is kept off the async workers
+    let lookup_prefix = prefix.clone();
+    let lookup = tokio::task::spawn_blocking(move || index.range(&lookup_prefix).map_err(|err| err.to_string())).await;
+
+    match lookup {
+        Ok(Ok(hashes)) => Ok(Json(BreachRangeResponse { prefix, hashes })),
+        Ok(Err(err)) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err)),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
@@ -1,9 +1,13 @@
 use axum::{Json, extract::State};
-use crate::bounded_context::domain::password::Password;
+use crate::bounded_context::domain::password::{Password, EncryptedText, CustomField};
+use crate::bounded_context::domain::item::{ItemData, ItemType};
+use crate::bounded_context::domain::folder::FolderError;
+use crate::bounded_context::domain::uri_match::EntryUri;
 use crate::bounded_context::infrastructure::db::postgres_db::Database;
 use crate::bounded_context::domain::password_db::PasswordDb;
-use crate::bounded_context::utility::encryption::{is_valid_cipher, is_valid_nonce};
+use crate::bounded_context::utility::encryption::{is_valid_cipher, is_valid_nonce, is_valid_fingerprint};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
@@ -14,6 +18,63 @@ pub struct NewPassword {
     cipher: String,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    fingerprint: Option<String>,
+    strength: Option<i16>,
+    username: Option<String>,
+    #[serde(default)]
+    uris: Vec<EntryUri>,
+    notes: Option<EncryptedText>,
+    totp: Option<EncryptedText>,
+    #[serde(default)]
+    custom_fields: Vec<CustomField>,
+    item_type: Option<ItemType>,
+    folder_id: Option<Uuid>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    favorite: bool,
+    rotate_by: Option<DateTime<Utc>>,
+    rotation_interval_days: Option<i32>,
+    /// Type specific fields, parsed into `ItemData` once `item_type` is known
+    #[serde(flatten)]
+    item_fields: Map<String, Value>,
+}
+
+impl NewPassword {
+    /// Builds the entry `id` from what the client sent and validates it. Returns the message to
+    /// reject it with.
+    pub(super) fn into_password(self, id: Uuid) -> Result<Password, String> {
+        let mut item_fields = self.item_fields;
+        item_fields.insert(
+            "item_type".to_string(),
+            Value::String(self.item_type.unwrap_or(ItemType::Login).as_str().to_string()),
+        );
+        let item: ItemData = serde_json::from_value(Value::Object(item_fields))
+            .map_err(|err| format!("Invalid item data provided: {}", err))?;
+
+        prepare_password(Password {
+            id,
+            service: self.service,
+            nonce: self.nonce,
+            cipher: self.cipher,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            fingerprint: self.fingerprint,
+            strength: self.strength,
+            username: self.username,
+            uris: self.uris,
+            notes: self.notes,
+            totp: self.totp,
+            custom_fields: self.custom_fields,
+            item,
+            folder_id: self.folder_id,
+            tags: self.tags,
+            favorite: self.favorite,
+            last_used_at: None,
+            rotate_by: self.rotate_by,
+            rotation_interval_days: self.rotation_interval_days,
+        })
+    }
 }
 
 #[derive(Serialize)]
@@ -25,22 +86,9 @@ pub async fn create_password(
     State(database): State<Database>,
     Json(payload): Json<NewPassword>,
 ) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
-    if !is_valid_nonce(&payload.nonce) {
-        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid nonce provided.".to_string()));
-    }
-
-    if !is_valid_cipher(&payload.cipher) {
-        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid cipher provided.".to_string()));
-    }
-
-    let password = Password {
-        id: Uuid::new_v4(),
-        service: payload.service,
-        nonce: payload.nonce,
-        cipher: payload.cipher,
-        created_at: payload.created_at,
-        updated_at: payload.updated_at,
-    };
+    let password = payload
+        .into_password(Uuid::new_v4())
+        .map_err(|message| (axum::http::StatusCode::BAD_REQUEST, message))?;
 
     let mut db = database;
 
@@ -48,6 +96,90 @@ pub async fn create_password(
         Ok(_) => Ok(Json(ResponseMessage {
             message: "Password saved successfully".to_string(),
         })),
+        Err(err) if err.is::<FolderError>() => Err((axum::http::StatusCode::BAD_REQUEST, err.to_string())),
         Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
     }
 }
+
+/// Normalizes and validates an entry sent by a client, whether it is created here or pushed by sync.
+/// Returns the message to reject it with.
+pub(super) fn prepare_password(password: Password) -> Result<Password, String> {
+    let mut password = password;
+    password.fingerprint = password.fingerprint.map(|fingerprint| fingerprint.to_lowercase());
+    password.uris = password.uris.into_iter().map(|uri| EntryUri::new(uri.uri.trim(), uri.match_strategy)).collect();
+
+    if !is_valid_nonce(&password.nonce) {
+        return Err("Invalid nonce provided.".to_string());
+    }
+
+    if !is_valid_cipher(&password.cipher) {
+        return Err("Invalid cipher provided.".to_string());
+    }
+
+    if password.fingerprint.as_deref().is_some_and(|fingerprint| !is_valid_fingerprint(fingerprint)) {
+        return Err("Invalid fingerprint provided.".to_string());
+    }
+
+    if password.strength.is_some_and(|strength| !(0..=4).contains(&strength)) {
+        return Err("Invalid strength provided.".to_string());
+    }
+
+    if password.uris.iter().any(|uri| !uri.is_valid()) {
+        return Err("Invalid URI provided.".to_string());
+    }
+
+    if password.notes.as_ref().is_some_and(|notes| !is_valid_nonce(&notes.nonce) || !is_valid_cipher(&notes.cipher)) {
+        return Err("Invalid notes provided.".to_string());
+    }
+
+    if password.totp.as_ref().is_some_and(|totp| !is_valid_nonce(&totp.nonce) || !is_valid_cipher(&totp.cipher)) {
+        return Err("Invalid TOTP secret provided.".to_string());
+    }
+
+    if let Some(field) = password.custom_fields.iter().find(|field| !is_valid_custom_field(field)) {
+        return Err(format!("Invalid custom field provided: {}", field.name()));
+    }
+
+    if password.rotation_interval_days.is_some_and(|days| days < 1) {
+        return Err("Invalid rotation interval provided.".to_string());
+    }
+
+    if password.tags.iter().any(|tag| tag.trim().is_empty()) {
+        return Err("Invalid tag provided.".to_string());
+    }
+
+    if !is_valid_item(&password.item) {
+        return Err(format!("Invalid {} fields provided.", password.item.item_type()));
+    }
+
+    Ok(password)
+}
+
+fn is_valid_custom_field(field: &CustomField) -> bool {
+    if field.name().trim().is_empty() {
+        return false;
+    }
+
+    match field {
+        CustomField::Hidden { nonce, cipher, .. } => is_valid_nonce(nonce) && is_valid_cipher(cipher),
+        CustomField::Text { .. } | CustomField::Boolean { .. } => true,
+    }
+}
+
+fn is_valid_item(item: &ItemData) -> bool {
+    if !item.encrypted_fields().iter().all(|field| is_valid_nonce(&field.nonce) && is_valid_cipher(&field.cipher)) {
+        return false;
+    }
+
+    match item {
+        ItemData::Card { last_four, .. } => last_four
+            .as_deref()
+            .is_none_or(|digits| digits.len() == 4 && digits.chars().all(|c| c.is_ascii_digit())),
+        ItemData::SshKey { public_key, .. } => {
+            let mut parts = public_key.split_whitespace();
+            parts.next().is_some_and(|algorithm| algorithm.starts_with("ssh-") || algorithm.starts_with("ecdsa-") || algorithm.starts_with("sk-"))
+                && parts.next().is_some()
+        }
+        ItemData::Login | ItemData::SecureNote | ItemData::Identity { .. } | ItemData::ApiKey { .. } => true,
+    }
+}
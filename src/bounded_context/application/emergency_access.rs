@@ -0,0 +1,212 @@
+use axum::{Json, extract::{Query, State}};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::emergency_access::{EmergencyAccess, EmergencyAccessAction, EmergencyAccessDb, EmergencyAccessError, EmergencyAccessEvent, EmergencyAccessStatus};
+use crate::bounded_context::domain::password::Password;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::utility::token::{generate_token, is_valid_token};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use uuid::Uuid;
+
+const DEFAULT_WAIT_HOURS: i32 = 48;
+const MAX_WAIT_HOURS: i32 = 90 * 24;
+
+#[derive(Deserialize)]
+pub struct InviteContactInput {
+    contact: String,
+    wait_hours: Option<i32>,
+}
+
+/// Owner actions name the grant by id
+#[derive(Deserialize)]
+pub struct EmergencyAccessIdInput {
+    id: Uuid,
+}
+
+/// Contact actions present the token handed out on invite
+#[derive(Deserialize)]
+pub struct EmergencyAccessTokenInput {
+    token: String,
+}
+
+#[derive(Deserialize)]
+pub struct EmergencyAccessEventsParams {
+    id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct InvitedContact {
+    #[serde(flatten)]
+    access: EmergencyAccess,
+    token: String,
+}
+
+/// What a contact is shown of their grant. The grant's id is left out, since it is what owner
+/// actions name the grant by.
+#[derive(Serialize)]
+pub struct ContactGrant {
+    contact: String,
+    status: EmergencyAccessStatus,
+    wait_hours: i32,
+    requested_at: Option<DateTime<Utc>>,
+    grants_at: Option<DateTime<Utc>>,
+}
+
+impl From<EmergencyAccess> for ContactGrant {
+    fn from(access: EmergencyAccess) -> Self {
+        ContactGrant {
+            grants_at: access.grants_at(),
+            contact: access.contact,
+            status: access.status,
+            wait_hours: access.wait_hours,
+            requested_at: access.requested_at,
+        }
+    }
+}
+
+fn emergency_access_error(err: Box<dyn Error>) -> (axum::http::StatusCode, String) {
+    match err.downcast_ref::<EmergencyAccessError>() {
+        Some(EmergencyAccessError::NotFound) => (axum::http::StatusCode::NOT_FOUND, err.to_string()),
+        Some(EmergencyAccessError::InvalidTransition { .. }) => (axum::http::StatusCode::CONFLICT, err.to_string()),
+        Some(EmergencyAccessError::NotGranted) => (axum::http::StatusCode::FORBIDDEN, err.to_string()),
+        _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+fn parse_token(token: &str) -> Result<&str, (axum::http::StatusCode, String)> {
+    if is_valid_token(token) {
+        Ok(token)
+    } else {
+        Err(emergency_access_error(Box::new(EmergencyAccessError::NotFound)))
+    }
+}
+
+pub async fn invite_contact(
+    State(database): State<Database>,
+    Json(payload): Json<InviteContactInput>,
+) -> Result<Json<InvitedContact>, (axum::http::StatusCode, String)> {
+    let contact = payload.contact.trim();
+    if contact.is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid contact provided.".to_string()));
+    }
+
+    let wait_hours = payload.wait_hours.unwrap_or(DEFAULT_WAIT_HOURS);
+    if !(1..=MAX_WAIT_HOURS).contains(&wait_hours) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid waiting period provided.".to_string()));
+    }
+
+    let token = generate_token();
+    let access = EmergencyAccess::new(Uuid::new_v4(), contact.to_string(), &token, wait_hours);
+
+    let mut db = database;
+
+    match db.create_emergency_access(access.clone()).await {
+        Ok(_) => Ok(Json(InvitedContact { access, token })),
+        Err(err) => Err(emergency_access_error(err)),
+    }
+}
+
+pub async fn list_emergency_access(
+    State(database): State<Database>,
+) -> Result<Json<Vec<EmergencyAccess>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.list_emergency_access(Utc::now()).await {
+        Ok(accesses) => Ok(Json(accesses)),
+        Err(err) => Err(emergency_access_error(err)),
+    }
+}
+
+pub async fn list_emergency_access_events(
+    State(database): State<Database>,
+    Query(params): Query<EmergencyAccessEventsParams>,
+) -> Result<Json<Vec<EmergencyAccessEvent>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.list_emergency_access_events(params.id).await {
+        Ok(events) => Ok(Json(events)),
+        Err(err) => Err(emergency_access_error(err)),
+    }
+}
+
+async fn owner_transition(
+    database: Database,
+    id: Uuid,
+    action: EmergencyAccessAction,
+) -> Result<Json<EmergencyAccess>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.transition_emergency_access(id, action, Utc::now()).await {
+        Ok(access) => Ok(Json(access)),
+        Err(err) => Err(emergency_access_error(err)),
+    }
+}
+
+async fn contact_transition(
+    database: Database,
+    token: &str,
+    action: EmergencyAccessAction,
+) -> Result<Json<ContactGrant>, (axum::http::StatusCode, String)> {
+    let token = parse_token(token)?;
+    let mut db = database;
+
+    match db.transition_emergency_access_by_token(token, action, Utc::now()).await {
+        Ok(access) => Ok(Json(access.into())),
+        Err(err) => Err(emergency_access_error(err)),
+    }
+}
+
+pub async fn accept_invite(
+    State(database): State<Database>,
+    Json(payload): Json<EmergencyAccessTokenInput>,
+) -> Result<Json<ContactGrant>, (axum::http::StatusCode, String)> {
+    contact_transition(database, &payload.token, EmergencyAccessAction::Accept).await
+}
+
+pub async fn request_access(
+    State(database): State<Database>,
+    Json(payload): Json<EmergencyAccessTokenInput>,
+) -> Result<Json<ContactGrant>, (axum::http::StatusCode, String)> {
+    contact_transition(database, &payload.token, EmergencyAccessAction::Request).await
+}
+
+pub async fn approve_request(
+    State(database): State<Database>,
+    Json(payload): Json<EmergencyAccessIdInput>,
+) -> Result<Json<EmergencyAccess>, (axum::http::StatusCode, String)> {
+    owner_transition(database, payload.id, EmergencyAccessAction::Approve).await
+}
+
+pub async fn reject_request(
+    State(database): State<Database>,
+    Json(payload): Json<EmergencyAccessIdInput>,
+) -> Result<Json<EmergencyAccess>, (axum::http::StatusCode, String)> {
+    owner_transition(database, payload.id, EmergencyAccessAction::Reject).await
+}
+
+pub async fn revoke_access(
+    State(database): State<Database>,
+    Json(payload): Json<EmergencyAccessIdInput>,
+) -> Result<Json<EmergencyAccess>, (axum::http::StatusCode, String)> {
+    owner_transition(database, payload.id, EmergencyAccessAction::Revoke).await
+}
+
+/// Read access for a contact whose request was granted. Entries stay encrypted; the contact
+/// needs the vault key from the owner to read them.
+pub async fn emergency_vault(
+    State(database): State<Database>,
+    Json(payload): Json<EmergencyAccessTokenInput>,
+) -> Result<Json<Vec<Password>>, (axum::http::StatusCode, String)> {
+    let token = parse_token(&payload.token)?;
+    let mut db = database;
+
+    if let Err(err) = db.authorize_emergency_access(token, Utc::now()).await {
+        return Err(emergency_access_error(err));
+    }
+
+    match db.list_all().await {
+        Ok(passwords) => Ok(Json(passwords)),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
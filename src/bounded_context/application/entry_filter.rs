@@ -0,0 +1,35 @@
+use crate::bounded_context::domain::password_db::EntryFilter;
+use crate::bounded_context::domain::item::ItemType;
+use serde::Deserialize;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Filter query parameters shared by the listing routes
+#[derive(Deserialize)]
+pub struct EntryFilterInput {
+    item_type: Option<String>,
+    folder_id: Option<String>,
+    include_subfolders: Option<String>,
+    tag: Option<String>,
+}
+
+impl EntryFilterInput {
+    pub fn parse(self) -> Result<EntryFilter, (axum::http::StatusCode, String)> {
+        let item_type = match self.item_type.as_deref().map(ItemType::from_str).transpose() {
+            Ok(item_type) => item_type,
+            Err(err) => return Err((axum::http::StatusCode::BAD_REQUEST, err.to_string())),
+        };
+
+        let folder_id = match self.folder_id.as_deref().map(Uuid::parse_str).transpose() {
+            Ok(folder_id) => folder_id,
+            Err(_) => return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid folder ID.".to_string())),
+        };
+
+        let include_subfolders = match self.include_subfolders.as_deref().map(bool::from_str).transpose() {
+            Ok(include_subfolders) => include_subfolders.unwrap_or(false),
+            Err(_) => return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid include_subfolders value.".to_string())),
+        };
+
+        Ok(EntryFilter { item_type, folder_id, include_subfolders, tag: self.tag, ..Default::default() })
+    }
+}
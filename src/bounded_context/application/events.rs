@@ -0,0 +1,64 @@
+use axum::{
+    extract::{State, ws::{Message, WebSocket, WebSocketUpgrade, rejection::WebSocketUpgradeRejection}},
+    response::{IntoResponse, Response, sse::{Event, KeepAlive, Sse}},
+};
+use crate::bounded_context::domain::change_feed::FeedMessage;
+use crate::bounded_context::infrastructure::db::change_feed::ChangeFeed;
+use futures_util::stream;
+use std::convert::Infallible;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+const RESYNC_EVENT: &str = "resync";
+
+/// The next message for a client as an event name and JSON body, or `None` once the feed closed.
+/// A client that fell behind is told to resync instead of being sent a partial history.
+async fn next_event(receiver: &mut Receiver<FeedMessage>) -> Option<(&'static str, String)> {
+    match receiver.recv().await {
+        Ok(FeedMessage::Change(change)) => {
+            let body = serde_json::to_string(&change).expect("Failed to serialize entry change");
+            Some((change.event.as_str(), body))
+        }
+        Ok(FeedMessage::Resync) | Err(RecvError::Lagged(_)) => {
+            Some((RESYNC_EVENT, serde_json::json!({ "event": RESYNC_EVENT }).to_string()))
+        }
+        Ok(FeedMessage::Closed) | Err(RecvError::Closed) => None,
+    }
+}
+
+async fn send_to_socket(mut socket: WebSocket, mut receiver: Receiver<FeedMessage>) {
+    loop {
+        tokio::select! {
+            event = next_event(&mut receiver) => {
+                let Some((_, body)) = event else { break };
+                if socket.send(Message::Text(body.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Streams entry changes as Server-Sent Events, or over a WebSocket when the request asks for an upgrade.
+pub async fn entry_events(
+    State(feed): State<ChangeFeed>,
+    upgrade: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+    // Subscribing before the response is sent means no change made after the request is missed
+    let receiver = feed.subscribe();
+
+    match upgrade {
+        Ok(upgrade) => upgrade.on_upgrade(move |socket| send_to_socket(socket, receiver)),
+        Err(_) => {
+            let events = stream::unfold(receiver, |mut receiver| async move {
+                let (name, body) = next_event(&mut receiver).await?;
+                Some((Ok::<_, Infallible>(Event::default().event(name).data(body)), receiver))
+            });
+
+            Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+        }
+    }
+}
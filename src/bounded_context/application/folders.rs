@@ -0,0 +1,118 @@
+use axum::{Json, extract::State};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::domain::folder::{Folder, FolderError};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateFolderInput {
+    name: String,
+    parent_id: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct RenameFolderInput {
+    id: Uuid,
+    name: String,
+}
+
+#[derive(Deserialize)]
+pub struct MoveFolderInput {
+    id: Uuid,
+    parent_id: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteFolderInput {
+    id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct ResponseMessage {
+    message: String,
+}
+
+fn folder_error(err: Box<dyn Error>) -> (axum::http::StatusCode, String) {
+    match err.downcast_ref::<FolderError>() {
+        Some(FolderError::NotFound(_)) => (axum::http::StatusCode::NOT_FOUND, err.to_string()),
+        Some(_) => (axum::http::StatusCode::BAD_REQUEST, err.to_string()),
+        None => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+pub async fn create_folder(
+    State(database): State<Database>,
+    Json(payload): Json<CreateFolderInput>,
+) -> Result<Json<Folder>, (axum::http::StatusCode, String)> {
+    if payload.name.trim().is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid folder name.".to_string()));
+    }
+
+    let folder = Folder::new(Uuid::new_v4(), payload.name.trim().to_string(), payload.parent_id);
+
+    let mut db = database;
+
+    match db.create_folder(folder.clone()).await {
+        Ok(_) => Ok(Json(folder)),
+        Err(err) => Err(folder_error(err)),
+    }
+}
+
+pub async fn list_folders(
+    State(database): State<Database>,
+) -> Result<Json<Vec<Folder>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.list_folders().await {
+        Ok(folders) => Ok(Json(folders)),
+        Err(err) => Err(folder_error(err)),
+    }
+}
+
+pub async fn rename_folder(
+    State(database): State<Database>,
+    Json(payload): Json<RenameFolderInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    if payload.name.trim().is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid folder name.".to_string()));
+    }
+
+    let mut db = database;
+
+    match db.rename_folder(payload.id, payload.name.trim()).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Folder renamed successfully".to_string(),
+        })),
+        Err(err) => Err(folder_error(err)),
+    }
+}
+
+pub async fn move_folder(
+    State(database): State<Database>,
+    Json(payload): Json<MoveFolderInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.move_folder(payload.id, payload.parent_id).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Folder moved successfully".to_string(),
+        })),
+        Err(err) => Err(folder_error(err)),
+    }
+}
+
+pub async fn delete_folder(
+    State(database): State<Database>,
+    Json(payload): Json<DeleteFolderInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.delete_folder(payload.id).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Folder deleted successfully".to_string(),
+        })),
+        Err(err) => Err(folder_error(err)),
+    }
+}
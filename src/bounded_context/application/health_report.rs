@@ -0,0 +1,33 @@
+use axum::{Json, extract::State, extract::Query};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::domain::health_report::{HealthPolicy, HealthReport};
+use chrono::Utc;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct HealthReportInput {
+    stale_after_days: Option<i64>,
+    weak_below_strength: Option<i16>,
+}
+
+pub async fn health_report(
+    State(database): State<Database>,
+    Query(payload): Query<HealthReportInput>,
+) -> Result<Json<HealthReport>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    let policy = HealthPolicy {
+        stale_after_days: payload.stale_after_days.unwrap_or(db.config.stale_after_days),
+        weak_below_strength: payload.weak_below_strength.unwrap_or(db.config.weak_below_strength),
+    };
+
+    if policy.stale_after_days < 0 {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid stale threshold.".to_string()));
+    }
+
+    match db.list_all().await {
+        Ok(passwords) => Ok(Json(HealthReport::build(&passwords, &policy, Utc::now()))),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
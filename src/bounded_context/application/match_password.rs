@@ -0,0 +1,29 @@
+use axum::{Json, extract::State, extract::Query};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::domain::uri_match::{rank_uri_matches, UriMatch};
+use crate::bounded_context::utility::uri::normalize_uri;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct MatchPasswordInput {
+    url: String,
+}
+
+pub async fn match_password(
+    State(database): State<Database>,
+    Query(payload): Query<MatchPasswordInput>,
+) -> Result<Json<Vec<UriMatch>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    let target = match normalize_uri(&payload.url) {
+        Some(target) => target,
+        None => return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid URL.".to_string())),
+    };
+
+    // Regex and prefix URIs can only be evaluated here, so every entry is a candidate
+    match db.list_all().await {
+        Ok(passwords) => Ok(Json(rank_uri_matches(passwords, &target))),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
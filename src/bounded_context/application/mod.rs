@@ -2,4 +2,25 @@ pub mod create_password;
 pub mod get_password;
 pub mod delete_password;
 pub mod search_password;
-pub mod sort_password;
\ No newline at end of file
+pub mod sort_password;
+pub mod breach_range;
+pub mod health_report;
+pub mod entry_filter;
+pub mod folders;
+pub mod tag_password;
+pub mod move_password;
+pub mod use_password;
+pub mod favorite_password;
+pub mod query_passwords;
+pub mod pagination;
+pub mod match_password;
+pub mod attachments;
+pub mod shares;
+pub mod emergency_access;
+pub mod rotation_due;
+pub mod audit_log;
+pub mod webhooks;
+pub mod events;
+pub mod sync;
+pub mod batch_passwords;
+pub mod backup;
\ No newline at end of file
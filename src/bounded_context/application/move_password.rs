@@ -0,0 +1,37 @@
+use axum::{Json, extract::State};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::domain::folder::FolderError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct MovePasswordInput {
+    id: String,
+    folder_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+pub struct ResponseMessage {
+    message: String,
+}
+
+pub async fn move_password(
+    State(database): State<Database>,
+    Json(payload): Json<MovePasswordInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let id = match Uuid::parse_str(&payload.id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid password ID.".to_string())),
+    };
+
+    let mut db = database;
+
+    match db.set_folder(id, payload.folder_id).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Password moved successfully".to_string(),
+        })),
+        Err(err) if err.is::<FolderError>() => Err((axum::http::StatusCode::BAD_REQUEST, err.to_string())),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
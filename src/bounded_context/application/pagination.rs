@@ -0,0 +1,28 @@
+use crate::bounded_context::domain::pagination::{Cursor, PageRequest};
+use crate::bounded_context::domain::password_db::SortBy;
+
+/// Builds the page request of a listing route from its `page`, `page_size` and `cursor` inputs.
+/// A cursor selects keyset pagination, otherwise `page` (default 1) selects an offset page.
+pub fn page_request(
+    page: Option<u32>,
+    page_size: Option<u32>,
+    cursor: Option<&str>,
+    sort_by: SortBy,
+    pagination_max_size: u32,
+) -> Result<PageRequest, (axum::http::StatusCode, String)> {
+    let page_size = page_size.unwrap_or(pagination_max_size);
+
+    if page_size >= pagination_max_size {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Max Pagination Size Exceeded".to_string()))
+    }
+
+    match (page, cursor) {
+        (Some(_), Some(_)) => Err((axum::http::StatusCode::BAD_REQUEST, "Use either page or cursor, not both.".to_string())),
+        (_, Some(cursor)) => match Cursor::decode(cursor, sort_by) {
+            Ok(cursor) => Ok(PageRequest::after(Some(cursor), page_size)),
+            Err(err) => Err((axum::http::StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        (Some(0), None) => Err((axum::http::StatusCode::BAD_REQUEST, "Invalid page number.".to_string())),
+        (page, None) => Ok(PageRequest::offset(page.unwrap_or(1), page_size)),
+    }
+}
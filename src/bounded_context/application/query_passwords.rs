@@ -0,0 +1,46 @@
+use axum::{Json, extract::State};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::{PasswordDb, SortBy, EntryFilter};
+use crate::bounded_context::domain::pagination::PasswordPage;
+use crate::bounded_context::application::pagination::page_request;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+pub struct QueryPasswordsInput {
+    #[serde(default)]
+    filter: EntryFilter,
+    sort_by: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    cursor: Option<String>,
+}
+
+pub async fn query_passwords(
+    State(database): State<Database>,
+    Json(payload): Json<QueryPasswordsInput>,
+) -> Result<Json<PasswordPage>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    let sort_by = match payload.sort_by.as_deref().map(SortBy::from_str).transpose() {
+        Ok(sort_by) => sort_by.unwrap_or(SortBy::UpdatedAtDesc),
+        Err(err) => return Err((axum::http::StatusCode::BAD_REQUEST, err.to_string())),
+    };
+
+    let page = page_request(
+        payload.page,
+        payload.page_size,
+        payload.cursor.as_deref(),
+        sort_by,
+        db.config.pagination_max_size,
+    )?;
+
+    if !payload.filter.has_valid_ranges() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid date range.".to_string()));
+    }
+
+    match db.query(&payload.filter, &sort_by, &page).await {
+        Ok(passwords) => Ok(Json(passwords)),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
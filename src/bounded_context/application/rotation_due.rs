@@ -0,0 +1,28 @@
+use axum::{Json, extract::State, extract::Query};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::domain::rotation::{rotation_due, RotationDue};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct RotationDueInput {
+    within_days: Option<i64>,
+}
+
+pub async fn list_rotation_due(
+    State(database): State<Database>,
+    Query(payload): Query<RotationDueInput>,
+) -> Result<Json<Vec<RotationDue>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    let within_days = payload.within_days.unwrap_or(db.config.rotation_warning_days);
+    if !(0..=36500).contains(&within_days) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid rotation window.".to_string()));
+    }
+
+    match db.list_all().await {
+        Ok(passwords) => Ok(Json(rotation_due(passwords, Utc::now(), Duration::days(within_days)))),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
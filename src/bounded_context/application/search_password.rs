@@ -1,7 +1,10 @@
 use axum::{Json, extract::State, extract::Query};
 use crate::bounded_context::infrastructure::db::postgres_db::Database;
-use crate::bounded_context::domain::password_db::PasswordDb;
-use crate::bounded_context::domain::password::Password;
+use crate::bounded_context::domain::password_db::{PasswordDb, SortBy};
+use crate::bounded_context::domain::pagination::{Page, PasswordPage};
+use crate::bounded_context::domain::fuzzy_search::{FuzzyMatch, DEFAULT_SIMILARITY_THRESHOLD};
+use crate::bounded_context::application::entry_filter::EntryFilterInput;
+use crate::bounded_context::application::pagination::page_request;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -16,28 +19,71 @@ pub enum SearchPasswordError {
     DatabaseError(#[from] Box<dyn std::error::Error>),
 }
 
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Case-insensitive substring of the service, username or a URI
+    #[default]
+    Substring,
+    /// Trigram similarity to the service, tolerating typos
+    Fuzzy,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum SearchResults {
+    Substring(PasswordPage),
+    Fuzzy(Page<FuzzyMatch>),
+}
+
 #[derive(Deserialize)]
 pub struct SearchPasswordInput {
     search_term: String,
+    #[serde(default)]
+    mode: SearchMode,
+    threshold: Option<f32>,
     page: Option<u32>,
     page_size: Option<u32>,
+    cursor: Option<String>,
+    #[serde(flatten)]
+    filter: EntryFilterInput,
 }
 
 pub async fn search_password(
     State(database): State<Database>,
     Query(payload): Query<SearchPasswordInput>,
-) -> Result<Json<Vec<Password>>, (axum::http::StatusCode, String)> {
+) -> Result<Json<SearchResults>, (axum::http::StatusCode, String)> {
     let mut db = database;
 
-    let page = payload.page.unwrap_or(1);
-    let page_size = payload.page_size.unwrap_or(db.config.pagination_max_size);
+    // Search results are ordered by service name, fuzzy results by score
+    let page = page_request(
+        payload.page,
+        payload.page_size,
+        payload.cursor.as_deref(),
+        SortBy::ServiceAsc,
+        db.config.pagination_max_size,
+    )?;
+
+    let filter = payload.filter.parse()?;
+
+    if payload.mode == SearchMode::Substring {
+        return match db.search_by_service(&payload.search_term, &filter, &page).await {
+            Ok(passwords) => Ok(Json(SearchResults::Substring(passwords))),
+            Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+        };
+    }
+
+    let Some(page_number) = page.page() else {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Fuzzy search does not support cursors.".to_string()));
+    };
 
-    if page_size >= db.config.pagination_max_size {
-        return Err((axum::http::StatusCode::BAD_REQUEST, "Max Pagination Size Exceeded".to_string()))
+    let threshold = payload.threshold.unwrap_or(DEFAULT_SIMILARITY_THRESHOLD);
+    if !(threshold > 0.0 && threshold <= 1.0) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid similarity threshold.".to_string()));
     }
 
-    match db.search_by_service(&payload.search_term, page, page_size).await {
-        Ok(passwords) => Ok(Json(passwords)),
+    match db.fuzzy_search(&payload.search_term, threshold, &filter, page_number, page.page_size()).await {
+        Ok(matches) => Ok(Json(SearchResults::Fuzzy(matches))),
         Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
     }
 }
@@ -0,0 +1,137 @@
+use axum::{Json, extract::State};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password::EncryptedText;
+use crate::bounded_context::domain::share::{Share, ShareDb, ShareError, SharedSecret};
+use crate::bounded_context::utility::encryption::{is_valid_cipher, is_valid_nonce};
+use crate::bounded_context::utility::token::{generate_token, is_valid_token};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const DEFAULT_LIFETIME_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Deserialize)]
+pub struct CreateShareInput {
+    nonce: String,
+    cipher: String,
+    #[serde(default = "default_max_views")]
+    max_views: i32,
+    /// Seconds until the share expires
+    expires_in: Option<i64>,
+    password: Option<String>,
+}
+
+fn default_max_views() -> i32 {
+    1
+}
+
+#[derive(Deserialize)]
+pub struct ViewShareInput {
+    id: String,
+    password: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteShareInput {
+    id: String,
+}
+
+#[derive(Serialize)]
+pub struct CreatedShare {
+    id: String,
+    max_views: i32,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct ResponseMessage {
+    message: String,
+}
+
+fn share_error(err: Box<dyn Error>) -> (axum::http::StatusCode, String) {
+    match err.downcast_ref::<ShareError>() {
+        Some(ShareError::NotFound) => (axum::http::StatusCode::NOT_FOUND, err.to_string()),
+        Some(ShareError::PasswordRequired) | Some(ShareError::InvalidPassword) => {
+            (axum::http::StatusCode::UNAUTHORIZED, err.to_string())
+        }
+        None => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+pub async fn create_share(
+    State(database): State<Database>,
+    Json(payload): Json<CreateShareInput>,
+) -> Result<Json<CreatedShare>, (axum::http::StatusCode, String)> {
+    if !is_valid_nonce(&payload.nonce) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid nonce provided.".to_string()));
+    }
+
+    if !is_valid_cipher(&payload.cipher) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid cipher provided.".to_string()));
+    }
+
+    if payload.max_views < 1 {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid max views provided.".to_string()));
+    }
+
+    let expires_in = payload.expires_in.unwrap_or(DEFAULT_LIFETIME_SECONDS.min(database.config.share_max_lifetime));
+    if expires_in < 1 || expires_in > database.config.share_max_lifetime {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid expiry provided.".to_string()));
+    }
+
+    if payload.password.as_deref().is_some_and(|password| password.is_empty()) {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid access password provided.".to_string()));
+    }
+
+    let token = generate_token();
+    let expires_at = Utc::now() + Duration::seconds(expires_in);
+    let secret = EncryptedText { nonce: payload.nonce, cipher: payload.cipher };
+    let (share_token, max_views, password) = (token.clone(), payload.max_views, payload.password);
+
+    // Hashing the access password with Argon2 takes long enough to stall other requests on the
+    // async workers
+    let share = tokio::task::spawn_blocking(move || Share::new(&share_token, secret, max_views, expires_at, password.as_deref()))
+        .await
+        .map_err(|err| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let mut db = database;
+
+    match db.create_share(share).await {
+        Ok(_) => Ok(Json(CreatedShare { id: token, max_views, expires_at })),
+        Err(err) => Err(share_error(err)),
+    }
+}
+
+pub async fn view_share(
+    State(database): State<Database>,
+    Json(payload): Json<ViewShareInput>,
+) -> Result<Json<SharedSecret>, (axum::http::StatusCode, String)> {
+    if !is_valid_token(&payload.id) {
+        return Err(share_error(Box::new(ShareError::NotFound)));
+    }
+
+    let mut db = database;
+
+    match db.view_share(&payload.id, payload.password.as_deref(), Utc::now()).await {
+        Ok(secret) => Ok(Json(secret)),
+        Err(err) => Err(share_error(err)),
+    }
+}
+
+pub async fn delete_share(
+    State(database): State<Database>,
+    Json(payload): Json<DeleteShareInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    if !is_valid_token(&payload.id) {
+        return Err(share_error(Box::new(ShareError::NotFound)));
+    }
+
+    let mut db = database;
+
+    match db.delete_share(&payload.id).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Share deleted successfully".to_string(),
+        })),
+        Err(err) => Err(share_error(err)),
+    }
+}
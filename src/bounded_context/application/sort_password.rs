@@ -1,7 +1,9 @@
 use axum::{Json, extract::State, extract::Query};
 use crate::bounded_context::infrastructure::db::postgres_db::Database;
 use crate::bounded_context::domain::password_db::{PasswordDb, SortBy};
-use crate::bounded_context::domain::password::Password;
+use crate::bounded_context::domain::pagination::PasswordPage;
+use crate::bounded_context::application::entry_filter::EntryFilterInput;
+use crate::bounded_context::application::pagination::page_request;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use std::str::FromStr;
@@ -22,28 +24,34 @@ pub struct SortPasswordInput {
     sort_by: String,
     page: Option<u32>,
     page_size: Option<u32>,
+    cursor: Option<String>,
+    #[serde(flatten)]
+    filter: EntryFilterInput,
 }
 
 pub async fn sort_passwords(
     State(database): State<Database>,
     Query(payload): Query<SortPasswordInput>,
-) -> Result<Json<Vec<Password>>, (axum::http::StatusCode, String)> {
+) -> Result<Json<PasswordPage>, (axum::http::StatusCode, String)> {
     let mut db = database;
     let convert_sort_by = SortBy::from_str(&payload.sort_by);
 
-    let page = payload.page.unwrap_or(1);
-    let page_size = payload.page_size.unwrap_or(db.config.pagination_max_size);
-
-    if page_size >= db.config.pagination_max_size {
-        return Err((axum::http::StatusCode::BAD_REQUEST, "Max Pagination Size Exceeded".to_string()))
-    }
-
     let sort_by = match convert_sort_by {
         Ok(sort_by) => sort_by,
         Err(err) => return Err((axum::http::StatusCode::BAD_REQUEST, err.to_string())),
     };
 
-    match db.list_sorted(&sort_by, page, page_size).await {
+    let page = page_request(
+        payload.page,
+        payload.page_size,
+        payload.cursor.as_deref(),
+        sort_by,
+        db.config.pagination_max_size,
+    )?;
+
+    let filter = payload.filter.parse()?;
+
+    match db.list_sorted(&sort_by, &filter, &page).await {
         Ok(passwords) => Ok(Json(passwords)),
         Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
     }
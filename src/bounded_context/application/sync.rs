@@ -0,0 +1,70 @@
+use axum::{Json, extract::State, extract::Query};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::folder::FolderError;
+use crate::bounded_context::domain::sync::{PushResult, SyncChange, SyncChanges, SyncDb, SyncToken};
+use super::create_password::prepare_password;
+use chrono::{SubsecRound, Utc};
+use serde::Deserialize;
+
+/// Changes accepted in one push
+const MAX_PUSH_CHANGES: usize = 1000;
+
+#[derive(Deserialize)]
+pub struct SyncInput {
+    since: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PushInput {
+    changes: Vec<SyncChange>,
+}
+
+pub async fn sync_changes(
+    State(database): State<Database>,
+    Query(payload): Query<SyncInput>,
+) -> Result<Json<SyncChanges>, (axum::http::StatusCode, String)> {
+    let since = match payload.since.as_deref() {
+        Some(token) => match SyncToken::decode(token) {
+            Some(token) => Some(token),
+            None => return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid sync token.".to_string())),
+        },
+        None => None,
+    };
+
+    let mut db = database;
+
+    match db.changes_since(since).await {
+        Ok(changes) => Ok(Json(changes)),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
+
+pub async fn push_changes(
+    State(database): State<Database>,
+    Json(payload): Json<PushInput>,
+) -> Result<Json<PushResult>, (axum::http::StatusCode, String)> {
+    if payload.changes.len() > MAX_PUSH_CHANGES {
+        return Err((axum::http::StatusCode::BAD_REQUEST, format!("At most {} changes can be pushed at once.", MAX_PUSH_CHANGES)));
+    }
+
+    let mut changes = Vec::with_capacity(payload.changes.len());
+    for change in payload.changes {
+        changes.push(match change {
+            SyncChange::Upsert { entry, base_updated_at } => {
+                let id = entry.id;
+                let entry = prepare_password(*entry)
+                    .map_err(|message| (axum::http::StatusCode::BAD_REQUEST, format!("{} ({})", message, id)))?;
+                SyncChange::Upsert { entry: Box::new(entry), base_updated_at }
+            }
+            delete => delete,
+        });
+    }
+
+    let mut db = database;
+
+    match db.apply_changes(changes, Utc::now().trunc_subsecs(6)).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) if err.is::<FolderError>() => Err((axum::http::StatusCode::BAD_REQUEST, err.to_string())),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
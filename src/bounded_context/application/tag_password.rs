@@ -0,0 +1,71 @@
+use axum::{Json, extract::State};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::domain::folder::TagCount;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct TagPasswordInput {
+    id: String,
+    tag: String,
+}
+
+#[derive(Serialize)]
+pub struct ResponseMessage {
+    message: String,
+}
+
+fn parse_input(payload: &TagPasswordInput) -> Result<Uuid, (axum::http::StatusCode, String)> {
+    if payload.tag.trim().is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid tag provided.".to_string()));
+    }
+
+    match Uuid::parse_str(&payload.id) {
+        Ok(uuid) => Ok(uuid),
+        Err(_) => Err((axum::http::StatusCode::BAD_REQUEST, "Invalid password ID.".to_string())),
+    }
+}
+
+pub async fn tag_password(
+    State(database): State<Database>,
+    Json(payload): Json<TagPasswordInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let id = parse_input(&payload)?;
+
+    let mut db = database;
+
+    match db.tag(id, &payload.tag).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Password tagged successfully".to_string(),
+        })),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
+
+pub async fn untag_password(
+    State(database): State<Database>,
+    Json(payload): Json<TagPasswordInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let id = parse_input(&payload)?;
+
+    let mut db = database;
+
+    match db.untag(id, &payload.tag).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Password untagged successfully".to_string(),
+        })),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
+
+pub async fn list_tags(
+    State(database): State<Database>,
+) -> Result<Json<Vec<TagCount>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.list_tags().await {
+        Ok(tags) => Ok(Json(tags)),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
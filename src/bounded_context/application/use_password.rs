@@ -0,0 +1,43 @@
+use axum::{Json, extract::State};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Deserialize)]
+pub struct UsePasswordInput {
+    id: String,
+    used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct ResponseMessage {
+    message: String,
+}
+
+pub async fn use_password(
+    State(database): State<Database>,
+    Json(payload): Json<UsePasswordInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let id = match Uuid::parse_str(&payload.id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid password ID.".to_string())),
+    };
+
+    let now = Utc::now();
+    let used_at = payload.used_at.unwrap_or(now);
+
+    if used_at > now {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Use time cannot be in the future.".to_string()));
+    }
+
+    let mut db = database;
+
+    match db.record_use(id, used_at).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Password use recorded successfully".to_string(),
+        })),
+        Err(err) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
@@ -0,0 +1,149 @@
+use axum::{Json, extract::State, extract::Query};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::domain::pagination::Page;
+use crate::bounded_context::domain::webhook::{DeliveryStatus, Webhook, WebhookDb, WebhookDelivery, WebhookError, WebhookEvent};
+use crate::bounded_context::utility::token::generate_token;
+use crate::bounded_context::utility::uri::is_public_address;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::net::IpAddr;
+use url::{Host, Url};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateWebhookInput {
+    url: String,
+    events: Vec<WebhookEvent>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteWebhookInput {
+    id: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryHistoryInput {
+    webhook_id: Option<Uuid>,
+    status: Option<DeliveryStatus>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+}
+
+/// The secret is only ever returned here
+#[derive(Serialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    webhook: Webhook,
+    secret: String,
+}
+
+#[derive(Serialize)]
+pub struct ResponseMessage {
+    message: String,
+}
+
+fn webhook_error(err: Box<dyn Error>) -> (axum::http::StatusCode, String) {
+    match err.downcast_ref::<WebhookError>() {
+        Some(WebhookError::NotFound(_)) => (axum::http::StatusCode::NOT_FOUND, err.to_string()),
+        None => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+/// Webhooks are sent from the server, so they may only point at hosts on the public internet.
+/// The dispatcher checks the addresses again when sending, see `webhook_client`.
+async fn is_valid_webhook_url(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+
+    let port = url.port_or_known_default().unwrap_or(80);
+    match url.host() {
+        Some(Host::Ipv4(ip)) => is_public_address(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_address(IpAddr::V6(ip)),
+        Some(Host::Domain(domain)) => match tokio::net::lookup_host((domain, port)).await {
+            Ok(addrs) => {
+                let addrs: Vec<_> = addrs.collect();
+                !addrs.is_empty() && addrs.iter().all(|addr| is_public_address(addr.ip()))
+            }
+            Err(_) => false,
+        },
+        None => false,
+    }
+}
+
+pub async fn create_webhook(
+    State(database): State<Database>,
+    Json(payload): Json<CreateWebhookInput>,
+) -> Result<Json<CreatedWebhook>, (axum::http::StatusCode, String)> {
+    if !is_valid_webhook_url(&payload.url).await {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid webhook URL provided.".to_string()));
+    }
+
+    if payload.events.is_empty() {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "At least one event is required.".to_string()));
+    }
+
+    let mut db = database;
+    let secret = generate_token();
+    let webhook = Webhook::new(payload.url, secret.clone(), &payload.events);
+
+    match db.create_webhook(&webhook).await {
+        Ok(_) => Ok(Json(CreatedWebhook { webhook, secret })),
+        Err(err) => Err(webhook_error(err)),
+    }
+}
+
+pub async fn list_webhooks(
+    State(database): State<Database>,
+) -> Result<Json<Vec<Webhook>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    match db.list_webhooks().await {
+        Ok(webhooks) => Ok(Json(webhooks)),
+        Err(err) => Err(webhook_error(err)),
+    }
+}
+
+pub async fn delete_webhook(
+    State(database): State<Database>,
+    Json(payload): Json<DeleteWebhookInput>,
+) -> Result<Json<ResponseMessage>, (axum::http::StatusCode, String)> {
+    let id = match Uuid::parse_str(&payload.id) {
+        Ok(uuid) => uuid,
+        Err(_) => return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid webhook ID.".to_string())),
+    };
+
+    let mut db = database;
+
+    match db.delete_webhook(id).await {
+        Ok(_) => Ok(Json(ResponseMessage {
+            message: "Webhook deleted successfully".to_string(),
+        })),
+        Err(err) => Err(webhook_error(err)),
+    }
+}
+
+pub async fn list_webhook_deliveries(
+    State(database): State<Database>,
+    Query(payload): Query<DeliveryHistoryInput>,
+) -> Result<Json<Page<WebhookDelivery>>, (axum::http::StatusCode, String)> {
+    let mut db = database;
+
+    let page = payload.page.unwrap_or(1);
+    if page == 0 {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Invalid page number.".to_string()));
+    }
+
+    let page_size = payload.page_size.unwrap_or(db.config.pagination_default_size);
+    if page_size >= db.config.pagination_max_size {
+        return Err((axum::http::StatusCode::BAD_REQUEST, "Max Pagination Size Exceeded".to_string()));
+    }
+
+    match db.list_webhook_deliveries(payload.webhook_id, payload.status, page, page_size).await {
+        Ok(deliveries) => Ok(Json(deliveries)),
+        Err(err) => Err(webhook_error(err)),
+    }
+}
@@ -0,0 +1,61 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::{Serialize, Deserialize};
+use sqlx::FromRow;
+use async_trait::async_trait;
+use std::error::Error;
+use std::pin::Pin;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Contents of an attachment, read or written in chunks so large files never sit in memory whole
+pub type BlobStream = Pin<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>>;
+
+#[derive(Debug, Error)]
+pub enum AttachmentError {
+    #[error("Attachment not found: {0}")]
+    NotFound(Uuid),
+    #[error("Password not found: {0}")]
+    PasswordNotFound(Uuid),
+    #[error("Attachment exceeds the size limit of {0} bytes")]
+    TooLarge(u64),
+    #[error("Attachments of an entry may not exceed {0} bytes in total")]
+    QuotaExceeded(u64),
+    #[error("Attachment directory is not configured")]
+    StorageNotConfigured,
+}
+
+/// Metadata of a file attached to an entry. The file itself is encrypted by the client and
+/// stored as an opaque blob.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub password_id: Uuid,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    pub fn new(id: Uuid, password_id: Uuid, file_name: String, content_type: Option<String>) -> Attachment {
+        Attachment {
+            id,
+            password_id,
+            file_name,
+            content_type,
+            size: 0,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait AttachmentDb {
+    /// Stores the blob read from `body` and returns the attachment with its final size.
+    async fn save_attachment(&mut self, attachment: Attachment, body: BlobStream) -> Result<Attachment, Box<dyn Error>>;
+    async fn list_attachments(&mut self, password_id: Uuid) -> Result<Vec<Attachment>, Box<dyn Error>>;
+    async fn open_attachment(&mut self, id: Uuid) -> Result<(Attachment, BlobStream), Box<dyn Error>>;
+    async fn delete_attachment(&mut self, id: Uuid) -> Result<(), Box<dyn Error>>;
+}
@@ -0,0 +1,151 @@
+use super::pagination::Page;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use std::error::Error;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// `prev_hash` of the first event in the chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+impl AuditOutcome {
+    pub fn from_status(status: u16) -> AuditOutcome {
+        if status < 400 {
+            AuditOutcome::Success
+        } else {
+            AuditOutcome::Failure
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+        }
+    }
+}
+
+/// A request as seen by the audit middleware, before it is chained
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NewAuditEvent {
+    pub occurred_at: DateTime<Utc>,
+    pub request_id: String,
+    pub actor: String,
+    /// Method and path of the request, e.g. `GET /password/`
+    pub action: String,
+    pub entry_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub status: i32,
+    pub outcome: String,
+}
+
+impl NewAuditEvent {
+    /// Hash of the event chained to the hash of the event before it. Timestamps are hashed at
+    /// microsecond precision, which is what Postgres keeps.
+    pub fn chain_hash(&self, prev_hash: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(prev_hash.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.chain_fields().as_bytes());
+
+        hex::encode(hasher.finalize())
+    }
+
+    /// The fields covered by the hash, as hashed after the previous hash and a newline
+    pub fn chain_fields(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize audit event")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct AuditEvent {
+    pub seq: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub request_id: String,
+    pub actor: String,
+    pub action: String,
+    pub entry_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub status: i32,
+    pub outcome: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEvent {
+    pub fn fields(&self) -> NewAuditEvent {
+        NewAuditEvent {
+            occurred_at: self.occurred_at,
+            request_id: self.request_id.clone(),
+            actor: self.actor.clone(),
+            action: self.action.clone(),
+            entry_id: self.entry_id,
+            ip: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+            status: self.status,
+            outcome: self.outcome.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AuditChainError {
+    #[error("Audit event {seq} does not follow the event before it")]
+    BrokenLink { seq: i64 },
+    #[error("Audit event {seq} was modified")]
+    HashMismatch { seq: i64 },
+}
+
+/// Checks that `events`, ordered by `seq`, form an unbroken chain starting at the genesis hash.
+/// An edited event no longer matches its hash, and a deleted or reordered event breaks the link
+/// of the event after it.
+pub fn verify_chain(events: &[AuditEvent]) -> Result<(), AuditChainError> {
+    let mut prev_hash = GENESIS_HASH;
+
+    for event in events {
+        if event.prev_hash != prev_hash {
+            return Err(AuditChainError::BrokenLink { seq: event.seq });
+        }
+        if event.fields().chain_hash(&event.prev_hash) != event.hash {
+            return Err(AuditChainError::HashMismatch { seq: event.seq });
+        }
+        prev_hash = &event.hash;
+    }
+
+    Ok(())
+}
+
+/// Conditions for querying the audit log. `after` is inclusive and `before` exclusive.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub entry_id: Option<Uuid>,
+    pub outcome: Option<AuditOutcome>,
+    pub request_id: Option<String>,
+    pub after: Option<DateTime<Utc>>,
+    pub before: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait AuditDb {
+    /// Appends the event to the end of the chain and returns it as stored.
+    async fn append_audit_event(&mut self, event: NewAuditEvent) -> Result<AuditEvent, Box<dyn Error>>;
+    /// Newest events first
+    async fn query_audit_events(&mut self, filter: &AuditFilter, page: u32, page_size: u32) -> Result<Page<AuditEvent>, Box<dyn Error>>;
+    /// Every event in chain order, for verification
+    async fn list_audit_chain(&mut self) -> Result<Vec<AuditEvent>, Box<dyn Error>>;
+}
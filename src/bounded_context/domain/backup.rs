@@ -0,0 +1,104 @@
+use super::folder::Folder;
+use super::password::Password;
+use crate::bounded_context::utility::backup::{BACKUP_FORMAT, BACKUP_VERSION, BackupEnvelope, open_backup, seal_backup};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Shortest passphrase a backup may be sealed with
+pub const MIN_PASSPHRASE_LENGTH: usize = 12;
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Invalid backup file: {0}")]
+    InvalidFile(String),
+    #[error("Unsupported backup version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("Backup integrity check failed: wrong passphrase or modified file")]
+    IntegrityCheckFailed,
+    #[error("Entry already exists: {0}")]
+    EntryExists(Uuid),
+    #[error("Folder already exists: {0}")]
+    FolderExists(Uuid),
+}
+
+/// How a backup is restored into a vault that may already hold some of its entries
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// Restores every folder and entry under its id and fails if any of them already exists
+    PreserveIds,
+    /// Adds the folders and entries that are missing and replaces entries the backup holds a
+    /// newer copy of, by `updated_at`
+    #[default]
+    Merge,
+}
+
+/// Everything needed to rebuild a vault. Entries stay encrypted with the keys of their owners.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VaultBackup {
+    pub exported_at: DateTime<Utc>,
+    pub folders: Vec<Folder>,
+    pub entries: Vec<Password>,
+}
+
+impl VaultBackup {
+    /// Encrypts the backup with a key derived from `passphrase`, see `utility::backup`
+    pub fn seal(&self, passphrase: &str) -> BackupEnvelope {
+        let plaintext = serde_json::to_vec(self).expect("Failed to serialize backup");
+        seal_backup(passphrase, &plaintext)
+    }
+
+    pub fn open(envelope: &BackupEnvelope, passphrase: &str) -> Result<VaultBackup, BackupError> {
+        if envelope.format != BACKUP_FORMAT {
+            return Err(BackupError::InvalidFile(format!("Unknown format {}", envelope.format)));
+        }
+        if envelope.version != BACKUP_VERSION {
+            return Err(BackupError::UnsupportedVersion(envelope.version));
+        }
+
+        let plaintext = open_backup(passphrase, envelope).ok_or(BackupError::IntegrityCheckFailed)?;
+        serde_json::from_slice(&plaintext).map_err(|err| BackupError::InvalidFile(err.to_string()))
+    }
+
+    /// The folders ordered so that every parent comes before its subfolders
+    pub fn folders_parents_first(&self) -> Vec<&Folder> {
+        let ids: HashSet<Uuid> = self.folders.iter().map(|folder| folder.id).collect();
+        let mut children: HashMap<Option<Uuid>, Vec<&Folder>> = HashMap::new();
+        for folder in &self.folders {
+            // Folders whose parent is not in the backup are restored below their parent in the vault
+            let parent = folder.parent_id.filter(|parent_id| ids.contains(parent_id));
+            children.entry(parent).or_default().push(folder);
+        }
+
+        let mut ordered = Vec::with_capacity(self.folders.len());
+        let mut pending = children.remove(&None).unwrap_or_default();
+        while let Some(folder) = pending.pop() {
+            ordered.push(folder);
+            pending.extend(children.remove(&Some(folder.id)).unwrap_or_default());
+        }
+
+        ordered
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub folders_created: usize,
+    pub entries_created: usize,
+    pub entries_updated: usize,
+    /// Entries left as they were because the vault holds a copy at least as new
+    pub entries_unchanged: usize,
+}
+
+#[async_trait]
+pub trait BackupDb {
+    /// Reads every folder and entry as of a single point in time
+    async fn export_vault(&mut self, now: DateTime<Utc>) -> Result<VaultBackup, Box<dyn Error>>;
+    /// Restores a backup in a single transaction
+    async fn restore_vault(&mut self, backup: &VaultBackup, mode: RestoreMode) -> Result<RestoreReport, Box<dyn Error>>;
+}
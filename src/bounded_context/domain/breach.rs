@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BreachedHash {
+    pub suffix: String,
+    pub count: u64,
+}
+
+impl BreachedHash {
+    pub fn new(suffix: String, count: u64) -> BreachedHash {
+        BreachedHash { suffix, count }
+    }
+}
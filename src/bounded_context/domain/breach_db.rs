@@ -0,0 +1,7 @@
+use super::breach::BreachedHash;
+use std::error::Error;
+
+pub trait BreachDb {
+    /// Returns every breached hash whose SHA-1 starts with the given 5 character hex prefix.
+    fn range(&self, prefix: &str) -> Result<Vec<BreachedHash>, Box<dyn Error>>;
+}
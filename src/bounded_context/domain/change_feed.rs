@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Updated => "updated",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+}
+
+/// A change to an entry as pushed to clients. Only the id is sent; clients fetch the entry itself.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntryChange {
+    pub event: ChangeKind,
+    pub password_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// What subscribers of the change feed receive
+#[derive(Clone, Debug, PartialEq)]
+pub enum FeedMessage {
+    Change(EntryChange),
+    /// Changes may have been missed, for example while the database connection was down, so
+    /// clients should reload what they have
+    Resync,
+    /// The server is shutting down and ends every stream, so clients should reconnect
+    Closed,
+}
@@ -0,0 +1,247 @@
+use crate::bounded_context::utility::token::hash_token;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::FromRow;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum EmergencyAccessError {
+    #[error("Emergency access not found")]
+    NotFound,
+    #[error("Cannot {action} emergency access that is {status}")]
+    InvalidTransition {
+        status: EmergencyAccessStatus,
+        action: EmergencyAccessAction,
+    },
+    #[error("Emergency access has not been granted")]
+    NotGranted,
+    #[error("Invalid emergency access status: {0}")]
+    InvalidStatus(String),
+    #[error("Invalid emergency access action: {0}")]
+    InvalidAction(String),
+}
+
+/// Lifecycle of a grant. A contact is invited, accepts, and may then request access, which the
+/// owner can approve or reject. A request that is neither approved nor rejected within the
+/// waiting period is granted automatically. A revoked grant stays revoked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmergencyAccessStatus {
+    Invited,
+    Accepted,
+    Requested,
+    Granted,
+    Revoked,
+}
+
+impl EmergencyAccessStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmergencyAccessStatus::Invited => "invited",
+            EmergencyAccessStatus::Accepted => "accepted",
+            EmergencyAccessStatus::Requested => "requested",
+            EmergencyAccessStatus::Granted => "granted",
+            EmergencyAccessStatus::Revoked => "revoked",
+        }
+    }
+}
+
+impl fmt::Display for EmergencyAccessStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EmergencyAccessStatus {
+    type Err = EmergencyAccessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "invited" => Ok(EmergencyAccessStatus::Invited),
+            "accepted" => Ok(EmergencyAccessStatus::Accepted),
+            "requested" => Ok(EmergencyAccessStatus::Requested),
+            "granted" => Ok(EmergencyAccessStatus::Granted),
+            "revoked" => Ok(EmergencyAccessStatus::Revoked),
+            _ => Err(EmergencyAccessError::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmergencyAccessAction {
+    Invite,
+    Accept,
+    Request,
+    Approve,
+    /// Approval recorded when the waiting period of a request runs out
+    AutoApprove,
+    Reject,
+    Revoke,
+}
+
+impl EmergencyAccessAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmergencyAccessAction::Invite => "invite",
+            EmergencyAccessAction::Accept => "accept",
+            EmergencyAccessAction::Request => "request",
+            EmergencyAccessAction::Approve => "approve",
+            EmergencyAccessAction::AutoApprove => "auto_approve",
+            EmergencyAccessAction::Reject => "reject",
+            EmergencyAccessAction::Revoke => "revoke",
+        }
+    }
+
+    /// Returns the status reached by taking this action in `status`, if the action is allowed there.
+    pub fn apply(&self, status: EmergencyAccessStatus) -> Option<EmergencyAccessStatus> {
+        use EmergencyAccessStatus::*;
+
+        match (self, status) {
+            (EmergencyAccessAction::Accept, Invited) => Some(Accepted),
+            (EmergencyAccessAction::Request, Accepted) => Some(Requested),
+            (EmergencyAccessAction::Approve, Requested) => Some(Granted),
+            (EmergencyAccessAction::AutoApprove, Requested) => Some(Granted),
+            (EmergencyAccessAction::Reject, Requested) => Some(Accepted),
+            (EmergencyAccessAction::Revoke, Invited | Accepted | Requested | Granted) => Some(Revoked),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EmergencyAccessAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EmergencyAccessAction {
+    type Err = EmergencyAccessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "invite" => Ok(EmergencyAccessAction::Invite),
+            "accept" => Ok(EmergencyAccessAction::Accept),
+            "request" => Ok(EmergencyAccessAction::Request),
+            "approve" => Ok(EmergencyAccessAction::Approve),
+            "auto_approve" => Ok(EmergencyAccessAction::AutoApprove),
+            "reject" => Ok(EmergencyAccessAction::Reject),
+            "revoke" => Ok(EmergencyAccessAction::Revoke),
+            _ => Err(EmergencyAccessError::InvalidAction(s.to_string())),
+        }
+    }
+}
+
+/// A trusted contact who may be given read access to the vault. The contact proves who they
+/// are with the token handed out on invite, of which only the hash is kept.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EmergencyAccess {
+    pub id: Uuid,
+    pub contact: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub status: EmergencyAccessStatus,
+    pub wait_hours: i32,
+    pub requested_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EmergencyAccess {
+    pub fn new(id: Uuid, contact: String, token: &str, wait_hours: i32) -> EmergencyAccess {
+        let now = Utc::now();
+        EmergencyAccess {
+            id,
+            contact,
+            token_hash: hash_token(token),
+            status: EmergencyAccessStatus::Invited,
+            wait_hours,
+            requested_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// When a pending request is granted without an answer from the owner
+    pub fn grants_at(&self) -> Option<DateTime<Utc>> {
+        match self.status {
+            EmergencyAccessStatus::Requested => self.requested_at.map(|at| at + Duration::hours(self.wait_hours as i64)),
+            _ => None,
+        }
+    }
+
+    pub fn wait_elapsed(&self, now: DateTime<Utc>) -> bool {
+        self.grants_at().is_some_and(|at| at <= now)
+    }
+}
+
+impl<'r> FromRow<'r, sqlx::postgres::PgRow> for EmergencyAccess {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let status: String = row.try_get("status")?;
+
+        Ok(EmergencyAccess {
+            id: row.try_get("id")?,
+            contact: row.try_get("contact")?,
+            token_hash: row.try_get("token_hash")?,
+            status: status.parse().map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+            wait_hours: row.try_get("wait_hours")?,
+            requested_at: row.try_get("requested_at")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// One entry of the audit trail kept for every transition of a grant
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EmergencyAccessEvent {
+    pub id: i64,
+    pub emergency_access_id: Uuid,
+    pub action: EmergencyAccessAction,
+    pub from_status: Option<EmergencyAccessStatus>,
+    pub to_status: EmergencyAccessStatus,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl<'r> FromRow<'r, sqlx::postgres::PgRow> for EmergencyAccessEvent {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let action: String = row.try_get("action")?;
+        let from_status: Option<String> = row.try_get("from_status")?;
+        let to_status: String = row.try_get("to_status")?;
+
+        Ok(EmergencyAccessEvent {
+            id: row.try_get("id")?,
+            emergency_access_id: row.try_get("emergency_access_id")?,
+            action: action.parse().map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+            from_status: from_status
+                .map(|status| status.parse())
+                .transpose()
+                .map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+            to_status: to_status.parse().map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+            occurred_at: row.try_get("occurred_at")?,
+        })
+    }
+}
+
+#[async_trait]
+pub trait EmergencyAccessDb {
+    async fn create_emergency_access(&mut self, access: EmergencyAccess) -> Result<(), Box<dyn Error>>;
+    /// Every method first grants the requests whose waiting period ran out before `now`.
+    async fn list_emergency_access(&mut self, now: DateTime<Utc>) -> Result<Vec<EmergencyAccess>, Box<dyn Error>>;
+    /// Takes an owner action on the grant with the given id.
+    async fn transition_emergency_access(&mut self, id: Uuid, action: EmergencyAccessAction, now: DateTime<Utc>) -> Result<EmergencyAccess, Box<dyn Error>>;
+    /// Takes a contact action on the grant the token was issued for.
+    async fn transition_emergency_access_by_token(&mut self, token: &str, action: EmergencyAccessAction, now: DateTime<Utc>) -> Result<EmergencyAccess, Box<dyn Error>>;
+    /// Returns the grant of the token if it currently allows reading the vault.
+    async fn authorize_emergency_access(&mut self, token: &str, now: DateTime<Utc>) -> Result<EmergencyAccess, Box<dyn Error>>;
+    async fn list_emergency_access_events(&mut self, id: Uuid) -> Result<Vec<EmergencyAccessEvent>, Box<dyn Error>>;
+}
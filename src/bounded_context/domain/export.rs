@@ -0,0 +1,275 @@
+use super::folder::Folder;
+use super::item::ItemData;
+use super::password::{CustomField, EncryptedText, Password};
+use crate::bounded_context::utility::encryption::try_decrypt;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Invalid export format: {0}")]
+    InvalidFormat(String),
+    #[error("Folder not found: {0}")]
+    FolderNotFound(String),
+    #[error("Failed to decrypt entry {0}: wrong key or corrupted entry")]
+    DecryptionFailed(Uuid),
+}
+
+/// Plaintext formats the vault can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Unencrypted JSON export as written by Bitwarden
+    BitwardenJson,
+    /// CSV as written by KeePassXC, which KeePass reads with its generic CSV importer
+    KeepassCsv,
+    /// One `NAME=secret` line per entry
+    Dotenv,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::BitwardenJson => "bitwarden_json",
+            ExportFormat::KeepassCsv => "keepass_csv",
+            ExportFormat::Dotenv => "dotenv",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized_str = s.to_lowercase().replace(['_', '-', '.'], "");
+
+        match normalized_str.as_str() {
+            "bitwarden" | "bitwardenjson" => Ok(ExportFormat::BitwardenJson),
+            "keepass" | "keepasscsv" => Ok(ExportFormat::KeepassCsv),
+            "env" | "dotenv" => Ok(ExportFormat::Dotenv),
+            _ => Err(ExportError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+/// A custom field after decryption
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportedField {
+    Text { name: String, value: String },
+    Hidden { name: String, value: String },
+    Boolean { name: String, value: bool },
+}
+
+/// Type specific fields after decryption. The primary secret of each type, see `ItemData`, is
+/// kept in `ExportedEntry::secret`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ExportedItem {
+    #[default]
+    Login,
+    SecureNote,
+    Card {
+        cardholder_name: Option<String>,
+        expiry: Option<String>,
+        security_code: Option<String>,
+        brand: Option<String>,
+    },
+    Identity {
+        full_name: Option<String>,
+        email: Option<String>,
+        phone: Option<String>,
+        address: Option<String>,
+        birth_date: Option<String>,
+    },
+    SshKey {
+        public_key: String,
+        key_fingerprint: Option<String>,
+        passphrase: Option<String>,
+    },
+    ApiKey {
+        key_id: Option<String>,
+        secret: Option<String>,
+        expires_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// An entry decrypted for a plaintext export
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportedEntry {
+    pub id: Uuid,
+    pub service: String,
+    pub username: Option<String>,
+    pub secret: String,
+    pub uris: Vec<String>,
+    pub notes: Option<String>,
+    pub totp: Option<String>,
+    pub fields: Vec<ExportedField>,
+    pub item: ExportedItem,
+    /// Names of the folders leading to the entry, outermost first
+    pub folder: Vec<String>,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExportedEntry {
+    /// Decrypts every secret of `password` with `master_key`. Fails if any of them cannot be
+    /// decrypted, so that an export never silently leaves secrets out.
+    pub fn decrypt(password: &Password, master_key: &str, folder: Vec<String>) -> Result<ExportedEntry, ExportError> {
+        let open = |nonce: &str, cipher: &str| try_decrypt(master_key, nonce, cipher).ok_or(ExportError::DecryptionFailed(password.id));
+        let open_some = |text: &Option<EncryptedText>| text.as_ref().map(|text| open(&text.nonce, &text.cipher)).transpose();
+
+        let item = match &password.item {
+            ItemData::Login => ExportedItem::Login,
+            ItemData::SecureNote => ExportedItem::SecureNote,
+            ItemData::Card { cardholder_name, expiry, security_code, brand, .. } => ExportedItem::Card {
+                cardholder_name: open_some(cardholder_name)?,
+                expiry: open_some(expiry)?,
+                security_code: open_some(security_code)?,
+                brand: brand.clone(),
+            },
+            ItemData::Identity { full_name, email, phone, address, birth_date } => ExportedItem::Identity {
+                full_name: open_some(full_name)?,
+                email: open_some(email)?,
+                phone: open_some(phone)?,
+                address: open_some(address)?,
+                birth_date: open_some(birth_date)?,
+            },
+            ItemData::SshKey { public_key, key_fingerprint, passphrase } => ExportedItem::SshKey {
+                public_key: public_key.clone(),
+                key_fingerprint: key_fingerprint.clone(),
+                passphrase: open_some(passphrase)?,
+            },
+            ItemData::ApiKey { key_id, secret, expires_at } => ExportedItem::ApiKey {
+                key_id: key_id.clone(),
+                secret: open_some(secret)?,
+                expires_at: *expires_at,
+            },
+        };
+
+        let fields = password
+            .custom_fields
+            .iter()
+            .map(|field| match field {
+                CustomField::Text { name, value } => Ok(ExportedField::Text { name: name.clone(), value: value.clone() }),
+                CustomField::Hidden { name, nonce, cipher } => Ok(ExportedField::Hidden { name: name.clone(), value: open(nonce, cipher)? }),
+                CustomField::Boolean { name, value } => Ok(ExportedField::Boolean { name: name.clone(), value: *value }),
+            })
+            .collect::<Result<_, ExportError>>()?;
+
+        Ok(ExportedEntry {
+            id: password.id,
+            service: password.service.clone(),
+            username: password.username.clone(),
+            secret: open(&password.nonce, &password.cipher)?,
+            uris: password.uris.iter().map(|uri| uri.uri.clone()).collect(),
+            notes: open_some(&password.notes)?,
+            totp: open_some(&password.totp)?,
+            fields,
+            item,
+            folder,
+            tags: password.tags.clone(),
+            favorite: password.favorite,
+            created_at: password.created_at,
+            updated_at: password.updated_at,
+        })
+    }
+}
+
+/// Which entries go into an export. Without a folder or tag, every entry does.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExportSelection {
+    /// The selected folder and its subfolders
+    pub folders: Option<HashSet<Uuid>>,
+    pub tag: Option<String>,
+}
+
+impl ExportSelection {
+    pub fn includes(&self, password: &Password) -> bool {
+        let in_folder = match &self.folders {
+            Some(folders) => password.folder_id.is_some_and(|folder_id| folders.contains(&folder_id)),
+            None => true,
+        };
+        let tagged = match &self.tag {
+            Some(tag) => password.tags.iter().any(|entry_tag| entry_tag == tag),
+            None => true,
+        };
+
+        in_folder && tagged
+    }
+}
+
+/// Resolves folder ids to the names of the folders leading to them, outermost first
+pub struct FolderPaths {
+    folders: HashMap<Uuid, (String, Option<Uuid>)>,
+}
+
+impl FolderPaths {
+    pub fn new(folders: &[Folder]) -> FolderPaths {
+        FolderPaths { folders: folders.iter().map(|folder| (folder.id, (folder.name.clone(), folder.parent_id))).collect() }
+    }
+
+    pub fn path(&self, folder_id: Option<Uuid>) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut current = folder_id;
+        // Bounded by the number of folders in case the tree holds a cycle
+        while let Some((name, parent_id)) = current.and_then(|id| self.folders.get(&id)) {
+            if path.len() == self.folders.len() {
+                break;
+            }
+            path.push(name.clone());
+            current = *parent_id;
+        }
+        path.reverse();
+
+        path
+    }
+
+    /// Finds the folder at `path`, comparing names case-insensitively
+    pub fn find(&self, path: &[String]) -> Option<Uuid> {
+        if path.is_empty() {
+            return None;
+        }
+        self.folders
+            .keys()
+            .copied()
+            .find(|id| {
+                let names = self.path(Some(*id));
+                names.len() == path.len() && names.iter().zip(path).all(|(name, wanted)| name.to_lowercase() == wanted.to_lowercase())
+            })
+    }
+
+    /// The ids of `folder_id` and every folder below it
+    pub fn subtree(&self, folder_id: Uuid) -> HashSet<Uuid> {
+        self.folders
+            .keys()
+            .copied()
+            .filter(|id| {
+                let mut current = Some(*id);
+                let mut depth = 0;
+                while let Some(id) = current {
+                    if id == folder_id {
+                        return true;
+                    }
+                    if depth == self.folders.len() {
+                        return false;
+                    }
+                    current = self.folders.get(&id).and_then(|(_, parent_id)| *parent_id);
+                    depth += 1;
+                }
+                false
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum FolderError {
+    #[error("Folder not found: {0}")]
+    NotFound(Uuid),
+    #[error("Parent folder not found: {0}")]
+    ParentNotFound(Uuid),
+    #[error("Folder cannot be moved into itself or one of its subfolders")]
+    Cycle,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Folder {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Folder {
+    pub fn new(id: Uuid, name: String, parent_id: Option<Uuid>) -> Folder {
+        let now = Utc::now();
+        Folder {
+            id,
+            name,
+            parent_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct TagCount {
+    pub name: String,
+    pub count: i64,
+}
+
+/// Tags are matched case-insensitively, so they are stored trimmed and lowercased.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
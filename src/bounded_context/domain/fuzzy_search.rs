@@ -0,0 +1,47 @@
+use super::password::Password;
+use crate::bounded_context::utility::trigram::similarity;
+use serde::Serialize;
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+
+/// Default minimum similarity, the same as the `pg_trgm.similarity_threshold` default
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.3;
+
+/// An entry found by fuzzy search, with the trigram similarity of its service to the search term
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FuzzyMatch {
+    #[serde(flatten)]
+    pub password: Password,
+    pub score: f32,
+}
+
+impl<'r> FromRow<'r, PgRow> for FuzzyMatch {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(FuzzyMatch {
+            password: Password::from_row(row)?,
+            score: row.try_get("score")?,
+        })
+    }
+}
+
+/// Scores and orders entries the way the Postgres fuzzy search does, for backends without `pg_trgm`.
+/// Entries scoring below `threshold` are dropped; ties are ordered by service name, then id.
+pub fn rank_by_similarity(passwords: impl IntoIterator<Item = Password>, search_term: &str, threshold: f32) -> Vec<FuzzyMatch> {
+    let mut matches: Vec<FuzzyMatch> = passwords
+        .into_iter()
+        .map(|password| {
+            let score = similarity(&password.service, search_term);
+            FuzzyMatch { password, score }
+        })
+        .filter(|found| found.score >= threshold)
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.password.service.to_lowercase().cmp(&b.password.service.to_lowercase()))
+            .then_with(|| a.password.id.cmp(&b.password.id))
+    });
+
+    matches
+}
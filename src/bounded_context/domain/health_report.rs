@@ -0,0 +1,117 @@
+use super::password::Password;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Upper bounds (exclusive, in days) of the age buckets; the last bucket is open ended.
+const AGE_BUCKET_BOUNDS: [i64; 4] = [30, 90, 180, 365];
+
+#[derive(Clone, Debug)]
+pub struct HealthPolicy {
+    /// Entries not updated for this many days are reported as stale
+    pub stale_after_days: i64,
+    /// Entries with a strength score below this are reported as weak
+    pub weak_below_strength: i16,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EntrySummary {
+    pub id: Uuid,
+    pub service: String,
+    pub updated_at: DateTime<Utc>,
+    pub age_days: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReusedGroup {
+    pub fingerprint: String,
+    pub entries: Vec<EntrySummary>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AgeBucket {
+    pub min_days: i64,
+    pub max_days: Option<i64>,
+    pub count: usize,
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthReport {
+    pub generated_at: DateTime<Utc>,
+    pub total_entries: usize,
+    pub stale_after_days: i64,
+    pub weak_below_strength: i16,
+    pub reused: Vec<ReusedGroup>,
+    pub stale: Vec<EntrySummary>,
+    pub weak: Vec<EntrySummary>,
+    pub unscored: usize,
+    pub age_buckets: Vec<AgeBucket>,
+}
+
+impl HealthReport {
+    pub fn build(passwords: &[Password], policy: &HealthPolicy, now: DateTime<Utc>) -> HealthReport {
+        let summarize = |password: &Password| EntrySummary {
+            id: password.id,
+            service: password.service.clone(),
+            updated_at: password.updated_at,
+            age_days: (now - password.updated_at).num_days().max(0),
+        };
+
+        let mut by_fingerprint: BTreeMap<&str, Vec<EntrySummary>> = BTreeMap::new();
+        for password in passwords {
+            if let Some(fingerprint) = &password.fingerprint {
+                by_fingerprint.entry(fingerprint).or_default().push(summarize(password));
+            }
+        }
+        let mut reused: Vec<ReusedGroup> = by_fingerprint
+            .into_iter()
+            .filter(|(_, entries)| entries.len() > 1)
+            .map(|(fingerprint, entries)| ReusedGroup { fingerprint: fingerprint.to_string(), entries })
+            .collect();
+        reused.sort_by_key(|group| std::cmp::Reverse(group.entries.len()));
+
+        let mut stale: Vec<EntrySummary> = passwords
+            .iter()
+            .map(summarize)
+            .filter(|entry| entry.age_days >= policy.stale_after_days)
+            .collect();
+        stale.sort_by_key(|entry| std::cmp::Reverse(entry.age_days));
+
+        let weak = passwords
+            .iter()
+            .filter(|password| password.strength.is_some_and(|strength| strength < policy.weak_below_strength))
+            .map(summarize)
+            .collect();
+
+        let mut age_buckets: Vec<AgeBucket> = (0..=AGE_BUCKET_BOUNDS.len())
+            .map(|i| AgeBucket {
+                min_days: if i == 0 { 0 } else { AGE_BUCKET_BOUNDS[i - 1] },
+                max_days: AGE_BUCKET_BOUNDS.get(i).copied(),
+                count: 0,
+                ids: Vec::new(),
+            })
+            .collect();
+        for entry in passwords.iter().map(summarize) {
+            let bucket = AGE_BUCKET_BOUNDS
+                .iter()
+                .position(|bound| entry.age_days < *bound)
+                .unwrap_or(AGE_BUCKET_BOUNDS.len());
+            age_buckets[bucket].count += 1;
+            age_buckets[bucket].ids.push(entry.id);
+        }
+
+        HealthReport {
+            generated_at: now,
+            total_entries: passwords.len(),
+            stale_after_days: policy.stale_after_days,
+            weak_below_strength: policy.weak_below_strength,
+            reused,
+            stale,
+            weak,
+            unscored: passwords.iter().filter(|password| password.strength.is_none()).count(),
+            age_buckets,
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use super::password::{CustomField, EncryptedText, Password};
+use super::item::ItemData;
+use super::uri_match::{EntryUri, MatchStrategy};
+use crate::bounded_context::utility::encryption::{encrypt, fingerprint};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Invalid import format: {0}")]
+    InvalidFormat(String),
+    #[error("Invalid export file: {0}")]
+    InvalidFile(String),
+}
+
+/// Password manager exports that can be imported
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    /// Unencrypted JSON export of Bitwarden
+    BitwardenJson,
+    /// KeePass 2.x XML export
+    KeepassXml,
+    /// 1Password Unencrypted Export (`.1pux`)
+    OnePassword1pux,
+    OnePasswordCsv,
+    ChromeCsv,
+    FirefoxCsv,
+}
+
+impl ImportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportFormat::BitwardenJson => "bitwarden_json",
+            ImportFormat::KeepassXml => "keepass_xml",
+            ImportFormat::OnePassword1pux => "1password_1pux",
+            ImportFormat::OnePasswordCsv => "1password_csv",
+            ImportFormat::ChromeCsv => "chrome_csv",
+            ImportFormat::FirefoxCsv => "firefox_csv",
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = ImportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized_str = s.to_lowercase().replace(['_', '-'], "");
+
+        match normalized_str.as_str() {
+            "bitwarden" | "bitwardenjson" => Ok(ImportFormat::BitwardenJson),
+            "keepass" | "keepassxml" => Ok(ImportFormat::KeepassXml),
+            "1pux" | "1password" | "1password1pux" => Ok(ImportFormat::OnePassword1pux),
+            "1passwordcsv" => Ok(ImportFormat::OnePasswordCsv),
+            "chrome" | "chromecsv" => Ok(ImportFormat::ChromeCsv),
+            "firefox" | "firefoxcsv" => Ok(ImportFormat::FirefoxCsv),
+            _ => Err(ImportError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+/// A custom field as read from an export, before encryption
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportedField {
+    Text { name: String, value: String },
+    Hidden { name: String, value: String },
+    Boolean { name: String, value: bool },
+}
+
+/// Type specific fields as read from an export. The primary secret of each type, see `ItemData`,
+/// is kept in `ImportedEntry::secret`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ImportedItem {
+    #[default]
+    Login,
+    SecureNote,
+    Card {
+        cardholder_name: Option<String>,
+        expiry: Option<String>,
+        security_code: Option<String>,
+        brand: Option<String>,
+    },
+    Identity {
+        full_name: Option<String>,
+        email: Option<String>,
+        phone: Option<String>,
+        address: Option<String>,
+        birth_date: Option<String>,
+    },
+    SshKey {
+        public_key: String,
+        key_fingerprint: Option<String>,
+    },
+}
+
+/// An entry read from an export, in plaintext until `encrypt` is called
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedEntry {
+    /// Position of the record in the export: its line in CSV exports, its index counting from 1
+    /// otherwise
+    pub record: usize,
+    pub service: String,
+    pub username: Option<String>,
+    pub secret: String,
+    pub uris: Vec<String>,
+    pub notes: Option<String>,
+    pub totp: Option<String>,
+    pub fields: Vec<ImportedField>,
+    pub item: ImportedItem,
+    /// Names of the folders leading to the entry, outermost first
+    pub folder: Vec<String>,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ImportedEntry {
+    /// Identifies entries for the same account, which are imported only once
+    pub fn duplicate_key(&self) -> (String, String) {
+        duplicate_key(&self.service, self.username.as_deref())
+    }
+
+    /// Encrypts every secret of the entry with `master_key`, see `utility::encryption::encrypt`.
+    /// Logins are fingerprinted with `fingerprint_key` for reuse detection. There is no strength
+    /// score, since clients compute those. Blank URIs are dropped.
+    pub fn encrypt(
+        self,
+        master_key: &str,
+        fingerprint_key: Option<&str>,
+        id: Uuid,
+        folder_id: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Password {
+        let seal = |value: String| {
+            let (nonce, cipher) = encrypt(master_key, value);
+            EncryptedText { nonce, cipher }
+        };
+        let seal_some = |value: Option<String>| value.filter(|value| !value.is_empty()).map(seal);
+
+        let item = match self.item {
+            ImportedItem::Login => ItemData::Login,
+            ImportedItem::SecureNote => ItemData::SecureNote,
+            ImportedItem::Card { cardholder_name, expiry, security_code, brand } => {
+                let digits: Vec<char> = self.secret.chars().filter(|c| c.is_ascii_digit()).collect();
+                ItemData::Card {
+                    cardholder_name: seal_some(cardholder_name),
+                    expiry: seal_some(expiry),
+                    security_code: seal_some(security_code),
+                    brand,
+                    last_four: (digits.len() >= 4).then(|| digits[digits.len() - 4..].iter().collect()),
+                }
+            }
+            ImportedItem::Identity { full_name, email, phone, address, birth_date } => ItemData::Identity {
+                full_name: seal_some(full_name),
+                email: seal_some(email),
+                phone: seal_some(phone),
+                address: seal_some(address),
+                birth_date: seal_some(birth_date),
+            },
+            ImportedItem::SshKey { public_key, key_fingerprint } => ItemData::SshKey { public_key, key_fingerprint, passphrase: None },
+        };
+
+        let custom_fields = self
+            .fields
+            .into_iter()
+            .map(|field| match field {
+                ImportedField::Text { name, value } => CustomField::Text { name, value },
+                ImportedField::Hidden { name, value } => {
+                    let sealed = seal(value);
+                    CustomField::Hidden { name, nonce: sealed.nonce, cipher: sealed.cipher }
+                }
+                ImportedField::Boolean { name, value } => CustomField::Boolean { name, value },
+            })
+            .collect();
+
+        let fingerprint = fingerprint_key
+            .filter(|_| matches!(item, ItemData::Login) && !self.secret.is_empty())
+            .map(|key| fingerprint(key, &self.secret));
+        let (nonce, cipher) = encrypt(master_key, self.secret);
+        let created_at = self.created_at.unwrap_or(now);
+
+        Password {
+            id,
+            service: self.service,
+            nonce,
+            cipher,
+            created_at,
+            updated_at: self.updated_at.unwrap_or(created_at),
+            fingerprint,
+            username: self.username.filter(|username| !username.is_empty()),
+            uris: self
+                .uris
+                .iter()
+                .map(|uri| EntryUri::new(uri.trim(), MatchStrategy::default()))
+                .filter(EntryUri::is_valid)
+                .collect(),
+            notes: seal_some(self.notes),
+            totp: seal_some(self.totp),
+            custom_fields,
+            item,
+            folder_id,
+            tags: self.tags,
+            favorite: self.favorite,
+            ..Default::default()
+        }
+    }
+}
+
+/// Services and usernames are compared case-insensitively and ignoring surrounding whitespace
+pub fn duplicate_key(service: &str, username: Option<&str>) -> (String, String) {
+    (service.trim().to_lowercase(), username.unwrap_or_default().trim().to_lowercase())
+}
+
+/// A record of an export that was not imported
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SkippedRecord {
+    pub record: usize,
+    pub reason: String,
+}
+
+/// A record left out because the vault or an earlier record already holds the same account
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateRecord {
+    pub record: usize,
+    pub service: String,
+    pub username: Option<String>,
+}
+
+/// What a parser read from an export
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedExport {
+    pub entries: Vec<ImportedEntry>,
+    pub skipped: Vec<SkippedRecord>,
+}
+
+impl ParsedExport {
+    /// Leaves out the entries whose `duplicate_key` is in `existing` or repeats an earlier entry
+    pub fn remove_duplicates(&mut self, existing: &HashSet<(String, String)>) -> Vec<DuplicateRecord> {
+        let mut seen = existing.clone();
+        let mut duplicates = Vec::new();
+        self.entries.retain(|entry| {
+            if seen.insert(entry.duplicate_key()) {
+                return true;
+            }
+            duplicates.push(DuplicateRecord { record: entry.record, service: entry.service.clone(), username: entry.username.clone() });
+            false
+        });
+
+        duplicates
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub folders_created: usize,
+    pub skipped: Vec<SkippedRecord>,
+    pub duplicates: Vec<DuplicateRecord>,
+}
@@ -0,0 +1,120 @@
+use super::password::EncryptedText;
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ItemTypeError {
+    #[error("Invalid item type: {0}")]
+    InvalidOption(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemType {
+    Login,
+    SecureNote,
+    Card,
+    Identity,
+    SshKey,
+    ApiKey,
+}
+
+impl ItemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemType::Login => "login",
+            ItemType::SecureNote => "secure_note",
+            ItemType::Card => "card",
+            ItemType::Identity => "identity",
+            ItemType::SshKey => "ssh_key",
+            ItemType::ApiKey => "api_key",
+        }
+    }
+}
+
+impl fmt::Display for ItemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ItemType {
+    type Err = ItemTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized_str = s.to_lowercase().replace('_', "");
+
+        match normalized_str.as_str() {
+            "login" => Ok(ItemType::Login),
+            "securenote" => Ok(ItemType::SecureNote),
+            "card" => Ok(ItemType::Card),
+            "identity" => Ok(ItemType::Identity),
+            "sshkey" => Ok(ItemType::SshKey),
+            "apikey" => Ok(ItemType::ApiKey),
+            _ => Err(ItemTypeError::InvalidOption(s.to_string())),
+        }
+    }
+}
+
+/// Type specific fields of an entry. The entry's own `nonce`/`cipher` always hold the primary
+/// secret: the password of a login, the body of a secure note, the number of a card, the
+/// identification number of an identity, the private key of an SSH key and the key of an API key.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "item_type", rename_all = "snake_case")]
+pub enum ItemData {
+    #[default]
+    Login,
+    SecureNote,
+    Card {
+        cardholder_name: Option<EncryptedText>,
+        expiry: Option<EncryptedText>,
+        security_code: Option<EncryptedText>,
+        brand: Option<String>,
+        last_four: Option<String>,
+    },
+    Identity {
+        full_name: Option<EncryptedText>,
+        email: Option<EncryptedText>,
+        phone: Option<EncryptedText>,
+        address: Option<EncryptedText>,
+        birth_date: Option<EncryptedText>,
+    },
+    SshKey {
+        public_key: String,
+        key_fingerprint: Option<String>,
+        passphrase: Option<EncryptedText>,
+    },
+    ApiKey {
+        key_id: Option<String>,
+        secret: Option<EncryptedText>,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+}
+
+impl ItemData {
+    pub fn item_type(&self) -> ItemType {
+        match self {
+            ItemData::Login => ItemType::Login,
+            ItemData::SecureNote => ItemType::SecureNote,
+            ItemData::Card { .. } => ItemType::Card,
+            ItemData::Identity { .. } => ItemType::Identity,
+            ItemData::SshKey { .. } => ItemType::SshKey,
+            ItemData::ApiKey { .. } => ItemType::ApiKey,
+        }
+    }
+
+    /// Every client-side encrypted value carried by the item
+    pub fn encrypted_fields(&self) -> Vec<&EncryptedText> {
+        let fields = match self {
+            ItemData::Login | ItemData::SecureNote => vec![],
+            ItemData::Card { cardholder_name, expiry, security_code, .. } => vec![cardholder_name, expiry, security_code],
+            ItemData::Identity { full_name, email, phone, address, birth_date } => vec![full_name, email, phone, address, birth_date],
+            ItemData::SshKey { passphrase, .. } => vec![passphrase],
+            ItemData::ApiKey { secret, .. } => vec![secret],
+        };
+
+        fields.into_iter().flatten().collect()
+    }
+}
@@ -0,0 +1,84 @@
+use super::item::ItemData;
+use super::password::{CustomField, EncryptedText, Password};
+use crate::bounded_context::utility::encryption::{encrypt, try_decrypt};
+use async_trait::async_trait;
+use std::error::Error;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum KeyRotationError {
+    #[error("Failed to decrypt entry {0} with the old key")]
+    DecryptionFailed(Uuid),
+}
+
+/// Re-encrypts every secret of `password` under `new_key`. Fingerprints are keyed separately and
+/// stay valid.
+pub fn reencrypt(password: &Password, old_key: &str, new_key: &str) -> Result<Password, KeyRotationError> {
+    let rotate = |nonce: &str, cipher: &str| {
+        let plaintext = try_decrypt(old_key, nonce, cipher).ok_or(KeyRotationError::DecryptionFailed(password.id))?;
+        let (nonce, cipher) = encrypt(new_key, plaintext);
+        Ok(EncryptedText { nonce, cipher })
+    };
+    let rotate_some = |text: &Option<EncryptedText>| text.as_ref().map(|text| rotate(&text.nonce, &text.cipher)).transpose();
+
+    let item = match &password.item {
+        ItemData::Login => ItemData::Login,
+        ItemData::SecureNote => ItemData::SecureNote,
+        ItemData::Card { cardholder_name, expiry, security_code, brand, last_four } => ItemData::Card {
+            cardholder_name: rotate_some(cardholder_name)?,
+            expiry: rotate_some(expiry)?,
+            security_code: rotate_some(security_code)?,
+            brand: brand.clone(),
+            last_four: last_four.clone(),
+        },
+        ItemData::Identity { full_name, email, phone, address, birth_date } => ItemData::Identity {
+            full_name: rotate_some(full_name)?,
+            email: rotate_some(email)?,
+            phone: rotate_some(phone)?,
+            address: rotate_some(address)?,
+            birth_date: rotate_some(birth_date)?,
+        },
+        ItemData::SshKey { public_key, key_fingerprint, passphrase } => ItemData::SshKey {
+            public_key: public_key.clone(),
+            key_fingerprint: key_fingerprint.clone(),
+            passphrase: rotate_some(passphrase)?,
+        },
+        ItemData::ApiKey { key_id, secret, expires_at } => ItemData::ApiKey {
+            key_id: key_id.clone(),
+            secret: rotate_some(secret)?,
+            expires_at: *expires_at,
+        },
+    };
+
+    let custom_fields = password
+        .custom_fields
+        .iter()
+        .map(|field| match field {
+            CustomField::Hidden { name, nonce, cipher } => {
+                let rotated = rotate(nonce, cipher)?;
+                Ok(CustomField::Hidden { name: name.clone(), nonce: rotated.nonce, cipher: rotated.cipher })
+            }
+            field => Ok(field.clone()),
+        })
+        .collect::<Result<_, KeyRotationError>>()?;
+
+    let secret = rotate(&password.nonce, &password.cipher)?;
+
+    Ok(Password {
+        nonce: secret.nonce,
+        cipher: secret.cipher,
+        notes: rotate_some(&password.notes)?,
+        totp: rotate_some(&password.totp)?,
+        custom_fields,
+        item,
+        ..password.clone()
+    })
+}
+
+#[async_trait]
+pub trait KeyRotationDb {
+    /// Re-encrypts every entry under `new_key` in a single transaction and returns how many were
+    /// rotated. Nothing is changed if any entry cannot be decrypted with `old_key`.
+    async fn rotate_master_key(&mut self, old_key: &str, new_key: &str) -> Result<usize, Box<dyn Error>>;
+}
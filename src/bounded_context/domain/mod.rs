@@ -1,2 +1,24 @@
 pub mod password_db;
-pub mod password;
\ No newline at end of file
+pub mod password;
+pub mod breach_db;
+pub mod breach;
+pub mod health_report;
+pub mod item;
+pub mod folder;
+pub mod pagination;
+pub mod fuzzy_search;
+pub mod uri_match;
+pub mod attachment;
+pub mod share;
+pub mod emergency_access;
+pub mod rotation;
+pub mod audit;
+pub mod webhook;
+pub mod change_feed;
+pub mod sync;
+pub mod import;
+pub mod backup;
+pub mod export;
+pub mod user;
+pub mod stats;
+pub mod key_rotation;
\ No newline at end of file
@@ -0,0 +1,107 @@
+use super::password::Password;
+use super::password_db::SortBy;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum CursorError {
+    #[error("Invalid cursor.")]
+    Invalid,
+    #[error("Cursor was issued for a different sort option.")]
+    SortMismatch,
+}
+
+/// Position after the last entry of a page. It carries every column the sort options order by,
+/// so the next page can be found by comparing values instead of skipping rows, and it stays
+/// valid when that entry is deleted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub sort_by: SortBy,
+    pub id: Uuid,
+    pub service: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub favorite: bool,
+}
+
+impl Cursor {
+    pub fn after(password: &Password, sort_by: SortBy) -> Cursor {
+        Cursor {
+            sort_by,
+            id: password.id,
+            service: password.service.clone(),
+            created_at: password.created_at,
+            updated_at: password.updated_at,
+            last_used_at: password.last_used_at,
+            favorite: password.favorite,
+        }
+    }
+
+    /// Opaque, URL safe form handed to clients
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("Cursor serialization cannot fail");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// Decodes a cursor previously returned for a listing sorted by `sort_by`.
+    pub fn decode(encoded: &str, sort_by: SortBy) -> Result<Cursor, CursorError> {
+        let json = URL_SAFE_NO_PAD.decode(encoded).map_err(|_| CursorError::Invalid)?;
+        let cursor: Cursor = serde_json::from_slice(&json).map_err(|_| CursorError::Invalid)?;
+
+        if cursor.sort_by != sort_by {
+            return Err(CursorError::SortMismatch);
+        }
+
+        Ok(cursor)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum PageRequest {
+    /// 1-based page number
+    Offset { page: u32, page_size: u32 },
+    /// Entries after `cursor`, or from the start when there is none
+    Cursor { cursor: Option<Cursor>, page_size: u32 },
+}
+
+impl PageRequest {
+    pub fn offset(page: u32, page_size: u32) -> PageRequest {
+        PageRequest::Offset { page, page_size }
+    }
+
+    pub fn after(cursor: Option<Cursor>, page_size: u32) -> PageRequest {
+        PageRequest::Cursor { cursor, page_size }
+    }
+
+    pub fn page_size(&self) -> u32 {
+        match self {
+            PageRequest::Offset { page_size, .. } | PageRequest::Cursor { page_size, .. } => *page_size,
+        }
+    }
+
+    /// The page number, which only offset requests have
+    pub fn page(&self) -> Option<u32> {
+        match self {
+            PageRequest::Offset { page, .. } => Some(*page),
+            PageRequest::Cursor { .. } => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Entries matching the filter across all pages
+    pub total_count: i64,
+    pub page: Option<u32>,
+    pub page_size: u32,
+    /// Cursor for the following page, absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+pub type PasswordPage = Page<Password>;
@@ -1,19 +1,67 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
+use super::item::ItemData;
+use super::uri_match::EntryUri;
 
 use sqlx::FromRow;
 use sqlx::postgres::PgRow;
 use sqlx::Row;
+use sqlx::types::Json;
 
+/// A value encrypted client-side, stored as hex nonce and cipher text like the password itself.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedText {
+    pub nonce: String,
+    pub cipher: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CustomField {
+    Text { name: String, value: String },
+    Hidden { name: String, nonce: String, cipher: String },
+    Boolean { name: String, value: bool },
+}
+
+impl CustomField {
+    pub fn name(&self) -> &str {
+        match self {
+            CustomField::Text { name, .. } => name,
+            CustomField::Hidden { name, .. } => name,
+            CustomField::Boolean { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Password {
     pub id: Uuid,
     pub service: String,
     pub nonce: String,
     pub cipher: String,
     pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>
+    pub updated_at: DateTime<Utc>,
+    /// Client-computed keyed HMAC of the plaintext, equal for entries sharing a secret
+    pub fingerprint: Option<String>,
+    /// Client-computed strength score from 0 (weakest) to 4
+    pub strength: Option<i16>,
+    pub username: Option<String>,
+    pub uris: Vec<EntryUri>,
+    pub notes: Option<EncryptedText>,
+    /// Encrypted `otpauth://` URI or base32 seed, see `utility::otp`
+    pub totp: Option<EncryptedText>,
+    pub custom_fields: Vec<CustomField>,
+    #[serde(flatten)]
+    pub item: ItemData,
+    pub folder_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Fixed date by which the secret has to be rotated
+    pub rotate_by: Option<DateTime<Utc>>,
+    /// The secret has to be rotated this many days after each update
+    pub rotation_interval_days: Option<i32>,
 }
 
 impl Password {
@@ -26,12 +74,49 @@ impl Password {
             cipher,
             created_at: now,
             updated_at: now,
+            fingerprint: None,
+            strength: None,
+            username: None,
+            uris: Vec::new(),
+            notes: None,
+            totp: None,
+            custom_fields: Vec::new(),
+            item: ItemData::Login,
+            folder_id: None,
+            tags: Vec::new(),
+            favorite: false,
+            last_used_at: None,
+            rotate_by: None,
+            rotation_interval_days: None,
         }
     }
+
+    /// The earliest of `rotate_by`, the end of the rotation interval and the expiry of an API key.
+    /// Entries without any of these never need rotating.
+    pub fn rotation_due_at(&self) -> Option<DateTime<Utc>> {
+        let interval_end = self
+            .rotation_interval_days
+            .map(|days| self.updated_at + Duration::days(days as i64));
+        let key_expiry = match &self.item {
+            ItemData::ApiKey { expires_at, .. } => *expires_at,
+            _ => None,
+        };
+
+        [self.rotate_by, interval_end, key_expiry].into_iter().flatten().min()
+    }
 }
 
 impl<'r> FromRow<'r, PgRow> for Password {
     fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let notes_nonce: Option<String> = row.get("notes_nonce");
+        let notes_cipher: Option<String> = row.get("notes_cipher");
+        let totp_nonce: Option<String> = row.get("totp_nonce");
+        let totp_cipher: Option<String> = row.get("totp_cipher");
+        let custom_fields: Json<Vec<CustomField>> = row.get("custom_fields");
+        let item: Json<ItemData> = row.get("item_data");
+        let uris: Vec<String> = row.get("uris");
+        let uri_match_strategies: Vec<String> = row.get("uri_match_strategies");
+
         Ok(Password {
             id: row.get("id"),
             service: row.get("service"),
@@ -39,6 +124,27 @@ impl<'r> FromRow<'r, PgRow> for Password {
             cipher: row.get("cipher"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            fingerprint: row.get("fingerprint"),
+            strength: row.get("strength"),
+            username: row.get("username"),
+            uris: uris
+                .into_iter()
+                .enumerate()
+                .map(|(i, uri)| EntryUri {
+                    uri,
+                    match_strategy: uri_match_strategies.get(i).and_then(|strategy| strategy.parse().ok()).unwrap_or_default(),
+                })
+                .collect(),
+            notes: notes_nonce.zip(notes_cipher).map(|(nonce, cipher)| EncryptedText { nonce, cipher }),
+            totp: totp_nonce.zip(totp_cipher).map(|(nonce, cipher)| EncryptedText { nonce, cipher }),
+            custom_fields: custom_fields.0,
+            item: item.0,
+            folder_id: row.get("folder_id"),
+            tags: row.get("tags"),
+            favorite: row.get("favorite"),
+            last_used_at: row.get("last_used_at"),
+            rotate_by: row.get("rotate_by"),
+            rotation_interval_days: row.get("rotation_interval_days"),
         })
     }
 }
\ No newline at end of file
@@ -1,6 +1,12 @@
 use super::password::Password;
+use super::item::ItemType;
+use super::folder::{Folder, TagCount};
+use super::pagination::{Page, PageRequest, PasswordPage};
+use super::fuzzy_search::FuzzyMatch;
 use uuid::Uuid;
+use chrono::{DateTime, Utc};
 use async_trait::async_trait;
+use serde::{Serialize, Deserialize};
 use std::error::Error;
 use std::str::FromStr;
 use thiserror::Error;
@@ -11,12 +17,17 @@ pub enum SortByError {
     InvalidOption(String),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortBy {
     CreatedAtAsc,
     CreatedAtDesc,
     UpdatedAtAsc,
     UpdatedAtDesc,
+    LastUsedDesc,
+    FavoritesFirst,
+    ServiceAsc,
+    ServiceDesc,
 }
 
 impl FromStr for SortBy {
@@ -30,28 +41,183 @@ impl FromStr for SortBy {
             "createdatdesc" => Ok(SortBy::CreatedAtDesc),
             "updatedatasc" => Ok(SortBy::UpdatedAtAsc),
             "updatedatdesc" => Ok(SortBy::UpdatedAtDesc),
+            "lastuseddesc" => Ok(SortBy::LastUsedDesc),
+            "favoritesfirst" => Ok(SortBy::FavoritesFirst),
+            "serviceasc" => Ok(SortBy::ServiceAsc),
+            "servicedesc" => Ok(SortBy::ServiceDesc),
             _ => Err(SortByError::InvalidOption(s.to_string())),
         }
     }
 }
 
+/// Narrows the entries returned by the listing queries. Every condition that is set must match;
+/// text conditions are case-insensitive substring matches and ranges include their `_after` bound
+/// but not their `_before` bound.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct EntryFilter {
+    /// Matches the service, the username or any of the URIs
+    pub search_term: Option<String>,
+    pub service: Option<String>,
+    pub username: Option<String>,
+    pub uri: Option<String>,
+    pub item_type: Option<ItemType>,
+    pub folder_id: Option<Uuid>,
+    /// Also match entries in subfolders of `folder_id`
+    pub include_subfolders: bool,
+    pub tag: Option<String>,
+    pub favorite: Option<bool>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+    pub last_used_after: Option<DateTime<Utc>>,
+    pub last_used_before: Option<DateTime<Utc>>,
+}
+
+impl EntryFilter {
+    /// Whether every date range is ordered, i.e. none can only ever match nothing
+    pub fn has_valid_ranges(&self) -> bool {
+        [
+            (self.created_after, self.created_before),
+            (self.updated_after, self.updated_before),
+            (self.last_used_after, self.last_used_before),
+        ]
+        .iter()
+        .all(|range| match range {
+            (Some(after), Some(before)) => after <= before,
+            _ => true,
+        })
+    }
+}
+
+/// How a batch treats the items that cannot be applied
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// A single failing item rolls back the whole batch
+    #[default]
+    AllOrNothing,
+    /// Every item that can be applied is kept
+    BestEffort,
+}
+
+impl BatchMode {
+    /// Whether a batch whose items ended with `outcomes` is committed
+    pub fn commits<'a>(&self, outcomes: impl IntoIterator<Item = &'a Result<(), String>>) -> bool {
+        match self {
+            BatchMode::AllOrNothing => outcomes.into_iter().all(Result::is_ok),
+            BatchMode::BestEffort => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Applied,
+    Failed,
+    /// The item could be applied but the batch was rolled back because of another one
+    RolledBack,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    /// Position of the item in the request
+    pub index: usize,
+    pub id: Option<Uuid>,
+    pub status: BatchItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub committed: bool,
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchResult {
+    /// Reports every item of a batch from the entry it targets and its outcome, in request order
+    pub fn new(mode: BatchMode, outcomes: Vec<(Option<Uuid>, Result<(), String>)>) -> Self {
+        let committed = mode.commits(outcomes.iter().map(|(_, outcome)| outcome));
+        let results = outcomes
+            .into_iter()
+            .enumerate()
+            .map(|(index, (id, outcome))| match outcome {
+                Ok(()) => BatchItemResult {
+                    index,
+                    id,
+                    status: if committed { BatchItemStatus::Applied } else { BatchItemStatus::RolledBack },
+                    error: None,
+                },
+                Err(error) => BatchItemResult { index, id, status: BatchItemStatus::Failed, error: Some(error) },
+            })
+            .collect();
+
+        Self { committed, results }
+    }
+}
+
 #[async_trait]
 pub trait PasswordDb {
     async fn save(&mut self, password: Password) -> Result<(), Box<dyn Error>>;
     async fn get_by_id(&mut self, id: Uuid) -> Result<Password, Box<dyn Error>>;
     async fn delete(&mut self, id: Uuid) -> Result<(), Box<dyn Error>>;
 
+    /// The batch methods apply every item in one transaction and return, in order, why each item
+    /// failed if it did. The transaction is committed when `mode` allows it.
+    async fn save_batch(&mut self, passwords: Vec<Password>, mode: BatchMode) -> Result<Vec<Result<(), String>>, Box<dyn Error>>;
+    /// Replaces every field of the entries a client can change
+    async fn update_batch(&mut self, passwords: Vec<Password>, mode: BatchMode) -> Result<Vec<Result<(), String>>, Box<dyn Error>>;
+    async fn delete_batch(&mut self, ids: Vec<Uuid>, mode: BatchMode) -> Result<Vec<Result<(), String>>, Box<dyn Error>>;
+
+    /// Matches the service, username or URIs, ordered like `SortBy::ServiceAsc`.
     async fn search_by_service(
         &mut self,
         search_term: &str,
+        filter: &EntryFilter,
+        page: &PageRequest,
+    ) -> Result<PasswordPage, Box<dyn std::error::Error>>;
+
+    /// Ranks entries by the trigram similarity of their service to `search_term`, best first,
+    /// keeping those scoring at least `threshold`.
+    async fn fuzzy_search(
+        &mut self,
+        search_term: &str,
+        threshold: f32,
+        filter: &EntryFilter,
         page: u32,
         page_size: u32,
-    ) -> Result<Vec<Password>, Box<dyn std::error::Error>>;
+    ) -> Result<Page<FuzzyMatch>, Box<dyn std::error::Error>>;
 
     async fn list_sorted(
         &mut self,
         sort_by: &SortBy,
-        page: u32,
-        page_size: u32,
-    ) -> Result<Vec<Password>, Box<dyn std::error::Error>>;
+        filter: &EntryFilter,
+        page: &PageRequest,
+    ) -> Result<PasswordPage, Box<dyn std::error::Error>>;
+
+    async fn query(
+        &mut self,
+        filter: &EntryFilter,
+        sort_by: &SortBy,
+        page: &PageRequest,
+    ) -> Result<PasswordPage, Box<dyn std::error::Error>>;
+
+    async fn list_all(&mut self) -> Result<Vec<Password>, Box<dyn std::error::Error>>;
+
+    async fn set_favorite(&mut self, id: Uuid, favorite: bool) -> Result<(), Box<dyn Error>>;
+    async fn record_use(&mut self, id: Uuid, used_at: DateTime<Utc>) -> Result<(), Box<dyn Error>>;
+
+    async fn set_folder(&mut self, id: Uuid, folder_id: Option<Uuid>) -> Result<(), Box<dyn Error>>;
+    async fn tag(&mut self, id: Uuid, tag: &str) -> Result<(), Box<dyn Error>>;
+    async fn untag(&mut self, id: Uuid, tag: &str) -> Result<(), Box<dyn Error>>;
+    async fn list_tags(&mut self) -> Result<Vec<TagCount>, Box<dyn Error>>;
+
+    async fn create_folder(&mut self, folder: Folder) -> Result<(), Box<dyn Error>>;
+    async fn list_folders(&mut self) -> Result<Vec<Folder>, Box<dyn Error>>;
+    async fn rename_folder(&mut self, id: Uuid, name: &str) -> Result<(), Box<dyn Error>>;
+    async fn move_folder(&mut self, id: Uuid, parent_id: Option<Uuid>) -> Result<(), Box<dyn Error>>;
+    async fn delete_folder(&mut self, id: Uuid) -> Result<(), Box<dyn Error>>;
 }
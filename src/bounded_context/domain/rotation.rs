@@ -0,0 +1,81 @@
+use super::password::Password;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::error::Error;
+use uuid::Uuid;
+
+/// An entry that has to be rotated within the warning window or already should have been
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RotationDue {
+    #[serde(flatten)]
+    pub password: Password,
+    pub due_at: DateTime<Utc>,
+    pub overdue: bool,
+}
+
+/// Lists the entries due for rotation before `now + warning`, soonest first.
+pub fn rotation_due(passwords: Vec<Password>, now: DateTime<Utc>, warning: Duration) -> Vec<RotationDue> {
+    let mut due: Vec<RotationDue> = passwords
+        .into_iter()
+        .filter_map(|password| {
+            let due_at = password.rotation_due_at()?;
+            (due_at <= now + warning).then(|| RotationDue { password, due_at, overdue: due_at <= now })
+        })
+        .collect();
+
+    due.sort_by(|a, b| {
+        a.due_at
+            .cmp(&b.due_at)
+            .then_with(|| a.password.service.to_lowercase().cmp(&b.password.service.to_lowercase()))
+            .then_with(|| a.password.id.cmp(&b.password.id))
+    });
+
+    due
+}
+
+/// What a notifier is told about an entry due for rotation. Secrets are left out.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RotationNotice {
+    pub password_id: Uuid,
+    pub service: String,
+    pub username: Option<String>,
+    pub due_at: DateTime<Utc>,
+    pub overdue: bool,
+}
+
+impl From<&RotationDue> for RotationNotice {
+    fn from(due: &RotationDue) -> Self {
+        RotationNotice {
+            password_id: due.password.id,
+            service: due.password.service.clone(),
+            username: due.password.username.clone(),
+            due_at: due.due_at,
+            overdue: due.overdue,
+        }
+    }
+}
+
+/// Delivers rotation reminders, for example to the log or a webhook
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notices: &[RotationNotice]) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Where the last search for newly overdue entries stopped
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OverdueCheck {
+    pub checked_at: DateTime<Utc>,
+    /// Sync token taken with the search, so entries changed after it are looked at again
+    pub sync_token: i64,
+}
+
+#[async_trait]
+pub trait RotationDb {
+    /// The due date each entry was last notified for, so every due date is only notified once
+    async fn list_rotation_notified(&mut self) -> Result<HashMap<Uuid, DateTime<Utc>>, Box<dyn Error>>;
+    async fn mark_rotation_notified(&mut self, notices: &[RotationNotice]) -> Result<(), Box<dyn Error>>;
+    /// Entries due by `now` that became due or changed since `since`, every overdue entry without it
+    async fn list_newly_overdue(&mut self, since: Option<OverdueCheck>, now: DateTime<Utc>) -> Result<(Vec<Password>, OverdueCheck), Box<dyn Error>>;
+}
@@ -0,0 +1,67 @@
+use crate::bounded_context::domain::password::EncryptedText;
+use crate::bounded_context::utility::share::hash_access_password;
+use crate::bounded_context::utility::token::hash_token;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::error::Error;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ShareError {
+    /// Also returned for expired and used up shares, so a link reveals nothing once it is gone
+    #[error("Share not found or no longer available")]
+    NotFound,
+    #[error("Share requires an access password")]
+    PasswordRequired,
+    #[error("Invalid access password")]
+    InvalidPassword,
+}
+
+/// A secret shared through a link that stops working after `max_views` views or at `expires_at`.
+/// Only hashes of the link id and access password are kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Share {
+    pub id_hash: String,
+    pub payload: EncryptedText,
+    pub password_hash: Option<String>,
+    pub max_views: i32,
+    pub view_count: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Share {
+    /// Hashes `password` with Argon2, so call it off the async workers
+    pub fn new(token: &str, payload: EncryptedText, max_views: i32, expires_at: DateTime<Utc>, password: Option<&str>) -> Share {
+        Share {
+            id_hash: hash_token(token),
+            payload,
+            password_hash: password.map(hash_access_password),
+            max_views,
+            view_count: 0,
+            expires_at,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// What a single view of a share returns
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharedSecret {
+    #[serde(flatten)]
+    pub payload: EncryptedText,
+    pub views_remaining: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait ShareDb {
+    async fn create_share(&mut self, share: Share) -> Result<(), Box<dyn Error>>;
+    /// Counts a view and deletes the share once the last view has been used. A wrong or
+    /// missing access password does not use up a view.
+    async fn view_share(&mut self, token: &str, password: Option<&str>, now: DateTime<Utc>) -> Result<SharedSecret, Box<dyn Error>>;
+    async fn delete_share(&mut self, token: &str) -> Result<(), Box<dyn Error>>;
+    /// Deletes every share that expired before `now` and returns how many were deleted.
+    async fn delete_expired_shares(&mut self, now: DateTime<Utc>) -> Result<u64, Box<dyn Error>>;
+}
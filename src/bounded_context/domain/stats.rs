@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Counts for operators, none of which reveal anything about the secrets themselves
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VaultStats {
+    pub entries: i64,
+    pub entries_by_type: BTreeMap<String, i64>,
+    pub favorites: i64,
+    pub folders: i64,
+    pub tags: i64,
+    pub attachments: i64,
+    pub attachment_bytes: i64,
+    /// Shares that have neither expired nor been used up
+    pub active_shares: i64,
+    pub emergency_contacts: i64,
+    pub webhooks: i64,
+    pub pending_webhook_deliveries: i64,
+    pub audit_events: i64,
+    pub users: i64,
+    /// Tokens that have not expired
+    pub active_api_tokens: i64,
+}
+
+#[async_trait]
+pub trait StatsDb {
+    async fn vault_stats(&mut self, now: DateTime<Utc>) -> Result<VaultStats, Box<dyn Error>>;
+}
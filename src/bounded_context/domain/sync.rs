@@ -0,0 +1,113 @@
+use super::password::Password;
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::FromRow;
+use std::error::Error;
+use uuid::Uuid;
+
+/// Position in the change history of the vault, handed to clients as an opaque string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyncToken(pub i64);
+
+impl SyncToken {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.0.to_be_bytes())
+    }
+
+    pub fn decode(token: &str) -> Option<SyncToken> {
+        let bytes: [u8; 8] = URL_SAFE_NO_PAD.decode(token).ok()?.try_into().ok()?;
+        let version = i64::from_be_bytes(bytes);
+
+        (version >= 0).then_some(SyncToken(version))
+    }
+}
+
+/// Marks an entry deleted since a client last synced
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Tombstone {
+    pub id: Uuid,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Everything that changed since a sync token, and the token to sync from next time
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SyncChanges {
+    pub entries: Vec<Password>,
+    pub tombstones: Vec<Tombstone>,
+    pub sync_token: String,
+}
+
+/// A change made by a client while it was offline. `base_updated_at` is the `updated_at` of the
+/// entry the client changed, and is absent for entries the client created.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum SyncChange {
+    Upsert { entry: Box<Password>, base_updated_at: Option<DateTime<Utc>> },
+    Delete { id: Uuid, base_updated_at: DateTime<Utc> },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictReason {
+    /// The entry was changed on the server after the client's version
+    Modified,
+    /// The entry was deleted on the server
+    Deleted,
+    /// The client created an entry with an id that is already in use
+    Exists,
+}
+
+impl SyncChange {
+    pub fn id(&self) -> Uuid {
+        match self {
+            SyncChange::Upsert { entry, .. } => entry.id,
+            SyncChange::Delete { id, .. } => *id,
+        }
+    }
+
+    /// Checks the change against the server's `updated_at` for the entry, `None` if there is no
+    /// such entry, and whether the entry was deleted. Deleting an entry that is already gone succeeds.
+    pub fn check(&self, current: Option<DateTime<Utc>>, deleted: bool) -> Result<(), ConflictReason> {
+        match (self, current) {
+            (SyncChange::Upsert { base_updated_at: None, .. }, Some(_)) => Err(ConflictReason::Exists),
+            (SyncChange::Upsert { base_updated_at: Some(base), .. }, Some(current))
+            | (SyncChange::Delete { base_updated_at: base, .. }, Some(current)) => {
+                if *base == current { Ok(()) } else { Err(ConflictReason::Modified) }
+            }
+            (SyncChange::Upsert { base_updated_at: Some(_), .. }, None) if deleted => Err(ConflictReason::Deleted),
+            (SyncChange::Upsert { .. }, None) | (SyncChange::Delete { .. }, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AppliedChange {
+    pub id: Uuid,
+    /// The entry's new `updated_at`, absent for deletions
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A change that was not applied, with the server's version of the entry if it still exists
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SyncConflict {
+    pub id: Uuid,
+    pub reason: ConflictReason,
+    pub server_entry: Option<Password>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PushResult {
+    pub applied: Vec<AppliedChange>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+#[async_trait]
+pub trait SyncDb {
+    /// Entries changed and deleted since `since`, or every entry when it is `None`
+    async fn changes_since(&mut self, since: Option<SyncToken>) -> Result<SyncChanges, Box<dyn Error>>;
+    /// Applies every change that does not conflict, all in one transaction. Applied entries are
+    /// stamped with `now` as their `updated_at`.
+    async fn apply_changes(&mut self, changes: Vec<SyncChange>, now: DateTime<Utc>) -> Result<PushResult, Box<dyn Error>>;
+}
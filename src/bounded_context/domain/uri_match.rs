@@ -0,0 +1,190 @@
+use super::password::Password;
+use crate::bounded_context::utility::uri::{normalize_uri, registrable_domain};
+use regex::{Regex, RegexBuilder};
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use url::Url;
+
+/// Upper bound on the compiled size of stored patterns
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Error)]
+pub enum MatchStrategyError {
+    #[error("Invalid match strategy: {0}")]
+    InvalidOption(String),
+}
+
+/// How a stored URI is compared to the URL of the page being filled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStrategy {
+    /// Same registrable domain, e.g. `login.example.co.uk` for `example.co.uk`
+    #[default]
+    BaseDomain,
+    /// Same host and port
+    Host,
+    /// The URL starts with the stored URI
+    StartsWith,
+    /// The stored URI is a regular expression the URL must match
+    Regex,
+}
+
+impl MatchStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchStrategy::BaseDomain => "base_domain",
+            MatchStrategy::Host => "host",
+            MatchStrategy::StartsWith => "starts_with",
+            MatchStrategy::Regex => "regex",
+        }
+    }
+}
+
+impl fmt::Display for MatchStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MatchStrategy {
+    type Err = MatchStrategyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized_str = s.to_lowercase().replace('_', "");
+
+        match normalized_str.as_str() {
+            "basedomain" => Ok(MatchStrategy::BaseDomain),
+            "host" => Ok(MatchStrategy::Host),
+            "startswith" => Ok(MatchStrategy::StartsWith),
+            "regex" => Ok(MatchStrategy::Regex),
+            _ => Err(MatchStrategyError::InvalidOption(s.to_string())),
+        }
+    }
+}
+
+/// How closely a stored URI matched, best first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchQuality {
+    Exact,
+    Prefix,
+    Host,
+    BaseDomain,
+    Regex,
+}
+
+/// A URI stored on an entry. Plain strings are accepted as URIs matched by base domain.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "EntryUriInput")]
+pub struct EntryUri {
+    pub uri: String,
+    pub match_strategy: MatchStrategy,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EntryUriInput {
+    Plain(String),
+    Full {
+        uri: String,
+        #[serde(default)]
+        match_strategy: MatchStrategy,
+    },
+}
+
+impl From<EntryUriInput> for EntryUri {
+    fn from(input: EntryUriInput) -> Self {
+        match input {
+            EntryUriInput::Plain(uri) => EntryUri { uri, match_strategy: MatchStrategy::default() },
+            EntryUriInput::Full { uri, match_strategy } => EntryUri { uri, match_strategy },
+        }
+    }
+}
+
+impl From<&str> for EntryUri {
+    fn from(uri: &str) -> Self {
+        EntryUri { uri: uri.to_string(), match_strategy: MatchStrategy::default() }
+    }
+}
+
+fn compile_pattern(pattern: &str) -> Option<Regex> {
+    RegexBuilder::new(pattern).size_limit(REGEX_SIZE_LIMIT).build().ok()
+}
+
+fn same_host(a: &Url, b: &Url) -> bool {
+    a.host_str().is_some() && a.host_str() == b.host_str() && a.port() == b.port()
+}
+
+impl EntryUri {
+    pub fn new(uri: &str, match_strategy: MatchStrategy) -> EntryUri {
+        EntryUri { uri: uri.to_string(), match_strategy }
+    }
+
+    /// Regex URIs must compile; other URIs that do not parse are kept but never match.
+    pub fn is_valid(&self) -> bool {
+        !self.uri.trim().is_empty()
+            && (self.match_strategy != MatchStrategy::Regex || compile_pattern(&self.uri).is_some())
+    }
+
+    /// Compares the stored URI to a URL normalized with `normalize_uri`. Regex URIs are
+    /// matched against the normalized URL.
+    pub fn matches(&self, target: &Url) -> Option<MatchQuality> {
+        if self.match_strategy == MatchStrategy::Regex {
+            return compile_pattern(&self.uri)?.is_match(target.as_str()).then_some(MatchQuality::Regex);
+        }
+
+        let stored = normalize_uri(&self.uri)?;
+        if stored == *target {
+            return Some(MatchQuality::Exact);
+        }
+
+        match self.match_strategy {
+            MatchStrategy::StartsWith => target.as_str().starts_with(stored.as_str()).then_some(MatchQuality::Prefix),
+            MatchStrategy::Host => same_host(&stored, target).then_some(MatchQuality::Host),
+            MatchStrategy::BaseDomain if same_host(&stored, target) => Some(MatchQuality::Host),
+            MatchStrategy::BaseDomain => {
+                let stored_domain = stored.host_str().and_then(registrable_domain)?;
+                let target_domain = target.host_str().and_then(registrable_domain)?;
+                (stored_domain == target_domain).then_some(MatchQuality::BaseDomain)
+            }
+            MatchStrategy::Regex => None,
+        }
+    }
+}
+
+/// An entry matching a URL, with its best matching URI
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct UriMatch {
+    #[serde(flatten)]
+    pub password: Password,
+    pub matched_uri: String,
+    pub quality: MatchQuality,
+}
+
+/// Entries with a URI matching `target`, best match first. Equally good matches put favorites
+/// first, then the most recently used.
+pub fn rank_uri_matches(passwords: impl IntoIterator<Item = Password>, target: &Url) -> Vec<UriMatch> {
+    let mut matches: Vec<UriMatch> = passwords
+        .into_iter()
+        .filter_map(|password| {
+            let (quality, matched_uri) = password
+                .uris
+                .iter()
+                .filter_map(|uri| uri.matches(target).map(|quality| (quality, uri.uri.clone())))
+                .min_by_key(|(quality, _)| *quality)?;
+            Some(UriMatch { password, matched_uri, quality })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        a.quality
+            .cmp(&b.quality)
+            .then_with(|| b.password.favorite.cmp(&a.password.favorite))
+            .then_with(|| b.password.last_used_at.cmp(&a.password.last_used_at))
+            .then_with(|| a.password.service.to_lowercase().cmp(&b.password.service.to_lowercase()))
+    });
+
+    matches
+}
@@ -0,0 +1,78 @@
+use crate::bounded_context::utility::token::hash_token;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::FromRow;
+use std::error::Error;
+use thiserror::Error;
+use uuid::Uuid;
+
+const MAX_USERNAME_LENGTH: usize = 64;
+
+#[derive(Debug, Error)]
+pub enum UserError {
+    #[error("Invalid username: {0}")]
+    InvalidUsername(String),
+    #[error("Username already taken: {0}")]
+    UsernameTaken(String),
+    #[error("User not found: {0}")]
+    NotFound(String),
+}
+
+/// Someone allowed to call the API. Their username is what the audit log records as the actor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl User {
+    pub fn new(username: &str, now: DateTime<Utc>) -> Result<User, UserError> {
+        let username = username.trim();
+        let is_valid = !username.is_empty()
+            && username.chars().count() <= MAX_USERNAME_LENGTH
+            && username.chars().all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'));
+        if !is_valid {
+            return Err(UserError::InvalidUsername(username.to_string()));
+        }
+
+        Ok(User { id: Uuid::new_v4(), username: username.to_string(), created_at: now })
+    }
+}
+
+/// A bearer token a user authenticates with. Only the hash of the token is kept.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub name: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub fn new(user_id: Uuid, token: &str, name: Option<String>, expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> ApiToken {
+        ApiToken {
+            id: Uuid::new_v4(),
+            user_id,
+            token_hash: hash_token(token),
+            name,
+            expires_at,
+            last_used_at: None,
+            created_at: now,
+        }
+    }
+}
+
+#[async_trait]
+pub trait UserDb {
+    async fn create_user(&mut self, user: User) -> Result<(), Box<dyn Error>>;
+    async fn get_user(&mut self, username: &str) -> Result<User, Box<dyn Error>>;
+    async fn create_token(&mut self, token: ApiToken) -> Result<(), Box<dyn Error>>;
+    /// Finds the user holding the unexpired token with `token_hash` and records its use
+    async fn authenticate(&mut self, token_hash: &str, now: DateTime<Utc>) -> Result<Option<User>, Box<dyn Error>>;
+}
@@ -0,0 +1,172 @@
+use super::pagination::Page;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::FromRow;
+use std::error::Error;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Delay before the first retry of a failed delivery; it doubles with every further attempt
+pub const RETRY_BASE_DELAY_SECONDS: i64 = 30;
+/// Retries are never spaced further apart than this
+pub const RETRY_MAX_DELAY_SECONDS: i64 = 6 * 60 * 60;
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Webhook not found: {0}")]
+    NotFound(Uuid),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    Created,
+    Updated,
+    Deleted,
+    /// An entry passed its rotation due date
+    Expired,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::Created => "created",
+            WebhookEvent::Updated => "updated",
+            WebhookEvent::Deleted => "deleted",
+            WebhookEvent::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Delivered,
+    /// Gave up after the last attempt failed
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// An endpoint that is sent the events it subscribed to. The secret signs every payload, so it has
+/// to be kept as is; it is only returned when the webhook is registered.
+#[derive(Clone, Debug, PartialEq, Serialize, FromRow)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    #[serde(skip)]
+    pub secret: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn new(url: String, secret: String, events: &[WebhookEvent]) -> Webhook {
+        let mut events: Vec<String> = events.iter().map(|event| event.as_str().to_string()).collect();
+        events.sort();
+        events.dedup();
+
+        Webhook { id: Uuid::new_v4(), url, secret, events, active: true, created_at: Utc::now() }
+    }
+}
+
+/// One event waiting in, or sent from, the outbox of a webhook
+#[derive(Clone, Debug, PartialEq, Serialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// A delivery claimed for sending, with what is needed to send it
+#[derive(Clone, Debug, FromRow)]
+pub struct PendingDelivery {
+    #[sqlx(flatten)]
+    pub delivery: WebhookDelivery,
+    pub url: String,
+    pub secret: String,
+}
+
+/// The result of sending a delivery once
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeliveryAttempt {
+    pub attempted_at: DateTime<Utc>,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    /// When to try again; `None` once the delivery succeeded or ran out of attempts
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+impl DeliveryAttempt {
+    pub fn status(&self) -> DeliveryStatus {
+        match (&self.error, self.retry_at) {
+            (None, _) => DeliveryStatus::Delivered,
+            (Some(_), Some(_)) => DeliveryStatus::Pending,
+            (Some(_), None) => DeliveryStatus::Failed,
+        }
+    }
+}
+
+/// When to retry a delivery that failed its `attempts`th attempt, with exponential backoff, or
+/// `None` when it has used up `max_attempts`.
+pub fn retry_at(attempts: i32, max_attempts: i32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if attempts >= max_attempts {
+        return None;
+    }
+
+    let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+    let delay = RETRY_BASE_DELAY_SECONDS.saturating_mul(1 << exponent).min(RETRY_MAX_DELAY_SECONDS);
+
+    Some(now + Duration::seconds(delay))
+}
+
+#[async_trait]
+pub trait WebhookDb {
+    async fn create_webhook(&mut self, webhook: &Webhook) -> Result<(), Box<dyn Error>>;
+    async fn list_webhooks(&mut self) -> Result<Vec<Webhook>, Box<dyn Error>>;
+    /// Also drops the webhook's outbox and delivery history
+    async fn delete_webhook(&mut self, id: Uuid) -> Result<(), Box<dyn Error>>;
+    /// Adds the event to the outbox of every active webhook subscribed to it. `event_key` identifies
+    /// the occurrence: an event that is already queued under the same key is not queued again.
+    /// Returns how many deliveries were queued.
+    async fn enqueue_webhook_event(
+        &mut self,
+        event: WebhookEvent,
+        event_key: &str,
+        payload: serde_json::Value,
+        now: DateTime<Utc>,
+    ) -> Result<u64, Box<dyn Error>>;
+    /// Claims up to `limit` pending deliveries that are due, oldest first. Claimed deliveries are
+    /// not handed out again before `lease_until`, so a worker that dies mid-send only delays them.
+    async fn claim_webhook_deliveries(&mut self, now: DateTime<Utc>, lease_until: DateTime<Utc>, limit: i64) -> Result<Vec<PendingDelivery>, Box<dyn Error>>;
+    async fn record_webhook_attempt(&mut self, delivery_id: Uuid, attempt: &DeliveryAttempt) -> Result<(), Box<dyn Error>>;
+    /// Newest deliveries first
+    async fn list_webhook_deliveries(
+        &mut self,
+        webhook_id: Option<Uuid>,
+        status: Option<DeliveryStatus>,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<WebhookDelivery>, Box<dyn Error>>;
+}
@@ -0,0 +1,138 @@
+use crate::bounded_context::domain::pagination::PasswordPage;
+use crate::bounded_context::domain::password::Password;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, RequestBuilder};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Page size asked for when reading every result. Listing routes only accept sizes below their
+/// `PAGINATION_MAX_SIZE`, which defaults to 20.
+pub const DEFAULT_PAGE_SIZE: u32 = 10;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Server responded with {0}: {1}")]
+    Status(u16, String),
+}
+
+/// An entry for `create_password`, already encrypted by the caller
+#[derive(Clone, Debug, Serialize)]
+pub struct NewEntry {
+    pub service: String,
+    pub username: Option<String>,
+    pub nonce: String,
+    pub cipher: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct DeleteInput {
+    id: Uuid,
+}
+
+/// Calls the password API. `base_url` includes the `/api` prefix, and `token` is sent as a
+/// bearer token for servers with `REQUIRE_API_TOKEN` set.
+#[derive(Clone, Debug)]
+pub struct ApiClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    page_size: u32,
+}
+
+impl ApiClient {
+    pub fn new(base_url: &str, token: Option<String>) -> ApiClient {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build API client");
+
+        ApiClient { client, base_url: base_url.trim_end_matches('/').to_string(), token, page_size: DEFAULT_PAGE_SIZE }
+    }
+
+    pub fn with_page_size(mut self, page_size: u32) -> ApiClient {
+        self.page_size = page_size;
+        self
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, ClientError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(ClientError::Status(status.as_u16(), message));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Follows `next_cursor` until every page of `path` has been read
+    async fn all_pages(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<Password>, ClientError> {
+        let page_size = self.page_size.to_string();
+        let mut passwords = Vec::new();
+        let mut cursor = None;
+        loop {
+            let mut request = self.request(Method::GET, path).query(query).query(&[("page_size", &page_size)]);
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("cursor", cursor)]);
+            }
+            let page: PasswordPage = Self::send(request).await?;
+            passwords.extend(page.items);
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(passwords),
+            }
+        }
+    }
+
+    /// Checks that the server is reachable and accepts the token
+    pub async fn status(&self) -> Result<(), ClientError> {
+        // `/status` is public, so listing tags is what proves the token works
+        Self::send::<serde_json::Value>(self.request(Method::GET, "/status")).await?;
+        Self::send::<serde_json::Value>(self.request(Method::GET, "/tag")).await?;
+
+        Ok(())
+    }
+
+    pub async fn create_password(&self, entry: &NewEntry) -> Result<(), ClientError> {
+        Self::send::<serde_json::Value>(self.request(Method::POST, "/password/create").json(entry)).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_password(&self, id: Uuid) -> Result<Password, ClientError> {
+        Self::send(self.request(Method::GET, "/password").query(&[("id", id.to_string())])).await
+    }
+
+    /// Every entry whose service, username or a URI contains `term`, ordered by service
+    pub async fn search_passwords(&self, term: &str) -> Result<Vec<Password>, ClientError> {
+        self.all_pages("/password/search", &[("search_term", term)]).await
+    }
+
+    /// Every entry, in the order named by a `SortBy` option such as `updated_at_desc`
+    pub async fn list_passwords(&self, sort_by: &str) -> Result<Vec<Password>, ClientError> {
+        self.all_pages("/password/passwords", &[("sort_by", sort_by)]).await
+    }
+
+    pub async fn delete_password(&self, id: Uuid) -> Result<(), ClientError> {
+        Self::send::<serde_json::Value>(self.request(Method::POST, "/password/delete").json(&DeleteInput { id })).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod api_client;
+pub mod session;
\ No newline at end of file
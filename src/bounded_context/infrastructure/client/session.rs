@@ -0,0 +1,134 @@
+use crate::bounded_context::domain::password::EncryptedText;
+use crate::bounded_context::utility::backup::KdfParams;
+use crate::bounded_context::utility::encryption::{derive_master_key, encrypt, try_decrypt};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use std::io::Write;
+use std::path::Path;
+
+pub const DEFAULT_IDLE_TIMEOUT_MINUTES: i64 = 15;
+
+/// Known plaintext encrypted with the derived key, so that a mistyped master password is caught
+/// at unlock instead of producing entries nobody can decrypt
+const KEY_CHECK: &str = "pw-key-check";
+
+/// What `pw login` saves: the server to call and how to derive and verify the master key.
+/// Nothing in it reveals the key.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientConfig {
+    pub server_url: String,
+    pub token: Option<String>,
+    pub kdf: KdfParams,
+    pub key_check: EncryptedText,
+    pub idle_timeout_minutes: i64,
+}
+
+impl ClientConfig {
+    pub fn new(server_url: &str, token: Option<String>, kdf: KdfParams, master_key: &str, idle_timeout_minutes: i64) -> ClientConfig {
+        let (nonce, cipher) = encrypt(master_key, KEY_CHECK.to_string());
+
+        ClientConfig {
+            server_url: server_url.trim_end_matches('/').to_string(),
+            token,
+            kdf,
+            key_check: EncryptedText { nonce, cipher },
+            idle_timeout_minutes,
+        }
+    }
+
+    /// Derives the master key from `password`. Returns `None` if the password is wrong.
+    pub fn unlock(&self, password: &str) -> Option<String> {
+        let master_key = derive_master_key(password, &self.kdf)?;
+        let check = try_decrypt(&master_key, &self.key_check.nonce, &self.key_check.cipher)?;
+
+        (check == KEY_CHECK).then_some(master_key)
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::minutes(self.idle_timeout_minutes)
+    }
+
+    /// Reads the config at `path`, `None` if there is none yet
+    pub fn load(path: &Path) -> Result<Option<ClientConfig>, String> {
+        read_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_private(path, &serde_json::to_vec_pretty(self).expect("Failed to serialize config"))
+    }
+}
+
+/// A derived master key cached between commands. It expires once it has gone unused for the
+/// idle timeout, and every use pushes the expiry back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeySession {
+    pub master_key: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl KeySession {
+    pub fn new(master_key: String, now: DateTime<Utc>, idle_timeout: Duration) -> KeySession {
+        KeySession { master_key, expires_at: now + idle_timeout }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn touch(&mut self, now: DateTime<Utc>, idle_timeout: Duration) {
+        self.expires_at = now + idle_timeout;
+    }
+
+    /// Reads the session at `path`. An expired session is deleted and reads as `None`.
+    pub fn load(path: &Path, now: DateTime<Utc>) -> Option<KeySession> {
+        let session: KeySession = read_json(path).ok().flatten()?;
+        if session.is_expired(now) {
+            KeySession::clear(path);
+            return None;
+        }
+
+        Some(session)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_private(path, &serde_json::to_vec(self).expect("Failed to serialize session"))
+    }
+
+    pub fn clear(path: &Path) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    match std::fs::read(path) {
+        Ok(data) => serde_json::from_slice(&data).map(Some).map_err(|err| format!("Invalid {}: {}", path.display(), err)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("Failed to read {}: {}", path.display(), err)),
+    }
+}
+
+/// Replaces the file at `path` with `contents`, readable only by the current user
+pub fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(dir)?;
+    }
+
+    // Written beside the target and renamed over it, so the file is never readable by others
+    // nor left half written
+    let temporary = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&temporary)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    #[cfg(unix)]
+    std::fs::set_permissions(&temporary, std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+
+    std::fs::rename(&temporary, path)
+}
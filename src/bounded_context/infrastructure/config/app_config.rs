@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 #[derive(Clone)]
 pub struct AppConfig {
     pub host: String,
@@ -10,6 +12,48 @@ pub struct AppConfig {
     
     pub pagination_default_size: u32,
     pub pagination_max_size: u32,
+
+    pub breach_dataset_path: Option<String>,
+
+    pub stale_after_days: i64,
+    pub weak_below_strength: i16,
+
+    /// Attachments are stored in this directory when set, in Postgres otherwise
+    pub attachment_dir: Option<String>,
+    pub attachment_max_size: u64,
+    pub attachment_entry_quota: u64,
+    /// Seconds an attachment upload may take, in place of the timeout other requests get
+    pub attachment_upload_timeout: u64,
+
+    /// Seconds between runs of the expired share sweeper
+    pub share_sweep_interval: u64,
+    /// Longest lifetime a share may be created with, in seconds
+    pub share_max_lifetime: i64,
+
+    /// Entries due for rotation within this many days are reported and notified
+    pub rotation_warning_days: i64,
+    /// Seconds between runs of the rotation reminder task
+    pub rotation_check_interval: u64,
+    /// Rotation reminders are posted here when set, logged otherwise
+    pub rotation_webhook_url: Option<String>,
+
+    /// Seconds between runs of the webhook dispatcher
+    pub webhook_dispatch_interval: u64,
+    /// Attempts before a webhook delivery is given up on
+    pub webhook_max_attempts: i32,
+
+    /// Changes buffered for each change feed client before it is told to resync
+    pub change_feed_capacity: usize,
+
+    /// Largest backup file accepted for restore, in bytes
+    pub backup_max_size: usize,
+
+    /// Requests must carry an API token minted with `password-admin mint-token`. Otherwise the
+    /// server relies on an authenticating proxy in front of it.
+    pub require_api_token: bool,
+    /// Peers whose `X-Forwarded-For` and `X-Actor` headers are believed. Requests from any other
+    /// address are recorded under that address and as anonymous.
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl Default for AppConfig {
@@ -32,5 +76,37 @@ pub fn load_config() -> AppConfig {
     let pagination_default_size = std::env::var("PAGINATION_DEFAULT_SIZE").unwrap_or_else(|_| "20".to_string()).parse().unwrap_or(20);
     let pagination_max_size = std::env::var("PAGINATION_MAX_SIZE").unwrap_or_else(|_| "20".to_string()).parse().unwrap_or(20);
 
-    AppConfig { host, port, db_url, test_db_url, max_connections, log_level, graceful_shutdown_time, pagination_default_size, pagination_max_size }
+    let breach_dataset_path = std::env::var("BREACH_DATASET_PATH").ok().filter(|path| !path.is_empty());
+
+    let stale_after_days = std::env::var("STALE_AFTER_DAYS").unwrap_or_else(|_| "365".to_string()).parse().unwrap_or(365);
+    let weak_below_strength = std::env::var("WEAK_BELOW_STRENGTH").unwrap_or_else(|_| "3".to_string()).parse().unwrap_or(3);
+
+    let attachment_dir = std::env::var("ATTACHMENT_DIR").ok().filter(|dir| !dir.is_empty());
+    let attachment_max_size = std::env::var("ATTACHMENT_MAX_SIZE").unwrap_or_else(|_| "10485760".to_string()).parse().unwrap_or(10 * 1024 * 1024);
+    let attachment_entry_quota = std::env::var("ATTACHMENT_ENTRY_QUOTA").unwrap_or_else(|_| "52428800".to_string()).parse().unwrap_or(50 * 1024 * 1024);
+    let attachment_upload_timeout = std::env::var("ATTACHMENT_UPLOAD_TIMEOUT").unwrap_or_else(|_| "300".to_string()).parse().unwrap_or(300);
+
+    let share_sweep_interval = std::env::var("SHARE_SWEEP_INTERVAL").unwrap_or_else(|_| "60".to_string()).parse().unwrap_or(60);
+    let share_max_lifetime = std::env::var("SHARE_MAX_LIFETIME").unwrap_or_else(|_| "2592000".to_string()).parse().unwrap_or(30 * 24 * 60 * 60);
+
+    let rotation_warning_days = std::env::var("ROTATION_WARNING_DAYS").unwrap_or_else(|_| "14".to_string()).parse().unwrap_or(14);
+    let rotation_check_interval = std::env::var("ROTATION_CHECK_INTERVAL").unwrap_or_else(|_| "3600".to_string()).parse().unwrap_or(3600);
+    let rotation_webhook_url = std::env::var("ROTATION_WEBHOOK_URL").ok().filter(|url| !url.is_empty());
+
+    let webhook_dispatch_interval = std::env::var("WEBHOOK_DISPATCH_INTERVAL").unwrap_or_else(|_| "10".to_string()).parse().unwrap_or(10);
+    let webhook_max_attempts = std::env::var("WEBHOOK_MAX_ATTEMPTS").unwrap_or_else(|_| "8".to_string()).parse().unwrap_or(8);
+
+    let change_feed_capacity = std::env::var("CHANGE_FEED_CAPACITY").unwrap_or_else(|_| "1024".to_string()).parse().unwrap_or(1024);
+
+    let backup_max_size = std::env::var("BACKUP_MAX_SIZE").unwrap_or_else(|_| "104857600".to_string()).parse().unwrap_or(100 * 1024 * 1024);
+
+    let require_api_token = std::env::var("REQUIRE_API_TOKEN").unwrap_or_else(|_| "false".to_string()).parse().unwrap_or(false);
+
+    let trusted_proxies = std::env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|proxy| proxy.trim().parse().ok())
+        .collect();
+
+    AppConfig { host, port, db_url, test_db_url, max_connections, log_level, graceful_shutdown_time, pagination_default_size, pagination_max_size, breach_dataset_path, stale_after_days, weak_below_strength, attachment_dir, attachment_max_size, attachment_entry_quota, attachment_upload_timeout, share_sweep_interval, share_max_lifetime, rotation_warning_days, rotation_check_interval, rotation_webhook_url, webhook_dispatch_interval, webhook_max_attempts, change_feed_capacity, backup_max_size, require_api_token, trusted_proxies }
 }
\ No newline at end of file
@@ -0,0 +1,296 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::attachment::{Attachment, AttachmentDb, AttachmentError, BlobStream};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use sqlx::{FromRow, Postgres, Transaction, query, query_as, query_scalar};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+const STORAGE_POSTGRES: &str = "postgres";
+const STORAGE_FILESYSTEM: &str = "filesystem";
+/// Blobs in Postgres are stored as rows of this size, so uploads and downloads never hold a whole file
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(FromRow)]
+struct StoredAttachment {
+    #[sqlx(flatten)]
+    attachment: Attachment,
+    storage: String,
+}
+
+fn blob_path(dir: &str, id: Uuid) -> PathBuf {
+    Path::new(dir).join(id.to_string())
+}
+
+async fn insert_chunk(tx: &mut Transaction<'_, Postgres>, id: Uuid, seq: i32, data: &[u8]) -> Result<(), BoxError> {
+    query("INSERT INTO attachment_chunks (attachment_id, seq, data) VALUES ($1, $2, $3)")
+        .bind(id)
+        .bind(seq)
+        .bind(data)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// Copies a spooled blob into chunk rows.
+async fn insert_chunks(tx: &mut Transaction<'_, Postgres>, id: Uuid, path: &Path) -> Result<(), BoxError> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut seq = 0;
+
+    loop {
+        let mut filled = 0;
+        while filled < CHUNK_SIZE {
+            let read = file.read(&mut buffer[filled..]).await?;
+            if read == 0 {
+                break;
+            }
+            filled += read;
+        }
+        if filled == 0 {
+            return Ok(());
+        }
+
+        insert_chunk(tx, id, seq, &buffer[..filled]).await?;
+        seq += 1;
+    }
+}
+
+/// Reads chunk rows one at a time as the stream is polled.
+fn chunk_stream(pool: sqlx::PgPool, id: Uuid) -> BlobStream {
+    Box::pin(stream::try_unfold(0i32, move |seq| {
+        let pool = pool.clone();
+        async move {
+            let data: Option<Vec<u8>> = query_scalar("SELECT data FROM attachment_chunks WHERE attachment_id = $1 AND seq = $2")
+                .bind(id)
+                .bind(seq)
+                .fetch_optional(&pool)
+                .await
+                .map_err(std::io::Error::other)?;
+
+            Ok(data.map(|data| (Bytes::from(data), seq + 1)))
+        }
+    }))
+}
+
+async fn insert_attachment(tx: &mut Transaction<'_, Postgres>, attachment: &Attachment, storage: &str) -> Result<(), BoxError> {
+    query(
+        r#"
+        INSERT INTO attachments (id, password_id, file_name, content_type, size, storage, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(attachment.id)
+    .bind(attachment.password_id)
+    .bind(&attachment.file_name)
+    .bind(&attachment.content_type)
+    .bind(attachment.size)
+    .bind(storage)
+    .bind(attachment.created_at)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Checks that the attachment fits the entry's quota. Locking the entry serializes uploads to it,
+/// so concurrent uploads cannot overrun the quota together.
+async fn check_quota(tx: &mut Transaction<'_, Postgres>, attachment: &Attachment, quota: u64) -> Result<(), BoxError> {
+    // NO KEY UPDATE does not conflict with the key share lock taken by inserting an attachment row
+    let entry: Option<Uuid> = query_scalar("SELECT id FROM passwords WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(attachment.password_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+    if entry.is_none() {
+        return Err(Box::new(AttachmentError::PasswordNotFound(attachment.password_id)));
+    }
+
+    let used: i64 = query_scalar("SELECT COALESCE(SUM(size), 0)::BIGINT FROM attachments WHERE password_id = $1 AND id <> $2")
+        .bind(attachment.password_id)
+        .bind(attachment.id)
+        .fetch_one(&mut **tx)
+        .await?;
+    if (used + attachment.size) as u64 > quota {
+        return Err(Box::new(AttachmentError::QuotaExceeded(quota)));
+    }
+
+    Ok(())
+}
+
+async fn receive_to_file(mut body: BlobStream, max_size: u64, path: &Path) -> Result<u64, BoxError> {
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut size = 0u64;
+
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        size += chunk.len() as u64;
+        if size > max_size {
+            return Err(Box::new(AttachmentError::TooLarge(max_size)));
+        }
+        file.write_all(&chunk).await?;
+    }
+    file.sync_all().await?;
+
+    Ok(size)
+}
+
+/// Removes blob files, ignoring ones that are already gone.
+pub(super) async fn remove_blob_files(dir: Option<&str>, ids: &[Uuid]) -> Result<(), BoxError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let dir = dir.ok_or(AttachmentError::StorageNotConfigured)?;
+
+    for id in ids {
+        match tokio::fs::remove_file(blob_path(dir, *id)).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(Box::new(err)),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+impl Database {
+    /// Spools the blob to a temporary file first, so that a slow upload does not hold a pooled
+    /// connection. The transaction only covers the quota check and the chunk inserts.
+    async fn store_in_postgres(&self, attachment: &mut Attachment, body: BlobStream) -> Result<(), BoxError> {
+        let spooled = std::env::temp_dir().join(format!("attachment-{}.part", attachment.id));
+
+        let result = self.commit_chunks(attachment, body, &spooled).await;
+        let _ = tokio::fs::remove_file(&spooled).await;
+
+        result
+    }
+
+    async fn commit_chunks(&self, attachment: &mut Attachment, body: BlobStream, spooled: &Path) -> Result<(), BoxError> {
+        attachment.size = receive_to_file(body, self.config.attachment_max_size, spooled).await? as i64;
+
+        let mut tx = self.pool.begin().await?;
+        check_quota(&mut tx, attachment, self.config.attachment_entry_quota).await?;
+        insert_attachment(&mut tx, attachment, STORAGE_POSTGRES).await?;
+        insert_chunks(&mut tx, attachment.id, spooled).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Streams the blob to a partial file, renamed into place once the metadata is committed.
+    async fn store_on_filesystem(&self, dir: &str, attachment: &mut Attachment, body: BlobStream) -> Result<(), BoxError> {
+        tokio::fs::create_dir_all(dir).await?;
+        let partial = Path::new(dir).join(format!("{}.part", attachment.id));
+
+        let result = self.commit_file(dir, attachment, body, &partial).await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&partial).await;
+        }
+
+        result
+    }
+
+    async fn commit_file(&self, dir: &str, attachment: &mut Attachment, body: BlobStream, partial: &Path) -> Result<(), BoxError> {
+        attachment.size = receive_to_file(body, self.config.attachment_max_size, partial).await? as i64;
+
+        let mut tx = self.pool.begin().await?;
+        check_quota(&mut tx, attachment, self.config.attachment_entry_quota).await?;
+        insert_attachment(&mut tx, attachment, STORAGE_FILESYSTEM).await?;
+
+        let path = blob_path(dir, attachment.id);
+        tokio::fs::rename(partial, &path).await?;
+        if let Err(err) = tx.commit().await {
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(Box::new(err));
+        }
+
+        Ok(())
+    }
+
+    async fn store_attachment(&self, mut attachment: Attachment, body: BlobStream) -> Result<Attachment, BoxError> {
+        let entry_exists: bool = query_scalar("SELECT EXISTS (SELECT 1 FROM passwords WHERE id = $1)")
+            .bind(attachment.password_id)
+            .fetch_one(&*self.pool)
+            .await?;
+        if !entry_exists {
+            return Err(Box::new(AttachmentError::PasswordNotFound(attachment.password_id)));
+        }
+
+        match self.config.attachment_dir.as_deref() {
+            Some(dir) => self.store_on_filesystem(dir, &mut attachment, body).await?,
+            None => self.store_in_postgres(&mut attachment, body).await?,
+        }
+
+        Ok(attachment)
+    }
+
+    async fn read_attachment(&self, id: Uuid) -> Result<(Attachment, BlobStream), BoxError> {
+        let stored: Option<StoredAttachment> = query_as(
+            "SELECT id, password_id, file_name, content_type, size, created_at, storage FROM attachments WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        let StoredAttachment { attachment, storage } = stored.ok_or(AttachmentError::NotFound(id))?;
+
+        let body: BlobStream = if storage == STORAGE_FILESYSTEM {
+            let dir = self.config.attachment_dir.as_deref().ok_or(AttachmentError::StorageNotConfigured)?;
+            let file = tokio::fs::File::open(blob_path(dir, id)).await?;
+            Box::pin(ReaderStream::with_capacity(file, CHUNK_SIZE))
+        } else {
+            chunk_stream((*self.pool).clone(), id)
+        };
+
+        Ok((attachment, body))
+    }
+
+    async fn remove_attachment(&self, id: Uuid) -> Result<(), BoxError> {
+        let storage: Option<String> = query_scalar("DELETE FROM attachments WHERE id = $1 RETURNING storage")
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+        match storage.as_deref() {
+            None => Err(Box::new(AttachmentError::NotFound(id))),
+            Some(STORAGE_FILESYSTEM) => remove_blob_files(self.config.attachment_dir.as_deref(), &[id]).await,
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl AttachmentDb for Database {
+    async fn save_attachment(&mut self, attachment: Attachment, body: BlobStream) -> Result<Attachment, Box<dyn Error>> {
+        self.store_attachment(attachment, body).await.map_err(|err| err as Box<dyn Error>)
+    }
+
+    async fn list_attachments(&mut self, password_id: Uuid) -> Result<Vec<Attachment>, Box<dyn Error>> {
+        let attachments = query_as(
+            r#"
+            SELECT id, password_id, file_name, content_type, size, created_at
+            FROM attachments
+            WHERE password_id = $1
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(password_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    async fn open_attachment(&mut self, id: Uuid) -> Result<(Attachment, BlobStream), Box<dyn Error>> {
+        self.read_attachment(id).await.map_err(|err| err as Box<dyn Error>)
+    }
+
+    async fn delete_attachment(&mut self, id: Uuid) -> Result<(), Box<dyn Error>> {
+        self.remove_attachment(id).await.map_err(|err| err as Box<dyn Error>)
+    }
+}
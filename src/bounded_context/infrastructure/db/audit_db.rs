@@ -0,0 +1,90 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::audit::{AuditDb, AuditEvent, AuditFilter, NewAuditEvent, GENESIS_HASH};
+use crate::bounded_context::domain::pagination::Page;
+use async_trait::async_trait;
+use sqlx::{Postgres, QueryBuilder, query_as};
+use std::error::Error;
+
+const AUDIT_COLUMNS: &str = "seq, occurred_at, request_id, actor, action, entry_id, ip, user_agent, status, outcome, prev_hash, hash";
+
+/// Key of the advisory lock that serializes appends, so every event links to the one before it
+const AUDIT_CHAIN_LOCK: i64 = 0x0061_7564_6974;
+
+fn push_filter(builder: &mut QueryBuilder<'_, Postgres>, filter: &AuditFilter) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(actor) = &filter.actor {
+        builder.push(" AND actor = ").push_bind(actor.clone());
+    }
+    if let Some(action) = &filter.action {
+        builder.push(" AND action = ").push_bind(action.clone());
+    }
+    if let Some(entry_id) = filter.entry_id {
+        builder.push(" AND entry_id = ").push_bind(entry_id);
+    }
+    if let Some(outcome) = filter.outcome {
+        builder.push(" AND outcome = ").push_bind(outcome.as_str());
+    }
+    if let Some(request_id) = &filter.request_id {
+        builder.push(" AND request_id = ").push_bind(request_id.clone());
+    }
+    if let Some(after) = filter.after {
+        builder.push(" AND occurred_at >= ").push_bind(after);
+    }
+    if let Some(before) = filter.before {
+        builder.push(" AND occurred_at < ").push_bind(before);
+    }
+}
+
+#[async_trait]
+impl AuditDb for Database {
+    async fn append_audit_event(&mut self, event: NewAuditEvent) -> Result<AuditEvent, Box<dyn Error>> {
+        // A single statement, so the chain lock taken by `append_audit_event` is only held for the
+        // insert and the commit that follows it
+        let stored: AuditEvent = query_as(&format!(
+            "SELECT {} FROM append_audit_event($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            AUDIT_COLUMNS
+        ))
+        .bind(AUDIT_CHAIN_LOCK)
+        .bind(GENESIS_HASH)
+        .bind(event.chain_fields().into_bytes())
+        .bind(event.occurred_at)
+        .bind(&event.request_id)
+        .bind(&event.actor)
+        .bind(&event.action)
+        .bind(event.entry_id)
+        .bind(&event.ip)
+        .bind(&event.user_agent)
+        .bind(event.status)
+        .bind(&event.outcome)
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(stored)
+    }
+
+    async fn query_audit_events(&mut self, filter: &AuditFilter, page: u32, page_size: u32) -> Result<Page<AuditEvent>, Box<dyn Error>> {
+        let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM audit_events");
+        push_filter(&mut count, filter);
+        let total_count: i64 = count.build_query_scalar().fetch_one(&*self.pool).await?;
+
+        let mut builder = QueryBuilder::<Postgres>::new(format!("SELECT {} FROM audit_events", AUDIT_COLUMNS));
+        push_filter(&mut builder, filter);
+        builder
+            .push(" ORDER BY seq DESC LIMIT ")
+            .push_bind(page_size as i64)
+            .push(" OFFSET ")
+            .push_bind(page.saturating_sub(1) as i64 * page_size as i64);
+        let items: Vec<AuditEvent> = builder.build_query_as().fetch_all(&*self.pool).await?;
+
+        Ok(Page { items, total_count, page: Some(page), page_size, next_cursor: None })
+    }
+
+    async fn list_audit_chain(&mut self) -> Result<Vec<AuditEvent>, Box<dyn Error>> {
+        let events = query_as(&format!("SELECT {} FROM audit_events ORDER BY seq ASC", AUDIT_COLUMNS))
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,86 @@
+use super::postgres_db::{Database, PASSWORD_COLUMNS, insert_entry, update_entry};
+use crate::bounded_context::domain::backup::{BackupDb, BackupError, RestoreMode, RestoreReport, VaultBackup};
+use crate::bounded_context::domain::folder::Folder;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as};
+use std::collections::HashMap;
+use std::error::Error;
+use uuid::Uuid;
+
+#[async_trait]
+impl BackupDb for Database {
+    async fn export_vault(&mut self, now: DateTime<Utc>) -> Result<VaultBackup, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        // Folders and entries are read from the same snapshot, so entries never point at missing folders
+        query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ").execute(&mut *tx).await?;
+
+        let folders: Vec<Folder> = query_as("SELECT id, name, parent_id, created_at, updated_at FROM folders ORDER BY created_at ASC, id ASC")
+            .fetch_all(&mut *tx)
+            .await?;
+        let entries = query_as(&format!("SELECT {} FROM passwords ORDER BY created_at ASC, id ASC", PASSWORD_COLUMNS))
+            .fetch_all(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(VaultBackup { exported_at: now, folders, entries })
+    }
+
+    async fn restore_vault(&mut self, backup: &VaultBackup, mode: RestoreMode) -> Result<RestoreReport, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        let mut report = RestoreReport::default();
+
+        for folder in backup.folders_parents_first() {
+            let rows_affected = query(
+                r#"
+                INSERT INTO folders (id, name, parent_id, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (id) DO NOTHING
+                "#,
+            )
+            .bind(folder.id)
+            .bind(&folder.name)
+            .bind(folder.parent_id)
+            .bind(folder.created_at)
+            .bind(folder.updated_at)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            match (rows_affected, mode) {
+                (0, RestoreMode::PreserveIds) => return Err(Box::new(BackupError::FolderExists(folder.id))),
+                (0, RestoreMode::Merge) => {}
+                _ => report.folders_created += 1,
+            }
+        }
+
+        let ids: Vec<Uuid> = backup.entries.iter().map(|entry| entry.id).collect();
+        // The row locks keep entries from changing between the comparison and the write
+        let current: HashMap<Uuid, DateTime<Utc>> = query_as("SELECT id, updated_at FROM passwords WHERE id = ANY($1) FOR UPDATE")
+            .bind(&ids)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+        for entry in &backup.entries {
+            match (current.get(&entry.id), mode) {
+                (None, _) => {
+                    insert_entry(&mut tx, entry).await?;
+                    report.entries_created += 1;
+                }
+                (Some(_), RestoreMode::PreserveIds) => return Err(Box::new(BackupError::EntryExists(entry.id))),
+                (Some(updated_at), RestoreMode::Merge) if entry.updated_at > *updated_at => {
+                    update_entry(&mut tx, entry).await?;
+                    report.entries_updated += 1;
+                }
+                (Some(_), RestoreMode::Merge) => report.entries_unchanged += 1,
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+}
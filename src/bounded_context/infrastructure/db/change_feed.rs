@@ -0,0 +1,68 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::change_feed::{EntryChange, FeedMessage};
+use sqlx::postgres::PgListener;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{warn, error};
+
+/// Channel the `passwords` trigger notifies on
+pub const CHANGE_CHANNEL: &str = "password_changes";
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Fans entry changes out to every client connected to this server
+#[derive(Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<FeedMessage>,
+}
+
+impl ChangeFeed {
+    /// Subscribers that fall more than `capacity` messages behind are told to resync
+    pub fn new(capacity: usize) -> ChangeFeed {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        ChangeFeed { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<FeedMessage> {
+        self.sender.subscribe()
+    }
+
+    /// Returns how many subscribers were sent the message
+    pub fn publish(&self, message: FeedMessage) -> usize {
+        self.sender.send(message).unwrap_or(0)
+    }
+}
+
+/// Starts listening for changes. Every server instance listens, so changes made through any of
+/// them, or directly in the database, reach all clients.
+pub async fn listen_for_changes(database: &Database) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(database.get_pool()).await?;
+    listener.listen(CHANGE_CHANNEL).await?;
+
+    Ok(listener)
+}
+
+/// Publishes every change notification to the feed until the server shuts down. Notifications
+/// sent while the connection is being re-established are lost, so subscribers are told to resync.
+pub async fn forward_changes(listener: PgListener, feed: ChangeFeed) {
+    let mut listener = listener;
+
+    loop {
+        match listener.try_recv().await {
+            Ok(Some(notification)) => match serde_json::from_str::<EntryChange>(notification.payload()) {
+                Ok(change) => {
+                    feed.publish(FeedMessage::Change(change));
+                }
+                Err(err) => error!("Invalid change notification {}: {}", notification.payload(), err),
+            },
+            Ok(None) => {
+                warn!("Change feed connection lost, reconnecting");
+                feed.publish(FeedMessage::Resync);
+            }
+            Err(err) => {
+                error!("Change feed failed to reconnect: {}", err);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::emergency_access::{
+    EmergencyAccess, EmergencyAccessAction, EmergencyAccessDb, EmergencyAccessError, EmergencyAccessEvent, EmergencyAccessStatus,
+};
+use crate::bounded_context::utility::token::hash_token;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, query, query_as};
+use std::error::Error;
+use uuid::Uuid;
+
+const EMERGENCY_ACCESS_COLUMNS: &str = "id, contact, token_hash, status, wait_hours, requested_at, created_at, updated_at";
+
+async fn record_event(
+    conn: &mut PgConnection,
+    id: Uuid,
+    action: EmergencyAccessAction,
+    from_status: Option<EmergencyAccessStatus>,
+    to_status: EmergencyAccessStatus,
+    occurred_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    query(
+        r#"
+        INSERT INTO emergency_access_events (emergency_access_id, action, from_status, to_status, occurred_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(id)
+    .bind(action.as_str())
+    .bind(from_status.map(|status| status.as_str()))
+    .bind(to_status.as_str())
+    .bind(occurred_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Grants every request whose waiting period ran out before `now`, recording the approval at
+/// the moment the wait ended rather than when it was noticed.
+async fn settle_elapsed_requests(conn: &mut PgConnection, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    let pending: Vec<EmergencyAccess> = query_as(&format!(
+        "SELECT {} FROM emergency_access WHERE status = 'requested' FOR UPDATE",
+        EMERGENCY_ACCESS_COLUMNS
+    ))
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for access in pending.iter().filter(|access| access.wait_elapsed(now)) {
+        let granted_at = access.grants_at().unwrap_or(now);
+
+        query("UPDATE emergency_access SET status = $2, updated_at = $3 WHERE id = $1")
+            .bind(access.id)
+            .bind(EmergencyAccessStatus::Granted.as_str())
+            .bind(granted_at)
+            .execute(&mut *conn)
+            .await?;
+        record_event(
+            conn,
+            access.id,
+            EmergencyAccessAction::AutoApprove,
+            Some(access.status),
+            EmergencyAccessStatus::Granted,
+            granted_at,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+enum Lookup<'a> {
+    Id(Uuid),
+    Token(&'a str),
+}
+
+async fn lock_access(conn: &mut PgConnection, lookup: Lookup<'_>) -> Result<Option<EmergencyAccess>, sqlx::Error> {
+    match lookup {
+        Lookup::Id(id) => {
+            query_as(&format!("SELECT {} FROM emergency_access WHERE id = $1 FOR UPDATE", EMERGENCY_ACCESS_COLUMNS))
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        }
+        Lookup::Token(token) => {
+            query_as(&format!("SELECT {} FROM emergency_access WHERE token_hash = $1 FOR UPDATE", EMERGENCY_ACCESS_COLUMNS))
+                .bind(hash_token(token))
+                .fetch_optional(conn)
+                .await
+        }
+    }
+}
+
+impl Database {
+    async fn transition(&self, lookup: Lookup<'_>, action: EmergencyAccessAction, now: DateTime<Utc>) -> Result<EmergencyAccess, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        settle_elapsed_requests(&mut tx, now).await?;
+
+        let mut access = lock_access(&mut tx, lookup).await?.ok_or(EmergencyAccessError::NotFound)?;
+
+        let from_status = access.status;
+        access.status = action
+            .apply(from_status)
+            .ok_or(EmergencyAccessError::InvalidTransition { status: from_status, action })?;
+        access.updated_at = now;
+        access.requested_at = match action {
+            EmergencyAccessAction::Request => Some(now),
+            EmergencyAccessAction::Reject => None,
+            _ => access.requested_at,
+        };
+
+        query("UPDATE emergency_access SET status = $2, requested_at = $3, updated_at = $4 WHERE id = $1")
+            .bind(access.id)
+            .bind(access.status.as_str())
+            .bind(access.requested_at)
+            .bind(access.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        record_event(&mut tx, access.id, action, Some(from_status), access.status, now).await?;
+
+        tx.commit().await?;
+
+        Ok(access)
+    }
+}
+
+#[async_trait]
+impl EmergencyAccessDb for Database {
+    async fn create_emergency_access(&mut self, access: EmergencyAccess) -> Result<(), Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+
+        query(
+            r#"
+            INSERT INTO emergency_access (id, contact, token_hash, status, wait_hours, requested_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(access.id)
+        .bind(&access.contact)
+        .bind(&access.token_hash)
+        .bind(access.status.as_str())
+        .bind(access.wait_hours)
+        .bind(access.requested_at)
+        .bind(access.created_at)
+        .bind(access.updated_at)
+        .execute(&mut *tx)
+        .await?;
+        record_event(&mut tx, access.id, EmergencyAccessAction::Invite, None, access.status, access.created_at).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn list_emergency_access(&mut self, now: DateTime<Utc>) -> Result<Vec<EmergencyAccess>, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        settle_elapsed_requests(&mut tx, now).await?;
+
+        let accesses = query_as(&format!(
+            "SELECT {} FROM emergency_access ORDER BY created_at ASC, id ASC",
+            EMERGENCY_ACCESS_COLUMNS
+        ))
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(accesses)
+    }
+
+    async fn transition_emergency_access(&mut self, id: Uuid, action: EmergencyAccessAction, now: DateTime<Utc>) -> Result<EmergencyAccess, Box<dyn Error>> {
+        self.transition(Lookup::Id(id), action, now).await
+    }
+
+    async fn transition_emergency_access_by_token(&mut self, token: &str, action: EmergencyAccessAction, now: DateTime<Utc>) -> Result<EmergencyAccess, Box<dyn Error>> {
+        self.transition(Lookup::Token(token), action, now).await
+    }
+
+    async fn authorize_emergency_access(&mut self, token: &str, now: DateTime<Utc>) -> Result<EmergencyAccess, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        settle_elapsed_requests(&mut tx, now).await?;
+
+        let access = lock_access(&mut tx, Lookup::Token(token)).await?.ok_or(EmergencyAccessError::NotFound)?;
+
+        tx.commit().await?;
+
+        if access.status == EmergencyAccessStatus::Granted {
+            Ok(access)
+        } else {
+            Err(Box::new(EmergencyAccessError::NotGranted))
+        }
+    }
+
+    async fn list_emergency_access_events(&mut self, id: Uuid) -> Result<Vec<EmergencyAccessEvent>, Box<dyn Error>> {
+        let events = query_as(
+            r#"
+            SELECT id, emergency_access_id, action, from_status, to_status, occurred_at
+            FROM emergency_access_events
+            WHERE emergency_access_id = $1
+            ORDER BY occurred_at ASC, id ASC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::bounded_context::domain::{breach::BreachedHash, breach_db::BreachDb};
+use crate::bounded_context::utility::breach::{is_valid_hash_prefix, is_valid_sha1_hex, HASH_PREFIX_SIZE};
+
+const PREFIX_COUNT: usize = 1 << (4 * HASH_PREFIX_SIZE);
+
+enum Dataset {
+    /// A single file of `HASH:COUNT` lines sorted by hash, with the byte offset where each prefix starts.
+    SortedFile { path: PathBuf, offsets: Vec<u64> },
+    /// A directory of `PREFIX.txt` files holding `SUFFIX:COUNT` lines, as produced by the HIBP downloader.
+    RangeDirectory { files: HashMap<u32, PathBuf> },
+}
+
+/// Lookup index over a locally downloaded Have I Been Pwned dataset.
+#[derive(Clone)]
+pub struct HibpIndex {
+    dataset: Arc<Dataset>,
+}
+
+impl HibpIndex {
+    /// Builds the index for a sorted hash file or a directory of range files
+    pub fn build(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let dataset = if path.is_dir() {
+            Dataset::RangeDirectory { files: index_range_directory(path)? }
+        } else {
+            Dataset::SortedFile { path: path.to_path_buf(), offsets: index_sorted_file(path)? }
+        };
+
+        Ok(Self { dataset: Arc::new(dataset) })
+    }
+
+    /// Number of prefixes that have at least one breached hash
+    pub fn prefix_count(&self) -> usize {
+        match &*self.dataset {
+            Dataset::SortedFile { offsets, .. } => offsets.windows(2).filter(|w| w[0] != w[1]).count(),
+            Dataset::RangeDirectory { files } => files.len(),
+        }
+    }
+}
+
+impl BreachDb for HibpIndex {
+    fn range(&self, prefix: &str) -> Result<Vec<BreachedHash>, Box<dyn Error>> {
+        if !is_valid_hash_prefix(prefix) {
+            return Err(Box::new(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid hash prefix: {}", prefix))));
+        }
+        let key = u32::from_str_radix(prefix, 16)?;
+
+        match &*self.dataset {
+            Dataset::SortedFile { path, offsets } => {
+                let start = offsets[key as usize];
+                let end = offsets[key as usize + 1];
+                if start == end {
+                    return Ok(Vec::new());
+                }
+
+                let mut file = File::open(path)?;
+                file.seek(SeekFrom::Start(start))?;
+                let mut chunk = String::new();
+                file.take(end - start).read_to_string(&mut chunk)?;
+
+                let mut hashes = Vec::new();
+                for line in chunk.lines() {
+                    if let Some((hash, count)) = parse_line(line)? {
+                        hashes.push(BreachedHash::new(hash[HASH_PREFIX_SIZE..].to_string(), count));
+                    }
+                }
+                Ok(hashes)
+            }
+            Dataset::RangeDirectory { files } => {
+                let Some(path) = files.get(&key) else {
+                    return Ok(Vec::new());
+                };
+
+                let mut hashes = Vec::new();
+                for line in BufReader::new(File::open(path)?).lines() {
+                    if let Some((suffix, count)) = parse_line(&line?)? {
+                        hashes.push(BreachedHash::new(suffix, count));
+                    }
+                }
+                Ok(hashes)
+            }
+        }
+    }
+}
+
+fn index_range_directory(dir: &Path) -> io::Result<HashMap<u32, PathBuf>> {
+    let mut files = HashMap::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if path.is_file() && is_valid_hash_prefix(stem) {
+            files.insert(u32::from_str_radix(stem, 16).expect("validated hex prefix"), path);
+        }
+    }
+
+    Ok(files)
+}
+
+fn index_sorted_file(path: &Path) -> io::Result<Vec<u64>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut offsets = Vec::with_capacity(PREFIX_COUNT + 1);
+    let mut line = String::new();
+    let mut position = 0u64;
+
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            break;
+        }
+
+        if let Some((hash, _)) = parse_line(&line)? {
+            if !is_valid_sha1_hex(&hash) {
+                return Err(invalid_data(format!("Invalid SHA-1 hash at byte {}", position)));
+            }
+            let key = u32::from_str_radix(&hash[..HASH_PREFIX_SIZE], 16).expect("validated hex hash") as usize;
+            if key + 1 < offsets.len() {
+                return Err(invalid_data(format!("Dataset is not sorted at byte {}", position)));
+            }
+            while offsets.len() <= key {
+                offsets.push(position);
+            }
+        }
+
+        position += read as u64;
+    }
+
+    offsets.resize(PREFIX_COUNT + 1, position);
+    Ok(offsets)
+}
+
+/// Parses a `HASH:COUNT` line, skipping blank lines
+fn parse_line(line: &str) -> io::Result<Option<(String, u64)>> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let (hash, count) = line
+        .split_once(':')
+        .ok_or_else(|| invalid_data(format!("Malformed dataset line: {}", line)))?;
+    let count = count
+        .trim()
+        .parse()
+        .map_err(|_| invalid_data(format!("Malformed breach count: {}", line)))?;
+
+    Ok(Some((hash.trim().to_uppercase(), count)))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,27 @@
+use super::postgres_db::{Database, PASSWORD_COLUMNS, update_entry};
+use crate::bounded_context::domain::key_rotation::{KeyRotationDb, reencrypt};
+use crate::bounded_context::domain::password::Password;
+use async_trait::async_trait;
+use sqlx::query_as;
+use std::error::Error;
+
+#[async_trait]
+impl KeyRotationDb for Database {
+    async fn rotate_master_key(&mut self, old_key: &str, new_key: &str) -> Result<usize, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+
+        // The row locks keep entries saved under the old key from being overwritten mid rotation
+        let passwords: Vec<Password> = query_as(&format!("SELECT {} FROM passwords ORDER BY id FOR UPDATE", PASSWORD_COLUMNS))
+            .fetch_all(&mut *tx)
+            .await?;
+
+        for password in &passwords {
+            let rotated = reencrypt(password, old_key, new_key)?;
+            update_entry(&mut tx, &rotated).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(passwords.len())
+    }
+}
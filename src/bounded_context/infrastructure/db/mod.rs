@@ -1 +1,14 @@
-pub mod postgres_db;
\ No newline at end of file
+pub mod postgres_db;
+pub mod hibp_index;
+pub mod attachment_db;
+pub mod share_db;
+pub mod emergency_access_db;
+pub mod rotation_db;
+pub mod audit_db;
+pub mod webhook_db;
+pub mod change_feed;
+pub mod sync_db;
+pub mod backup_db;
+pub mod user_db;
+pub mod key_rotation_db;
+pub mod stats_db;
\ No newline at end of file
@@ -1,16 +1,371 @@
 use sqlx::postgres::PgPoolOptions;
-use sqlx::{PgPool, query, query_as};
+use sqlx::{Acquire, PgConnection, PgPool, QueryBuilder, query, query_as, query_scalar};
 use sqlx::pool::PoolConnection;
 use sqlx::Postgres;
 use uuid::Uuid;
+use chrono::{DateTime, Utc};
 use async_trait::async_trait;
 use std::sync::Arc;
-use crate::bounded_context::domain::{password::Password, password_db::PasswordDb, password_db::SortBy};
+use sqlx::types::Json;
+use crate::bounded_context::domain::{password::Password, password_db::PasswordDb, password_db::SortBy, password_db::EntryFilter, password_db::BatchMode};
+use crate::bounded_context::domain::folder::{Folder, FolderError, TagCount, normalize_tag};
+use crate::bounded_context::domain::pagination::{Cursor, Page, PageRequest, PasswordPage};
+use crate::bounded_context::domain::fuzzy_search::FuzzyMatch;
 use crate::bounded_context::infrastructure::config::app_config::AppConfig;
+use super::attachment_db::remove_blob_files;
+
+/// Columns read by `Password::from_row`
+pub(super) const PASSWORD_COLUMNS: &str = "id, service, nonce, cipher, created_at, updated_at, fingerprint, strength, \
+    username, uris, uri_match_strategies, notes_nonce, notes_cipher, totp_nonce, totp_cipher, custom_fields, \
+    item_data, folder_id, \
+    favorite, last_used_at, rotate_by, rotation_interval_days, \
+    ARRAY(SELECT tags.name FROM password_tags JOIN tags ON tags.id = password_tags.tag_id \
+        WHERE password_tags.password_id = passwords.id ORDER BY tags.name) AS tags";
+
+/// Key of the advisory lock that serializes folder moves, so two moves cannot pass the cycle
+/// check together and commit a cycle between them
+const FOLDER_MOVE_LOCK: i64 = 0x666f_6c64_6572;
+
+/// Escapes the LIKE wildcards in `term` and wraps it in a substring pattern.
+fn contains_pattern(term: &str) -> String {
+    let escaped = term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Appends a `WHERE` clause matching `filter`. Every value is sent as a bind parameter, only
+/// fixed SQL fragments are pushed as text.
+fn push_filter(builder: &mut QueryBuilder<'_, Postgres>, filter: &EntryFilter) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(term) = &filter.search_term {
+        let pattern = contains_pattern(term);
+        builder
+            .push(" AND (service ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR username ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR EXISTS (SELECT 1 FROM unnest(uris) AS uri WHERE uri ILIKE ")
+            .push_bind(pattern)
+            .push("))");
+    }
+    if let Some(service) = &filter.service {
+        builder.push(" AND service ILIKE ").push_bind(contains_pattern(service));
+    }
+    if let Some(username) = &filter.username {
+        builder.push(" AND username ILIKE ").push_bind(contains_pattern(username));
+    }
+    if let Some(uri) = &filter.uri {
+        builder
+            .push(" AND EXISTS (SELECT 1 FROM unnest(uris) AS uri WHERE uri ILIKE ")
+            .push_bind(contains_pattern(uri))
+            .push(")");
+    }
+    if let Some(item_type) = filter.item_type {
+        builder.push(" AND item_type = ").push_bind(item_type.as_str());
+    }
+    if let Some(folder_id) = filter.folder_id {
+        builder.push(" AND (folder_id = ").push_bind(folder_id);
+        if filter.include_subfolders {
+            builder
+                .push(
+                    r#" OR folder_id IN (
+                    WITH RECURSIVE subfolders AS (
+                        SELECT id FROM folders WHERE parent_id = "#,
+                )
+                .push_bind(folder_id)
+                .push(
+                    r#"
+                        UNION
+                        SELECT folders.id FROM folders JOIN subfolders ON folders.parent_id = subfolders.id
+                    )
+                    SELECT id FROM subfolders
+                )"#,
+                );
+        }
+        builder.push(")");
+    }
+    if let Some(tag) = &filter.tag {
+        builder
+            .push(
+                r#" AND EXISTS (
+                    SELECT 1 FROM password_tags JOIN tags ON tags.id = password_tags.tag_id
+                    WHERE password_tags.password_id = passwords.id AND tags.name = "#,
+            )
+            .push_bind(normalize_tag(tag))
+            .push(")");
+    }
+    if let Some(favorite) = filter.favorite {
+        builder.push(" AND favorite = ").push_bind(favorite);
+    }
+
+    let ranges = [
+        ("created_at", filter.created_after, filter.created_before),
+        ("updated_at", filter.updated_after, filter.updated_before),
+        ("last_used_at", filter.last_used_after, filter.last_used_before),
+    ];
+    for (column, after, before) in ranges {
+        if let Some(after) = after {
+            builder.push(format_args!(" AND {} >= ", column)).push_bind(after);
+        }
+        if let Some(before) = before {
+            builder.push(format_args!(" AND {} < ", column)).push_bind(before);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SortKey {
+    Favorite,
+    LastUsed,
+    CreatedAt,
+    UpdatedAt,
+    Service,
+    Id,
+}
+
+impl SortKey {
+    fn expression(self) -> &'static str {
+        match self {
+            SortKey::Favorite => "favorite",
+            // Never used entries sort after every used one in descending order
+            SortKey::LastUsed => "COALESCE(last_used_at, '-infinity'::TIMESTAMPTZ)",
+            SortKey::CreatedAt => "created_at",
+            SortKey::UpdatedAt => "updated_at",
+            SortKey::Service => "LOWER(service)",
+            SortKey::Id => "id",
+        }
+    }
+
+    /// Appends the cursor's value for this key, computed the same way as `expression`.
+    fn push_value(self, builder: &mut QueryBuilder<'_, Postgres>, cursor: &Cursor) {
+        match self {
+            SortKey::Favorite => builder.push_bind(cursor.favorite),
+            SortKey::LastUsed => builder
+                .push("COALESCE(")
+                .push_bind(cursor.last_used_at)
+                .push("::TIMESTAMPTZ, '-infinity'::TIMESTAMPTZ)"),
+            SortKey::CreatedAt => builder.push_bind(cursor.created_at),
+            SortKey::UpdatedAt => builder.push_bind(cursor.updated_at),
+            SortKey::Service => builder.push("LOWER(").push_bind(cursor.service.clone()).push(")"),
+            SortKey::Id => builder.push_bind(cursor.id),
+        };
+    }
+}
+
+/// Keys and whether they are descending, ending with the id so every entry has a distinct position.
+fn sort_keys(sort_by: &SortBy) -> &'static [(SortKey, bool)] {
+    match sort_by {
+        SortBy::CreatedAtAsc => &[(SortKey::CreatedAt, false), (SortKey::Id, false)],
+        SortBy::CreatedAtDesc => &[(SortKey::CreatedAt, true), (SortKey::Id, true)],
+        SortBy::UpdatedAtAsc => &[(SortKey::UpdatedAt, false), (SortKey::Id, false)],
+        SortBy::UpdatedAtDesc => &[(SortKey::UpdatedAt, true), (SortKey::Id, true)],
+        SortBy::LastUsedDesc => &[(SortKey::LastUsed, true), (SortKey::UpdatedAt, true), (SortKey::Id, true)],
+        SortBy::FavoritesFirst => &[
+            (SortKey::Favorite, true),
+            (SortKey::LastUsed, true),
+            (SortKey::UpdatedAt, true),
+            (SortKey::Id, true),
+        ],
+        SortBy::ServiceAsc => &[(SortKey::Service, false), (SortKey::CreatedAt, false), (SortKey::Id, false)],
+        SortBy::ServiceDesc => &[(SortKey::Service, true), (SortKey::CreatedAt, false), (SortKey::Id, false)],
+    }
+}
+
+fn push_order(builder: &mut QueryBuilder<'_, Postgres>, keys: &[(SortKey, bool)]) {
+    builder.push(" ORDER BY ");
+    let mut separated = builder.separated(", ");
+    for (key, descending) in keys {
+        separated.push(format_args!("{} {}", key.expression(), if *descending { "DESC" } else { "ASC" }));
+    }
+}
+
+/// Appends a condition matching the entries ordered after `cursor`. Keys may mix directions,
+/// so this spells out the lexicographic comparison instead of using a row comparison.
+fn push_after(builder: &mut QueryBuilder<'_, Postgres>, keys: &[(SortKey, bool)], cursor: &Cursor) {
+    builder.push(" AND (");
+    for (i, (key, descending)) in keys.iter().enumerate() {
+        if i > 0 {
+            builder.push(" OR ");
+        }
+        builder.push("(");
+        for (equal_key, _) in &keys[..i] {
+            builder.push(format_args!("{} = ", equal_key.expression()));
+            equal_key.push_value(builder, cursor);
+            builder.push(" AND ");
+        }
+        builder.push(format_args!("{} {} ", key.expression(), if *descending { "<" } else { ">" }));
+        key.push_value(builder, cursor);
+        builder.push(")");
+    }
+    builder.push(")");
+}
+
+pub(super) async fn attach_tag(conn: &mut PgConnection, password_id: Uuid, tag: &str) -> Result<(), sqlx::Error> {
+    let tag = normalize_tag(tag);
+
+    query("INSERT INTO tags (id, name) VALUES ($1, $2) ON CONFLICT (name) DO NOTHING")
+        .bind(Uuid::new_v4())
+        .bind(&tag)
+        .execute(&mut *conn)
+        .await?;
+
+    query(
+        r#"
+        INSERT INTO password_tags (password_id, tag_id)
+        SELECT $1, id FROM tags WHERE name = $2
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(password_id)
+    .bind(&tag)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub(super) async fn folder_exists(conn: &mut PgConnection, id: Uuid) -> Result<bool, sqlx::Error> {
+    query_scalar("SELECT EXISTS (SELECT 1 FROM folders WHERE id = $1)")
+        .bind(id)
+        .fetch_one(conn)
+        .await
+}
+
+/// Inserts an entry with its tags. Fails with `FolderError::NotFound` if its folder does not exist.
+pub(super) async fn insert_entry(conn: &mut PgConnection, password: &Password) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(folder_id) = password.folder_id {
+        if !folder_exists(conn, folder_id).await? {
+            return Err(Box::new(FolderError::NotFound(folder_id)));
+        }
+    }
+
+    query(
+        r#"
+        INSERT INTO passwords (id, service, nonce, cipher, created_at, updated_at, fingerprint, strength,
+            username, uris, uri_match_strategies, notes_nonce, notes_cipher, totp_nonce, totp_cipher,
+            custom_fields, item_type, item_data, folder_id, favorite, last_used_at, rotate_by, rotation_interval_days)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
+        "#,
+    )
+    .bind(password.id)
+    .bind(&password.service)
+    .bind(&password.nonce)
+    .bind(&password.cipher)
+    .bind(password.created_at)
+    .bind(password.updated_at)
+    .bind(&password.fingerprint)
+    .bind(password.strength)
+    .bind(&password.username)
+    .bind(password.uris.iter().map(|uri| uri.uri.clone()).collect::<Vec<_>>())
+    .bind(password.uris.iter().map(|uri| uri.match_strategy.as_str()).collect::<Vec<_>>())
+    .bind(password.notes.as_ref().map(|notes| notes.nonce.clone()))
+    .bind(password.notes.as_ref().map(|notes| notes.cipher.clone()))
+    .bind(password.totp.as_ref().map(|totp| totp.nonce.clone()))
+    .bind(password.totp.as_ref().map(|totp| totp.cipher.clone()))
+    .bind(Json(&password.custom_fields))
+    .bind(password.item.item_type().as_str())
+    .bind(Json(&password.item))
+    .bind(password.folder_id)
+    .bind(password.favorite)
+    .bind(password.last_used_at)
+    .bind(password.rotate_by)
+    .bind(password.rotation_interval_days)
+    .execute(&mut *conn)
+    .await?;
+
+    for tag in &password.tags {
+        attach_tag(conn, password.id, tag).await?;
+    }
+
+    Ok(())
+}
+
+/// Replaces every field of an entry a client can change, keeping when it was created and last used.
+/// Returns whether there was such an entry.
+pub(super) async fn update_entry(conn: &mut PgConnection, password: &Password) -> Result<bool, Box<dyn std::error::Error>> {
+    if let Some(folder_id) = password.folder_id {
+        if !folder_exists(conn, folder_id).await? {
+            return Err(Box::new(FolderError::NotFound(folder_id)));
+        }
+    }
+
+    let rows_affected = query(
+        r#"
+        UPDATE passwords
+        SET service = $2, nonce = $3, cipher = $4, updated_at = $5, fingerprint = $6, strength = $7,
+            username = $8, uris = $9, uri_match_strategies = $10, notes_nonce = $11, notes_cipher = $12,
+            totp_nonce = $13, totp_cipher = $14, custom_fields = $15, item_type = $16, item_data = $17,
+            folder_id = $18, favorite = $19, rotate_by = $20, rotation_interval_days = $21
+        WHERE id = $1
+        "#,
+    )
+    .bind(password.id)
+    .bind(&password.service)
+    .bind(&password.nonce)
+    .bind(&password.cipher)
+    .bind(password.updated_at)
+    .bind(&password.fingerprint)
+    .bind(password.strength)
+    .bind(&password.username)
+    .bind(password.uris.iter().map(|uri| uri.uri.clone()).collect::<Vec<_>>())
+    .bind(password.uris.iter().map(|uri| uri.match_strategy.as_str()).collect::<Vec<_>>())
+    .bind(password.notes.as_ref().map(|notes| notes.nonce.clone()))
+    .bind(password.notes.as_ref().map(|notes| notes.cipher.clone()))
+    .bind(password.totp.as_ref().map(|totp| totp.nonce.clone()))
+    .bind(password.totp.as_ref().map(|totp| totp.cipher.clone()))
+    .bind(Json(&password.custom_fields))
+    .bind(password.item.item_type().as_str())
+    .bind(Json(&password.item))
+    .bind(password.folder_id)
+    .bind(password.favorite)
+    .bind(password.rotate_by)
+    .bind(password.rotation_interval_days)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+    if rows_affected == 0 {
+        return Ok(false);
+    }
+
+    query("DELETE FROM password_tags WHERE password_id = $1")
+        .bind(password.id)
+        .execute(&mut *conn)
+        .await?;
+    for tag in &password.tags {
+        attach_tag(conn, password.id, tag).await?;
+    }
+
+    Ok(true)
+}
+
+/// Deletes an entry and returns the attachment files to remove once the transaction commits,
+/// or `None` if there was no such entry.
+pub(super) async fn delete_entry(conn: &mut PgConnection, id: Uuid) -> Result<Option<Vec<Uuid>>, sqlx::Error> {
+    // Attachment rows cascade with the entry, but files on disk have to be removed by hand
+    let attachment_files: Vec<Uuid> = query_scalar(
+        "SELECT id FROM attachments WHERE password_id = $1 AND storage = 'filesystem'",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let rows_affected = query(
+        r#"
+        DELETE FROM passwords
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    Ok((rows_affected > 0).then_some(attachment_files))
+}
 
 #[derive(Clone)]
 pub struct Database {
-    pool: Arc<PgPool>,
+    pub(super) pool: Arc<PgPool>,
     pub config: AppConfig,
 }
 
@@ -38,35 +393,120 @@ impl Database {
 #[async_trait]
 impl PasswordDb for Database {
     async fn save(&mut self, password: Password) -> Result<(), Box<dyn std::error::Error>> {
-        query(
-            r#"
-            INSERT INTO passwords (id, service, nonce, cipher, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            "#,
-        )
-        .bind(password.id)
-        .bind(password.service)
-        .bind(password.nonce)
-        .bind(password.cipher)
-        .bind(password.created_at)
-        .bind(password.updated_at)
-        .execute(&*self.pool)
-        .await?;
+        let mut tx = self.pool.begin().await?;
+        insert_entry(&mut tx, &password).await?;
+        tx.commit().await?;
 
         Ok(())
     }
 
+    async fn save_batch(&mut self, passwords: Vec<Password>, mode: BatchMode) -> Result<Vec<Result<(), String>>, Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+        let mut outcomes = Vec::with_capacity(passwords.len());
+        for password in &passwords {
+            // Each item runs in a savepoint so a failing one does not abort the transaction
+            let mut savepoint = tx.begin().await?;
+            match insert_entry(&mut savepoint, password).await.map_err(|err| err.to_string()) {
+                Ok(()) => {
+                    savepoint.commit().await?;
+                    outcomes.push(Ok(()));
+                }
+                Err(message) => {
+                    savepoint.rollback().await?;
+                    outcomes.push(Err(message));
+                }
+            }
+        }
+
+        if mode.commits(&outcomes) {
+            tx.commit().await?;
+        } else {
+            tx.rollback().await?;
+        }
+
+        Ok(outcomes)
+    }
+
+    async fn update_batch(&mut self, passwords: Vec<Password>, mode: BatchMode) -> Result<Vec<Result<(), String>>, Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+        let mut outcomes = Vec::with_capacity(passwords.len());
+        for password in &passwords {
+            let mut savepoint = tx.begin().await?;
+            match update_entry(&mut savepoint, password).await.map_err(|err| err.to_string()) {
+                Ok(true) => {
+                    savepoint.commit().await?;
+                    outcomes.push(Ok(()));
+                }
+                Ok(false) => {
+                    savepoint.rollback().await?;
+                    outcomes.push(Err(format!("Password not found: {}", password.id)));
+                }
+                Err(message) => {
+                    savepoint.rollback().await?;
+                    outcomes.push(Err(message));
+                }
+            }
+        }
+
+        if mode.commits(&outcomes) {
+            tx.commit().await?;
+        } else {
+            tx.rollback().await?;
+        }
+
+        Ok(outcomes)
+    }
+
+    async fn delete_batch(&mut self, ids: Vec<Uuid>, mode: BatchMode) -> Result<Vec<Result<(), String>>, Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+        let mut outcomes = Vec::with_capacity(ids.len());
+        let mut attachment_files = Vec::new();
+        for &id in &ids {
+            let mut savepoint = tx.begin().await?;
+            match delete_entry(&mut savepoint, id).await {
+                Ok(Some(files)) => {
+                    savepoint.commit().await?;
+                    attachment_files.extend(files);
+                    outcomes.push(Ok(()));
+                }
+                Ok(None) => {
+                    savepoint.rollback().await?;
+                    outcomes.push(Err(format!("Password not found: {}", id)));
+                }
+                Err(err) => {
+                    savepoint.rollback().await?;
+                    outcomes.push(Err(err.to_string()));
+                }
+            }
+        }
+
+        if !mode.commits(&outcomes) {
+            tx.rollback().await?;
+            return Ok(outcomes);
+        }
+        tx.commit().await?;
+
+        remove_blob_files(self.config.attachment_dir.as_deref(), &attachment_files)
+            .await
+            .map_err(|err| err as Box<dyn std::error::Error>)?;
+
+        Ok(outcomes)
+    }
+
     async fn get_by_id(&mut self, id: Uuid) -> Result<Password, Box<dyn std::error::Error>> {
-        let result: Option<Password> = query_as(
+        let query_str = format!(
             r#"
-            SELECT id, service, nonce, cipher, created_at, updated_at
+            SELECT {}
             FROM passwords
             WHERE id = $1
-            "#
-        )
-        .bind(id)
-        .fetch_optional(&*self.pool)
-        .await?;
+            "#,
+            PASSWORD_COLUMNS
+        );
+
+        let result: Option<Password> = query_as(&query_str)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await?;
 
         match result {
             Some(password) => Ok(password),
@@ -75,13 +515,158 @@ impl PasswordDb for Database {
     }
 
     async fn delete(&mut self, id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+        let Some(attachment_files) = delete_entry(&mut tx, id).await? else {
+            return Err(Box::new(sqlx::Error::RowNotFound));
+        };
+        tx.commit().await?;
+
+        remove_blob_files(self.config.attachment_dir.as_deref(), &attachment_files)
+            .await
+            .map_err(|err| err as Box<dyn std::error::Error>)
+    }
+
+    async fn search_by_service(
+        &mut self,
+        search_term: &str,
+        filter: &EntryFilter,
+        page: &PageRequest,
+    ) -> Result<PasswordPage, Box<dyn std::error::Error>> {
+        let filter = EntryFilter { search_term: Some(search_term.to_string()), ..filter.clone() };
+
+        self.query(&filter, &SortBy::ServiceAsc, page).await
+    }
+
+    async fn fuzzy_search(
+        &mut self,
+        search_term: &str,
+        threshold: f32,
+        filter: &EntryFilter,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<FuzzyMatch>, Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+
+        // `%` only uses the trigram index with the session threshold, so set it for this transaction
+        query("SELECT set_config('pg_trgm.similarity_threshold', $1, true)")
+            .bind(threshold.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM passwords");
+        push_filter(&mut count, filter);
+        count.push(" AND service % ").push_bind(search_term.to_string());
+        let total_count: i64 = count.build_query_scalar().fetch_one(&mut *tx).await?;
+
+        let mut builder = QueryBuilder::new(format!("SELECT {}, similarity(service, ", PASSWORD_COLUMNS));
+        builder.push_bind(search_term.to_string()).push(") AS score FROM passwords");
+        push_filter(&mut builder, filter);
+        builder
+            .push(" AND service % ")
+            .push_bind(search_term.to_string())
+            .push(" ORDER BY score DESC, LOWER(service) ASC, id ASC LIMIT ")
+            .push_bind(page_size as i64)
+            .push(" OFFSET ")
+            .push_bind(page.saturating_sub(1) as i64 * page_size as i64);
+
+        let items: Vec<FuzzyMatch> = builder.build_query_as().fetch_all(&mut *tx).await?;
+        tx.commit().await?;
+
+        Ok(Page { items, total_count, page: Some(page), page_size, next_cursor: None })
+    }
+
+    async fn list_sorted(
+        &mut self,
+        sort_by: &SortBy,
+        filter: &EntryFilter,
+        page: &PageRequest,
+    ) -> Result<PasswordPage, Box<dyn std::error::Error>> {
+        self.query(filter, sort_by, page).await
+    }
+
+    async fn query(
+        &mut self,
+        filter: &EntryFilter,
+        sort_by: &SortBy,
+        page: &PageRequest,
+    ) -> Result<PasswordPage, Box<dyn std::error::Error>> {
+        let keys = sort_keys(sort_by);
+        let page_size = page.page_size();
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM passwords");
+        push_filter(&mut count, filter);
+        let total_count: i64 = count.build_query_scalar().fetch_one(&*self.pool).await?;
+
+        let mut builder = QueryBuilder::new(format!("SELECT {} FROM passwords", PASSWORD_COLUMNS));
+        push_filter(&mut builder, filter);
+        if let PageRequest::Cursor { cursor: Some(cursor), .. } = page {
+            push_after(&mut builder, keys, cursor);
+        }
+        push_order(&mut builder, keys);
+
+        // One extra row tells whether another page follows
+        builder.push(" LIMIT ").push_bind(page_size as i64 + 1);
+        if let PageRequest::Offset { page, .. } = page {
+            builder.push(" OFFSET ").push_bind(page.saturating_sub(1) as i64 * page_size as i64);
+        }
+
+        let mut items: Vec<Password> = builder
+            .build_query_as()
+            .fetch_all(&*self.pool)
+            .await?;
+
+        let next_cursor = if items.len() > page_size as usize {
+            items.truncate(page_size as usize);
+            items.last().map(|last| Cursor::after(last, *sort_by).encode())
+        } else {
+            None
+        };
+
+        Ok(PasswordPage { items, total_count, page: page.page(), page_size, next_cursor })
+    }
+
+    async fn list_all(&mut self) -> Result<Vec<Password>, Box<dyn std::error::Error>> {
+        let query_str = format!(
+            r#"
+            SELECT {}
+            FROM passwords
+            ORDER BY updated_at ASC
+            "#,
+            PASSWORD_COLUMNS
+        );
+
+        let passwords = query_as(&query_str)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(passwords)
+    }
+
+    async fn set_favorite(&mut self, id: Uuid, favorite: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let rows_affected = query("UPDATE passwords SET favorite = $2 WHERE id = $1")
+            .bind(id)
+            .bind(favorite)
+            .execute(&*self.pool)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            Err(Box::new(sqlx::Error::RowNotFound))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn record_use(&mut self, id: Uuid, used_at: DateTime<Utc>) -> Result<(), Box<dyn std::error::Error>> {
         let rows_affected = query(
             r#"
-            DELETE FROM passwords
+            UPDATE passwords
+            SET last_used_at = GREATEST(last_used_at, $2)
             WHERE id = $1
             "#,
         )
         .bind(id)
+        .bind(used_at)
         .execute(&*self.pool)
         .await?
         .rows_affected();
@@ -93,64 +678,197 @@ impl PasswordDb for Database {
         }
     }
 
-    async fn search_by_service(
-        &mut self,
-        search_term: &str,
-        page: u32,
-        page_size: u32,
-    ) -> Result<Vec<Password>, Box<dyn std::error::Error>> {
-        let search_pattern = format!("%{}%", search_term);
-    
-        let offset = (page - 1) * page_size;
-    
-        let passwords = query_as(
+    async fn set_folder(&mut self, id: Uuid, folder_id: Option<Uuid>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut conn = self.pool.acquire().await?;
+
+        if let Some(folder_id) = folder_id {
+            if !folder_exists(&mut conn, folder_id).await? {
+                return Err(Box::new(FolderError::NotFound(folder_id)));
+            }
+        }
+
+        let rows_affected = query("UPDATE passwords SET folder_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(folder_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            Err(Box::new(sqlx::Error::RowNotFound))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn tag(&mut self, id: Uuid, tag: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+
+        let exists: bool = query_scalar("SELECT EXISTS (SELECT 1 FROM passwords WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if !exists {
+            return Err(Box::new(sqlx::Error::RowNotFound));
+        }
+
+        attach_tag(&mut tx, id, tag).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn untag(&mut self, id: Uuid, tag: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let rows_affected = query(
             r#"
-            SELECT id, service, nonce, cipher, created_at, updated_at
-            FROM passwords
-            WHERE service ILIKE $1
-            LIMIT $2 OFFSET $3
+            DELETE FROM password_tags
+            USING tags
+            WHERE password_tags.tag_id = tags.id
+                AND password_tags.password_id = $1
+                AND tags.name = $2
+            "#,
+        )
+        .bind(id)
+        .bind(normalize_tag(tag))
+        .execute(&*self.pool)
+        .await?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            Err(Box::new(sqlx::Error::RowNotFound))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn list_tags(&mut self) -> Result<Vec<TagCount>, Box<dyn std::error::Error>> {
+        let tags = query_as(
+            r#"
+            SELECT tags.name, COUNT(*) AS count
+            FROM tags
+            JOIN password_tags ON password_tags.tag_id = tags.id
+            GROUP BY tags.name
+            ORDER BY tags.name
             "#,
         )
-        .bind(search_pattern)
-        .bind(page_size as i64)
-        .bind(offset as i64)
         .fetch_all(&*self.pool)
         .await?;
-    
-        Ok(passwords)
+
+        Ok(tags)
     }
 
-    async fn list_sorted(
-        &mut self,
-        sort_by: &SortBy,
-        page: u32,
-        page_size: u32,
-    ) -> Result<Vec<Password>, Box<dyn std::error::Error>> {
-        let order_clause = match sort_by {
-            SortBy::CreatedAtAsc => "created_at ASC",
-            SortBy::CreatedAtDesc => "created_at DESC",
-            SortBy::UpdatedAtAsc => "updated_at ASC",
-            SortBy::UpdatedAtDesc => "updated_at DESC",
-        };
-    
-        let offset = (page - 1) * page_size;
-    
-        let query_str = format!(
+    async fn create_folder(&mut self, folder: Folder) -> Result<(), Box<dyn std::error::Error>> {
+        let mut conn = self.pool.acquire().await?;
+
+        if let Some(parent_id) = folder.parent_id {
+            if !folder_exists(&mut conn, parent_id).await? {
+                return Err(Box::new(FolderError::ParentNotFound(parent_id)));
+            }
+        }
+
+        query(
             r#"
-            SELECT id, service, nonce, cipher, created_at, updated_at
-            FROM passwords
-            ORDER BY {}
-            LIMIT $1 OFFSET $2
+            INSERT INTO folders (id, name, parent_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
             "#,
-            order_clause
-        );
-    
-        let passwords = query_as(&query_str)
-            .bind(page_size as i64)
-            .bind(offset as i64)
-            .fetch_all(&*self.pool)
+        )
+        .bind(folder.id)
+        .bind(folder.name)
+        .bind(folder.parent_id)
+        .bind(folder.created_at)
+        .bind(folder.updated_at)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_folders(&mut self) -> Result<Vec<Folder>, Box<dyn std::error::Error>> {
+        let folders = query_as(
+            r#"
+            SELECT id, name, parent_id, created_at, updated_at
+            FROM folders
+            ORDER BY name
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(folders)
+    }
+
+    async fn rename_folder(&mut self, id: Uuid, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let rows_affected = query("UPDATE folders SET name = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(name)
+            .execute(&*self.pool)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            Err(Box::new(FolderError::NotFound(id)))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn move_folder(&mut self, id: Uuid, parent_id: Option<Uuid>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(parent_id) = parent_id {
+            query("SELECT pg_advisory_xact_lock($1)").bind(FOLDER_MOVE_LOCK).execute(&mut *tx).await?;
+
+            if !folder_exists(&mut tx, parent_id).await? {
+                return Err(Box::new(FolderError::ParentNotFound(parent_id)));
+            }
+
+            let creates_cycle: bool = query_scalar(
+                r#"
+                WITH RECURSIVE subtree AS (
+                    SELECT id FROM folders WHERE id = $1
+                    UNION
+                    SELECT folders.id FROM folders JOIN subtree ON folders.parent_id = subtree.id
+                )
+                SELECT EXISTS (SELECT 1 FROM subtree WHERE id = $2)
+                "#,
+            )
+            .bind(id)
+            .bind(parent_id)
+            .fetch_one(&mut *tx)
             .await?;
-    
-        Ok(passwords)
+
+            if creates_cycle {
+                return Err(Box::new(FolderError::Cycle));
+            }
+        }
+
+        let rows_affected = query("UPDATE folders SET parent_id = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(parent_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(Box::new(FolderError::NotFound(id)));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_folder(&mut self, id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        let rows_affected = query("DELETE FROM folders WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            Err(Box::new(FolderError::NotFound(id)))
+        } else {
+            Ok(())
+        }
     }
 }
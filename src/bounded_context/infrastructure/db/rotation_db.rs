@@ -0,0 +1,77 @@
+use super::postgres_db::{Database, PASSWORD_COLUMNS};
+use crate::bounded_context::domain::password::Password;
+use crate::bounded_context::domain::rotation::{OverdueCheck, RotationDb, RotationNotice};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, query_scalar};
+use std::collections::HashMap;
+use std::error::Error;
+use uuid::Uuid;
+
+#[async_trait]
+impl RotationDb for Database {
+    async fn list_rotation_notified(&mut self) -> Result<HashMap<Uuid, DateTime<Utc>>, Box<dyn Error>> {
+        let rows: Vec<(Uuid, DateTime<Utc>)> = query_as("SELECT password_id, due_at FROM rotation_notifications")
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn mark_rotation_notified(&mut self, notices: &[RotationNotice]) -> Result<(), Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+
+        for notice in notices {
+            query(
+                r#"
+                INSERT INTO rotation_notifications (password_id, due_at, notified_at)
+                VALUES ($1, $2, NOW())
+                ON CONFLICT (password_id) DO UPDATE SET due_at = EXCLUDED.due_at, notified_at = EXCLUDED.notified_at
+                "#,
+            )
+            .bind(notice.password_id)
+            .bind(notice.due_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn list_newly_overdue(&mut self, since: Option<OverdueCheck>, now: DateTime<Utc>) -> Result<(Vec<Password>, OverdueCheck), Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        // The entries are read from the snapshot the token is taken from, see `SyncDb::changes_since`
+        query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ").execute(&mut *tx).await?;
+
+        let sync_token: i64 = query_scalar("SELECT pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT")
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let passwords = match since {
+            Some(since) => {
+                // Changed entries are included because a change can move the due date into the past
+                query_as(&format!(
+                    "SELECT {} FROM passwords WHERE rotation_due_at <= $1 AND (rotation_due_at > $2 OR sync_version >= $3)",
+                    PASSWORD_COLUMNS
+                ))
+                .bind(now)
+                .bind(since.checked_at)
+                .bind(since.sync_token)
+                .fetch_all(&mut *tx)
+                .await?
+            }
+            None => {
+                query_as(&format!("SELECT {} FROM passwords WHERE rotation_due_at <= $1", PASSWORD_COLUMNS))
+                    .bind(now)
+                    .fetch_all(&mut *tx)
+                    .await?
+            }
+        };
+
+        tx.commit().await?;
+
+        Ok((passwords, OverdueCheck { checked_at: now, sync_token }))
+    }
+}
@@ -0,0 +1,144 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::password::EncryptedText;
+use crate::bounded_context::domain::share::{Share, ShareDb, ShareError, SharedSecret};
+use crate::bounded_context::utility::share::verify_access_password;
+use crate::bounded_context::utility::token::hash_token;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, query, query_as, query_scalar};
+use std::error::Error;
+use std::time::Duration;
+use tracing::{info, error};
+
+#[derive(FromRow)]
+struct ShareRow {
+    nonce: String,
+    cipher: String,
+    password_hash: Option<String>,
+    max_views: i32,
+    expires_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl ShareDb for Database {
+    async fn create_share(&mut self, share: Share) -> Result<(), Box<dyn Error>> {
+        query(
+            r#"
+            INSERT INTO shares (id_hash, nonce, cipher, password_hash, max_views, view_count, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(&share.id_hash)
+        .bind(&share.payload.nonce)
+        .bind(&share.payload.cipher)
+        .bind(&share.password_hash)
+        .bind(share.max_views)
+        .bind(share.view_count)
+        .bind(share.expires_at)
+        .bind(share.created_at)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn view_share(&mut self, token: &str, password: Option<&str>, now: DateTime<Utc>) -> Result<SharedSecret, Box<dyn Error>> {
+        let id_hash = hash_token(token);
+
+        let row: Option<ShareRow> = query_as(
+            "SELECT nonce, cipher, password_hash, max_views, view_count, expires_at FROM shares WHERE id_hash = $1",
+        )
+        .bind(&id_hash)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        let row = match row {
+            Some(row) if row.expires_at > now => row,
+            Some(_) => {
+                query("DELETE FROM shares WHERE id_hash = $1").bind(&id_hash).execute(&*self.pool).await?;
+                return Err(Box::new(ShareError::NotFound));
+            }
+            None => return Err(Box::new(ShareError::NotFound)),
+        };
+
+        // Argon2 is slow on purpose, so it runs on the blocking pool and before the row is locked
+        if let Some(password_hash) = row.password_hash {
+            let Some(password) = password.map(str::to_string) else {
+                return Err(Box::new(ShareError::PasswordRequired));
+            };
+            let verified = tokio::task::spawn_blocking(move || verify_access_password(&password, &password_hash)).await?;
+            if !verified {
+                return Err(Box::new(ShareError::InvalidPassword));
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // The row lock makes concurrent views of the last remaining view race safely
+        let view_count: Option<i32> = query_scalar("SELECT view_count FROM shares WHERE id_hash = $1 AND expires_at > $2 FOR UPDATE")
+            .bind(&id_hash)
+            .bind(now)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(view_count) = view_count.map(|count| count + 1) else {
+            return Err(Box::new(ShareError::NotFound));
+        };
+
+        if view_count >= row.max_views {
+            query("DELETE FROM shares WHERE id_hash = $1").bind(&id_hash).execute(&mut *tx).await?;
+        } else {
+            query("UPDATE shares SET view_count = $2 WHERE id_hash = $1")
+                .bind(&id_hash)
+                .bind(view_count)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(SharedSecret {
+            payload: EncryptedText { nonce: row.nonce, cipher: row.cipher },
+            views_remaining: row.max_views - view_count,
+            expires_at: row.expires_at,
+        })
+    }
+
+    async fn delete_share(&mut self, token: &str) -> Result<(), Box<dyn Error>> {
+        let rows_affected = query("DELETE FROM shares WHERE id_hash = $1")
+            .bind(hash_token(token))
+            .execute(&*self.pool)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            Err(Box::new(ShareError::NotFound))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn delete_expired_shares(&mut self, now: DateTime<Utc>) -> Result<u64, Box<dyn Error>> {
+        let rows_affected = query("DELETE FROM shares WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&*self.pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows_affected)
+    }
+}
+
+/// Deletes expired shares every `interval` until the server shuts down.
+pub async fn sweep_expired_shares(database: Database, interval: Duration) {
+    let mut db = database;
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        match db.delete_expired_shares(Utc::now()).await {
+            Ok(0) => {}
+            Ok(deleted) => info!("Deleted {} expired shares", deleted),
+            Err(err) => error!("Failed to delete expired shares: {}", err),
+        }
+    }
+}
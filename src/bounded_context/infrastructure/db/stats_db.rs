@@ -0,0 +1,60 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::stats::{StatsDb, VaultStats};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, query, query_as};
+use std::error::Error;
+
+#[async_trait]
+impl StatsDb for Database {
+    async fn vault_stats(&mut self, now: DateTime<Utc>) -> Result<VaultStats, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        // Every count is taken from the same snapshot
+        query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ").execute(&mut *tx).await?;
+
+        let entries_by_type: Vec<(String, i64)> = query_as("SELECT item_type, COUNT(*) FROM passwords GROUP BY item_type")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let row = query(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM passwords) AS entries,
+                (SELECT COUNT(*) FROM passwords WHERE favorite) AS favorites,
+                (SELECT COUNT(*) FROM folders) AS folders,
+                (SELECT COUNT(*) FROM tags) AS tags,
+                (SELECT COUNT(*) FROM attachments) AS attachments,
+                (SELECT COALESCE(SUM(size), 0)::BIGINT FROM attachments) AS attachment_bytes,
+                (SELECT COUNT(*) FROM shares WHERE expires_at > $1 AND view_count < max_views) AS active_shares,
+                (SELECT COUNT(*) FROM emergency_access WHERE status <> 'revoked') AS emergency_contacts,
+                (SELECT COUNT(*) FROM webhooks) AS webhooks,
+                (SELECT COUNT(*) FROM webhook_deliveries WHERE status = 'pending') AS pending_webhook_deliveries,
+                (SELECT COUNT(*) FROM audit_events) AS audit_events,
+                (SELECT COUNT(*) FROM users) AS users,
+                (SELECT COUNT(*) FROM api_tokens WHERE expires_at IS NULL OR expires_at > $1) AS active_api_tokens
+            "#,
+        )
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(VaultStats {
+            entries: row.try_get("entries")?,
+            entries_by_type: entries_by_type.into_iter().collect(),
+            favorites: row.try_get("favorites")?,
+            folders: row.try_get("folders")?,
+            tags: row.try_get("tags")?,
+            attachments: row.try_get("attachments")?,
+            attachment_bytes: row.try_get("attachment_bytes")?,
+            active_shares: row.try_get("active_shares")?,
+            emergency_contacts: row.try_get("emergency_contacts")?,
+            webhooks: row.try_get("webhooks")?,
+            pending_webhook_deliveries: row.try_get("pending_webhook_deliveries")?,
+            audit_events: row.try_get("audit_events")?,
+            users: row.try_get("users")?,
+            active_api_tokens: row.try_get("active_api_tokens")?,
+        })
+    }
+}
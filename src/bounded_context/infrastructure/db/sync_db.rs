@@ -0,0 +1,107 @@
+use super::postgres_db::{Database, PASSWORD_COLUMNS, delete_entry, insert_entry, update_entry};
+use super::attachment_db::remove_blob_files;
+use crate::bounded_context::domain::password::Password;
+use crate::bounded_context::domain::sync::{AppliedChange, PushResult, SyncChange, SyncChanges, SyncConflict, SyncDb, SyncToken, Tombstone};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, query, query_as, query_scalar};
+use std::error::Error;
+use uuid::Uuid;
+
+async fn fetch_entry(conn: &mut PgConnection, id: Uuid) -> Result<Option<Password>, sqlx::Error> {
+    query_as(&format!("SELECT {} FROM passwords WHERE id = $1", PASSWORD_COLUMNS))
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+}
+
+#[async_trait]
+impl SyncDb for Database {
+    async fn changes_since(&mut self, since: Option<SyncToken>) -> Result<SyncChanges, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        // Every query below sees the same snapshot, the one the token is taken from
+        query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ").execute(&mut *tx).await?;
+
+        // Transactions at or after the oldest one still running may commit later, so the next sync
+        // starts there. Entries they already changed are sent again, which clients can ignore.
+        let token: i64 = query_scalar("SELECT pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT")
+            .fetch_one(&mut *tx)
+            .await?;
+        let since = since.map(|since| since.0).unwrap_or(0);
+
+        let entries = query_as(&format!(
+            "SELECT {} FROM passwords WHERE sync_version >= $1 ORDER BY sync_version ASC, id ASC",
+            PASSWORD_COLUMNS
+        ))
+        .bind(since)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // A first sync has nothing to delete
+        let tombstones: Vec<Tombstone> = if since == 0 {
+            Vec::new()
+        } else {
+            query_as("SELECT id, deleted_at FROM password_tombstones WHERE sync_version >= $1 ORDER BY sync_version ASC, id ASC")
+                .bind(since)
+                .fetch_all(&mut *tx)
+                .await?
+        };
+
+        tx.commit().await?;
+
+        Ok(SyncChanges { entries, tombstones, sync_token: SyncToken(token).encode() })
+    }
+
+    async fn apply_changes(&mut self, changes: Vec<SyncChange>, now: DateTime<Utc>) -> Result<PushResult, Box<dyn Error>> {
+        let mut tx = self.pool.begin().await?;
+        let mut result = PushResult::default();
+        let mut attachment_files = Vec::new();
+
+        for change in changes {
+            let id = change.id();
+
+            // The row lock keeps the entry from changing between the check and the write
+            let current: Option<DateTime<Utc>> = query_scalar("SELECT updated_at FROM passwords WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?;
+            let deleted: bool = query_scalar("SELECT EXISTS (SELECT 1 FROM password_tombstones WHERE id = $1)")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            if let Err(reason) = change.check(current, deleted) {
+                let server_entry = fetch_entry(&mut tx, id).await?;
+                result.conflicts.push(SyncConflict { id, reason, server_entry });
+                continue;
+            }
+
+            match change {
+                SyncChange::Upsert { mut entry, .. } => {
+                    entry.updated_at = now;
+                    if current.is_some() {
+                        update_entry(&mut tx, &entry).await?;
+                    } else {
+                        entry.last_used_at = None;
+                        insert_entry(&mut tx, &entry).await?;
+                    }
+                    result.applied.push(AppliedChange { id, updated_at: Some(now) });
+                }
+                SyncChange::Delete { .. } => {
+                    if let Some(files) = delete_entry(&mut tx, id).await? {
+                        attachment_files.extend(files);
+                    }
+                    result.applied.push(AppliedChange { id, updated_at: None });
+                }
+            }
+        }
+
+        tx.commit().await?;
+
+        remove_blob_files(self.config.attachment_dir.as_deref(), &attachment_files)
+            .await
+            .map_err(|err| err as Box<dyn Error>)?;
+
+        Ok(result)
+    }
+}
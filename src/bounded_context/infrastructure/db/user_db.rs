@@ -0,0 +1,82 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::user::{ApiToken, User, UserDb, UserError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as};
+use std::error::Error;
+
+#[async_trait]
+impl UserDb for Database {
+    async fn create_user(&mut self, user: User) -> Result<(), Box<dyn Error>> {
+        let rows_affected = query(
+            r#"
+            INSERT INTO users (id, username, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (username) DO NOTHING
+            "#,
+        )
+        .bind(user.id)
+        .bind(&user.username)
+        .bind(user.created_at)
+        .execute(&*self.pool)
+        .await?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(Box::new(UserError::UsernameTaken(user.username)));
+        }
+
+        Ok(())
+    }
+
+    async fn get_user(&mut self, username: &str) -> Result<User, Box<dyn Error>> {
+        let user: Option<User> = query_as("SELECT id, username, created_at FROM users WHERE username = $1")
+            .bind(username)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+        user.ok_or_else(|| Box::new(UserError::NotFound(username.to_string())) as Box<dyn Error>)
+    }
+
+    async fn create_token(&mut self, token: ApiToken) -> Result<(), Box<dyn Error>> {
+        query(
+            r#"
+            INSERT INTO api_tokens (id, user_id, token_hash, name, expires_at, last_used_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(token.id)
+        .bind(token.user_id)
+        .bind(&token.token_hash)
+        .bind(&token.name)
+        .bind(token.expires_at)
+        .bind(token.last_used_at)
+        .bind(token.created_at)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn authenticate(&mut self, token_hash: &str, now: DateTime<Utc>) -> Result<Option<User>, Box<dyn Error>> {
+        let user = query_as(
+            r#"
+            WITH used AS (
+                UPDATE api_tokens
+                SET last_used_at = $2
+                WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > $2)
+                RETURNING user_id
+            )
+            SELECT users.id, users.username, users.created_at
+            FROM users
+            JOIN used ON used.user_id = users.id
+            "#,
+        )
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(user)
+    }
+}
@@ -0,0 +1,181 @@
+use super::postgres_db::Database;
+use crate::bounded_context::domain::pagination::Page;
+use crate::bounded_context::domain::webhook::{
+    DeliveryAttempt, DeliveryStatus, PendingDelivery, Webhook, WebhookDb, WebhookDelivery, WebhookError, WebhookEvent,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, QueryBuilder, query, query_as};
+use std::error::Error;
+use uuid::Uuid;
+
+const DELIVERY_COLUMNS: &str = r#"
+    webhook_deliveries.id, webhook_deliveries.webhook_id, webhook_deliveries.event, webhook_deliveries.payload,
+    webhook_deliveries.status, webhook_deliveries.attempts, webhook_deliveries.next_attempt_at,
+    webhook_deliveries.last_attempt_at, webhook_deliveries.last_response_status, webhook_deliveries.last_error,
+    webhook_deliveries.created_at, webhook_deliveries.delivered_at
+"#;
+
+fn push_filter(builder: &mut QueryBuilder<'_, Postgres>, webhook_id: Option<Uuid>, status: Option<DeliveryStatus>) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(webhook_id) = webhook_id {
+        builder.push(" AND webhook_id = ").push_bind(webhook_id);
+    }
+    if let Some(status) = status {
+        builder.push(" AND status = ").push_bind(status.as_str());
+    }
+}
+
+#[async_trait]
+impl WebhookDb for Database {
+    async fn create_webhook(&mut self, webhook: &Webhook) -> Result<(), Box<dyn Error>> {
+        query("INSERT INTO webhooks (id, url, secret, events, active, created_at) VALUES ($1, $2, $3, $4, $5, $6)")
+            .bind(webhook.id)
+            .bind(&webhook.url)
+            .bind(&webhook.secret)
+            .bind(&webhook.events)
+            .bind(webhook.active)
+            .bind(webhook.created_at)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list_webhooks(&mut self) -> Result<Vec<Webhook>, Box<dyn Error>> {
+        let webhooks = query_as("SELECT id, url, secret, events, active, created_at FROM webhooks ORDER BY created_at ASC")
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(webhooks)
+    }
+
+    async fn delete_webhook(&mut self, id: Uuid) -> Result<(), Box<dyn Error>> {
+        let rows_affected = query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await?
+            .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(Box::new(WebhookError::NotFound(id)));
+        }
+
+        Ok(())
+    }
+
+    async fn enqueue_webhook_event(
+        &mut self,
+        event: WebhookEvent,
+        event_key: &str,
+        payload: serde_json::Value,
+        now: DateTime<Utc>,
+    ) -> Result<u64, Box<dyn Error>> {
+        let queued = query(
+            r#"
+            INSERT INTO webhook_deliveries (id, webhook_id, event, event_key, payload, next_attempt_at, created_at)
+            SELECT gen_random_uuid(), id, $1, $2, $3, $4, $4
+            FROM webhooks
+            WHERE active AND $1 = ANY (events)
+            ON CONFLICT (webhook_id, event_key) DO NOTHING
+            "#,
+        )
+        .bind(event.as_str())
+        .bind(event_key)
+        .bind(payload)
+        .bind(now)
+        .execute(&*self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(queued)
+    }
+
+    async fn claim_webhook_deliveries(&mut self, now: DateTime<Utc>, lease_until: DateTime<Utc>, limit: i64) -> Result<Vec<PendingDelivery>, Box<dyn Error>> {
+        // SKIP LOCKED lets several workers claim from the outbox without waiting on each other
+        let claimed = query_as(&format!(
+            r#"
+            WITH claimed AS (
+                UPDATE webhook_deliveries
+                SET next_attempt_at = $2
+                WHERE id IN (
+                    SELECT webhook_deliveries.id
+                    FROM webhook_deliveries
+                    JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+                    WHERE webhook_deliveries.status = 'pending'
+                      AND webhook_deliveries.next_attempt_at <= $1
+                      AND webhooks.active
+                    ORDER BY webhook_deliveries.next_attempt_at ASC, webhook_deliveries.created_at ASC
+                    LIMIT $3
+                    FOR UPDATE OF webhook_deliveries SKIP LOCKED
+                )
+                RETURNING *
+            )
+            SELECT {}, webhooks.url, webhooks.secret
+            FROM claimed AS webhook_deliveries
+            JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+            ORDER BY webhook_deliveries.created_at ASC
+            "#,
+            DELIVERY_COLUMNS
+        ))
+        .bind(now)
+        .bind(lease_until)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(claimed)
+    }
+
+    async fn record_webhook_attempt(&mut self, delivery_id: Uuid, attempt: &DeliveryAttempt) -> Result<(), Box<dyn Error>> {
+        let status = attempt.status();
+
+        query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2,
+                attempts = attempts + 1,
+                last_attempt_at = $3,
+                last_response_status = $4,
+                last_error = $5,
+                next_attempt_at = COALESCE($6, next_attempt_at),
+                delivered_at = CASE WHEN $2 = 'delivered' THEN $3 ELSE delivered_at END
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(status.as_str())
+        .bind(attempt.attempted_at)
+        .bind(attempt.response_status)
+        .bind(&attempt.error)
+        .bind(attempt.retry_at)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_webhook_deliveries(
+        &mut self,
+        webhook_id: Option<Uuid>,
+        status: Option<DeliveryStatus>,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<WebhookDelivery>, Box<dyn Error>> {
+        let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM webhook_deliveries");
+        push_filter(&mut count, webhook_id, status);
+        let total_count: i64 = count.build_query_scalar().fetch_one(&*self.pool).await?;
+
+        let mut builder = QueryBuilder::<Postgres>::new(format!("SELECT {} FROM webhook_deliveries", DELIVERY_COLUMNS));
+        push_filter(&mut builder, webhook_id, status);
+        builder
+            .push(" ORDER BY created_at DESC, id ASC LIMIT ")
+            .push_bind(page_size as i64)
+            .push(" OFFSET ")
+            .push_bind(page.saturating_sub(1) as i64 * page_size as i64);
+        let items: Vec<WebhookDelivery> = builder.build_query_as().fetch_all(&*self.pool).await?;
+
+        Ok(Page { items, total_count, page: Some(page), page_size, next_cursor: None })
+    }
+}
@@ -0,0 +1,240 @@
+use crate::bounded_context::domain::export::{ExportedEntry, ExportedField, ExportedItem};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[derive(Serialize)]
+struct BitwardenExport {
+    encrypted: bool,
+    folders: Vec<BitwardenFolder>,
+    items: Vec<BitwardenItem>,
+}
+
+#[derive(Serialize)]
+struct BitwardenFolder {
+    id: Uuid,
+    name: String,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenItem {
+    id: Uuid,
+    folder_id: Option<Uuid>,
+    #[serde(rename = "type")]
+    item_type: u8,
+    name: String,
+    notes: Option<String>,
+    favorite: bool,
+    fields: Vec<BitwardenField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    login: Option<BitwardenLogin>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secure_note: Option<BitwardenSecureNote>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    card: Option<BitwardenCard>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity: Option<BitwardenIdentity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ssh_key: Option<BitwardenSshKey>,
+    creation_date: DateTime<Utc>,
+    revision_date: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct BitwardenField {
+    name: String,
+    value: String,
+    #[serde(rename = "type")]
+    field_type: u8,
+}
+
+#[derive(Serialize)]
+struct BitwardenLogin {
+    uris: Vec<BitwardenUri>,
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BitwardenUri {
+    #[serde(rename = "match")]
+    match_type: Option<u8>,
+    uri: String,
+}
+
+#[derive(Serialize)]
+struct BitwardenSecureNote {
+    #[serde(rename = "type")]
+    note_type: u8,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenCard {
+    cardholder_name: Option<String>,
+    brand: Option<String>,
+    number: String,
+    exp_month: Option<String>,
+    exp_year: Option<String>,
+    code: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenIdentity {
+    first_name: Option<String>,
+    last_name: Option<String>,
+    address1: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    ssn: Option<String>,
+    username: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenSshKey {
+    private_key: String,
+    public_key: String,
+    key_fingerprint: Option<String>,
+}
+
+fn text_field(name: &str, value: String) -> BitwardenField {
+    BitwardenField { name: name.to_string(), value, field_type: 0 }
+}
+
+fn hidden_field(name: &str, value: String) -> BitwardenField {
+    BitwardenField { name: name.to_string(), value, field_type: 1 }
+}
+
+/// Splits `MM/YY` or `MM/YYYY` into the month and the year
+fn split_expiry(expiry: &str) -> (Option<String>, Option<String>) {
+    match expiry.split_once('/') {
+        Some((month, year)) => (Some(month.trim().trim_start_matches('0').to_string()), Some(year.trim().to_string())),
+        None => (None, Some(expiry.trim().to_string())),
+    }
+}
+
+fn not_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Writes an unencrypted Bitwarden JSON export. Nested folders are named by their path joined
+/// with `/`, as Bitwarden does. Bitwarden has no tags or API keys: tags are left out and API keys
+/// become logins with the key id as username.
+pub fn write_bitwarden_json(entries: &[ExportedEntry]) -> Vec<u8> {
+    let folder_ids: BTreeMap<String, Uuid> = entries
+        .iter()
+        .filter(|entry| !entry.folder.is_empty())
+        .map(|entry| (entry.folder.join("/"), Uuid::new_v4()))
+        .collect();
+
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let mut item = BitwardenItem {
+                id: entry.id,
+                folder_id: folder_ids.get(&entry.folder.join("/")).copied(),
+                name: entry.service.clone(),
+                notes: entry.notes.clone(),
+                favorite: entry.favorite,
+                fields: entry
+                    .fields
+                    .iter()
+                    .map(|field| match field {
+                        ExportedField::Text { name, value } => text_field(name, value.clone()),
+                        ExportedField::Hidden { name, value } => hidden_field(name, value.clone()),
+                        ExportedField::Boolean { name, value } => BitwardenField { name: name.clone(), value: value.to_string(), field_type: 2 },
+                    })
+                    .collect(),
+                creation_date: entry.created_at,
+                revision_date: entry.updated_at,
+                ..Default::default()
+            };
+            let login = |username: Option<String>| BitwardenLogin {
+                uris: entry.uris.iter().map(|uri| BitwardenUri { match_type: None, uri: uri.clone() }).collect(),
+                username,
+                password: not_empty(&entry.secret),
+                totp: entry.totp.clone(),
+            };
+
+            match &entry.item {
+                ExportedItem::Login => {
+                    item.item_type = 1;
+                    item.login = Some(login(entry.username.clone()));
+                }
+                ExportedItem::SecureNote => {
+                    item.item_type = 2;
+                    item.secure_note = Some(BitwardenSecureNote { note_type: 0 });
+                    // The body of a secure note is its secret, which Bitwarden keeps in the notes
+                    item.notes = Some([Some(entry.secret.as_str()), entry.notes.as_deref()].into_iter().flatten().collect::<Vec<_>>().join("\n\n"));
+                }
+                ExportedItem::Card { cardholder_name, expiry, security_code, brand } => {
+                    let (exp_month, exp_year) = expiry.as_deref().map(split_expiry).unwrap_or_default();
+                    item.item_type = 3;
+                    item.card = Some(BitwardenCard {
+                        cardholder_name: cardholder_name.clone(),
+                        brand: brand.clone(),
+                        number: entry.secret.clone(),
+                        exp_month,
+                        exp_year,
+                        code: security_code.clone(),
+                    });
+                }
+                ExportedItem::Identity { full_name, email, phone, address, birth_date } => {
+                    let (first_name, last_name) = match full_name.as_deref().map(str::trim).and_then(|name| name.rsplit_once(' ')) {
+                        Some((first, last)) => (Some(first.trim().to_string()), Some(last.to_string())),
+                        None => (full_name.clone(), None),
+                    };
+                    item.item_type = 4;
+                    item.identity = Some(BitwardenIdentity {
+                        first_name,
+                        last_name,
+                        address1: address.clone(),
+                        email: email.clone(),
+                        phone: phone.clone(),
+                        ssn: not_empty(&entry.secret),
+                        username: entry.username.clone(),
+                    });
+                    if let Some(birth_date) = birth_date {
+                        item.fields.push(text_field("Birth date", birth_date.clone()));
+                    }
+                }
+                ExportedItem::SshKey { public_key, key_fingerprint, passphrase } => {
+                    item.item_type = 5;
+                    item.ssh_key = Some(BitwardenSshKey {
+                        private_key: entry.secret.clone(),
+                        public_key: public_key.clone(),
+                        key_fingerprint: key_fingerprint.clone(),
+                    });
+                    if let Some(passphrase) = passphrase {
+                        item.fields.push(hidden_field("Passphrase", passphrase.clone()));
+                    }
+                }
+                ExportedItem::ApiKey { key_id, secret, expires_at } => {
+                    item.item_type = 1;
+                    item.login = Some(login(key_id.clone().or_else(|| entry.username.clone())));
+                    if let Some(secret) = secret {
+                        item.fields.push(hidden_field("Secret", secret.clone()));
+                    }
+                    if let Some(expires_at) = expires_at {
+                        item.fields.push(text_field("Expires at", expires_at.to_rfc3339()));
+                    }
+                }
+            }
+
+            item
+        })
+        .collect();
+
+    let export = BitwardenExport {
+        encrypted: false,
+        folders: folder_ids.into_iter().map(|(name, id)| BitwardenFolder { id, name }).collect(),
+        items,
+    };
+
+    serde_json::to_vec_pretty(&export).expect("Failed to serialize export")
+}
@@ -0,0 +1,59 @@
+use crate::bounded_context::domain::export::ExportedEntry;
+use std::collections::HashSet;
+
+const HEADER: &str = "# Plaintext secrets exported from rust-password-server. Delete this file once it is no longer needed.\n";
+
+/// Turns a service name into a variable name: `api.example.com` becomes `API_EXAMPLE_COM`
+pub fn variable_name(service: &str) -> String {
+    let name = service
+        .to_uppercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    match name.chars().next() {
+        None => "SECRET".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("_{}", name),
+        Some(_) => name,
+    }
+}
+
+/// Single quotes keep the value literal for shells and dotenv parsers alike. Values that cannot
+/// be single quoted are double quoted with escapes, so that shells expand neither `$` nor
+/// backticks. Dotenv parsers reject an escaped backtick, so backticks are single quoted instead.
+fn quote(value: &str) -> String {
+    if !value.contains(['\'', '\n', '\r']) {
+        return format!("'{}'", value);
+    }
+
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('`', "\"'`'\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("\"{}\"", escaped)
+}
+
+/// Writes one `NAME='secret'` line per entry, named after its service. Names shared by several
+/// entries get a numbered suffix.
+pub fn write_dotenv(entries: &[ExportedEntry]) -> Vec<u8> {
+    let mut used = HashSet::new();
+    let mut output = String::from(HEADER);
+
+    for entry in entries {
+        let base = variable_name(&entry.service);
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while !used.insert(name.clone()) {
+            suffix += 1;
+            name = format!("{}_{}", base, suffix);
+        }
+
+        output.push_str(&format!("{}={}\n", name, quote(&entry.secret)));
+    }
+
+    output.into_bytes()
+}
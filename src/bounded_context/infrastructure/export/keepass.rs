@@ -0,0 +1,99 @@
+use crate::bounded_context::domain::export::{ExportedEntry, ExportedField, ExportedItem};
+
+/// Columns written by KeePassXC, which KeePass maps with its generic CSV importer
+const HEADERS: [&str; 10] = ["Group", "Title", "Username", "Password", "URL", "Notes", "TOTP", "Icon", "Last Modified", "Created"];
+
+/// Name of the group every entry is placed under, as in KeePassXC exports
+const ROOT_GROUP: &str = "Root";
+
+/// The notes of an entry followed by `name: value` lines for everything the CSV has no column
+/// for: further URIs, type specific fields, custom fields and tags
+fn notes(entry: &ExportedEntry) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut push = |name: &str, value: Option<&str>| {
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", name, value));
+        }
+    };
+
+    for uri in entry.uris.iter().skip(1) {
+        push("URL", Some(uri));
+    }
+    match &entry.item {
+        ExportedItem::Login | ExportedItem::SecureNote => {}
+        ExportedItem::Card { cardholder_name, expiry, security_code, brand } => {
+            push("Cardholder name", cardholder_name.as_deref());
+            push("Expiry", expiry.as_deref());
+            push("Security code", security_code.as_deref());
+            push("Brand", brand.as_deref());
+        }
+        ExportedItem::Identity { full_name, email, phone, address, birth_date } => {
+            push("Full name", full_name.as_deref());
+            push("Email", email.as_deref());
+            push("Phone", phone.as_deref());
+            push("Address", address.as_deref());
+            push("Birth date", birth_date.as_deref());
+        }
+        ExportedItem::SshKey { public_key, key_fingerprint, passphrase } => {
+            push("Public key", Some(public_key));
+            push("Key fingerprint", key_fingerprint.as_deref());
+            push("Passphrase", passphrase.as_deref());
+        }
+        ExportedItem::ApiKey { secret, expires_at, .. } => {
+            push("Secret", secret.as_deref());
+            push("Expires at", expires_at.map(|expires_at| expires_at.to_rfc3339()).as_deref());
+        }
+    }
+    for field in &entry.fields {
+        match field {
+            ExportedField::Text { name, value } | ExportedField::Hidden { name, value } => push(name, Some(value)),
+            ExportedField::Boolean { name, value } => push(name, Some(&value.to_string())),
+        }
+    }
+    if !entry.tags.is_empty() {
+        push("Tags", Some(&entry.tags.join(", ")));
+    }
+
+    let body = match entry.item {
+        // The body of a secure note is its secret
+        ExportedItem::SecureNote => [Some(entry.secret.as_str()), entry.notes.as_deref()].into_iter().flatten().collect::<Vec<_>>().join("\n\n"),
+        _ => entry.notes.clone().unwrap_or_default(),
+    };
+    match (body.is_empty(), lines.is_empty()) {
+        (_, true) => body,
+        (true, false) => lines.join("\n"),
+        (false, false) => format!("{}\n\n{}", body, lines.join("\n")),
+    }
+}
+
+/// Writes a CSV in the layout of KeePassXC. Folders become groups below `Root`.
+pub fn write_keepass_csv(entries: &[ExportedEntry]) -> Vec<u8> {
+    let mut writer = csv::WriterBuilder::new().quote_style(csv::QuoteStyle::Always).from_writer(Vec::new());
+    writer.write_record(HEADERS).expect("Writing to memory cannot fail");
+
+    for entry in entries {
+        let group = std::iter::once(ROOT_GROUP).chain(entry.folder.iter().map(String::as_str)).collect::<Vec<_>>().join("/");
+        let (username, password) = match &entry.item {
+            ExportedItem::SecureNote => (String::new(), String::new()),
+            ExportedItem::ApiKey { key_id, .. } => (key_id.clone().or_else(|| entry.username.clone()).unwrap_or_default(), entry.secret.clone()),
+            _ => (entry.username.clone().unwrap_or_default(), entry.secret.clone()),
+        };
+
+        writer
+            .write_record([
+                group.as_str(),
+                &entry.service,
+                &username,
+                &password,
+                entry.uris.first().map_or("", String::as_str),
+                &notes(entry),
+                entry.totp.as_deref().unwrap_or_default(),
+                "0",
+                &entry.updated_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                &entry.created_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            ])
+            .expect("Writing to memory cannot fail");
+    }
+
+    writer.into_inner().expect("Writing to memory cannot fail")
+}
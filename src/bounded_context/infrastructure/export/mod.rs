@@ -0,0 +1,46 @@
+pub mod bitwarden;
+pub mod keepass;
+pub mod dotenv;
+
+use crate::bounded_context::domain::export::{ExportError, ExportFormat, ExportSelection, ExportedEntry, FolderPaths};
+use crate::bounded_context::domain::folder::normalize_tag;
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use std::error::Error;
+
+pub fn write_export(format: ExportFormat, entries: &[ExportedEntry]) -> Vec<u8> {
+    match format {
+        ExportFormat::BitwardenJson => bitwarden::write_bitwarden_json(entries),
+        ExportFormat::KeepassCsv => keepass::write_keepass_csv(entries),
+        ExportFormat::Dotenv => dotenv::write_dotenv(entries),
+    }
+}
+
+/// Reads and decrypts the entries in `folder`, a path like `Work/Servers` whose subfolders are
+/// included, and tagged `tag`. Entries are ordered by folder and service.
+pub async fn collect_export(
+    database: &mut Database,
+    folder: Option<&str>,
+    tag: Option<&str>,
+    master_key: &str,
+) -> Result<Vec<ExportedEntry>, Box<dyn Error>> {
+    let folder_paths = FolderPaths::new(&database.list_folders().await?);
+
+    let mut selection = ExportSelection { tag: tag.map(normalize_tag), ..Default::default() };
+    if let Some(folder) = folder {
+        let path: Vec<String> = folder.split('/').map(str::trim).filter(|name| !name.is_empty()).map(str::to_string).collect();
+        let folder_id = folder_paths.find(&path).ok_or_else(|| ExportError::FolderNotFound(folder.to_string()))?;
+        selection.folders = Some(folder_paths.subtree(folder_id));
+    }
+
+    let mut entries = database
+        .list_all()
+        .await?
+        .iter()
+        .filter(|password| selection.includes(password))
+        .map(|password| ExportedEntry::decrypt(password, master_key, folder_paths.path(password.folder_id)))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_cached_key(|entry| (entry.folder.join("/").to_lowercase(), entry.service.to_lowercase()));
+
+    Ok(entries)
+}
@@ -0,0 +1,137 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{SubsecRound, Utc};
+use std::net::{IpAddr, SocketAddr};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::bounded_context::domain::audit::{AuditDb, AuditOutcome, NewAuditEvent};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::infrastructure::http::auth_middleware::RequestActor;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// Set by the authenticating proxy in front of the server; requests without it, or that did not
+/// come through a proxy listed in `TRUSTED_PROXIES`, are recorded as anonymous. Ignored when the
+/// server checks API tokens itself, see `auth_middleware`.
+pub const ACTOR_HEADER: &str = "x-actor";
+const ANONYMOUS_ACTOR: &str = "anonymous";
+
+/// JSON bodies up to this size are inspected for the id of the entry they act on
+const MAX_INSPECTED_BODY: u64 = 64 * 1024;
+
+fn header_string(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn peer_ip(request: &Request) -> Option<IpAddr> {
+    request.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip())
+}
+
+/// The peer address, or when the peer is a trusted proxy the last address of `X-Forwarded-For`
+/// that is not one. Addresses further left were added by the client and may be made up.
+fn client_ip(request: &Request, peer: Option<IpAddr>, trusted_proxies: &[IpAddr]) -> Option<String> {
+    let peer = peer?;
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer.to_string());
+    }
+
+    let forwarded: Vec<IpAddr> = header_string(request.headers(), "x-forwarded-for")
+        .map(|value| value.split(',').filter_map(|ip| ip.trim().parse().ok()).collect())
+        .unwrap_or_default();
+    let client = forwarded.iter().rev().find(|ip| !trusted_proxies.contains(ip)).or(forwarded.first());
+
+    Some(client.copied().unwrap_or(peer).to_string())
+}
+
+fn entry_id_in_query(request: &Request) -> Option<Uuid> {
+    url::form_urlencoded::parse(request.uri().query()?.as_bytes())
+        .find(|(key, _)| key == "id" || key == "password_id")
+        .and_then(|(_, value)| Uuid::parse_str(&value).ok())
+}
+
+fn entry_id_in_json(body: &[u8]) -> Option<Uuid> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+
+    ["id", "password_id"]
+        .iter()
+        .filter_map(|key| value.get(key)?.as_str())
+        .find_map(|id| Uuid::parse_str(id).ok())
+}
+
+fn is_small_json(headers: &HeaderMap) -> bool {
+    let is_json = header_string(headers, header::CONTENT_TYPE.as_str()).is_some_and(|value| value.starts_with("application/json"));
+    let length = header_string(headers, header::CONTENT_LENGTH.as_str()).and_then(|value| value.parse::<u64>().ok());
+
+    is_json && length.is_some_and(|length| length <= MAX_INSPECTED_BODY)
+}
+
+/// Records every request in the audit log once its response is ready, and answers with a 500 in
+/// place of the response when it cannot be recorded. Only ids are taken from requests: bodies,
+/// tokens and secrets are never logged.
+pub async fn audit_middleware(State(database): State<Database>, request: Request, next: Next) -> Response {
+    let request_id = header_string(request.headers(), REQUEST_ID_HEADER).unwrap_or_else(|| Uuid::new_v4().to_string());
+    let peer = peer_ip(&request);
+    let trusted_proxies = &database.config.trusted_proxies;
+    let actor = header_string(request.headers(), ACTOR_HEADER)
+        .filter(|_| peer.is_some_and(|peer| trusted_proxies.contains(&peer)))
+        .unwrap_or_else(|| ANONYMOUS_ACTOR.to_string());
+    let user_agent = header_string(request.headers(), header::USER_AGENT.as_str());
+    let ip = client_ip(&request, peer, trusted_proxies);
+    let action = format!("{} {}", request.method(), request.uri().path());
+    let mut entry_id = entry_id_in_query(&request);
+
+    let request = if entry_id.is_none() && is_small_json(request.headers()) {
+        let (parts, body) = request.into_parts();
+        let bytes = match to_bytes(body, MAX_INSPECTED_BODY as usize).await {
+            Ok(bytes) => bytes,
+            Err(_) => return Response::builder().status(StatusCode::BAD_REQUEST).body(Body::empty()).unwrap(),
+        };
+        entry_id = entry_id_in_json(&bytes);
+        Request::from_parts(parts, Body::from(bytes))
+    } else {
+        request
+    };
+
+    let response = next.run(request).await;
+    let request_header = HeaderValue::from_str(&request_id).ok();
+
+    let actor = response.extensions().get::<RequestActor>().map_or(actor, |RequestActor(username)| username.clone());
+    let status = response.status().as_u16();
+    let event = NewAuditEvent {
+        occurred_at: Utc::now().trunc_subsecs(6),
+        request_id,
+        actor,
+        action,
+        entry_id,
+        ip,
+        user_agent,
+        status: status as i32,
+        outcome: AuditOutcome::from_status(status).as_str().to_string(),
+    };
+
+    // A response that could not be recorded is replaced by an error, so no entry is handed out
+    // without a trace
+    let mut db = database;
+    let mut response = match db.append_audit_event(event).await {
+        Ok(_) => response,
+        Err(err) => {
+            error!("Failed to record audit event: {}", err);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to record the request in the audit log.".to_string()).into_response()
+        }
+    };
+
+    if let Some(value) = request_header {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
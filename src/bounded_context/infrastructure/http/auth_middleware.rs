@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{SubsecRound, Utc};
+use tracing::error;
+
+use crate::bounded_context::domain::user::{User, UserDb};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::utility::token::{hash_token, is_valid_token};
+
+/// Who a request was authenticated as. Set on responses so the audit log records the token's
+/// user instead of a client supplied actor header.
+#[derive(Clone, Debug)]
+pub struct RequestActor(pub String);
+
+/// Recorded for requests rejected for lacking a valid token
+const UNAUTHENTICATED_ACTOR: &str = "anonymous";
+
+/// Routes open without an API token: the status check, and the routes used by share recipients
+/// and emergency contacts, who present their own tokens
+const PUBLIC_PATHS: [&str; 5] = ["/status", "/share/view", "/emergency/accept", "/emergency/request", "/emergency/vault"];
+
+fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| is_valid_token(token))
+}
+
+fn unauthorized(message: &str) -> Response {
+    let mut response = (StatusCode::UNAUTHORIZED, message.to_string()).into_response();
+    response.extensions_mut().insert(RequestActor(UNAUTHENTICATED_ACTOR.to_string()));
+    response
+}
+
+async fn authenticate(database: Database, token: Option<String>) -> Result<User, Response> {
+    let Some(token) = token else {
+        return Err(unauthorized("Missing or malformed API token."));
+    };
+
+    let mut db = database;
+    match db.authenticate(&hash_token(&token), Utc::now().trunc_subsecs(6)).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(unauthorized("Invalid or expired API token.")),
+        Err(err) => {
+            error!("Failed to authenticate request: {}", err);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response())
+        }
+    }
+}
+
+async fn run_as(user: User, request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(RequestActor(user.username));
+    response
+}
+
+/// Rejects requests without an unexpired API token when `REQUIRE_API_TOKEN` is set. Tokens are
+/// minted with `password-admin mint-token`.
+pub async fn auth_middleware(State(database): State<Database>, request: Request, next: Next) -> Response {
+    if !database.config.require_api_token || PUBLIC_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match authenticate(database, bearer_token(&request).map(str::to_string)).await {
+        Ok(user) => run_as(user, request, next).await,
+        Err(response) => response,
+    }
+}
+
+/// Requires an API token whether or not `REQUIRE_API_TOKEN` is set. Guards owner decisions that
+/// the other party of a grant must not be able to take, such as approving emergency access.
+pub async fn owner_auth_middleware(State(database): State<Database>, request: Request, next: Next) -> Response {
+    match authenticate(database, bearer_token(&request).map(str::to_string)).await {
+        Ok(user) => run_as(user, request, next).await,
+        Err(response) => response,
+    }
+}
@@ -2,18 +2,52 @@ use crate::bounded_context::infrastructure::http::status_controller::status_hand
 use crate::bounded_context::application::{
     get_password::get_password,
     create_password::create_password,
+    batch_passwords::{batch_create, batch_update, batch_delete},
+    backup::{export_backup, restore_backup},
     delete_password::delete_password,
     search_password::search_password,
     sort_password::sort_passwords,
+    query_passwords::query_passwords,
+    match_password::match_password,
+    breach_range::breach_range,
+    health_report::health_report,
+    rotation_due::list_rotation_due,
+    audit_log::audit_log,
+    events::entry_events,
+    sync::{sync_changes, push_changes},
+    webhooks::{create_webhook, list_webhooks, delete_webhook, list_webhook_deliveries},
+    folders::{create_folder, list_folders, rename_folder, move_folder, delete_folder},
+    tag_password::{tag_password, untag_password, list_tags},
+    move_password::move_password,
+    use_password::use_password,
+    favorite_password::favorite_password,
+    attachments::{upload_attachment, download_attachment, list_attachments, delete_attachment},
+    shares::{create_share, view_share, delete_share},
+    emergency_access::{
+        invite_contact, list_emergency_access, list_emergency_access_events, accept_invite, request_access,
+        approve_request, reject_request, revoke_access, emergency_vault,
+    },
 };
-use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::infrastructure::db::{postgres_db::Database, hibp_index::HibpIndex, change_feed::ChangeFeed};
+use crate::bounded_context::infrastructure::http::audit_middleware::audit_middleware;
+use crate::bounded_context::infrastructure::http::auth_middleware::{auth_middleware, owner_auth_middleware};
 
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{get, post},
     Router
 };
+use std::time::Duration;
+use tower_http::timeout::TimeoutLayer;
+
+pub fn configure_routes(database: Database, breach_index: Option<HibpIndex>, change_feed: ChangeFeed) -> Router {
+    let audit_database = database.clone();
+    let auth_database = database.clone();
+    let backup_max_size = database.config.backup_max_size;
+    let request_timeout = Duration::from_secs(database.config.graceful_shutdown_time);
+    let upload_timeout = Duration::from_secs(database.config.attachment_upload_timeout);
 
-pub fn configure_routes(database: Database) -> Router {
     Router::new()
         .route("/status", get(status_handler))
         .nest("/password", 
@@ -21,8 +55,117 @@ pub fn configure_routes(database: Database) -> Router {
             .route("/", get(get_password))
             .route("/search", get(search_password))
             .route("/passwords", get(sort_passwords))
+            .route("/query", post(query_passwords))
+            .route("/match", get(match_password))
+            .route("/rotation", get(list_rotation_due))
             .route("/create", post(create_password))
             .route("/delete", post(delete_password))
-            .with_state(database)
+            .route("/move", post(move_password))
+            .route("/tag", post(tag_password))
+            .route("/untag", post(untag_password))
+            .route("/use", post(use_password))
+            .route("/favorite", post(favorite_password))
+            .route("/batch/create", post(batch_create))
+            .route("/batch/update", post(batch_update))
+            .route("/batch/delete", post(batch_delete))
+            .with_state(database.clone())
+        )
+        .nest("/folder",
+        Router::new()
+            .route("/", get(list_folders))
+            .route("/create", post(create_folder))
+            .route("/rename", post(rename_folder))
+            .route("/move", post(move_folder))
+            .route("/delete", post(delete_folder))
+            .with_state(database.clone())
+        )
+        .nest("/attachment",
+        Router::new()
+            .route("/", get(download_attachment))
+            .route("/list", get(list_attachments))
+            .route("/delete", post(delete_attachment))
+            .with_state(database.clone())
+        )
+        .nest("/share",
+        Router::new()
+            .route("/create", post(create_share))
+            .route("/view", post(view_share))
+            .route("/delete", post(delete_share))
+            .with_state(database.clone())
+        )
+        .nest("/emergency",
+        Router::new()
+            .route("/", get(list_emergency_access))
+            .route("/events", get(list_emergency_access_events))
+            .route("/invite", post(invite_contact))
+            .route("/accept", post(accept_invite))
+            .route("/request", post(request_access))
+            .route("/vault", post(emergency_vault))
+            .merge(
+                // Contacts reach this server too, so deciding on their requests always takes an API token
+                Router::new()
+                    .route("/approve", post(approve_request))
+                    .route("/reject", post(reject_request))
+                    .route("/revoke", post(revoke_access))
+                    .route_layer(middleware::from_fn_with_state(database.clone(), owner_auth_middleware))
+            )
+            .with_state(database.clone())
+        )
+        .nest("/tag",
+        Router::new()
+            .route("/", get(list_tags))
+            .with_state(database.clone())
+        )
+        .nest("/audit",
+        Router::new()
+            .route("/", get(audit_log))
+            .with_state(database.clone())
+        )
+        .nest("/sync",
+        Router::new()
+            .route("/", get(sync_changes))
+            .route("/push", post(push_changes))
+            .with_state(database.clone())
+        )
+        .nest("/events",
+        Router::new()
+            .route("/", get(entry_events))
+            .with_state(change_feed)
+        )
+        .nest("/webhook",
+        Router::new()
+            .route("/", get(list_webhooks))
+            .route("/create", post(create_webhook))
+            .route("/delete", post(delete_webhook))
+            .route("/deliveries", get(list_webhook_deliveries))
+            .with_state(database.clone())
+        )
+        .nest("/backup",
+        Router::new()
+            .route("/export", post(export_backup))
+            .route("/restore", post(restore_backup))
+            .layer(DefaultBodyLimit::max(backup_max_size))
+            .with_state(database.clone())
+        )
+        .nest("/report",
+        Router::new()
+            .route("/health", get(health_report))
+            .with_state(database.clone())
+        )
+        .nest("/breach",
+        Router::new()
+            .route("/range/{prefix}", get(breach_range))
+            .with_state(breach_index)
+        )
+        .layer(TimeoutLayer::new(request_timeout))
+        // Uploads read their body inside the handler, so they get a timeout of their own
+        .merge(
+            Router::new()
+                .route("/attachment/upload", post(upload_attachment))
+                .layer(TimeoutLayer::new(upload_timeout))
+                .with_state(database)
         )
+        // The audit layer wraps the auth layer so that rejected requests are recorded too
+        .layer(middleware::from_fn_with_state(auth_database, auth_middleware))
+        .layer(middleware::from_fn_with_state(audit_database, audit_middleware))
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
 pub mod configure_routes;
 pub mod status_controller;
 pub mod run_server;
-pub mod shutdown;
\ No newline at end of file
+pub mod shutdown;
+pub mod audit_middleware;
+pub mod auth_middleware;
\ No newline at end of file
@@ -4,7 +4,6 @@ use axum::{
     Router,
 };
 use tracing::{info, error};
-use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{
     layer::SubscriberExt, 
@@ -12,11 +11,17 @@ use tracing_subscriber::{
 };
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::bounded_context::domain::change_feed::FeedMessage;
 use crate::bounded_context::infrastructure::{
     http::configure_routes::configure_routes, 
     http::shutdown::shutdown_signal,
     config::app_config::AppConfig, 
-    db::postgres_db::Database
+    db::postgres_db::Database,
+    db::hibp_index::HibpIndex,
+    db::share_db::sweep_expired_shares,
+    notify::rotation_reminder::{notifier_from_config, run_rotation_reminders},
+    notify::webhook_dispatcher::run_webhook_dispatcher,
+    db::change_feed::{ChangeFeed, listen_for_changes, forward_changes},
 };
 
 pub async fn run_server(config: AppConfig) {
@@ -36,15 +41,46 @@ pub async fn run_server(config: AppConfig) {
 
     let database = Database::new(&config.db_url, config.max_connections, config.clone()).await.expect("Failed to connect to db.");
 
+    tokio::spawn(sweep_expired_shares(database.clone(), Duration::from_secs(config.share_sweep_interval.max(1))));
+    tokio::spawn(run_rotation_reminders(
+        database.clone(),
+        notifier_from_config(&config),
+        Duration::from_secs(config.rotation_check_interval.max(1)),
+        chrono::Duration::days(config.rotation_warning_days),
+    ));
+    tokio::spawn(run_webhook_dispatcher(
+        database.clone(),
+        Duration::from_secs(config.webhook_dispatch_interval.max(1)),
+        config.webhook_max_attempts.max(1),
+    ));
+
+    let change_feed = ChangeFeed::new(config.change_feed_capacity);
+    let change_listener = listen_for_changes(&database).await.expect("Failed to listen for changes.");
+    tokio::spawn(forward_changes(change_listener, change_feed.clone()));
+
+    let breach_index = match config.breach_dataset_path.clone() {
+        Some(path) => {
+            info!("Building breach index from {}", path);
+            let index = tokio::task::spawn_blocking(move || HibpIndex::build(path))
+                .await
+                .expect("Breach index task panicked.")
+                .expect("Failed to build breach index.");
+            info!("Indexed {} breach prefixes", index.prefix_count());
+            Some(index)
+        }
+        None => None,
+    };
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    let app = Router::new().nest("/api", configure_routes(database)).layer(cors).layer((
-        TraceLayer::new_for_http(),
-        TimeoutLayer::new(Duration::from_secs(config.graceful_shutdown_time)),
-    ));
+    // Request timeouts are set per route group by `configure_routes`
+    let app = Router::new()
+        .nest("/api", configure_routes(database, breach_index, change_feed.clone()))
+        .layer(cors)
+        .layer(TraceLayer::new_for_http());
 
     let listener = tokio::net::TcpListener::bind(&format!("{}:{}", config.host, config.port).parse::<std::net::SocketAddr>().unwrap())
         .await
@@ -52,7 +88,11 @@ pub async fn run_server(config: AppConfig) {
 
     info!("Listening on {}", listener.local_addr().unwrap());
 
-    if let Err(e) = axum::serve(listener, app).with_graceful_shutdown(shutdown_signal())
+    if let Err(e) = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).with_graceful_shutdown(async move {
+        shutdown_signal().await;
+        // Open event streams would otherwise keep the server from shutting down
+        change_feed.publish(FeedMessage::Closed);
+    })
         .await
     {
         error!("Server failed to start: {}", e);
@@ -0,0 +1,226 @@
+use crate::bounded_context::domain::import::{ImportError, ImportedEntry, ImportedField, ImportedItem, ParsedExport, SkippedRecord};
+use super::{folder_path, non_empty, service_from_uris};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenExport {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    folders: Vec<BitwardenFolder>,
+    #[serde(default)]
+    items: Vec<BitwardenItem>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenFolder {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenItem {
+    #[serde(rename = "type")]
+    item_type: u8,
+    name: Option<String>,
+    notes: Option<String>,
+    folder_id: Option<String>,
+    #[serde(default)]
+    favorite: bool,
+    #[serde(default)]
+    fields: Vec<BitwardenField>,
+    login: Option<BitwardenLogin>,
+    card: Option<BitwardenCard>,
+    identity: Option<BitwardenIdentity>,
+    ssh_key: Option<BitwardenSshKey>,
+    creation_date: Option<DateTime<Utc>>,
+    revision_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenField {
+    name: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "type")]
+    field_type: u8,
+}
+
+#[derive(Deserialize)]
+struct BitwardenLogin {
+    #[serde(default)]
+    uris: Vec<BitwardenUri>,
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenUri {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenCard {
+    cardholder_name: Option<String>,
+    brand: Option<String>,
+    number: Option<String>,
+    exp_month: Option<String>,
+    exp_year: Option<String>,
+    code: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenIdentity {
+    first_name: Option<String>,
+    middle_name: Option<String>,
+    last_name: Option<String>,
+    address1: Option<String>,
+    address2: Option<String>,
+    address3: Option<String>,
+    city: Option<String>,
+    state: Option<String>,
+    postal_code: Option<String>,
+    country: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    ssn: Option<String>,
+    passport_number: Option<String>,
+    license_number: Option<String>,
+    username: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenSshKey {
+    private_key: Option<String>,
+    public_key: Option<String>,
+    key_fingerprint: Option<String>,
+}
+
+/// Joins the parts that are set with `separator`, or `None` if none is
+fn join_parts(parts: &[&Option<String>], separator: &str) -> Option<String> {
+    non_empty(Some(parts.iter().filter_map(|part| non_empty((*part).clone())).collect::<Vec<_>>().join(separator)))
+}
+
+/// Reads an unencrypted Bitwarden JSON export. Folder names containing `/` are nested folders.
+pub fn parse_bitwarden_json(data: &[u8]) -> Result<ParsedExport, ImportError> {
+    let export: BitwardenExport = serde_json::from_slice(data).map_err(|err| ImportError::InvalidFile(err.to_string()))?;
+    if export.encrypted {
+        return Err(ImportError::InvalidFile("Encrypted Bitwarden exports cannot be imported, export unencrypted JSON instead".to_string()));
+    }
+
+    let folders: HashMap<String, Vec<String>> = export.folders.into_iter().map(|folder| (folder.id, folder_path(&folder.name, '/'))).collect();
+    let mut parsed = ParsedExport::default();
+
+    for (index, item) in export.items.into_iter().enumerate() {
+        let record = index + 1;
+        let mut entry = ImportedEntry {
+            record,
+            notes: non_empty(item.notes),
+            folder: item.folder_id.and_then(|id| folders.get(&id).cloned()).unwrap_or_default(),
+            favorite: item.favorite,
+            created_at: item.creation_date,
+            updated_at: item.revision_date,
+            fields: item
+                .fields
+                .into_iter()
+                .filter_map(|field| {
+                    let name = non_empty(field.name)?;
+                    let value = field.value.unwrap_or_default();
+                    match field.field_type {
+                        0 => Some(ImportedField::Text { name, value }),
+                        1 => Some(ImportedField::Hidden { name, value }),
+                        2 => Some(ImportedField::Boolean { name, value: value.eq_ignore_ascii_case("true") }),
+                        // Linked fields point at another field of the item and carry no value
+                        _ => None,
+                    }
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        match item.item_type {
+            1 => {
+                let login = item.login.unwrap_or(BitwardenLogin { uris: Vec::new(), username: None, password: None, totp: None });
+                entry.uris = login.uris.into_iter().filter_map(|uri| non_empty(uri.uri)).collect();
+                entry.username = non_empty(login.username);
+                entry.secret = login.password.unwrap_or_default();
+                entry.totp = non_empty(login.totp);
+            }
+            2 => {
+                entry.item = ImportedItem::SecureNote;
+                entry.secret = entry.notes.take().unwrap_or_default();
+            }
+            3 => {
+                let Some(card) = item.card else {
+                    parsed.skipped.push(SkippedRecord { record, reason: "Card without card details".to_string() });
+                    continue;
+                };
+                let expiry = match (non_empty(card.exp_month), non_empty(card.exp_year)) {
+                    (Some(month), Some(year)) => Some(format!("{:0>2}/{}", month, year)),
+                    (month, year) => month.or(year),
+                };
+                entry.secret = card.number.unwrap_or_default();
+                entry.item = ImportedItem::Card {
+                    cardholder_name: non_empty(card.cardholder_name),
+                    expiry,
+                    security_code: non_empty(card.code),
+                    brand: non_empty(card.brand),
+                };
+            }
+            4 => {
+                let Some(identity) = item.identity else {
+                    parsed.skipped.push(SkippedRecord { record, reason: "Identity without identity details".to_string() });
+                    continue;
+                };
+                entry.username = non_empty(identity.username);
+                entry.secret = [&identity.ssn, &identity.passport_number, &identity.license_number]
+                    .into_iter()
+                    .find_map(|number| non_empty(number.clone()))
+                    .unwrap_or_default();
+                entry.item = ImportedItem::Identity {
+                    full_name: join_parts(&[&identity.first_name, &identity.middle_name, &identity.last_name], " "),
+                    email: non_empty(identity.email),
+                    phone: non_empty(identity.phone),
+                    address: join_parts(
+                        &[&identity.address1, &identity.address2, &identity.address3, &identity.city, &identity.state, &identity.postal_code, &identity.country],
+                        ", ",
+                    ),
+                    birth_date: None,
+                };
+            }
+            5 => {
+                let Some(ssh_key) = item.ssh_key else {
+                    parsed.skipped.push(SkippedRecord { record, reason: "SSH key without key details".to_string() });
+                    continue;
+                };
+                let Some(public_key) = non_empty(ssh_key.public_key) else {
+                    parsed.skipped.push(SkippedRecord { record, reason: "SSH key without a public key".to_string() });
+                    continue;
+                };
+                entry.secret = ssh_key.private_key.unwrap_or_default();
+                entry.item = ImportedItem::SshKey { public_key, key_fingerprint: non_empty(ssh_key.key_fingerprint) };
+            }
+            other => {
+                parsed.skipped.push(SkippedRecord { record, reason: format!("Unsupported item type {}", other) });
+                continue;
+            }
+        }
+
+        match non_empty(item.name).or_else(|| service_from_uris(&entry.uris)) {
+            Some(service) => {
+                entry.service = service;
+                parsed.entries.push(entry);
+            }
+            None => parsed.skipped.push(SkippedRecord { record, reason: "Missing name".to_string() }),
+        }
+    }
+
+    Ok(parsed)
+}
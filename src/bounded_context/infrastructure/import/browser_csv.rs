@@ -0,0 +1,91 @@
+use crate::bounded_context::domain::import::{ImportError, ImportedEntry, ParsedExport, SkippedRecord};
+use super::{CsvExport, service_from_uris};
+use chrono::{DateTime, Utc};
+
+/// Firefox writes times as milliseconds since the epoch
+fn firefox_time(value: Option<String>) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(value?.parse().ok()?)
+}
+
+/// Reads the passwords CSV exported by Chrome and other Chromium based browsers
+pub fn parse_chrome_csv(data: &[u8]) -> Result<ParsedExport, ImportError> {
+    let export = CsvExport::read(data)?;
+    let name = export.column(&["name"]);
+    let url = export.column(&["url"]);
+    let username = export.column(&["username"]);
+    let password = export.column(&["password"]);
+    let note = export.column(&["note", "notes"]);
+    if url.is_none() || password.is_none() {
+        return Err(ImportError::InvalidFile("Expected url and password columns".to_string()));
+    }
+
+    let mut parsed = ParsedExport::default();
+    for (record, row) in export.rows {
+        let entry = ImportedEntry {
+            record,
+            username: CsvExport::value(&row, username),
+            secret: CsvExport::value(&row, password).unwrap_or_default(),
+            uris: CsvExport::value(&row, url).into_iter().collect(),
+            notes: CsvExport::value(&row, note),
+            ..Default::default()
+        };
+        let service = CsvExport::value(&row, name).or_else(|| service_from_uris(&entry.uris));
+
+        push_login(&mut parsed, entry, service);
+    }
+
+    Ok(parsed)
+}
+
+/// Reads the logins CSV exported by Firefox. Logins Firefox keeps for itself, such as the one
+/// of the Firefox account, are left out.
+pub fn parse_firefox_csv(data: &[u8]) -> Result<ParsedExport, ImportError> {
+    let export = CsvExport::read(data)?;
+    let url = export.column(&["url"]);
+    let username = export.column(&["username"]);
+    let password = export.column(&["password"]);
+    let created = export.column(&["timecreated"]);
+    let changed = export.column(&["timepasswordchanged"]);
+    if url.is_none() || password.is_none() {
+        return Err(ImportError::InvalidFile("Expected url and password columns".to_string()));
+    }
+
+    let mut parsed = ParsedExport::default();
+    for (record, row) in export.rows {
+        let uri = CsvExport::value(&row, url);
+        if uri.as_deref().is_some_and(|uri| uri.starts_with("chrome://")) {
+            parsed.skipped.push(SkippedRecord { record, reason: "Firefox internal login".to_string() });
+            continue;
+        }
+
+        let entry = ImportedEntry {
+            record,
+            username: CsvExport::value(&row, username),
+            secret: CsvExport::value(&row, password).unwrap_or_default(),
+            uris: uri.into_iter().collect(),
+            created_at: firefox_time(CsvExport::value(&row, created)),
+            updated_at: firefox_time(CsvExport::value(&row, changed)),
+            ..Default::default()
+        };
+        let service = service_from_uris(&entry.uris);
+
+        push_login(&mut parsed, entry, service);
+    }
+
+    Ok(parsed)
+}
+
+/// Browsers only export logins, so rows without a password hold nothing to import
+fn push_login(parsed: &mut ParsedExport, mut entry: ImportedEntry, service: Option<String>) {
+    let reason = match service {
+        _ if entry.secret.is_empty() => "Missing password",
+        Some(service) => {
+            entry.service = service;
+            parsed.entries.push(entry);
+            return;
+        }
+        None => "Missing name and URL",
+    };
+
+    parsed.skipped.push(SkippedRecord { record: entry.record, reason: reason.to_string() });
+}
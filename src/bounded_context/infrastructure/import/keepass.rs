@@ -0,0 +1,100 @@
+use crate::bounded_context::domain::import::{ImportError, ImportedEntry, ImportedField, ParsedExport, SkippedRecord};
+use super::{non_empty, service_from_uris};
+use chrono::{DateTime, Utc};
+use roxmltree::{Document, Node};
+
+/// Fields KeePass and its TOTP plugins keep one-time password secrets in
+const TOTP_FIELDS: [&str; 3] = ["otp", "TOTP Seed", "TimeOtp-Secret-Base32"];
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name).and_then(|child| child.text()).map(str::to_string)
+}
+
+/// KeePass 2.x writes times as ISO 8601 in XML exports
+fn entry_time(entry: Node, name: &str) -> Option<DateTime<Utc>> {
+    let times = child(entry, "Times")?;
+    DateTime::parse_from_rfc3339(child_text(times, name)?.trim()).ok().map(|time| time.with_timezone(&Utc))
+}
+
+/// Reads a KeePass 2.x XML export. Groups below the root group become folders, and the recycle
+/// bin and entry history are left out.
+pub fn parse_keepass_xml(data: &[u8]) -> Result<ParsedExport, ImportError> {
+    let text = std::str::from_utf8(data).map_err(|err| ImportError::InvalidFile(err.to_string()))?;
+    let document = Document::parse(text).map_err(|err| ImportError::InvalidFile(err.to_string()))?;
+    let file = document.root_element();
+    if !file.has_tag_name("KeePassFile") {
+        return Err(ImportError::InvalidFile("Missing KeePassFile element".to_string()));
+    }
+
+    let recycle_bin = child(file, "Meta").and_then(|meta| child_text(meta, "RecycleBinUUID"));
+    let root_group = child(file, "Root")
+        .and_then(|root| child(root, "Group"))
+        .ok_or_else(|| ImportError::InvalidFile("Missing root group".to_string()))?;
+
+    let mut parsed = ParsedExport::default();
+    parse_group(root_group, &[], recycle_bin.as_deref(), &mut parsed);
+
+    Ok(parsed)
+}
+
+/// Reads the entries of `group` and its subgroups in document order, numbering them from the last
+/// record in `parsed`
+fn parse_group(group: Node, folder: &[String], recycle_bin: Option<&str>, parsed: &mut ParsedExport) {
+    for node in group.children().filter(Node::is_element) {
+        if node.has_tag_name("Group") {
+            if recycle_bin.is_some() && child_text(node, "UUID").as_deref() == recycle_bin {
+                continue;
+            }
+            let mut subfolder = folder.to_vec();
+            subfolder.push(child_text(node, "Name").unwrap_or_default());
+            parse_group(node, &subfolder, recycle_bin, parsed);
+        } else if node.has_tag_name("Entry") {
+            let record = parsed.entries.len() + parsed.skipped.len() + 1;
+            match parse_entry(node, record, folder) {
+                Some(entry) => parsed.entries.push(entry),
+                None => parsed.skipped.push(SkippedRecord { record, reason: "Missing title and URL".to_string() }),
+            }
+        }
+    }
+}
+
+fn parse_entry(node: Node, record: usize, folder: &[String]) -> Option<ImportedEntry> {
+    let mut entry = ImportedEntry {
+        record,
+        folder: folder.to_vec(),
+        created_at: entry_time(node, "CreationTime"),
+        updated_at: entry_time(node, "LastModificationTime"),
+        tags: child_text(node, "Tags")
+            .map(|tags| tags.split([';', ',']).map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let mut title = None;
+
+    for string in node.children().filter(|child| child.has_tag_name("String")) {
+        let Some(key) = child_text(string, "Key") else {
+            continue;
+        };
+        let value_node = child(string, "Value");
+        let value = value_node.and_then(|value| value.text()).unwrap_or_default().to_string();
+        let protected = value_node.is_some_and(|value| value.attribute("ProtectInMemory") == Some("True"));
+
+        match key.as_str() {
+            "Title" => title = non_empty(Some(value)),
+            "UserName" => entry.username = non_empty(Some(value)),
+            "Password" => entry.secret = value,
+            "URL" => entry.uris.extend(non_empty(Some(value))),
+            "Notes" => entry.notes = non_empty(Some(value)),
+            key if TOTP_FIELDS.contains(&key) => entry.totp = non_empty(Some(value)),
+            _ if protected => entry.fields.push(ImportedField::Hidden { name: key, value }),
+            _ => entry.fields.push(ImportedField::Text { name: key, value }),
+        }
+    }
+
+    entry.service = title.or_else(|| service_from_uris(&entry.uris))?;
+    Some(entry)
+}
@@ -0,0 +1,155 @@
+pub mod bitwarden;
+pub mod keepass;
+pub mod one_password;
+pub mod browser_csv;
+
+use crate::bounded_context::domain::import::{ImportError, ImportFormat, ImportReport, ParsedExport, SkippedRecord, duplicate_key};
+use crate::bounded_context::domain::folder::Folder;
+use crate::bounded_context::domain::password_db::{BatchMode, PasswordDb};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::utility::uri::normalize_uri;
+use chrono::{DateTime, Utc};
+use csv::StringRecord;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use uuid::Uuid;
+
+/// Entries saved per transaction
+const IMPORT_BATCH_SIZE: usize = 500;
+
+pub fn parse_export(format: ImportFormat, data: &[u8]) -> Result<ParsedExport, ImportError> {
+    match format {
+        ImportFormat::BitwardenJson => bitwarden::parse_bitwarden_json(data),
+        ImportFormat::KeepassXml => keepass::parse_keepass_xml(data),
+        ImportFormat::OnePassword1pux => one_password::parse_1pux(data),
+        ImportFormat::OnePasswordCsv => one_password::parse_1password_csv(data),
+        ImportFormat::ChromeCsv => browser_csv::parse_chrome_csv(data),
+        ImportFormat::FirefoxCsv => browser_csv::parse_firefox_csv(data),
+    }
+}
+
+/// Encrypts the entries of `export` with `master_key`, fingerprinting them with `fingerprint_key`
+/// when given, and saves them, creating the folders they are in. Entries for an account already in the vault or earlier in the export are reported as
+/// duplicates instead.
+pub async fn import_export(
+    database: &mut Database,
+    export: ParsedExport,
+    master_key: &str,
+    fingerprint_key: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<ImportReport, Box<dyn Error>> {
+    let mut export = export;
+    let existing: HashSet<_> = database
+        .list_all()
+        .await?
+        .iter()
+        .map(|password| duplicate_key(&password.service, password.username.as_deref()))
+        .collect();
+    let duplicates = export.remove_duplicates(&existing);
+
+    let mut folders: HashMap<(Option<Uuid>, String), Uuid> = database
+        .list_folders()
+        .await?
+        .into_iter()
+        .map(|folder| ((folder.parent_id, folder.name.to_lowercase()), folder.id))
+        .collect();
+    let mut folders_created = 0;
+    let mut passwords = Vec::with_capacity(export.entries.len());
+
+    for entry in export.entries {
+        let mut folder_id = None;
+        for name in &entry.folder {
+            let key = (folder_id, name.to_lowercase());
+            folder_id = Some(match folders.get(&key) {
+                Some(id) => *id,
+                None => {
+                    let folder = Folder::new(Uuid::new_v4(), name.clone(), folder_id);
+                    let id = folder.id;
+                    database.create_folder(folder).await?;
+                    folders.insert(key, id);
+                    folders_created += 1;
+                    id
+                }
+            });
+        }
+
+        let record = entry.record;
+        passwords.push((record, entry.encrypt(master_key, fingerprint_key, Uuid::new_v4(), folder_id, now)));
+    }
+
+    let mut report = ImportReport { folders_created, skipped: export.skipped, duplicates, ..Default::default() };
+    let mut remaining = passwords.into_iter().peekable();
+    while remaining.peek().is_some() {
+        let (records, batch): (Vec<_>, Vec<_>) = remaining.by_ref().take(IMPORT_BATCH_SIZE).unzip();
+        let outcomes = database.save_batch(batch, BatchMode::BestEffort).await?;
+        for (record, outcome) in records.into_iter().zip(outcomes) {
+            match outcome {
+                Ok(()) => report.imported += 1,
+                Err(reason) => report.skipped.push(SkippedRecord { record, reason }),
+            }
+        }
+    }
+    report.skipped.sort_by_key(|skipped| skipped.record);
+
+    Ok(report)
+}
+
+/// Trims `value`, treating blank values as missing
+pub(super) fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Splits a folder name holding a path into the names of the folders along it
+pub(super) fn folder_path(name: &str, separator: char) -> Vec<String> {
+    name.split(separator).map(str::trim).filter(|name| !name.is_empty()).map(str::to_string).collect()
+}
+
+/// Names an entry without a title after the host of its first URI
+pub(super) fn service_from_uris(uris: &[String]) -> Option<String> {
+    let url = normalize_uri(uris.first()?)?;
+    url.host_str().map(|host| host.trim_start_matches("www.").to_string())
+}
+
+/// A CSV export whose columns are looked up by header
+pub(super) struct CsvExport {
+    headers: Vec<String>,
+    /// Rows with the line they start on
+    pub(super) rows: Vec<(usize, StringRecord)>,
+}
+
+impl CsvExport {
+    pub(super) fn read(data: &[u8]) -> Result<CsvExport, ImportError> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(data);
+        let headers = reader
+            .headers()
+            .map_err(|err| ImportError::InvalidFile(err.to_string()))?
+            .iter()
+            .map(|header| header.trim_start_matches('\u{feff}').trim().to_lowercase())
+            .collect();
+
+        let mut rows = Vec::new();
+        for row in reader.records() {
+            let row = row.map_err(|err| ImportError::InvalidFile(err.to_string()))?;
+            if row.iter().all(|value| value.trim().is_empty()) {
+                continue;
+            }
+            let line = row.position().map_or(0, |position| position.line() as usize);
+            rows.push((line, row));
+        }
+
+        Ok(CsvExport { headers, rows })
+    }
+
+    /// Index of the first column named like one of `names`, which are lowercase
+    pub(super) fn column(&self, names: &[&str]) -> Option<usize> {
+        names.iter().find_map(|name| self.headers.iter().position(|header| header == name))
+    }
+
+    pub(super) fn value(row: &StringRecord, column: Option<usize>) -> Option<String> {
+        non_empty(row.get(column?).map(str::to_string))
+    }
+
+    pub(super) fn flag(row: &StringRecord, column: Option<usize>) -> bool {
+        CsvExport::value(row, column).is_some_and(|value| matches!(value.to_lowercase().as_str(), "true" | "1" | "yes"))
+    }
+}
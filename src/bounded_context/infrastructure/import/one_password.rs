@@ -0,0 +1,296 @@
+use crate::bounded_context::domain::import::{ImportError, ImportedEntry, ImportedField, ImportedItem, ParsedExport, SkippedRecord};
+use super::{CsvExport, non_empty, service_from_uris};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{Cursor, Read};
+
+#[derive(Deserialize)]
+struct OnePuxExport {
+    #[serde(default)]
+    accounts: Vec<OnePuxAccount>,
+}
+
+#[derive(Deserialize)]
+struct OnePuxAccount {
+    #[serde(default)]
+    vaults: Vec<OnePuxVault>,
+}
+
+#[derive(Deserialize)]
+struct OnePuxVault {
+    attrs: OnePuxVaultAttrs,
+    #[serde(default)]
+    items: Vec<OnePuxItem>,
+}
+
+#[derive(Deserialize)]
+struct OnePuxVaultAttrs {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OnePuxItem {
+    #[serde(default)]
+    fav_index: i64,
+    created_at: Option<i64>,
+    updated_at: Option<i64>,
+    state: Option<String>,
+    category_uuid: String,
+    #[serde(default)]
+    details: OnePuxDetails,
+    #[serde(default)]
+    overview: OnePuxOverview,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OnePuxDetails {
+    #[serde(default)]
+    login_fields: Vec<OnePuxLoginField>,
+    notes_plain: Option<String>,
+    #[serde(default)]
+    sections: Vec<OnePuxSection>,
+    password: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OnePuxLoginField {
+    value: Option<String>,
+    designation: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OnePuxSection {
+    #[serde(default)]
+    fields: Vec<OnePuxField>,
+}
+
+#[derive(Deserialize)]
+struct OnePuxField {
+    title: Option<String>,
+    id: Option<String>,
+    #[serde(default)]
+    value: Value,
+}
+
+#[derive(Default, Deserialize)]
+struct OnePuxOverview {
+    title: Option<String>,
+    url: Option<String>,
+    #[serde(default)]
+    urls: Vec<OnePuxUrl>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OnePuxUrl {
+    url: Option<String>,
+}
+
+/// A section field value, flattened to text
+enum FieldValue {
+    Text(String),
+    Concealed(String),
+    Totp(String),
+}
+
+/// 1Password tags section field values with their type, e.g. `{ "concealed": "..." }`
+fn field_value(value: &Value) -> Option<FieldValue> {
+    let (kind, value) = value.as_object()?.iter().next()?;
+    let text = match (kind.as_str(), value) {
+        ("concealed" | "creditCardNumber", Value::String(text)) => return non_empty(Some(text.clone())).map(FieldValue::Concealed),
+        ("totp", Value::String(text)) => return non_empty(Some(text.clone())).map(FieldValue::Totp),
+        ("email", Value::Object(email)) => email.get("email_address")?.as_str()?.to_string(),
+        ("monthYear", Value::Number(month_year)) => {
+            let month_year = month_year.as_i64()?;
+            format!("{:02}/{}", month_year % 100, month_year / 100)
+        }
+        ("date", Value::Number(timestamp)) => DateTime::from_timestamp(timestamp.as_i64()?, 0)?.format("%Y-%m-%d").to_string(),
+        ("address", Value::Object(address)) => ["street", "city", "state", "zip", "country"]
+            .iter()
+            .filter_map(|part| address.get(*part)?.as_str().filter(|part| !part.is_empty()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Value::String(text)) => text.clone(),
+        _ => return None,
+    };
+
+    non_empty(Some(text)).map(FieldValue::Text)
+}
+
+fn timestamp(seconds: Option<i64>) -> Option<DateTime<Utc>> {
+    seconds.and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+}
+
+/// Reads a 1Password Unencrypted Export (`.1pux`), a zip archive holding an `export.data` JSON
+/// document. Vaults become folders and archived items are left out. Logins and passwords, cards,
+/// identities and secure notes keep their type; other categories are imported as secure notes
+/// with their fields as custom fields.
+pub fn parse_1pux(data: &[u8]) -> Result<ParsedExport, ImportError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(|err| ImportError::InvalidFile(err.to_string()))?;
+    let mut export_data = String::new();
+    archive
+        .by_name("export.data")
+        .map_err(|err| ImportError::InvalidFile(format!("Missing export.data: {}", err)))?
+        .read_to_string(&mut export_data)
+        .map_err(|err| ImportError::InvalidFile(err.to_string()))?;
+    let export: OnePuxExport = serde_json::from_str(&export_data).map_err(|err| ImportError::InvalidFile(err.to_string()))?;
+
+    let mut parsed = ParsedExport::default();
+    let mut record = 0;
+
+    for vault in export.accounts.into_iter().flat_map(|account| account.vaults) {
+        let folder: Vec<String> = non_empty(vault.attrs.name).into_iter().collect();
+        for item in vault.items {
+            record += 1;
+            if item.state.as_deref() == Some("archived") {
+                parsed.skipped.push(SkippedRecord { record, reason: "Archived item".to_string() });
+                continue;
+            }
+            if item.category_uuid == "006" {
+                parsed.skipped.push(SkippedRecord { record, reason: "Documents cannot be imported".to_string() });
+                continue;
+            }
+
+            match parse_item(item, record, &folder) {
+                Some(entry) => parsed.entries.push(entry),
+                None => parsed.skipped.push(SkippedRecord { record, reason: "Missing title".to_string() }),
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_item(item: OnePuxItem, record: usize, folder: &[String]) -> Option<ImportedEntry> {
+    let mut entry = ImportedEntry {
+        record,
+        uris: item
+            .overview
+            .urls
+            .into_iter()
+            .filter_map(|url| non_empty(url.url))
+            .chain(non_empty(item.overview.url))
+            .fold(Vec::new(), |mut uris, uri| {
+                if !uris.contains(&uri) {
+                    uris.push(uri);
+                }
+                uris
+            }),
+        notes: non_empty(item.details.notes_plain),
+        folder: folder.to_vec(),
+        tags: item.overview.tags,
+        favorite: item.fav_index > 0,
+        created_at: timestamp(item.created_at),
+        updated_at: timestamp(item.updated_at),
+        ..Default::default()
+    };
+
+    for field in item.details.login_fields {
+        match field.designation.as_deref() {
+            Some("username") => entry.username = non_empty(field.value),
+            Some("password") => entry.secret = field.value.unwrap_or_default(),
+            _ => {}
+        }
+    }
+    if let Some(password) = non_empty(item.details.password) {
+        entry.secret = password;
+    }
+
+    entry.item = match item.category_uuid.as_str() {
+        "002" => ImportedItem::Card { cardholder_name: None, expiry: None, security_code: None, brand: None },
+        "004" => ImportedItem::Identity { full_name: None, email: None, phone: None, address: None, birth_date: None },
+        _ => ImportedItem::Login,
+    };
+    let mut name_parts = Vec::new();
+
+    for field in item.details.sections.into_iter().flat_map(|section| section.fields) {
+        let Some(value) = field_value(&field.value) else {
+            continue;
+        };
+        let id = field.id.unwrap_or_default();
+        let name = non_empty(field.title).unwrap_or_else(|| id.clone());
+
+        match (&mut entry.item, id.as_str(), value) {
+            (_, _, FieldValue::Totp(totp)) => entry.totp = Some(totp),
+            (ImportedItem::Card { .. }, "ccnum", FieldValue::Concealed(number)) => entry.secret = number,
+            (ImportedItem::Card { cardholder_name, .. }, "cardholder", FieldValue::Text(text)) => *cardholder_name = Some(text),
+            (ImportedItem::Card { expiry, .. }, "expiry", FieldValue::Text(text)) => *expiry = Some(text),
+            (ImportedItem::Card { security_code, .. }, "cvv", FieldValue::Concealed(text)) => *security_code = Some(text),
+            (ImportedItem::Card { brand, .. }, "type", FieldValue::Text(text)) => *brand = Some(text),
+            (ImportedItem::Identity { .. }, "firstname" | "initial" | "lastname", FieldValue::Text(text)) => name_parts.push(text),
+            (ImportedItem::Identity { email, .. }, "email", FieldValue::Text(text)) => *email = Some(text),
+            (ImportedItem::Identity { phone, .. }, "defphone", FieldValue::Text(text)) => *phone = Some(text),
+            (ImportedItem::Identity { address, .. }, "address", FieldValue::Text(text)) => *address = Some(text),
+            (ImportedItem::Identity { birth_date, .. }, "birthdate", FieldValue::Text(text)) => *birth_date = Some(text),
+            (_, _, FieldValue::Concealed(value)) => entry.fields.push(ImportedField::Hidden { name, value }),
+            (_, _, FieldValue::Text(value)) => entry.fields.push(ImportedField::Text { name, value }),
+        }
+    }
+
+    match &mut entry.item {
+        ImportedItem::Identity { full_name, .. } => *full_name = non_empty(Some(name_parts.join(" "))),
+        ImportedItem::Login if entry.secret.is_empty() && !matches!(item.category_uuid.as_str(), "001" | "005") => {
+            entry.item = ImportedItem::SecureNote;
+            entry.secret = entry.notes.take().unwrap_or_default();
+        }
+        _ => {}
+    }
+
+    entry.service = non_empty(item.overview.title).or_else(|| service_from_uris(&entry.uris))?;
+    Some(entry)
+}
+
+/// Reads a 1Password CSV export. Columns are found by their header, so both the 1Password 7 and
+/// 1Password 8 layouts work; archived rows are left out.
+pub fn parse_1password_csv(data: &[u8]) -> Result<ParsedExport, ImportError> {
+    let export = CsvExport::read(data)?;
+    let title = export.column(&["title", "name"]);
+    let url = export.column(&["url", "website", "login_uri"]);
+    let username = export.column(&["username"]);
+    let password = export.column(&["password"]);
+    let otp = export.column(&["otpauth", "one-time password", "totp"]);
+    let notes = export.column(&["notes", "notesplain"]);
+    let favorite = export.column(&["favorite"]);
+    let archived = export.column(&["archived"]);
+    let tags = export.column(&["tags"]);
+
+    let mut parsed = ParsedExport::default();
+    for (record, row) in export.rows {
+        if CsvExport::flag(&row, archived) {
+            parsed.skipped.push(SkippedRecord { record, reason: "Archived item".to_string() });
+            continue;
+        }
+
+        let mut entry = ImportedEntry {
+            record,
+            username: CsvExport::value(&row, username),
+            secret: CsvExport::value(&row, password).unwrap_or_default(),
+            uris: CsvExport::value(&row, url).into_iter().collect(),
+            notes: CsvExport::value(&row, notes),
+            totp: CsvExport::value(&row, otp),
+            favorite: CsvExport::flag(&row, favorite),
+            tags: CsvExport::value(&row, tags)
+                .map(|tags| tags.split([';', ',']).map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        if entry.secret.is_empty() && entry.username.is_none() {
+            entry.item = ImportedItem::SecureNote;
+            entry.secret = entry.notes.take().unwrap_or_default();
+        }
+
+        match CsvExport::value(&row, title).or_else(|| service_from_uris(&entry.uris)) {
+            Some(service) => {
+                entry.service = service;
+                parsed.entries.push(entry);
+            }
+            None => parsed.skipped.push(SkippedRecord { record, reason: "Missing title".to_string() }),
+        }
+    }
+
+    Ok(parsed)
+}
@@ -1,3 +1,7 @@
 pub mod config;
 pub mod http;
-pub mod db;
\ No newline at end of file
+pub mod db;
+pub mod notify;
+pub mod import;
+pub mod export;
+pub mod client;
\ No newline at end of file
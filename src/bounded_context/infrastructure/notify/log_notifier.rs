@@ -0,0 +1,23 @@
+use crate::bounded_context::domain::rotation::{Notifier, RotationNotice};
+use async_trait::async_trait;
+use std::error::Error;
+use tracing::warn;
+
+/// Writes rotation reminders to the server log
+#[derive(Clone, Debug, Default)]
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, notices: &[RotationNotice]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for notice in notices {
+            if notice.overdue {
+                warn!("Rotation of {} ({}) was due at {}", notice.service, notice.password_id, notice.due_at);
+            } else {
+                warn!("Rotation of {} ({}) is due at {}", notice.service, notice.password_id, notice.due_at);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod log_notifier;
+pub mod webhook_notifier;
+pub mod rotation_reminder;
+
+pub mod webhook_dispatcher;
\ No newline at end of file
@@ -0,0 +1,60 @@
+use crate::bounded_context::domain::password_db::PasswordDb;
+use crate::bounded_context::domain::rotation::{rotation_due, Notifier, RotationDb, RotationNotice};
+use crate::bounded_context::infrastructure::config::app_config::AppConfig;
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use super::{log_notifier::LogNotifier, webhook_notifier::WebhookNotifier};
+use chrono::{DateTime, Duration, Utc};
+use std::error::Error;
+use std::sync::Arc;
+use tracing::{info, error};
+
+/// Posts to `ROTATION_WEBHOOK_URL` when it is set and logs otherwise.
+pub fn notifier_from_config(config: &AppConfig) -> Arc<dyn Notifier> {
+    match &config.rotation_webhook_url {
+        Some(url) => Arc::new(WebhookNotifier::new(url.clone())),
+        None => Arc::new(LogNotifier),
+    }
+}
+
+/// Notifies every entry that came due for rotation since the last run and returns how many
+/// were notified. Entries whose notification fails are retried on the next run.
+pub async fn send_rotation_reminders(
+    database: &mut Database,
+    notifier: &dyn Notifier,
+    now: DateTime<Utc>,
+    warning: Duration,
+) -> Result<usize, Box<dyn Error>> {
+    let notified = database.list_rotation_notified().await?;
+    let passwords = database.list_all().await?;
+
+    let notices: Vec<RotationNotice> = rotation_due(passwords, now, warning)
+        .iter()
+        .filter(|due| notified.get(&due.password.id) != Some(&due.due_at))
+        .map(RotationNotice::from)
+        .collect();
+
+    if notices.is_empty() {
+        return Ok(0);
+    }
+
+    notifier.notify(&notices).await.map_err(|err| err as Box<dyn Error>)?;
+    database.mark_rotation_notified(&notices).await?;
+
+    Ok(notices.len())
+}
+
+/// Sends rotation reminders every `interval` until the server shuts down.
+pub async fn run_rotation_reminders(database: Database, notifier: Arc<dyn Notifier>, interval: std::time::Duration, warning: Duration) {
+    let mut db = database;
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        match send_rotation_reminders(&mut db, notifier.as_ref(), Utc::now(), warning).await {
+            Ok(0) => {}
+            Ok(sent) => info!("Sent rotation reminders for {} entries", sent),
+            Err(err) => error!("Failed to send rotation reminders: {}", err),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::bounded_context::domain::rotation::{OverdueCheck, RotationDb};
+use crate::bounded_context::domain::webhook::{retry_at, DeliveryAttempt, PendingDelivery, WebhookDb, WebhookEvent};
+use crate::bounded_context::infrastructure::db::postgres_db::Database;
+use crate::bounded_context::utility::signature::sign_payload;
+use crate::bounded_context::utility::uri::is_public_address;
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::join_all;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::Serialize;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, error};
+use uuid::Uuid;
+
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+pub const EVENT_HEADER: &str = "x-webhook-event";
+pub const DELIVERY_HEADER: &str = "x-webhook-delivery";
+
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Deliveries sent per batch. They are sent concurrently, so a batch takes at most `REQUEST_TIMEOUT`.
+const BATCH_SIZE: i64 = 50;
+/// How long a claimed delivery is held back from other workers while it is being sent. Has to be
+/// well above `REQUEST_TIMEOUT`, or a slow batch could be claimed and sent twice.
+const CLAIM_LEASE_SECONDS: i64 = 5 * 60;
+/// Error messages are cut to this many characters in the delivery history
+const MAX_ERROR_LENGTH: usize = 500;
+
+/// The JSON body posted to a webhook
+#[derive(Serialize)]
+struct WebhookBody<'a> {
+    id: Uuid,
+    event: &'a str,
+    created_at: DateTime<Utc>,
+    data: &'a serde_json::Value,
+}
+
+/// Resolves webhook hosts to their public addresses only, so a host registered with a public
+/// address can't be pointed at internal services later
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Redirects are not followed, since they could lead to any address
+pub fn webhook_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .expect("Failed to build webhook client")
+}
+
+/// Queues an `expired` event for every entry that became overdue or changed since `since`, or
+/// for every overdue entry without it. Each due date is queued once per webhook, so checks may
+/// overlap. Returns where the next check continues from.
+pub async fn enqueue_expired_entries(
+    database: &mut Database,
+    since: Option<OverdueCheck>,
+    now: DateTime<Utc>,
+) -> Result<OverdueCheck, Box<dyn Error>> {
+    let (passwords, check) = database.list_newly_overdue(since, now).await?;
+
+    for password in passwords {
+        let Some(due_at) = password.rotation_due_at() else {
+            continue;
+        };
+
+        let event_key = format!("expired:{}:{}", password.id, due_at.timestamp_micros());
+        let payload = serde_json::json!({
+            "password_id": password.id,
+            "service": password.service,
+            "due_at": due_at,
+        });
+
+        database.enqueue_webhook_event(WebhookEvent::Expired, &event_key, payload, now).await?;
+    }
+
+    Ok(check)
+}
+
+async fn send_delivery(client: &reqwest::Client, pending: &PendingDelivery, now: DateTime<Utc>) -> Result<u16, (Option<u16>, String)> {
+    let delivery = &pending.delivery;
+    let body = serde_json::to_vec(&WebhookBody {
+        id: delivery.id,
+        event: &delivery.event,
+        created_at: delivery.created_at,
+        data: &delivery.payload,
+    })
+    .map_err(|err| (None, err.to_string()))?;
+    let timestamp = now.timestamp();
+
+    let response = client
+        .post(&pending.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, sign_payload(&pending.secret, timestamp, &body))
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(EVENT_HEADER, &delivery.event)
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| (None, err.to_string()))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((Some(status.as_u16()), format!("Webhook responded with {}", status)))
+    }
+}
+
+/// Sends every delivery that is due and records the outcome of each attempt. Failed deliveries
+/// are retried with exponential backoff until `max_attempts` is reached. Returns how many
+/// deliveries succeeded.
+pub async fn dispatch_webhooks(
+    database: &mut Database,
+    client: &reqwest::Client,
+    now: DateTime<Utc>,
+    max_attempts: i32,
+) -> Result<usize, Box<dyn Error>> {
+    let mut delivered = 0;
+    loop {
+        let batch = database
+            .claim_webhook_deliveries(now, now + Duration::seconds(CLAIM_LEASE_SECONDS), BATCH_SIZE)
+            .await?;
+        let batch_size = batch.len();
+
+        let results = join_all(batch.iter().map(|pending| send_delivery(client, pending, now))).await;
+
+        for (pending, result) in batch.iter().zip(results) {
+            let attempt = match result {
+                Ok(status) => {
+                    delivered += 1;
+                    DeliveryAttempt { attempted_at: now, response_status: Some(status as i32), error: None, retry_at: None }
+                }
+                Err((status, message)) => DeliveryAttempt {
+                    attempted_at: now,
+                    response_status: status.map(|status| status as i32),
+                    error: Some(message.chars().take(MAX_ERROR_LENGTH).collect()),
+                    retry_at: retry_at(pending.delivery.attempts + 1, max_attempts, now),
+                },
+            };
+
+            database.record_webhook_attempt(pending.delivery.id, &attempt).await?;
+        }
+
+        if (batch_size as i64) < BATCH_SIZE {
+            return Ok(delivered);
+        }
+    }
+}
+
+/// Queues `expired` events and dispatches webhook deliveries every `interval` until the server
+/// shuts down.
+pub async fn run_webhook_dispatcher(database: Database, interval: std::time::Duration, max_attempts: i32) {
+    let mut db = database;
+    let client = webhook_client();
+    let mut ticker = tokio::time::interval(interval);
+    let mut overdue_check = None;
+
+    loop {
+        ticker.tick().await;
+        let now = Utc::now();
+
+        match enqueue_expired_entries(&mut db, overdue_check, now).await {
+            Ok(check) => overdue_check = Some(check),
+            Err(err) => error!("Failed to queue expired entries: {}", err),
+        }
+
+        match dispatch_webhooks(&mut db, &client, now, max_attempts).await {
+            Ok(0) => {}
+            Ok(delivered) => info!("Delivered {} webhook events", delivered),
+            Err(err) => error!("Failed to dispatch webhooks: {}", err),
+        }
+    }
+}
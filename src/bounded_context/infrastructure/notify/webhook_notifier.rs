@@ -0,0 +1,45 @@
+use crate::bounded_context::domain::rotation::{Notifier, RotationNotice};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::error::Error;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize)]
+struct RotationPayload<'a> {
+    event: &'static str,
+    notices: &'a [RotationNotice],
+}
+
+/// Posts rotation reminders as JSON to a URL
+#[derive(Clone, Debug)]
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> WebhookNotifier {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build webhook client");
+
+        WebhookNotifier { client, url }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notices: &[RotationNotice]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.client
+            .post(&self.url)
+            .json(&RotationPayload { event: "rotation_due", notices })
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
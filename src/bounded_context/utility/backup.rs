@@ -0,0 +1,135 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, rand_core::RngCore},
+    Aes256Gcm, Key, Nonce
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use hmac::{Hmac, Mac};
+use serde::{Serialize, Deserialize};
+use sha2::Sha256;
+
+pub const BACKUP_FORMAT: &str = "rust-password-server-backup";
+pub const BACKUP_VERSION: u32 = 1;
+
+const SALT_SIZE: usize = 16;
+const KEY_SIZE: usize = 32;
+/// Highest Argon2id cost accepted from an envelope, a small margin over the cost backups are sealed
+/// with, so that a crafted one cannot tie up the server
+const MAX_MEMORY_KIB: u32 = 2 * Params::DEFAULT_M_COST;
+const MAX_ITERATIONS: u32 = 2 * Params::DEFAULT_T_COST;
+const MAX_PARALLELISM: u32 = 4 * Params::DEFAULT_P_COST;
+
+/// Argon2id parameters the envelope keys were derived with, kept in the envelope so that they
+/// can be raised without breaking older backups
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// Hex encoded
+    pub salt: String,
+}
+
+impl KdfParams {
+    /// The default Argon2id cost with a fresh random salt
+    pub fn generate() -> KdfParams {
+        let mut salt = [0u8; SALT_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        let defaults = Params::default();
+        KdfParams {
+            memory_kib: defaults.m_cost(),
+            iterations: defaults.t_cost(),
+            parallelism: defaults.p_cost(),
+            salt: hex::encode(salt),
+        }
+    }
+}
+
+/// Contents encrypted with a key derived from a passphrase. `mac` is an HMAC-SHA256 of every
+/// other field, keyed separately from the encryption.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackupEnvelope {
+    pub format: String,
+    pub version: u32,
+    pub kdf: KdfParams,
+    /// Hex encoded
+    pub nonce: String,
+    /// Base64 encoded AES-256-GCM cipher text
+    pub ciphertext: String,
+    /// Hex encoded
+    pub mac: String,
+}
+
+/// Derives the encryption key and the MAC key from `passphrase`
+fn derive_keys(passphrase: &str, kdf: &KdfParams) -> Option<([u8; KEY_SIZE], [u8; KEY_SIZE])> {
+    let salt = hex::decode(&kdf.salt).ok()?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(2 * KEY_SIZE)).ok()?;
+    let mut output = [0u8; 2 * KEY_SIZE];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut output)
+        .ok()?;
+
+    let mut encryption_key = [0u8; KEY_SIZE];
+    let mut mac_key = [0u8; KEY_SIZE];
+    encryption_key.copy_from_slice(&output[..KEY_SIZE]);
+    mac_key.copy_from_slice(&output[KEY_SIZE..]);
+    Some((encryption_key, mac_key))
+}
+
+fn envelope_mac(mac_key: &[u8], envelope: &BackupEnvelope) -> Hmac<Sha256> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(mac_key).expect("HMAC accepts keys of any size");
+    let kdf = &envelope.kdf;
+    for field in [
+        envelope.format.as_str(),
+        &envelope.version.to_string(),
+        &kdf.memory_kib.to_string(),
+        &kdf.iterations.to_string(),
+        &kdf.parallelism.to_string(),
+        &kdf.salt,
+        &envelope.nonce,
+        &envelope.ciphertext,
+    ] {
+        // Length prefixes keep fields from running into each other
+        mac.update(&(field.len() as u64).to_be_bytes());
+        mac.update(field.as_bytes());
+    }
+    mac
+}
+
+/// Encrypts `plaintext` with a key derived from `passphrase` using the default Argon2id cost
+pub fn seal_backup(passphrase: &str, plaintext: &[u8]) -> BackupEnvelope {
+    let kdf = KdfParams::generate();
+    let (encryption_key, mac_key) = derive_keys(passphrase, &kdf).expect("Default Argon2 parameters are valid");
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&encryption_key));
+    let nonce = Aes256Gcm::generate_nonce(OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).expect("Failed to Encrypt");
+
+    let mut envelope = BackupEnvelope {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        kdf,
+        nonce: hex::encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+        mac: String::new(),
+    };
+    envelope.mac = hex::encode(envelope_mac(&mac_key, &envelope).finalize().into_bytes());
+    envelope
+}
+
+/// Checks the MAC of `envelope` and decrypts it. Returns `None` if the passphrase is wrong or
+/// any field was modified.
+pub fn open_backup(passphrase: &str, envelope: &BackupEnvelope) -> Option<Vec<u8>> {
+    let kdf = &envelope.kdf;
+    if kdf.memory_kib > MAX_MEMORY_KIB || kdf.iterations > MAX_ITERATIONS || kdf.parallelism > MAX_PARALLELISM {
+        return None;
+    }
+
+    let (encryption_key, mac_key) = derive_keys(passphrase, &envelope.kdf)?;
+    envelope_mac(&mac_key, envelope).verify_slice(&hex::decode(&envelope.mac).ok()?).ok()?;
+
+    let nonce = hex::decode(&envelope.nonce).ok().filter(|nonce| nonce.len() == 12)?;
+    let ciphertext = STANDARD.decode(&envelope.ciphertext).ok()?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&encryption_key));
+    cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref()).ok()
+}
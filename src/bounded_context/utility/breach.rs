@@ -0,0 +1,25 @@
+use sha1::{Digest, Sha1};
+
+pub const HASH_PREFIX_SIZE: usize = 5;
+const SHA1_HEX_SIZE: usize = 40;
+
+/// Uppercase hex SHA-1 of a password, the form used by Have I Been Pwned datasets.
+pub fn sha1_hex(password: &str) -> String {
+    hex::encode_upper(Sha1::digest(password.as_bytes()))
+}
+
+/// Splits a SHA-1 hex hash into the prefix sent to the server and the suffix kept by the client.
+pub fn split_hash(hash_hex: &str) -> (String, String) {
+    let hash = hash_hex.to_uppercase();
+    let (prefix, suffix) = hash.split_at(HASH_PREFIX_SIZE);
+
+    (prefix.to_string(), suffix.to_string())
+}
+
+pub fn is_valid_hash_prefix(prefix: &str) -> bool {
+    prefix.len() == HASH_PREFIX_SIZE && prefix.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn is_valid_sha1_hex(hash_hex: &str) -> bool {
+    hash_hex.len() == SHA1_HEX_SIZE && hash_hex.chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -3,10 +3,16 @@ use aes_gcm::{
     Aes256Gcm, Nonce, Key
 };
 
+use argon2::{Algorithm, Argon2, Params, Version};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 use hex;
 
+use super::backup::KdfParams;
+
 const NONCE_SIZE: usize = 12;
 const MASTER_KEY_SIZE: usize = 32;
+const FINGERPRINT_SIZE: usize = 32;
 
 pub fn generate_key() -> String {
     let key_bytes: [u8; MASTER_KEY_SIZE] = Aes256Gcm::generate_key(OsRng).into();
@@ -14,6 +20,19 @@ pub fn generate_key() -> String {
     hex::encode(key_bytes)
 }
 
+/// Derives a hex encoded master key from a password with Argon2id, so that a client can rebuild
+/// the same key on any device that knows the password and `kdf`. Returns `None` if `kdf` is invalid.
+pub fn derive_master_key(password: &str, kdf: &KdfParams) -> Option<String> {
+    let salt = hex::decode(&kdf.salt).ok()?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(MASTER_KEY_SIZE)).ok()?;
+    let mut key_bytes = [0u8; MASTER_KEY_SIZE];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), &salt, &mut key_bytes)
+        .ok()?;
+
+    Some(hex::encode(key_bytes))
+}
+
 pub fn is_valid_masterkey(master_key: &str) -> bool {
     let key_bytes = hex::decode(master_key);
     match key_bytes {
@@ -38,19 +57,23 @@ pub fn encrypt(master_key: &str, password: String) -> (String, String) {
 }
 
 pub fn decrypt(master_key: &str, nonce_hex: &str, cipher_hex: &str) -> String {
-    let key_bytes = hex::decode(master_key).expect("Invalid Master Key Hex");
-    assert_eq!(key_bytes.len(), MASTER_KEY_SIZE);
+    try_decrypt(master_key, nonce_hex, cipher_hex).expect("Decryption Failed")
+}
+
+/// Like `decrypt`, but returns `None` for a wrong key or malformed input instead of panicking
+pub fn try_decrypt(master_key: &str, nonce_hex: &str, cipher_hex: &str) -> Option<String> {
+    let key_bytes = hex::decode(master_key).ok().filter(|bytes| bytes.len() == MASTER_KEY_SIZE)?;
+
+    let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
+    let cipher = Aes256Gcm::new(key);
 
-   let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
-   let cipher = Aes256Gcm::new(key);
+    let nonce_bytes = hex::decode(nonce_hex).ok().filter(|bytes| bytes.len() == NONCE_SIZE)?;
+    let nonce = Nonce::from_slice(&nonce_bytes);
+    let cipher_text = hex::decode(cipher_hex).ok()?;
 
-   let nonce_bytes = hex::decode(nonce_hex).expect("Invalid Nonce Hex");
-   let nonce = Nonce::from_slice(&nonce_bytes);
-   let cipher_text = hex::decode(cipher_hex).expect("Invalid Cipher Hex");
+    let password = cipher.decrypt(nonce, cipher_text.as_ref()).ok()?;
 
-   let password = cipher.decrypt(nonce, cipher_text.as_ref()).expect("Decryption Failed");
-   
-   String::from_utf8(password).expect("Invalid UTF-8")
+    String::from_utf8(password).ok()
 }
 
 pub fn is_valid_cipher(cipher_hex: &str) -> bool {
@@ -65,4 +88,21 @@ pub fn is_valid_nonce(nonce_hex: &str) -> bool {
         Ok(bytes) => bytes.len() == NONCE_SIZE,
         Err(_) => false,
     }
-}
\ No newline at end of file
+}
+
+/// Keyed HMAC-SHA256 of a plaintext password, letting the server detect reuse without seeing the password.
+pub fn fingerprint(fingerprint_key: &str, password: &str) -> String {
+    let key_bytes = hex::decode(fingerprint_key).expect("Invalid Fingerprint Key Hex");
+
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key_bytes).expect("HMAC accepts any key size");
+    mac.update(password.as_bytes());
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+pub fn is_valid_fingerprint(fingerprint_hex: &str) -> bool {
+    match hex::decode(fingerprint_hex) {
+        Ok(bytes) => bytes.len() == FINGERPRINT_SIZE,
+        Err(_) => false,
+    }
+}
@@ -1 +1,10 @@
-pub mod encryption;
\ No newline at end of file
+pub mod encryption;
+pub mod breach;
+pub mod trigram;
+pub mod uri;
+pub mod clock;
+pub mod otp;
+pub mod share;
+pub mod token;
+pub mod signature;
+pub mod backup;
\ No newline at end of file
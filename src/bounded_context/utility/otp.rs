@@ -0,0 +1,267 @@
+use super::clock::Clock;
+use hmac::{Hmac, Mac};
+use percent_encoding::percent_decode_str;
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use thiserror::Error;
+use url::Url;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const STEAM_ALPHABET: &[u8; 26] = b"23456789BCDFGHJKMNPQRTVWXY";
+const STEAM_DIGITS: u32 = 5;
+const DEFAULT_DIGITS: u32 = 6;
+/// Numbers of digits authenticator apps accept
+const VALID_DIGITS: RangeInclusive<u32> = 6..=8;
+const DEFAULT_PERIOD: u64 = 30;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum OtpError {
+    #[error("Invalid OTP URI.")]
+    InvalidUri,
+    #[error("Unsupported OTP type: {0}")]
+    UnsupportedType(String),
+    #[error("OTP secret is missing.")]
+    MissingSecret,
+    #[error("OTP secret is not valid base32.")]
+    InvalidSecret,
+    #[error("Unsupported OTP algorithm: {0}")]
+    InvalidAlgorithm(String),
+    #[error("Invalid number of OTP digits: {0}")]
+    InvalidDigits(String),
+    #[error("Invalid OTP period: {0}")]
+    InvalidPeriod(String),
+    #[error("Invalid HOTP counter.")]
+    InvalidCounter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OtpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl FromStr for OtpAlgorithm {
+    type Err = OtpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().replace('-', "").as_str() {
+            "SHA1" => Ok(OtpAlgorithm::Sha1),
+            "SHA256" => Ok(OtpAlgorithm::Sha256),
+            "SHA512" => Ok(OtpAlgorithm::Sha512),
+            _ => Err(OtpError::InvalidAlgorithm(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OtpKind {
+    /// Time based (RFC 6238), a new code every `period` seconds
+    Totp { period: u64 },
+    /// Counter based (RFC 4226)
+    Hotp { counter: u64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OtpFormat {
+    Digits(u32),
+    /// Five characters from the Steam Guard alphabet
+    Steam,
+}
+
+/// A one-time password generator, usually read from an `otpauth://` URI
+#[derive(Clone, Debug, PartialEq)]
+pub struct OtpAuth {
+    pub kind: OtpKind,
+    pub algorithm: OtpAlgorithm,
+    pub format: OtpFormat,
+    pub secret: Vec<u8>,
+    pub issuer: Option<String>,
+    pub account: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OtpCode {
+    pub code: String,
+    /// Seconds until a TOTP code changes; HOTP codes do not expire
+    pub remaining_seconds: Option<u64>,
+}
+
+/// Decodes RFC 4648 base32, ignoring case, whitespace, dashes and padding.
+pub fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '-' && *c != '=') {
+        let value = BASE32_ALPHABET.iter().position(|a| *a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(output)
+}
+
+fn hmac<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn parse_secret(secret: Option<&str>) -> Result<Vec<u8>, OtpError> {
+    let secret = secret.filter(|secret| !secret.trim().is_empty()).ok_or(OtpError::MissingSecret)?;
+    match decode_base32(secret) {
+        Some(bytes) if !bytes.is_empty() => Ok(bytes),
+        _ => Err(OtpError::InvalidSecret),
+    }
+}
+
+impl OtpAuth {
+    /// A TOTP generator with the usual defaults: SHA-1, 6 digits, 30 seconds.
+    pub fn totp(secret: Vec<u8>) -> OtpAuth {
+        OtpAuth {
+            kind: OtpKind::Totp { period: DEFAULT_PERIOD },
+            algorithm: OtpAlgorithm::Sha1,
+            format: OtpFormat::Digits(DEFAULT_DIGITS),
+            secret,
+            issuer: None,
+            account: None,
+        }
+    }
+
+    /// Rejects generators built by hand with settings no URI is parsed into
+    fn validate(&self) -> Result<(), OtpError> {
+        if let OtpFormat::Digits(digits) = self.format {
+            if !VALID_DIGITS.contains(&digits) {
+                return Err(OtpError::InvalidDigits(digits.to_string()));
+            }
+        }
+        if self.kind == (OtpKind::Totp { period: 0 }) {
+            return Err(OtpError::InvalidPeriod("0".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// The code for a given counter value (RFC 4226).
+    pub fn code_at(&self, counter: u64) -> Result<String, OtpError> {
+        self.validate()?;
+
+        let digest = match self.algorithm {
+            OtpAlgorithm::Sha1 => hmac::<Hmac<Sha1>>(&self.secret, &counter.to_be_bytes()),
+            OtpAlgorithm::Sha256 => hmac::<Hmac<Sha256>>(&self.secret, &counter.to_be_bytes()),
+            OtpAlgorithm::Sha512 => hmac::<Hmac<Sha512>>(&self.secret, &counter.to_be_bytes()),
+        };
+
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let mut value = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+
+        let code = match self.format {
+            OtpFormat::Digits(digits) => format!("{:0width$}", value % 10u32.pow(digits), width = digits as usize),
+            OtpFormat::Steam => (0..STEAM_DIGITS)
+                .map(|_| {
+                    let c = STEAM_ALPHABET[(value % STEAM_ALPHABET.len() as u32) as usize] as char;
+                    value /= STEAM_ALPHABET.len() as u32;
+                    c
+                })
+                .collect(),
+        };
+
+        Ok(code)
+    }
+
+    /// The code to enter now. HOTP generators use their stored counter.
+    pub fn current_code(&self, clock: &impl Clock) -> Result<OtpCode, OtpError> {
+        self.validate()?;
+
+        let code = match self.kind {
+            OtpKind::Totp { period } => {
+                let seconds = clock.now().timestamp().max(0) as u64;
+                OtpCode {
+                    code: self.code_at(seconds / period)?,
+                    remaining_seconds: Some(period - seconds % period),
+                }
+            }
+            OtpKind::Hotp { counter } => OtpCode { code: self.code_at(counter)?, remaining_seconds: None },
+        };
+
+        Ok(code)
+    }
+}
+
+/// Parses `otpauth://totp/...` and `otpauth://hotp/...` URIs, Steam Guard `steam://SECRET` URIs
+/// and bare base32 secrets, which are read as TOTP with the usual defaults.
+impl FromStr for OtpAuth {
+    type Err = OtpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(secret) = s.strip_prefix("steam://") {
+            return Ok(OtpAuth { format: OtpFormat::Steam, ..OtpAuth::totp(parse_secret(Some(secret))?) });
+        }
+        if !s.contains("://") {
+            return Ok(OtpAuth::totp(parse_secret(Some(s))?));
+        }
+
+        let url = Url::parse(s).map_err(|_| OtpError::InvalidUri)?;
+        if url.scheme() != "otpauth" {
+            return Err(OtpError::InvalidUri);
+        }
+
+        let param = |name: &str| url.query_pairs().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.into_owned());
+
+        let kind = match url.host_str().map(str::to_lowercase).as_deref() {
+            Some("totp") => {
+                let period = match param("period") {
+                    Some(period) => period.parse().ok().filter(|period| *period > 0).ok_or(OtpError::InvalidPeriod(period))?,
+                    None => DEFAULT_PERIOD,
+                };
+                OtpKind::Totp { period }
+            }
+            Some("hotp") => {
+                let counter = param("counter").and_then(|counter| counter.parse().ok()).ok_or(OtpError::InvalidCounter)?;
+                OtpKind::Hotp { counter }
+            }
+            other => return Err(OtpError::UnsupportedType(other.unwrap_or_default().to_string())),
+        };
+
+        let algorithm = match param("algorithm") {
+            Some(algorithm) => algorithm.parse()?,
+            None => OtpAlgorithm::Sha1,
+        };
+
+        let format = if param("encoder").is_some_and(|encoder| encoder.eq_ignore_ascii_case("steam")) {
+            OtpFormat::Steam
+        } else {
+            match param("digits") {
+                Some(digits) => OtpFormat::Digits(
+                    digits.parse().ok().filter(|digits| VALID_DIGITS.contains(digits)).ok_or(OtpError::InvalidDigits(digits))?,
+                ),
+                None => OtpFormat::Digits(DEFAULT_DIGITS),
+            }
+        };
+
+        // The label is "issuer:account" or just "account"
+        let label = percent_decode_str(url.path().trim_start_matches('/')).decode_utf8_lossy();
+        let (label_issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (Some(issuer.trim().to_string()), account.trim().to_string()),
+            None => (None, label.trim().to_string()),
+        };
+
+        Ok(OtpAuth {
+            kind,
+            algorithm,
+            format,
+            secret: parse_secret(param("secret").as_deref())?,
+            issuer: param("issuer").or(label_issuer).filter(|issuer| !issuer.is_empty()),
+            account: Some(account).filter(|account| !account.is_empty()),
+        })
+    }
+}
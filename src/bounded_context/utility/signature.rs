@@ -0,0 +1,27 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+fn payload_mac(secret: &str, timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Signature of a webhook body, `sha256=` followed by the hex HMAC-SHA256 of `<timestamp>.<body>`.
+/// Signing the timestamp lets receivers reject replayed requests.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    format!("{}{}", SIGNATURE_PREFIX, hex::encode(payload_mac(secret, timestamp, body).finalize().into_bytes()))
+}
+
+/// Checks a signature made by `sign_payload` in constant time
+pub fn verify_payload_signature(secret: &str, timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix(SIGNATURE_PREFIX).and_then(|hex_mac| hex::decode(hex_mac).ok()) else {
+        return false;
+    };
+
+    payload_mac(secret, timestamp, body).verify_slice(&expected).is_ok()
+}
@@ -0,0 +1,25 @@
+use aes_gcm::aead::{OsRng, rand_core::RngCore};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+
+const TOKEN_SIZE: usize = 32;
+
+/// Generates an unguessable bearer token, such as the id in a share link
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; TOKEN_SIZE];
+    OsRng.fill_bytes(&mut bytes);
+
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Only the hash of a token is stored, so the database alone cannot be used to present it
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub fn is_valid_token(token: &str) -> bool {
+    URL_SAFE_NO_PAD
+        .decode(token)
+        .map(|bytes| bytes.len() == TOKEN_SIZE)
+        .unwrap_or(false)
+}
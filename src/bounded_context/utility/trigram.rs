@@ -0,0 +1,30 @@
+use std::collections::BTreeSet;
+
+/// Trigrams of `text` as extracted by Postgres `pg_trgm`: the text is lowercased and split into
+/// alphanumeric words, and each word is padded with two spaces in front and one behind. Postgres
+/// decides which non-ASCII characters are alphanumeric from the database locale, so results only
+/// agree for those under a UTF-8 locale.
+pub fn trigrams(text: &str) -> BTreeSet<String> {
+    let mut trigrams = BTreeSet::new();
+
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        let padded: Vec<char> = format!("  {} ", word.to_lowercase()).chars().collect();
+        for window in padded.windows(3) {
+            trigrams.insert(window.iter().collect());
+        }
+    }
+
+    trigrams
+}
+
+/// Share of trigrams two texts have in common, from 0 (none) to 1 (same trigrams). Matches the
+/// `similarity` function of `pg_trgm`.
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (trigrams(a), trigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let common = a.intersection(&b).count();
+    common as f32 / (a.len() + b.len() - common) as f32
+}
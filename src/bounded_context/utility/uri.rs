@@ -0,0 +1,82 @@
+use publicsuffix::{List, Psl};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::LazyLock;
+use url::Url;
+
+/// Snapshot of https://publicsuffix.org/list/public_suffix_list.dat, including private domains
+/// such as `github.io` so that separate sites on them get separate registrable domains.
+static PUBLIC_SUFFIX_LIST: LazyLock<List> = LazyLock::new(|| {
+    include_str!("../../../data/public_suffix_list.dat")
+        .parse()
+        .expect("Embedded Public Suffix List is invalid")
+});
+
+/// Parses a URL as users type it. The scheme defaults to `https://`, and a trailing dot on the
+/// host and the fragment are dropped. Parsing itself lowercases the host, converts it to
+/// punycode and removes default ports.
+pub fn normalize_uri(input: &str) -> Option<Url> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    let with_scheme = if input.contains("://") { input.to_string() } else { format!("https://{}", input) };
+    let mut url = Url::parse(&with_scheme).ok()?;
+    url.set_fragment(None);
+
+    if let Some(host) = url.host_str().filter(|host| host.ends_with('.')).map(|host| host.trim_end_matches('.').to_string()) {
+        url.set_host(Some(&host)).ok()?;
+    }
+
+    Some(url)
+}
+
+/// The registrable domain of `host`, e.g. `example.co.uk` for `login.example.co.uk`. IP
+/// addresses, single labels and bare public suffixes have none.
+pub fn registrable_domain(host: &str) -> Option<String> {
+    if host.starts_with('[') || host.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+
+    let domain = PUBLIC_SUFFIX_LIST.domain(host.as_bytes())?;
+    std::str::from_utf8(domain.as_bytes()).ok().map(str::to_string)
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // Shared address space (100.64.0.0/10), benchmarking (198.18.0.0/15) and reserved (240.0.0.0/4)
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+/// Whether `ip` is reachable on the public internet, as opposed to loopback, private, link-local
+/// and other special-purpose ranges.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_ipv4(mapped);
+            }
+
+            let first = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // Unique local (fc00::/7), link-local (fe80::/10) and documentation (2001:db8::/32)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+        }
+    }
+}
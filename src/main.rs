@@ -1,11 +1,163 @@
-use crate::bounded_context::infrastructure::{http::run_server, config::app_config};
-
-mod bounded_context;
+use rust_password_server::bounded_context::domain::audit::{verify_chain, AuditDb};
+use rust_password_server::bounded_context::domain::export::ExportFormat;
+use rust_password_server::bounded_context::domain::import::ImportFormat;
+use rust_password_server::bounded_context::infrastructure::{http::run_server, config::app_config, db::postgres_db::Database};
+use rust_password_server::bounded_context::infrastructure::import::{import_export, parse_export};
+use rust_password_server::bounded_context::infrastructure::export::{collect_export, write_export};
+use rust_password_server::bounded_context::infrastructure::client::session::write_private;
+use rust_password_server::bounded_context::utility::encryption::is_valid_masterkey;
+use chrono::{SubsecRound, Utc};
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
     let config = app_config::load_config();
-    run_server::run_server(config).await;
+
+    match std::env::args().nth(1).as_deref() {
+        Some("verify-audit") => verify_audit(config).await,
+        Some("import") => import(config).await,
+        Some("export") => export(config).await,
+        _ => run_server::run_server(config).await,
+    }
 
     Ok(())
 }
+
+/// Walks the audit log and exits non-zero if the hash chain was tampered with.
+async fn verify_audit(config: app_config::AppConfig) {
+    let mut database = Database::new(&config.db_url, 1, config.clone()).await.expect("Failed to connect to db.");
+    let events = database.list_audit_chain().await.expect("Failed to read the audit log.");
+
+    match verify_chain(&events) {
+        Ok(()) => println!("Audit log intact: {} events verified.", events.len()),
+        Err(err) => {
+            eprintln!("Audit log verification failed: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Imports a password manager export: `import <format> <file>`. Entries are encrypted with the hex
+/// master key in `IMPORT_KEY`, which is read from the environment so it stays out of the shell history.
+/// Logins are fingerprinted with the hex key in `IMPORT_FINGERPRINT_KEY` when it is set.
+async fn import(config: app_config::AppConfig) {
+    let args: Vec<String> = std::env::args().skip(2).collect();
+    let [format, path] = args.as_slice() else {
+        eprintln!("Usage: rust-password-server import <format> <file>");
+        std::process::exit(2);
+    };
+    let format: ImportFormat = format.parse().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let master_key = std::env::var("IMPORT_KEY").unwrap_or_default();
+    if !is_valid_masterkey(&master_key) {
+        eprintln!("IMPORT_KEY must hold the hex encoded 32 byte master key.");
+        std::process::exit(2);
+    }
+    let fingerprint_key = std::env::var("IMPORT_FINGERPRINT_KEY").ok().filter(|key| !key.is_empty());
+    if fingerprint_key.as_deref().is_some_and(|key| !hex::decode(key).is_ok_and(|bytes| !bytes.is_empty())) {
+        eprintln!("IMPORT_FINGERPRINT_KEY must hold a hex encoded key.");
+        std::process::exit(2);
+    }
+
+    let data = std::fs::read(path).expect("Failed to read the export file.");
+    let export = parse_export(format, &data).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    let mut database = Database::new(&config.db_url, 1, config.clone()).await.expect("Failed to connect to db.");
+    let report = import_export(&mut database, export, &master_key, fingerprint_key.as_deref(), Utc::now().trunc_subsecs(6))
+        .await
+        .expect("Failed to import the export.");
+
+    println!("Imported {} entries, created {} folders.", report.imported, report.folders_created);
+    if report.imported > 0 {
+        if fingerprint_key.is_none() {
+            println!("IMPORT_FINGERPRINT_KEY is not set, so the health report cannot detect reuse of imported entries.");
+        }
+        println!("Imported entries have no strength score, so the health report does not flag them as weak until a client scores them.");
+    }
+    for duplicate in &report.duplicates {
+        println!(
+            "Duplicate record {}: {} ({})",
+            duplicate.record,
+            duplicate.service,
+            duplicate.username.as_deref().unwrap_or("no username")
+        );
+    }
+    for skipped in &report.skipped {
+        println!("Skipped record {}: {}", skipped.record, skipped.reason);
+    }
+}
+
+/// Writes the vault in plaintext: `export <format> <file> --plaintext [--folder <path>] [--tag <tag>] [--overwrite]`.
+/// Entries are decrypted with the hex master key in `EXPORT_KEY`. Nothing is written without
+/// `--plaintext`, and an existing file is only replaced with `--overwrite`.
+async fn export(config: app_config::AppConfig) {
+    const USAGE: &str = "Usage: rust-password-server export <format> <file> --plaintext [--folder <path>] [--tag <tag>] [--overwrite]";
+
+    let mut positional = Vec::new();
+    let (mut folder, mut tag, mut plaintext, mut overwrite) = (None, None, false, false);
+    let mut args = std::env::args().skip(2);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--plaintext" => plaintext = true,
+            "--overwrite" => overwrite = true,
+            "--folder" => folder = args.next(),
+            "--tag" => tag = args.next(),
+            _ if arg.starts_with("--") => {
+                eprintln!("Unknown option {}\n{}", arg, USAGE);
+                std::process::exit(2);
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let [format, path] = positional.as_slice() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    let format: ExportFormat = format.parse().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    if !plaintext {
+        eprintln!("The export holds every selected secret in plaintext. Pass --plaintext to confirm.");
+        std::process::exit(2);
+    }
+    let master_key = std::env::var("EXPORT_KEY").unwrap_or_default();
+    if !is_valid_masterkey(&master_key) {
+        eprintln!("EXPORT_KEY must hold the hex encoded 32 byte master key.");
+        std::process::exit(2);
+    }
+
+    let mut database = Database::new(&config.db_url, 1, config.clone()).await.expect("Failed to connect to db.");
+    let entries = collect_export(&mut database, folder.as_deref(), tag.as_deref(), &master_key)
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+
+    let contents = write_export(format, &entries);
+    if overwrite {
+        // A new file renamed over the old one, which would otherwise keep its permissions
+        write_private(Path::new(path), &contents).unwrap_or_else(|err| {
+            eprintln!("Failed to replace {}: {}", path, err);
+            std::process::exit(1);
+        });
+    } else {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(path).unwrap_or_else(|err| {
+            eprintln!("Failed to create {}: {}. Pass --overwrite to replace an existing file.", path, err);
+            std::process::exit(1);
+        });
+        std::io::Write::write_all(&mut file, &contents).expect("Failed to write the export file.");
+    }
+
+    println!("Exported {} entries to {} as {}. The file is not encrypted, delete it once it is no longer needed.", entries.len(), path, format);
+}
@@ -0,0 +1,73 @@
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use sqlx::{Executor, PgPool};
+use std::process::Command;
+
+/// The schema `docker/init.sql` created before any column was added to `passwords`
+const ORIGINAL_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS passwords (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    service TEXT NOT NULL,
+    nonce TEXT NOT NULL,
+    cipher TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT NOW() NOT NULL
+);
+";
+
+/// Kept apart from the tables other tests use
+const SCHEMA: &str = "password_admin_migrate";
+
+/// `url` with every connection looking up tables in `SCHEMA` first
+fn schema_url(url: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{}{}options=-c%20search_path%3D{}%2Cpublic", url, separator, SCHEMA)
+}
+
+fn migrate(url: &str) {
+    let output = Command::new(env!("CARGO_BIN_EXE_password-admin"))
+        .arg("migrate")
+        .env("DATABASE_URL", url)
+        .output()
+        .expect("Failed to run password-admin");
+
+    assert!(output.status.success(), "migrate failed: {}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[tokio::test]
+async fn test_migrate_updates_the_original_schema() {
+    let url = schema_url(&app_config::load_config().test_db_url);
+    let pool = PgPool::connect(&url).await.expect("Failed to connect to the test database");
+    pool.execute(format!("DROP SCHEMA IF EXISTS {0} CASCADE; CREATE SCHEMA {0};", SCHEMA).as_str()).await.unwrap();
+    pool.execute(ORIGINAL_SCHEMA).await.unwrap();
+    pool.execute("INSERT INTO passwords (service, nonce, cipher, updated_at) VALUES ('example', 'nonce', 'cipher', NOW() - INTERVAL '1 day')")
+        .await
+        .unwrap();
+
+    migrate(&url);
+    // Running it again changes nothing
+    migrate(&url);
+
+    let columns: Vec<String> = sqlx::query_scalar(
+        "SELECT column_name::TEXT FROM information_schema.columns WHERE table_schema = $1 AND table_name = 'passwords'",
+    )
+    .bind(SCHEMA)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    for column in ["fingerprint", "item_data", "folder_id", "rotate_by", "sync_version", "rotation_due_at"] {
+        assert!(columns.iter().any(|name| name == column), "Column {} is missing", column);
+    }
+
+    let (service, item_type, favorite): (String, String, bool) = sqlx::query_as("SELECT service, item_type, favorite FROM passwords")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!((service.as_str(), item_type.as_str(), favorite), ("example", "login", false));
+
+    // Triggers added since work on the migrated table
+    pool.execute("UPDATE passwords SET rotation_interval_days = 1").await.unwrap();
+    let overdue: bool = sqlx::query_scalar("SELECT rotation_due_at <= NOW() FROM passwords").fetch_one(&pool).await.unwrap();
+    assert!(overdue);
+
+    pool.execute(format!("DROP SCHEMA {} CASCADE", SCHEMA).as_str()).await.unwrap();
+}
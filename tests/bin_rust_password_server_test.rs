@@ -0,0 +1,27 @@
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use std::process::Command;
+
+#[cfg(unix)]
+#[test]
+fn test_export_overwrite_makes_the_file_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("export-{}.env", uuid::Uuid::new_v4()));
+    std::fs::write(&path, "OLD='contents'\n").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    // A tag no entry has, so nothing from other tests is exported
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-password-server"))
+        .args(["export", "dotenv", path.to_str().unwrap(), "--plaintext", "--overwrite", "--tag", "export-overwrite-test"])
+        .env("DATABASE_URL", app_config::load_config().test_db_url)
+        .env("EXPORT_KEY", "00".repeat(32))
+        .output()
+        .expect("Failed to run the export");
+    assert!(output.status.success(), "export failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert!(!std::fs::read_to_string(&path).unwrap().contains("OLD"));
+
+    std::fs::remove_file(&path).unwrap();
+}
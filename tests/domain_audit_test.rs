@@ -0,0 +1,97 @@
+use rust_password_server::bounded_context::domain::audit::*;
+use chrono::{SubsecRound, Utc};
+use uuid::Uuid;
+
+fn new_event(action: &str, status: i32) -> NewAuditEvent {
+    NewAuditEvent {
+        occurred_at: Utc::now().trunc_subsecs(6),
+        request_id: Uuid::new_v4().to_string(),
+        actor: "alice".to_string(),
+        action: action.to_string(),
+        entry_id: Some(Uuid::new_v4()),
+        ip: Some("127.0.0.1".to_string()),
+        user_agent: Some("test".to_string()),
+        status,
+        outcome: AuditOutcome::from_status(status as u16).as_str().to_string(),
+    }
+}
+
+fn chain(actions: &[&str]) -> Vec<AuditEvent> {
+    let mut prev_hash = GENESIS_HASH.to_string();
+
+    actions
+        .iter()
+        .enumerate()
+        .map(|(index, action)| {
+            let event = new_event(action, 200);
+            let hash = event.chain_hash(&prev_hash);
+            let stored = AuditEvent {
+                seq: index as i64 + 1,
+                occurred_at: event.occurred_at,
+                request_id: event.request_id,
+                actor: event.actor,
+                action: event.action,
+                entry_id: event.entry_id,
+                ip: event.ip,
+                user_agent: event.user_agent,
+                status: event.status,
+                outcome: event.outcome,
+                prev_hash: prev_hash.clone(),
+                hash: hash.clone(),
+            };
+            prev_hash = hash;
+            stored
+        })
+        .collect()
+}
+
+#[test]
+fn test_outcome_from_status() {
+    assert_eq!(AuditOutcome::from_status(200), AuditOutcome::Success);
+    assert_eq!(AuditOutcome::from_status(304), AuditOutcome::Success);
+    assert_eq!(AuditOutcome::from_status(404), AuditOutcome::Failure);
+    assert_eq!(AuditOutcome::from_status(500).as_str(), "failure");
+}
+
+#[test]
+fn test_chain_hash_depends_on_previous_hash_and_fields() {
+    let event = new_event("GET /password/", 200);
+    let hash = event.chain_hash(GENESIS_HASH);
+
+    assert_eq!(hash.len(), 64);
+    assert_eq!(hash, event.chain_hash(GENESIS_HASH));
+    assert_ne!(hash, event.chain_hash(&hash));
+
+    let mut edited = event.clone();
+    edited.status = 500;
+    assert_ne!(hash, edited.chain_hash(GENESIS_HASH));
+}
+
+#[test]
+fn test_verify_intact_chain() {
+    assert_eq!(verify_chain(&[]), Ok(()));
+    assert_eq!(verify_chain(&chain(&["GET /password/", "POST /password/", "DELETE /password/"])), Ok(()));
+}
+
+#[test]
+fn test_verify_detects_edited_event() {
+    let mut events = chain(&["GET /password/", "POST /password/", "DELETE /password/"]);
+    events[1].actor = "mallory".to_string();
+
+    assert_eq!(verify_chain(&events), Err(AuditChainError::HashMismatch { seq: 2 }));
+}
+
+#[test]
+fn test_verify_detects_deleted_and_reordered_events() {
+    let mut events = chain(&["GET /password/", "POST /password/", "DELETE /password/"]);
+    events.remove(1);
+    assert_eq!(verify_chain(&events), Err(AuditChainError::BrokenLink { seq: 3 }));
+
+    let mut events = chain(&["GET /password/", "POST /password/"]);
+    events.swap(0, 1);
+    assert_eq!(verify_chain(&events), Err(AuditChainError::BrokenLink { seq: 2 }));
+
+    let mut events = chain(&["GET /password/", "POST /password/"]);
+    events.remove(0);
+    assert_eq!(verify_chain(&events), Err(AuditChainError::BrokenLink { seq: 2 }));
+}
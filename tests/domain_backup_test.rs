@@ -0,0 +1,44 @@
+use rust_password_server::bounded_context::domain::backup::*;
+use rust_password_server::bounded_context::domain::folder::Folder;
+use rust_password_server::bounded_context::domain::password::Password;
+use chrono::Utc;
+use uuid::Uuid;
+
+fn backup(folders: Vec<Folder>) -> VaultBackup {
+    let entry = Password::new(Uuid::new_v4(), "example".to_string(), "nonce".to_string(), "cipher".to_string());
+    VaultBackup { exported_at: Utc::now(), folders, entries: vec![entry] }
+}
+
+#[test]
+fn test_seal_and_open() {
+    let backup = backup(Vec::new());
+    let envelope = backup.seal("correct horse battery");
+
+    assert_eq!(VaultBackup::open(&envelope, "correct horse battery").unwrap(), backup);
+    assert!(matches!(VaultBackup::open(&envelope, "wrong horse battery"), Err(BackupError::IntegrityCheckFailed)));
+
+    let mut format = envelope.clone();
+    format.format = "something-else".to_string();
+    assert!(matches!(VaultBackup::open(&format, "correct horse battery"), Err(BackupError::InvalidFile(_))));
+
+    let mut version = envelope;
+    version.version = 2;
+    assert!(matches!(VaultBackup::open(&version, "correct horse battery"), Err(BackupError::UnsupportedVersion(2))));
+}
+
+#[test]
+fn test_folders_parents_first() {
+    let root = Folder::new(Uuid::new_v4(), "root".to_string(), None);
+    let child = Folder::new(Uuid::new_v4(), "child".to_string(), Some(root.id));
+    let grandchild = Folder::new(Uuid::new_v4(), "grandchild".to_string(), Some(child.id));
+    let orphan = Folder::new(Uuid::new_v4(), "orphan".to_string(), Some(Uuid::new_v4()));
+    let backup = backup(vec![grandchild.clone(), orphan.clone(), child.clone(), root.clone()]);
+
+    let ordered: Vec<Uuid> = backup.folders_parents_first().iter().map(|folder| folder.id).collect();
+    let position = |id: Uuid| ordered.iter().position(|ordered_id| *ordered_id == id).unwrap();
+
+    assert_eq!(ordered.len(), 4);
+    assert!(position(root.id) < position(child.id));
+    assert!(position(child.id) < position(grandchild.id));
+    assert!(ordered.contains(&orphan.id));
+}
@@ -0,0 +1,75 @@
+use rust_password_server::bounded_context::domain::emergency_access::*;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use EmergencyAccessAction as Action;
+use EmergencyAccessStatus as Status;
+
+#[test]
+fn test_allowed_transitions() {
+    assert_eq!(Action::Accept.apply(Status::Invited), Some(Status::Accepted));
+    assert_eq!(Action::Request.apply(Status::Accepted), Some(Status::Requested));
+    assert_eq!(Action::Approve.apply(Status::Requested), Some(Status::Granted));
+    assert_eq!(Action::AutoApprove.apply(Status::Requested), Some(Status::Granted));
+    assert_eq!(Action::Reject.apply(Status::Requested), Some(Status::Accepted));
+
+    for status in [Status::Invited, Status::Accepted, Status::Requested, Status::Granted] {
+        assert_eq!(Action::Revoke.apply(status), Some(Status::Revoked));
+    }
+}
+
+#[test]
+fn test_rejected_transitions() {
+    assert_eq!(Action::Request.apply(Status::Invited), None);
+    assert_eq!(Action::Approve.apply(Status::Accepted), None);
+    assert_eq!(Action::Reject.apply(Status::Granted), None);
+    assert_eq!(Action::Accept.apply(Status::Accepted), None);
+    assert_eq!(Action::Invite.apply(Status::Invited), None);
+
+    for action in [Action::Accept, Action::Request, Action::Approve, Action::Reject, Action::Revoke] {
+        assert_eq!(action.apply(Status::Revoked), None);
+    }
+}
+
+#[test]
+fn test_status_and_action_round_trip() {
+    for status in [Status::Invited, Status::Accepted, Status::Requested, Status::Granted, Status::Revoked] {
+        assert_eq!(status.as_str().parse::<Status>().unwrap(), status);
+    }
+
+    for action in [Action::Invite, Action::Accept, Action::Request, Action::Approve, Action::AutoApprove, Action::Reject, Action::Revoke] {
+        assert_eq!(action.to_string().parse::<Action>().unwrap(), action);
+    }
+
+    assert!("pending".parse::<Status>().is_err());
+    assert!("grant".parse::<Action>().is_err());
+}
+
+#[test]
+fn test_wait_elapsed() {
+    let mut access = EmergencyAccess::new(Uuid::new_v4(), "Alex".to_string(), "token", 24);
+    let requested_at = Utc::now();
+
+    assert_eq!(access.status, Status::Invited);
+    assert_eq!(access.grants_at(), None);
+    assert_ne!(access.token_hash, "token");
+
+    access.status = Status::Requested;
+    access.requested_at = Some(requested_at);
+
+    assert_eq!(access.grants_at(), Some(requested_at + Duration::hours(24)));
+    assert!(!access.wait_elapsed(requested_at + Duration::hours(23)));
+    assert!(access.wait_elapsed(requested_at + Duration::hours(24)));
+
+    access.status = Status::Accepted;
+    assert!(!access.wait_elapsed(requested_at + Duration::hours(48)));
+}
+
+#[test]
+fn test_token_hash_is_not_serialized() {
+    let access = EmergencyAccess::new(Uuid::new_v4(), "Alex".to_string(), "token", 24);
+    let json = serde_json::to_value(&access).unwrap();
+
+    assert!(json.get("token_hash").is_none());
+    assert_eq!(json["status"], "invited");
+}
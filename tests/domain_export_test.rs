@@ -0,0 +1,111 @@
+use rust_password_server::bounded_context::domain::export::*;
+use rust_password_server::bounded_context::domain::folder::Folder;
+use rust_password_server::bounded_context::domain::import::{ImportedEntry, ImportedField, ImportedItem};
+use rust_password_server::bounded_context::domain::password::Password;
+use rust_password_server::bounded_context::utility::encryption::generate_key;
+use chrono::{SubsecRound, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[test]
+fn test_export_format_from_str() {
+    assert_eq!("bitwarden".parse::<ExportFormat>().unwrap(), ExportFormat::BitwardenJson);
+    assert_eq!("KeePass-CSV".parse::<ExportFormat>().unwrap(), ExportFormat::KeepassCsv);
+    assert_eq!(".env".parse::<ExportFormat>().unwrap(), ExportFormat::Dotenv);
+    assert!("keepass_xml".parse::<ExportFormat>().is_err());
+
+    for format in [ExportFormat::BitwardenJson, ExportFormat::KeepassCsv, ExportFormat::Dotenv] {
+        assert_eq!(format.as_str().parse::<ExportFormat>().unwrap(), format);
+    }
+}
+
+#[test]
+fn test_decrypt_entry() {
+    let key = generate_key();
+    let now = Utc::now().trunc_subsecs(6);
+    let password = ImportedEntry {
+        service: "Visa".to_string(),
+        secret: "4111 1111 1111 1111".to_string(),
+        uris: vec!["https://bank.example.com".to_string()],
+        notes: Some("call the bank first".to_string()),
+        fields: vec![
+            ImportedField::Text { name: "branch".to_string(), value: "main street".to_string() },
+            ImportedField::Hidden { name: "pin".to_string(), value: "1234".to_string() },
+        ],
+        item: ImportedItem::Card {
+            cardholder_name: Some("Jane Doe".to_string()),
+            expiry: Some("04/2030".to_string()),
+            security_code: Some("123".to_string()),
+            brand: Some("Visa".to_string()),
+        },
+        tags: vec!["finance".to_string()],
+        favorite: true,
+        ..Default::default()
+    }
+    .encrypt(&key, None, Uuid::new_v4(), None, now);
+
+    let entry = ExportedEntry::decrypt(&password, &key, vec!["Personal".to_string()]).unwrap();
+    assert_eq!(entry.id, password.id);
+    assert_eq!(entry.secret, "4111 1111 1111 1111");
+    assert_eq!(entry.uris, vec!["https://bank.example.com".to_string()]);
+    assert_eq!(entry.notes.as_deref(), Some("call the bank first"));
+    assert_eq!(
+        entry.fields,
+        vec![
+            ExportedField::Text { name: "branch".to_string(), value: "main street".to_string() },
+            ExportedField::Hidden { name: "pin".to_string(), value: "1234".to_string() },
+        ]
+    );
+    assert_eq!(
+        entry.item,
+        ExportedItem::Card {
+            cardholder_name: Some("Jane Doe".to_string()),
+            expiry: Some("04/2030".to_string()),
+            security_code: Some("123".to_string()),
+            brand: Some("Visa".to_string()),
+        }
+    );
+    assert_eq!(entry.folder, vec!["Personal".to_string()]);
+    assert_eq!(entry.tags, vec!["finance".to_string()]);
+    assert!(entry.favorite);
+    assert_eq!(entry.created_at, now);
+
+    assert!(matches!(ExportedEntry::decrypt(&password, &generate_key(), Vec::new()), Err(ExportError::DecryptionFailed(id)) if id == password.id));
+}
+
+#[test]
+fn test_export_selection() {
+    let folder_id = Uuid::new_v4();
+    let mut tagged = Password::new(Uuid::new_v4(), "tagged".to_string(), "nonce".to_string(), "cipher".to_string());
+    tagged.tags = vec!["ci".to_string()];
+    let mut filed = Password::new(Uuid::new_v4(), "filed".to_string(), "nonce".to_string(), "cipher".to_string());
+    filed.folder_id = Some(folder_id);
+    filed.tags = vec!["ci".to_string()];
+
+    assert!(ExportSelection::default().includes(&tagged));
+
+    let by_tag = ExportSelection { tag: Some("ci".to_string()), ..Default::default() };
+    assert!(by_tag.includes(&tagged) && by_tag.includes(&filed));
+
+    let by_folder = ExportSelection { folders: Some(HashSet::from([folder_id])), ..Default::default() };
+    assert!(!by_folder.includes(&tagged) && by_folder.includes(&filed));
+
+    let by_both = ExportSelection { folders: Some(HashSet::from([folder_id])), tag: Some("other".to_string()) };
+    assert!(!by_both.includes(&filed));
+}
+
+#[test]
+fn test_folder_paths() {
+    let work = Folder::new(Uuid::new_v4(), "Work".to_string(), None);
+    let servers = Folder::new(Uuid::new_v4(), "Servers".to_string(), Some(work.id));
+    let staging = Folder::new(Uuid::new_v4(), "Staging".to_string(), Some(servers.id));
+    let home = Folder::new(Uuid::new_v4(), "Servers".to_string(), None);
+    let paths = FolderPaths::new(&[work.clone(), servers.clone(), staging.clone(), home.clone()]);
+
+    assert_eq!(paths.path(Some(staging.id)), vec!["Work", "Servers", "Staging"]);
+    assert!(paths.path(None).is_empty());
+    assert_eq!(paths.find(&["work".to_string(), "servers".to_string()]), Some(servers.id));
+    assert_eq!(paths.find(&["Servers".to_string()]), Some(home.id));
+    assert_eq!(paths.find(&["Staging".to_string()]), None);
+    assert_eq!(paths.subtree(servers.id), HashSet::from([servers.id, staging.id]));
+}
@@ -0,0 +1,29 @@
+use rust_password_server::bounded_context::domain::fuzzy_search::*;
+use rust_password_server::bounded_context::domain::password::Password;
+use uuid::Uuid;
+
+fn password(service: &str) -> Password {
+    Password::new(Uuid::new_v4(), service.to_string(), "n".to_string(), "c".to_string())
+}
+
+#[test]
+fn test_rank_by_similarity() {
+    let passwords = vec![password("GitLab"), password("Netflix"), password("GitHub"), password("github")];
+
+    let ranked = rank_by_similarity(passwords, "githbu", DEFAULT_SIMILARITY_THRESHOLD);
+    let services: Vec<String> = ranked.iter().map(|found| found.password.service.to_lowercase()).collect();
+
+    assert_eq!(&services[..2], &["github", "github"][..]);
+    assert!(!services.contains(&"netflix".to_string()));
+    assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    assert!(ranked.iter().all(|found| found.score >= DEFAULT_SIMILARITY_THRESHOLD));
+}
+
+#[test]
+fn test_rank_by_similarity_threshold() {
+    let ranked = rank_by_similarity(vec![password("GitHub")], "githbu", 0.5);
+    assert!(ranked.is_empty());
+
+    let ranked = rank_by_similarity(vec![password("GitHub")], "githbu", 0.4);
+    assert_eq!(ranked.len(), 1);
+}
@@ -0,0 +1,106 @@
+use rust_password_server::bounded_context::domain::health_report::*;
+use rust_password_server::bounded_context::domain::password::Password;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+const POLICY: HealthPolicy = HealthPolicy { stale_after_days: 90, weak_below_strength: 3 };
+
+fn entry(service: &str, age_days: i64, fingerprint: Option<&str>, strength: Option<i16>) -> Password {
+    let updated_at = Utc::now() - Duration::days(age_days);
+    Password {
+        id: Uuid::new_v4(),
+        service: service.to_string(),
+        nonce: "n".to_string(),
+        cipher: "c".to_string(),
+        created_at: updated_at,
+        updated_at,
+        fingerprint: fingerprint.map(|f| f.to_string()),
+        strength,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_reused_groups() {
+    let passwords = vec![
+        entry("Gmail", 1, Some("aaaa"), Some(4)),
+        entry("GitHub", 1, Some("aaaa"), Some(4)),
+        entry("Work", 1, Some("aaaa"), Some(4)),
+        entry("Bank", 1, Some("bbbb"), Some(4)),
+        entry("Forum", 1, Some("bbbb"), Some(4)),
+        entry("Unique", 1, Some("cccc"), Some(4)),
+        entry("Legacy", 1, None, Some(4)),
+    ];
+
+    let report = HealthReport::build(&passwords, &POLICY, Utc::now());
+
+    assert_eq!(report.reused.len(), 2);
+    assert_eq!(report.reused[0].fingerprint, "aaaa");
+    assert_eq!(report.reused[0].entries.len(), 3);
+    assert_eq!(report.reused[1].fingerprint, "bbbb");
+    assert_eq!(report.reused[1].entries.len(), 2);
+}
+
+#[test]
+fn test_stale_entries_sorted_by_age() {
+    let passwords = vec![
+        entry("Fresh", 10, None, None),
+        entry("Stale", 100, None, None),
+        entry("Very Stale", 500, None, None),
+        entry("Boundary", 90, None, None),
+    ];
+
+    let report = HealthReport::build(&passwords, &POLICY, Utc::now());
+
+    let services: Vec<&str> = report.stale.iter().map(|e| e.service.as_str()).collect();
+    assert_eq!(services, vec!["Very Stale", "Stale", "Boundary"]);
+    assert_eq!(report.stale[0].age_days, 500);
+}
+
+#[test]
+fn test_weak_and_unscored_entries() {
+    let passwords = vec![
+        entry("Weak", 1, None, Some(0)),
+        entry("Fair", 1, None, Some(2)),
+        entry("Strong", 1, None, Some(3)),
+        entry("Unknown", 1, None, None),
+    ];
+
+    let report = HealthReport::build(&passwords, &POLICY, Utc::now());
+
+    let services: Vec<&str> = report.weak.iter().map(|e| e.service.as_str()).collect();
+    assert_eq!(services, vec!["Weak", "Fair"]);
+    assert_eq!(report.unscored, 1);
+}
+
+#[test]
+fn test_age_buckets() {
+    let passwords = vec![
+        entry("A", 0, None, None),
+        entry("B", 29, None, None),
+        entry("C", 30, None, None),
+        entry("D", 200, None, None),
+        entry("E", 1000, None, None),
+    ];
+
+    let report = HealthReport::build(&passwords, &POLICY, Utc::now());
+
+    let counts: Vec<usize> = report.age_buckets.iter().map(|b| b.count).collect();
+    assert_eq!(counts, vec![2, 1, 0, 1, 1]);
+    assert_eq!(report.age_buckets[0].min_days, 0);
+    assert_eq!(report.age_buckets[0].max_days, Some(30));
+    assert_eq!(report.age_buckets[4].min_days, 365);
+    assert_eq!(report.age_buckets[4].max_days, None);
+    assert_eq!(report.total_entries, 5);
+}
+
+#[test]
+fn test_empty_vault() {
+    let report = HealthReport::build(&[], &POLICY, Utc::now());
+
+    assert_eq!(report.total_entries, 0);
+    assert!(report.reused.is_empty());
+    assert!(report.stale.is_empty());
+    assert!(report.weak.is_empty());
+    assert!(report.age_buckets.iter().all(|b| b.count == 0));
+}
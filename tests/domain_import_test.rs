@@ -0,0 +1,126 @@
+use rust_password_server::bounded_context::domain::import::*;
+use rust_password_server::bounded_context::domain::item::ItemData;
+use rust_password_server::bounded_context::domain::password::CustomField;
+use rust_password_server::bounded_context::utility::encryption::{decrypt, fingerprint, generate_key};
+use chrono::Utc;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[test]
+fn test_import_format_from_str() {
+    assert_eq!("bitwarden".parse::<ImportFormat>().unwrap(), ImportFormat::BitwardenJson);
+    assert_eq!("KeePass-XML".parse::<ImportFormat>().unwrap(), ImportFormat::KeepassXml);
+    assert_eq!("1pux".parse::<ImportFormat>().unwrap(), ImportFormat::OnePassword1pux);
+    assert_eq!("1password_csv".parse::<ImportFormat>().unwrap(), ImportFormat::OnePasswordCsv);
+    assert_eq!("chrome".parse::<ImportFormat>().unwrap(), ImportFormat::ChromeCsv);
+    assert_eq!("firefox_csv".parse::<ImportFormat>().unwrap(), ImportFormat::FirefoxCsv);
+    assert!("lastpass".parse::<ImportFormat>().is_err());
+
+    for format in [ImportFormat::BitwardenJson, ImportFormat::OnePassword1pux, ImportFormat::FirefoxCsv] {
+        assert_eq!(format.as_str().parse::<ImportFormat>().unwrap(), format);
+    }
+}
+
+#[test]
+fn test_encrypt_imported_entry() {
+    let key = generate_key();
+    let now = Utc::now();
+    let folder_id = Uuid::new_v4();
+    let entry = ImportedEntry {
+        record: 3,
+        service: "GitHub".to_string(),
+        username: Some("octocat".to_string()),
+        secret: "hunter2".to_string(),
+        uris: vec!["https://github.com".to_string(), " ".to_string()],
+        notes: Some("recovery codes in the safe".to_string()),
+        totp: Some("JBSWY3DPEHPK3PXP".to_string()),
+        fields: vec![
+            ImportedField::Text { name: "team".to_string(), value: "platform".to_string() },
+            ImportedField::Hidden { name: "pin".to_string(), value: "1234".to_string() },
+        ],
+        tags: vec!["work".to_string()],
+        favorite: true,
+        ..Default::default()
+    };
+
+    let fingerprint_key = generate_key();
+    let password = entry.encrypt(&key, Some(&fingerprint_key), Uuid::new_v4(), Some(folder_id), now);
+
+    assert_eq!(password.service, "GitHub");
+    assert_eq!(decrypt(&key, &password.nonce, &password.cipher), "hunter2");
+    assert_eq!(password.fingerprint, Some(fingerprint(&fingerprint_key, "hunter2")));
+    let notes = password.notes.expect("Notes were not imported");
+    assert_eq!(decrypt(&key, &notes.nonce, &notes.cipher), "recovery codes in the safe");
+    let totp = password.totp.expect("TOTP secret was not imported");
+    assert_eq!(decrypt(&key, &totp.nonce, &totp.cipher), "JBSWY3DPEHPK3PXP");
+    assert_eq!(password.uris.len(), 1);
+    assert_eq!(password.custom_fields[0], CustomField::Text { name: "team".to_string(), value: "platform".to_string() });
+    match &password.custom_fields[1] {
+        CustomField::Hidden { name, nonce, cipher } => {
+            assert_eq!(name, "pin");
+            assert_eq!(decrypt(&key, nonce, cipher), "1234");
+        }
+        other => panic!("Expected a hidden field, got {:?}", other),
+    }
+    assert_eq!(password.folder_id, Some(folder_id));
+    assert_eq!(password.created_at, now);
+    assert_eq!(password.updated_at, now);
+    assert!(password.favorite);
+}
+
+#[test]
+fn test_encrypt_imported_card() {
+    let key = generate_key();
+    let entry = ImportedEntry {
+        service: "Visa".to_string(),
+        secret: "4111 1111 1111 1234".to_string(),
+        item: ImportedItem::Card {
+            cardholder_name: Some("Ada Lovelace".to_string()),
+            expiry: Some("04/2030".to_string()),
+            security_code: None,
+            brand: Some("Visa".to_string()),
+        },
+        ..Default::default()
+    };
+
+    let password = entry.encrypt(&key, Some(&generate_key()), Uuid::new_v4(), None, Utc::now());
+
+    assert!(password.fingerprint.is_none());
+    match password.item {
+        ItemData::Card { cardholder_name, expiry, security_code, brand, last_four } => {
+            let cardholder_name = cardholder_name.expect("Cardholder name was not imported");
+            assert_eq!(decrypt(&key, &cardholder_name.nonce, &cardholder_name.cipher), "Ada Lovelace");
+            assert!(expiry.is_some());
+            assert!(security_code.is_none());
+            assert_eq!(brand.as_deref(), Some("Visa"));
+            assert_eq!(last_four.as_deref(), Some("1234"));
+        }
+        other => panic!("Expected a card, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_remove_duplicates() {
+    let entry = |record: usize, service: &str, username: Option<&str>| ImportedEntry {
+        record,
+        service: service.to_string(),
+        username: username.map(str::to_string),
+        ..Default::default()
+    };
+    let mut export = ParsedExport {
+        entries: vec![
+            entry(1, "GitHub", Some("octocat")),
+            entry(2, "github ", Some("OctoCat")),
+            entry(3, "GitLab", Some("octocat")),
+            entry(4, "Example", None),
+        ],
+        skipped: Vec::new(),
+    };
+    let existing: HashSet<_> = [duplicate_key("example", None)].into_iter().collect();
+
+    let duplicates = export.remove_duplicates(&existing);
+
+    assert_eq!(export.entries.iter().map(|entry| entry.record).collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(duplicates.iter().map(|duplicate| duplicate.record).collect::<Vec<_>>(), vec![2, 4]);
+    assert_eq!(duplicates[0].username.as_deref(), Some("OctoCat"));
+}
@@ -0,0 +1,93 @@
+use rust_password_server::bounded_context::domain::item::*;
+use rust_password_server::bounded_context::domain::password::{EncryptedText, Password};
+use std::str::FromStr;
+use uuid::uuid;
+
+fn encrypted(value: &str) -> EncryptedText {
+    EncryptedText { nonce: format!("{}_nonce", value), cipher: format!("{}_cipher", value) }
+}
+
+#[test]
+fn test_item_type_from_str() {
+    assert_eq!(ItemType::from_str("login").unwrap(), ItemType::Login);
+    assert_eq!(ItemType::from_str("secure_note").unwrap(), ItemType::SecureNote);
+    assert_eq!(ItemType::from_str("SecureNote").unwrap(), ItemType::SecureNote);
+    assert_eq!(ItemType::from_str("CARD").unwrap(), ItemType::Card);
+    assert_eq!(ItemType::from_str("identity").unwrap(), ItemType::Identity);
+    assert_eq!(ItemType::from_str("ssh_key").unwrap(), ItemType::SshKey);
+    assert_eq!(ItemType::from_str("api_key").unwrap(), ItemType::ApiKey);
+    assert!(ItemType::from_str("passport").is_err());
+}
+
+#[test]
+fn test_item_type_round_trips_as_str() {
+    for item_type in [ItemType::Login, ItemType::SecureNote, ItemType::Card, ItemType::Identity, ItemType::SshKey, ItemType::ApiKey] {
+        assert_eq!(ItemType::from_str(item_type.as_str()).unwrap(), item_type);
+        assert_eq!(serde_json::to_value(item_type).unwrap(), item_type.as_str());
+    }
+}
+
+#[test]
+fn test_item_data_item_type() {
+    assert_eq!(ItemData::default().item_type(), ItemType::Login);
+    assert_eq!(ItemData::SecureNote.item_type(), ItemType::SecureNote);
+    let key = ItemData::SshKey { public_key: "ssh-ed25519 AAAA".to_string(), key_fingerprint: None, passphrase: None };
+    assert_eq!(key.item_type(), ItemType::SshKey);
+}
+
+#[test]
+fn test_encrypted_fields() {
+    let card = ItemData::Card {
+        cardholder_name: Some(encrypted("name")),
+        expiry: None,
+        security_code: Some(encrypted("cvv")),
+        brand: Some("visa".to_string()),
+        last_four: Some("4242".to_string()),
+    };
+
+    let fields = card.encrypted_fields();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0].nonce, "name_nonce");
+    assert_eq!(fields[1].cipher, "cvv_cipher");
+
+    assert!(ItemData::Login.encrypted_fields().is_empty());
+}
+
+#[test]
+fn test_password_json_shape_per_type() {
+    let mut password = Password::new(
+        uuid!("00000000-0000-0000-0000-000000000001"),
+        "Visa".to_string(),
+        "nonce".to_string(),
+        "cipher".to_string(),
+    );
+    password.item = ItemData::Card {
+        cardholder_name: None,
+        expiry: Some(encrypted("expiry")),
+        security_code: None,
+        brand: Some("visa".to_string()),
+        last_four: Some("4242".to_string()),
+    };
+
+    let json = serde_json::to_value(&password).expect("Failed to serialize");
+    assert_eq!(json["item_type"], "card");
+    assert_eq!(json["last_four"], "4242");
+    assert_eq!(json["expiry"]["nonce"], "expiry_nonce");
+
+    let parsed: Password = serde_json::from_value(json).expect("Failed to deserialize");
+    assert_eq!(parsed, password);
+}
+
+#[test]
+fn test_login_json_shape() {
+    let password = Password::new(
+        uuid!("00000000-0000-0000-0000-000000000001"),
+        "GitHub".to_string(),
+        "nonce".to_string(),
+        "cipher".to_string(),
+    );
+
+    let json = serde_json::to_value(&password).expect("Failed to serialize");
+    assert_eq!(json["item_type"], "login");
+    assert!(json.get("public_key").is_none());
+}
@@ -0,0 +1,54 @@
+use rust_password_server::bounded_context::domain::import::{ImportedEntry, ImportedField, ImportedItem};
+use rust_password_server::bounded_context::domain::item::ItemData;
+use rust_password_server::bounded_context::domain::key_rotation::*;
+use rust_password_server::bounded_context::domain::password::{CustomField, Password};
+use rust_password_server::bounded_context::utility::encryption::{decrypt, generate_key};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn entry(key: &str) -> Password {
+    ImportedEntry {
+        service: "Visa".to_string(),
+        secret: "4111111111111111".to_string(),
+        notes: Some("call the bank first".to_string()),
+        fields: vec![
+            ImportedField::Text { name: "branch".to_string(), value: "main street".to_string() },
+            ImportedField::Hidden { name: "pin".to_string(), value: "1234".to_string() },
+        ],
+        item: ImportedItem::Card { cardholder_name: Some("Jane Doe".to_string()), expiry: None, security_code: Some("123".to_string()), brand: None },
+        ..Default::default()
+    }
+    .encrypt(key, None, Uuid::new_v4(), None, Utc::now())
+}
+
+#[test]
+fn test_reencrypt() {
+    let old_key = generate_key();
+    let new_key = generate_key();
+    let password = entry(&old_key);
+
+    let rotated = reencrypt(&password, &old_key, &new_key).unwrap();
+    assert_eq!(rotated.id, password.id);
+    assert_eq!(rotated.updated_at, password.updated_at);
+    assert_ne!(rotated.cipher, password.cipher);
+    assert_eq!(decrypt(&new_key, &rotated.nonce, &rotated.cipher), "4111111111111111");
+
+    let notes = rotated.notes.as_ref().unwrap();
+    assert_eq!(decrypt(&new_key, &notes.nonce, &notes.cipher), "call the bank first");
+    assert_eq!(rotated.custom_fields[0], password.custom_fields[0]);
+    let CustomField::Hidden { nonce, cipher, .. } = &rotated.custom_fields[1] else { panic!("Expected a hidden field") };
+    assert_eq!(decrypt(&new_key, nonce, cipher), "1234");
+    let ItemData::Card { cardholder_name, security_code, last_four, .. } = &rotated.item else { panic!("Expected a card") };
+    let cardholder_name = cardholder_name.as_ref().unwrap();
+    assert_eq!(decrypt(&new_key, &cardholder_name.nonce, &cardholder_name.cipher), "Jane Doe");
+    let security_code = security_code.as_ref().unwrap();
+    assert_eq!(decrypt(&new_key, &security_code.nonce, &security_code.cipher), "123");
+    assert_eq!(last_four.as_deref(), Some("1111"));
+}
+
+#[test]
+fn test_reencrypt_with_wrong_key() {
+    let password = entry(&generate_key());
+
+    assert!(matches!(reencrypt(&password, &generate_key(), &generate_key()), Err(KeyRotationError::DecryptionFailed(id)) if id == password.id));
+}
@@ -0,0 +1,52 @@
+use rust_password_server::bounded_context::domain::pagination::*;
+use rust_password_server::bounded_context::domain::password::Password;
+use rust_password_server::bounded_context::domain::password_db::SortBy;
+use chrono::Utc;
+use uuid::Uuid;
+
+fn sample_password() -> Password {
+    Password {
+        last_used_at: Some(Utc::now()),
+        favorite: true,
+        ..Password::new(Uuid::new_v4(), "example.com".to_string(), "n".to_string(), "c".to_string())
+    }
+}
+
+#[test]
+fn test_cursor_round_trip() {
+    let password = sample_password();
+    let cursor = Cursor::after(&password, SortBy::FavoritesFirst);
+
+    let encoded = cursor.encode();
+    assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+    let decoded = Cursor::decode(&encoded, SortBy::FavoritesFirst).expect("Failed to decode cursor");
+    assert_eq!(decoded, cursor);
+    assert_eq!(decoded.id, password.id);
+    assert_eq!(decoded.last_used_at, password.last_used_at);
+}
+
+#[test]
+fn test_cursor_rejects_other_sort_option() {
+    let cursor = Cursor::after(&sample_password(), SortBy::ServiceAsc).encode();
+
+    assert!(matches!(Cursor::decode(&cursor, SortBy::CreatedAtAsc), Err(CursorError::SortMismatch)));
+}
+
+#[test]
+fn test_cursor_rejects_garbage() {
+    for input in ["", "not a cursor", "e30", "!!!"] {
+        assert!(matches!(Cursor::decode(input, SortBy::ServiceAsc), Err(CursorError::Invalid)), "Failed for {}", input);
+    }
+}
+
+#[test]
+fn test_page_request_accessors() {
+    let offset = PageRequest::offset(3, 20);
+    assert_eq!(offset.page(), Some(3));
+    assert_eq!(offset.page_size(), 20);
+
+    let after = PageRequest::after(None, 15);
+    assert_eq!(after.page(), None);
+    assert_eq!(after.page_size(), 15);
+}
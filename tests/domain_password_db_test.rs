@@ -0,0 +1,88 @@
+use rust_password_server::bounded_context::domain::password_db::*;
+use std::str::FromStr;
+
+#[test]
+fn test_sort_by_from_str() {
+    let test_cases = vec![
+        ("created_at_asc", "CreatedAtAsc"),
+        ("createdAtDesc", "CreatedAtDesc"),
+        ("UPDATED_AT_ASC", "UpdatedAtAsc"),
+        ("updated_at_desc", "UpdatedAtDesc"),
+        ("last_used_desc", "LastUsedDesc"),
+        ("favorites_first", "FavoritesFirst"),
+        ("service_asc", "ServiceAsc"),
+        ("ServiceDesc", "ServiceDesc"),
+    ];
+
+    for (input, expected) in test_cases {
+        let sort_by = SortBy::from_str(input).expect("Failed to parse sort option");
+        assert_eq!(format!("{:?}", sort_by), expected, "Failed for {}", input);
+    }
+}
+
+#[test]
+fn test_sort_by_invalid_option() {
+    let err = SortBy::from_str("popularity").unwrap_err();
+    assert_eq!(err.to_string(), "Invalid sort option: popularity");
+}
+
+#[test]
+fn test_entry_filter_ranges() {
+    let now = chrono::Utc::now();
+    let earlier = now - chrono::Duration::days(1);
+
+    assert!(EntryFilter::default().has_valid_ranges());
+    assert!(EntryFilter { created_after: Some(earlier), created_before: Some(now), ..Default::default() }.has_valid_ranges());
+    assert!(EntryFilter { updated_before: Some(earlier), ..Default::default() }.has_valid_ranges());
+    assert!(!EntryFilter { updated_after: Some(now), updated_before: Some(earlier), ..Default::default() }.has_valid_ranges());
+    assert!(!EntryFilter { last_used_after: Some(now), last_used_before: Some(earlier), ..Default::default() }.has_valid_ranges());
+}
+
+#[test]
+fn test_entry_filter_deserialize() {
+    let filter: EntryFilter = serde_json::from_str(
+        r#"{ "service": "git", "item_type": "secure_note", "favorite": true, "created_after": "2024-01-01T00:00:00Z" }"#,
+    )
+    .expect("Failed to parse filter");
+
+    assert_eq!(filter.service.as_deref(), Some("git"));
+    assert_eq!(filter.item_type, Some(rust_password_server::bounded_context::domain::item::ItemType::SecureNote));
+    assert_eq!(filter.favorite, Some(true));
+    assert!(filter.created_after.is_some());
+    assert!(!filter.include_subfolders);
+}
+
+#[test]
+fn test_batch_mode_commits() {
+    let outcomes = vec![Ok(()), Err("Invalid nonce provided.".to_string())];
+
+    assert!(!BatchMode::AllOrNothing.commits(&outcomes));
+    assert!(BatchMode::BestEffort.commits(&outcomes));
+    assert!(BatchMode::AllOrNothing.commits(&[Ok(()), Ok(())]));
+    assert_eq!(serde_json::from_str::<BatchMode>(r#""best_effort""#).unwrap(), BatchMode::BestEffort);
+    assert_eq!(BatchMode::default(), BatchMode::AllOrNothing);
+}
+
+#[test]
+fn test_batch_result_statuses() {
+    let ids = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+    let outcomes = vec![(Some(ids[0]), Ok(())), (Some(ids[1]), Err("Invalid cipher provided.".to_string()))];
+
+    let rolled_back = BatchResult::new(BatchMode::AllOrNothing, outcomes.clone());
+    assert!(!rolled_back.committed);
+    assert_eq!(rolled_back.results[0].status, BatchItemStatus::RolledBack);
+    assert_eq!(rolled_back.results[1].status, BatchItemStatus::Failed);
+    assert_eq!(rolled_back.results[1].error.as_deref(), Some("Invalid cipher provided."));
+
+    let best_effort = BatchResult::new(BatchMode::BestEffort, outcomes);
+    assert!(best_effort.committed);
+    assert_eq!(best_effort.results[0].index, 0);
+    assert_eq!(best_effort.results[0].id, Some(ids[0]));
+    assert_eq!(best_effort.results[0].status, BatchItemStatus::Applied);
+    assert_eq!(best_effort.results[1].index, 1);
+    assert_eq!(best_effort.results[1].status, BatchItemStatus::Failed);
+
+    let json = serde_json::to_value(&best_effort).unwrap();
+    assert_eq!(json["results"][0]["status"], "applied");
+    assert!(json["results"][0].get("error").is_none());
+}
@@ -41,4 +41,34 @@ fn test_password_decryption_static_key() {
 
     let decrypted_password = decrypt(&master_key, &password.nonce, &password.cipher);
     assert_eq!(decrypted_password, plaintext_password);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_password_creation_defaults_rich_fields() {
+    let password = Password::new(ID, "example_service".to_string(), "nonce".to_string(), "cipher".to_string());
+
+    assert_eq!(password.username, None);
+    assert!(password.uris.is_empty());
+    assert_eq!(password.notes, None);
+    assert_eq!(password.totp, None);
+    assert!(password.custom_fields.is_empty());
+}
+
+#[test]
+fn test_custom_field_serialization() {
+    let fields = vec![
+        CustomField::Text { name: "Org".to_string(), value: "acme".to_string() },
+        CustomField::Hidden { name: "PIN".to_string(), nonce: "n".to_string(), cipher: "c".to_string() },
+        CustomField::Boolean { name: "Shared".to_string(), value: true },
+    ];
+
+    let json = serde_json::to_value(&fields).expect("Failed to serialize");
+    assert_eq!(json[0]["type"], "text");
+    assert_eq!(json[1]["type"], "hidden");
+    assert_eq!(json[2]["type"], "boolean");
+    assert_eq!(json[2]["value"], true);
+
+    let parsed: Vec<CustomField> = serde_json::from_value(json).expect("Failed to deserialize");
+    assert_eq!(parsed, fields);
+    assert_eq!(parsed[1].name(), "PIN");
+}
@@ -0,0 +1,71 @@
+use rust_password_server::bounded_context::domain::{password::Password, item::ItemData, rotation::*};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+fn entry(service: &str) -> Password {
+    Password::new(Uuid::new_v4(), service.to_string(), "nonce".to_string(), "cipher".to_string())
+}
+
+#[test]
+fn test_rotation_due_at() {
+    let mut password = entry("example");
+    assert_eq!(password.rotation_due_at(), None);
+
+    password.rotation_interval_days = Some(90);
+    assert_eq!(password.rotation_due_at(), Some(password.updated_at + Duration::days(90)));
+
+    let rotate_by = password.updated_at + Duration::days(30);
+    password.rotate_by = Some(rotate_by);
+    assert_eq!(password.rotation_due_at(), Some(rotate_by));
+
+    password.rotate_by = Some(password.updated_at + Duration::days(120));
+    assert_eq!(password.rotation_due_at(), Some(password.updated_at + Duration::days(90)));
+}
+
+#[test]
+fn test_api_key_expiry_is_a_rotation_deadline() {
+    let mut password = entry("api");
+    let expires_at = Utc::now() + Duration::days(5);
+    password.item = ItemData::ApiKey { key_id: None, secret: None, expires_at: Some(expires_at) };
+    password.rotation_interval_days = Some(30);
+
+    assert_eq!(password.rotation_due_at(), Some(expires_at));
+}
+
+#[test]
+fn test_rotation_due() {
+    let now = Utc::now();
+
+    let mut overdue = entry("Overdue");
+    overdue.rotate_by = Some(now - Duration::days(1));
+    let mut soon = entry("soon");
+    soon.rotate_by = Some(now + Duration::days(3));
+    let mut later = entry("later");
+    later.rotate_by = Some(now + Duration::days(30));
+    let never = entry("never");
+
+    let due = rotation_due(vec![later, soon.clone(), never, overdue.clone()], now, Duration::days(14));
+
+    assert_eq!(due.len(), 2);
+    assert_eq!(due[0].password.id, overdue.id);
+    assert!(due[0].overdue);
+    assert_eq!(due[1].password.id, soon.id);
+    assert!(!due[1].overdue);
+    assert_eq!(due[1].due_at, now + Duration::days(3));
+}
+
+#[test]
+fn test_rotation_notice_leaves_out_secrets() {
+    let mut password = entry("example");
+    password.username = Some("alice".to_string());
+    password.rotate_by = Some(Utc::now());
+
+    let due = rotation_due(vec![password.clone()], Utc::now(), Duration::days(1));
+    let notice = RotationNotice::from(&due[0]);
+    let json = serde_json::to_value(&notice).unwrap();
+
+    assert_eq!(notice.password_id, password.id);
+    assert_eq!(notice.username.as_deref(), Some("alice"));
+    assert!(json.get("cipher").is_none());
+    assert!(json.get("nonce").is_none());
+}
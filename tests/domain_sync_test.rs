@@ -0,0 +1,60 @@
+use rust_password_server::bounded_context::domain::{password::Password, sync::*};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+fn upsert(base_updated_at: Option<chrono::DateTime<Utc>>) -> SyncChange {
+    let entry = Password::new(Uuid::new_v4(), "example".to_string(), "nonce".to_string(), "cipher".to_string());
+    SyncChange::Upsert { entry: Box::new(entry), base_updated_at }
+}
+
+#[test]
+fn test_sync_token_round_trip() {
+    let token = SyncToken(123_456_789);
+
+    assert_eq!(SyncToken::decode(&token.encode()), Some(token));
+    assert_eq!(SyncToken::decode("not a token"), None);
+    assert_eq!(SyncToken::decode(&SyncToken(-1).encode()), None);
+    assert_eq!(SyncToken::decode("AAAA"), None);
+}
+
+#[test]
+fn test_upsert_conflicts() {
+    let now = Utc::now();
+    let earlier = now - Duration::minutes(5);
+
+    assert_eq!(upsert(None).check(None, false), Ok(()));
+    assert_eq!(upsert(None).check(Some(now), false), Err(ConflictReason::Exists));
+    assert_eq!(upsert(Some(now)).check(Some(now), false), Ok(()));
+    assert_eq!(upsert(Some(earlier)).check(Some(now), false), Err(ConflictReason::Modified));
+    assert_eq!(upsert(Some(now)).check(None, true), Err(ConflictReason::Deleted));
+    // Recreating an entry the client knows was deleted is fine
+    assert_eq!(upsert(None).check(None, true), Ok(()));
+}
+
+#[test]
+fn test_delete_conflicts() {
+    let now = Utc::now();
+    let id = Uuid::new_v4();
+
+    assert_eq!(SyncChange::Delete { id, base_updated_at: now }.check(Some(now), false), Ok(()));
+    assert_eq!(
+        SyncChange::Delete { id, base_updated_at: now - Duration::seconds(1) }.check(Some(now), false),
+        Err(ConflictReason::Modified)
+    );
+    assert_eq!(SyncChange::Delete { id, base_updated_at: now }.check(None, true), Ok(()));
+    assert_eq!(SyncChange::Delete { id, base_updated_at: now }.id(), id);
+}
+
+#[test]
+fn test_changes_deserialize_by_op() {
+    let id = Uuid::new_v4();
+    let change: SyncChange = serde_json::from_value(serde_json::json!({
+        "op": "delete",
+        "id": id,
+        "base_updated_at": "2024-01-01T00:00:00Z",
+    }))
+    .unwrap();
+
+    assert_eq!(change.id(), id);
+    assert!(matches!(change, SyncChange::Delete { .. }));
+}
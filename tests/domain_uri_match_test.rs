@@ -0,0 +1,117 @@
+use rust_password_server::bounded_context::domain::uri_match::*;
+use rust_password_server::bounded_context::domain::password::Password;
+use rust_password_server::bounded_context::utility::uri::normalize_uri;
+use chrono::{Duration, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+fn entry(service: &str, uris: Vec<EntryUri>) -> Password {
+    Password {
+        uris,
+        ..Password::new(Uuid::new_v4(), service.to_string(), "n".to_string(), "c".to_string())
+    }
+}
+
+#[test]
+fn test_match_strategy_from_str() {
+    let test_cases = vec![
+        ("base_domain", MatchStrategy::BaseDomain),
+        ("Host", MatchStrategy::Host),
+        ("startsWith", MatchStrategy::StartsWith),
+        ("REGEX", MatchStrategy::Regex),
+    ];
+
+    for (input, expected) in test_cases {
+        assert_eq!(MatchStrategy::from_str(input).expect("Failed to parse strategy"), expected, "Failed for {}", input);
+        assert_eq!(MatchStrategy::from_str(expected.as_str()).unwrap(), expected);
+    }
+
+    assert!(MatchStrategy::from_str("fuzzy").is_err());
+}
+
+#[test]
+fn test_entry_uri_deserialize() {
+    let uris: Vec<EntryUri> = serde_json::from_str(
+        r#"["example.com", { "uri": "https://a.example.com", "match_strategy": "host" }, { "uri": "b.example.com" }]"#,
+    )
+    .expect("Failed to parse URIs");
+
+    assert_eq!(uris, vec![
+        EntryUri::new("example.com", MatchStrategy::BaseDomain),
+        EntryUri::new("https://a.example.com", MatchStrategy::Host),
+        EntryUri::new("b.example.com", MatchStrategy::BaseDomain),
+    ]);
+}
+
+#[test]
+fn test_entry_uri_validity() {
+    assert!(EntryUri::from("example.com").is_valid());
+    assert!(EntryUri::new(r"^https://(www\.)?example\.com/", MatchStrategy::Regex).is_valid());
+    assert!(!EntryUri::new("(unclosed", MatchStrategy::Regex).is_valid());
+    assert!(!EntryUri::from("  ").is_valid());
+}
+
+#[test]
+fn test_entry_uri_matches() {
+    let target = normalize_uri("https://login.example.co.uk/path?next=1").unwrap();
+
+    let test_cases = vec![
+        (EntryUri::from("https://login.example.co.uk/path?next=1"), Some(MatchQuality::Exact)),
+        (EntryUri::new("https://login.example.co.uk/", MatchStrategy::StartsWith), Some(MatchQuality::Prefix)),
+        (EntryUri::new("https://login.example.co.uk/other", MatchStrategy::StartsWith), None),
+        (EntryUri::new("login.example.co.uk", MatchStrategy::Host), Some(MatchQuality::Host)),
+        (EntryUri::new("login.example.co.uk:8443", MatchStrategy::Host), None),
+        (EntryUri::new("www.example.co.uk", MatchStrategy::Host), None),
+        (EntryUri::from("login.example.co.uk"), Some(MatchQuality::Host)),
+        (EntryUri::from("http://www.example.co.uk"), Some(MatchQuality::BaseDomain)),
+        (EntryUri::from("other.co.uk"), None),
+        (EntryUri::from("example.co.uk.evil.com"), None),
+        (EntryUri::new(r"^https://[a-z]+\.example\.co\.uk/", MatchStrategy::Regex), Some(MatchQuality::Regex)),
+        (EntryUri::new(r"^https://example\.com/", MatchStrategy::Regex), None),
+        (EntryUri::new("(unclosed", MatchStrategy::Regex), None),
+        (EntryUri::from("not a url"), None),
+    ];
+
+    for (uri, expected) in test_cases {
+        assert_eq!(uri.matches(&target), expected, "Failed for {:?}", uri);
+    }
+}
+
+#[test]
+fn test_base_domain_respects_private_suffixes() {
+    let target = normalize_uri("https://alice.github.io").unwrap();
+
+    assert_eq!(EntryUri::from("https://bob.github.io").matches(&target), None);
+    assert_eq!(EntryUri::from("https://www.alice.github.io").matches(&target), Some(MatchQuality::BaseDomain));
+}
+
+#[test]
+fn test_rank_uri_matches() {
+    let now = Utc::now();
+    let target = normalize_uri("https://login.example.com/signin").unwrap();
+
+    let passwords = vec![
+        entry("Regex", vec![EntryUri::new("example", MatchStrategy::Regex)]),
+        entry("Unrelated", vec![EntryUri::from("example.org")]),
+        Password { last_used_at: Some(now - Duration::days(3)), ..entry("Domain old", vec![EntryUri::from("example.com")]) },
+        Password { last_used_at: Some(now), ..entry("Domain recent", vec![EntryUri::from("www.example.com")]) },
+        entry("Best of many", vec![EntryUri::from("example.org"), EntryUri::from("example.com"), EntryUri::from("https://login.example.com/signin")]),
+        Password { favorite: true, ..entry("Host favorite", vec![EntryUri::new("login.example.com", MatchStrategy::Host)]) },
+        entry("Host", vec![EntryUri::new("login.example.com", MatchStrategy::Host)]),
+    ];
+
+    let ranked = rank_uri_matches(passwords, &target);
+    let summary: Vec<(&str, MatchQuality, &str)> = ranked
+        .iter()
+        .map(|found| (found.password.service.as_str(), found.quality, found.matched_uri.as_str()))
+        .collect();
+
+    assert_eq!(summary, vec![
+        ("Best of many", MatchQuality::Exact, "https://login.example.com/signin"),
+        ("Host favorite", MatchQuality::Host, "login.example.com"),
+        ("Host", MatchQuality::Host, "login.example.com"),
+        ("Domain recent", MatchQuality::BaseDomain, "www.example.com"),
+        ("Domain old", MatchQuality::BaseDomain, "example.com"),
+        ("Regex", MatchQuality::Regex, "example"),
+    ]);
+}
@@ -0,0 +1,24 @@
+use rust_password_server::bounded_context::domain::user::*;
+use rust_password_server::bounded_context::utility::token::{generate_token, hash_token};
+use chrono::Utc;
+use uuid::Uuid;
+
+#[test]
+fn test_new_user() {
+    let user = User::new("  alice@example.com ", Utc::now()).unwrap();
+    assert_eq!(user.username, "alice@example.com");
+
+    assert!(User::new("ci-bot_2.0", Utc::now()).is_ok());
+    for invalid in ["", "   ", "two words", "semi;colon", &"a".repeat(65)] {
+        assert!(matches!(User::new(invalid, Utc::now()), Err(UserError::InvalidUsername(_))), "{:?}", invalid);
+    }
+}
+
+#[test]
+fn test_api_token_keeps_only_the_hash() {
+    let token = generate_token();
+    let api_token = ApiToken::new(Uuid::new_v4(), &token, Some("ci".to_string()), None, Utc::now());
+
+    assert_eq!(api_token.token_hash, hash_token(&token));
+    assert!(!serde_json::to_string(&api_token).unwrap().contains(&api_token.token_hash));
+}
@@ -0,0 +1,45 @@
+use rust_password_server::bounded_context::domain::webhook::*;
+use chrono::{Duration, Utc};
+
+#[test]
+fn test_retry_backoff_doubles_up_to_the_limit() {
+    let now = Utc::now();
+
+    assert_eq!(retry_at(1, 8, now), Some(now + Duration::seconds(30)));
+    assert_eq!(retry_at(2, 8, now), Some(now + Duration::seconds(60)));
+    assert_eq!(retry_at(3, 8, now), Some(now + Duration::seconds(120)));
+    assert_eq!(retry_at(40, 50, now), Some(now + Duration::seconds(RETRY_MAX_DELAY_SECONDS)));
+}
+
+#[test]
+fn test_no_retry_after_max_attempts() {
+    let now = Utc::now();
+
+    assert_eq!(retry_at(8, 8, now), None);
+    assert_eq!(retry_at(1, 1, now), None);
+}
+
+#[test]
+fn test_delivery_attempt_status() {
+    let now = Utc::now();
+    let delivered = DeliveryAttempt { attempted_at: now, response_status: Some(204), error: None, retry_at: None };
+    let retried = DeliveryAttempt { attempted_at: now, response_status: Some(500), error: Some("failed".to_string()), retry_at: Some(now) };
+    let failed = DeliveryAttempt { retry_at: None, ..retried.clone() };
+
+    assert_eq!(delivered.status(), DeliveryStatus::Delivered);
+    assert_eq!(retried.status(), DeliveryStatus::Pending);
+    assert_eq!(failed.status(), DeliveryStatus::Failed);
+}
+
+#[test]
+fn test_new_webhook_deduplicates_events() {
+    let webhook = Webhook::new(
+        "https://example.com/hook".to_string(),
+        "secret".to_string(),
+        &[WebhookEvent::Deleted, WebhookEvent::Created, WebhookEvent::Deleted],
+    );
+
+    assert_eq!(webhook.events, vec!["created".to_string(), "deleted".to_string()]);
+    assert!(webhook.active);
+    assert!(!serde_json::to_string(&webhook).unwrap().contains("secret"));
+}
@@ -0,0 +1,90 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::client::api_client::*;
+use rust_password_server::bounded_context::infrastructure::http::configure_routes::configure_routes;
+use rust_password_server::bounded_context::infrastructure::db::change_feed::ChangeFeed;
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::encryption::{decrypt, encrypt, generate_key};
+use chrono::{Duration, SubsecRound, Utc};
+use sqlx::Executor;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let mut config = app_config::load_config();
+    config.require_api_token = false;
+    let database = Database::new(&config.test_db_url.clone(), 2, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+async fn serve(database: Database) -> String {
+    let app = configure_routes(database, None, ChangeFeed::new(16));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
+    });
+
+    format!("http://{}", addr)
+}
+
+fn new_entry(key: &str, service: &str, secret: &str, age_days: i64) -> NewEntry {
+    let (nonce, cipher) = encrypt(key, secret.to_string());
+    let created_at = (Utc::now() - Duration::days(age_days)).trunc_subsecs(6);
+    NewEntry { service: service.to_string(), username: Some("alice".to_string()), nonce, cipher, created_at, updated_at: created_at }
+}
+
+#[tokio::test]
+async fn test_client_creates_finds_and_deletes_entries() {
+    let _guard = DB_LOCK.lock().await;
+    let base = serve(get_test_database().await).await;
+    let client = ApiClient::new(&format!("{}/", base), None).with_page_size(2);
+    let key = generate_key();
+
+    client.status().await.unwrap();
+    for (service, secret, age_days) in [("github", "gh-secret", 3), ("gitlab", "gl-secret", 1), ("aws", "aws-secret", 2)] {
+        client.create_password(&new_entry(&key, service, secret, age_days)).await.unwrap();
+    }
+
+    // Three entries with a page size of two, so the client has to follow the cursor
+    let services: Vec<String> = client.list_passwords("service_asc").await.unwrap().into_iter().map(|p| p.service).collect();
+    assert_eq!(services, vec!["aws", "github", "gitlab"]);
+    let services: Vec<String> = client.list_passwords("updated_at_desc").await.unwrap().into_iter().map(|p| p.service).collect();
+    assert_eq!(services, vec!["gitlab", "aws", "github"]);
+
+    let found = client.search_passwords("git").await.unwrap();
+    assert_eq!(found.iter().map(|p| p.service.as_str()).collect::<Vec<_>>(), vec!["github", "gitlab"]);
+    let github = client.get_password(found[0].id).await.unwrap();
+    assert_eq!(decrypt(&key, &github.nonce, &github.cipher), "gh-secret");
+    assert_eq!(github.username.as_deref(), Some("alice"));
+
+    client.delete_password(github.id).await.unwrap();
+    let services: Vec<String> = client.search_passwords("git").await.unwrap().into_iter().map(|p| p.service).collect();
+    assert_eq!(services, vec!["gitlab"]);
+}
+
+#[tokio::test]
+async fn test_client_reports_server_errors() {
+    let _guard = DB_LOCK.lock().await;
+    let base = serve(get_test_database().await).await;
+    let client = ApiClient::new(&base, None);
+
+    let mut entry = new_entry(&generate_key(), "github", "secret", 0);
+    entry.nonce = "not hex".to_string();
+    match client.create_password(&entry).await {
+        Err(ClientError::Status(400, message)) => assert_eq!(message, "Invalid nonce provided."),
+        other => panic!("Unexpected result: {:?}", other),
+    }
+
+    assert!(matches!(client.list_passwords("sideways").await, Err(ClientError::Status(400, _))));
+    assert!(matches!(ApiClient::new("http://127.0.0.1:1", None).status().await, Err(ClientError::Request(_))));
+}
@@ -0,0 +1,67 @@
+use rust_password_server::bounded_context::infrastructure::client::session::*;
+use rust_password_server::bounded_context::utility::backup::KdfParams;
+use rust_password_server::bounded_context::utility::encryption::derive_master_key;
+use chrono::{Duration, Utc};
+
+fn kdf() -> KdfParams {
+    KdfParams { memory_kib: 64, iterations: 1, parallelism: 1, salt: "00112233445566778899aabbccddeeff".to_string() }
+}
+
+#[test]
+fn test_config_unlocks_with_the_master_password_only() {
+    let master_key = derive_master_key("correct horse battery", &kdf()).unwrap();
+    let config = ClientConfig::new("http://localhost:8080/api/", None, kdf(), &master_key, DEFAULT_IDLE_TIMEOUT_MINUTES);
+
+    assert_eq!(config.server_url, "http://localhost:8080/api");
+    assert_eq!(config.unlock("correct horse battery"), Some(master_key));
+    assert_eq!(config.unlock("wrong horse battery"), None);
+    assert_eq!(config.idle_timeout(), Duration::minutes(DEFAULT_IDLE_TIMEOUT_MINUTES));
+}
+
+#[test]
+fn test_config_save_and_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pw").join("config.json");
+    assert_eq!(ClientConfig::load(&path), Ok(None));
+
+    let master_key = derive_master_key("correct horse battery", &kdf()).unwrap();
+    let config = ClientConfig::new("http://localhost:8080/api", Some("token".to_string()), kdf(), &master_key, 5);
+    config.save(&path).unwrap();
+
+    assert_eq!(ClientConfig::load(&path), Ok(Some(config)));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    std::fs::write(&path, "not json").unwrap();
+    assert!(ClientConfig::load(&path).is_err());
+}
+
+#[test]
+fn test_session_expires_after_idle_timeout() {
+    let now = Utc::now();
+    let mut session = KeySession::new("key".to_string(), now, Duration::minutes(15));
+
+    assert!(!session.is_expired(now + Duration::minutes(14)));
+    assert!(session.is_expired(now + Duration::minutes(15)));
+    session.touch(now + Duration::minutes(10), Duration::minutes(15));
+    assert!(!session.is_expired(now + Duration::minutes(20)));
+    assert!(session.is_expired(now + Duration::minutes(25)));
+}
+
+#[test]
+fn test_session_load_deletes_expired_session() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    let now = Utc::now();
+    assert_eq!(KeySession::load(&path, now), None);
+
+    let session = KeySession::new("key".to_string(), now, Duration::minutes(15));
+    session.save(&path).unwrap();
+    assert_eq!(KeySession::load(&path, now + Duration::minutes(1)), Some(session));
+
+    assert_eq!(KeySession::load(&path, now + Duration::minutes(15)), None);
+    assert!(!path.exists());
+}
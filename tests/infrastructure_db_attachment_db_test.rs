@@ -0,0 +1,241 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::{password::Password, password_db::PasswordDb, attachment::{Attachment, AttachmentDb, AttachmentError, BlobStream}};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use bytes::Bytes;
+use futures_util::{stream, TryStreamExt};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Connects with the given attachment settings. Every test gets its own pool, since pooled
+/// connections cannot be handed from one test runtime to the next.
+async fn get_test_database(dir: Option<&std::path::Path>, max_size: u64, quota: u64) -> Database {
+    let mut config = app_config::load_config();
+    config.attachment_dir = dir.map(|dir| dir.to_string_lossy().to_string());
+    config.attachment_max_size = max_size;
+    config.attachment_entry_quota = quota;
+
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+    setup_db(&database).await;
+    database
+}
+
+async fn setup_db(database: &Database) {
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+}
+
+async fn save_entry(database: &mut Database) -> Uuid {
+    let password = Password {
+        id: Uuid::new_v4(),
+        service: "attachments".to_string(),
+        nonce: "nonce".to_string(),
+        cipher: "cipher".to_string(),
+        ..Default::default()
+    };
+    database.save(password.clone()).await.expect("Failed to save password");
+    password.id
+}
+
+fn body(chunks: &[&[u8]]) -> BlobStream {
+    let chunks: Vec<Result<Bytes, std::io::Error>> = chunks.iter().map(|chunk| Ok(Bytes::copy_from_slice(chunk))).collect();
+    Box::pin(stream::iter(chunks))
+}
+
+async fn read_all(stream: BlobStream) -> Vec<u8> {
+    let chunks: Vec<Bytes> = stream.try_collect().await.expect("Failed to read attachment");
+    chunks.concat()
+}
+
+fn attachment_for(password_id: Uuid, file_name: &str) -> Attachment {
+    Attachment::new(Uuid::new_v4(), password_id, file_name.to_string(), Some("application/pdf".to_string()))
+}
+
+#[tokio::test]
+async fn test_attachment_round_trip_in_postgres() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(None, 1024, 4096).await;
+    let password_id = save_entry(&mut db).await;
+
+    let saved = db
+        .save_attachment(attachment_for(password_id, "scan.pdf"), body(&[b"first ", b"second"]))
+        .await
+        .expect("Failed to save attachment");
+    assert_eq!(saved.size, 12);
+
+    let (opened, stream) = db.open_attachment(saved.id).await.expect("Failed to open attachment");
+    assert_eq!(opened.file_name, "scan.pdf");
+    assert_eq!(opened.content_type.as_deref(), Some("application/pdf"));
+    assert_eq!(read_all(stream).await, b"first second");
+
+    let listed = db.list_attachments(password_id).await.expect("Failed to list attachments");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, saved.id);
+
+    db.delete_attachment(saved.id).await.expect("Failed to delete attachment");
+    assert!(db.list_attachments(password_id).await.unwrap().is_empty());
+
+    let err = db.delete_attachment(saved.id).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<AttachmentError>(), Some(AttachmentError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_attachment_in_postgres_spans_chunks() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(None, 1024 * 1024, 1024 * 1024).await;
+    let password_id = save_entry(&mut db).await;
+
+    let data: Vec<u8> = (0..200 * 1024).map(|i| (i % 251) as u8).collect();
+    let pieces: Vec<&[u8]> = data.chunks(10_000).collect();
+
+    let saved = db
+        .save_attachment(attachment_for(password_id, "large.bin"), body(&pieces))
+        .await
+        .expect("Failed to save attachment");
+    assert_eq!(saved.size, data.len() as i64);
+
+    let chunks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM attachment_chunks WHERE attachment_id = $1")
+        .bind(saved.id)
+        .fetch_one(db.get_pool())
+        .await
+        .unwrap();
+    assert_eq!(chunks, 4);
+
+    let (_, stream) = db.open_attachment(saved.id).await.expect("Failed to open attachment");
+    assert_eq!(read_all(stream).await, data);
+}
+
+#[tokio::test]
+async fn test_pending_upload_does_not_hold_a_connection() {
+    let _lock = DB_LOCK.lock().await;
+    // The pool has a single connection
+    let mut db = get_test_database(None, 1024, 4096).await;
+    let password_id = save_entry(&mut db).await;
+
+    let (sender, receiver) = tokio::sync::mpsc::channel::<Result<Bytes, std::io::Error>>(1);
+    let slow_body: BlobStream = Box::pin(stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    }));
+    let mut uploader = db.clone();
+    let upload = tokio::spawn(async move { uploader.save_attachment(attachment_for(password_id, "slow.pdf"), slow_body).await.map_err(|err| err.to_string()) });
+
+    sender.send(Ok(Bytes::from_static(b"partial"))).await.unwrap();
+    let listed = tokio::time::timeout(std::time::Duration::from_secs(5), db.list_attachments(password_id))
+        .await
+        .expect("The pending upload holds the only connection")
+        .unwrap();
+    assert!(listed.is_empty());
+
+    drop(sender);
+    let saved = upload.await.unwrap().expect("Failed to save attachment");
+    assert_eq!(saved.size, 7);
+}
+
+#[tokio::test]
+async fn test_attachment_round_trip_on_filesystem() {
+    let _lock = DB_LOCK.lock().await;
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let mut db = get_test_database(Some(dir.path()), 1024, 4096).await;
+    let password_id = save_entry(&mut db).await;
+
+    let saved = db
+        .save_attachment(attachment_for(password_id, "key.bin"), body(&[b"abc", b"def"]))
+        .await
+        .expect("Failed to save attachment");
+
+    let path = dir.path().join(saved.id.to_string());
+    assert_eq!(std::fs::read(&path).expect("Blob file missing"), b"abcdef");
+
+    let (_, stream) = db.open_attachment(saved.id).await.expect("Failed to open attachment");
+    assert_eq!(read_all(stream).await, b"abcdef");
+
+    db.delete_attachment(saved.id).await.expect("Failed to delete attachment");
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_attachment_size_limit() {
+    let _lock = DB_LOCK.lock().await;
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+
+    for storage in [None, Some(dir.path())] {
+        let mut db = get_test_database(storage, 8, 4096).await;
+        let password_id = save_entry(&mut db).await;
+
+        let err = db
+            .save_attachment(attachment_for(password_id, "big.bin"), body(&[b"12345", b"6789"]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<AttachmentError>(), Some(AttachmentError::TooLarge(8))));
+        assert!(db.list_attachments(password_id).await.unwrap().is_empty());
+
+        db.save_attachment(attachment_for(password_id, "fits.bin"), body(&[b"1234", b"5678"]))
+            .await
+            .expect("Attachment at the limit should be accepted");
+    }
+
+    // Neither the rejected upload nor its partial file may be left behind
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[tokio::test]
+async fn test_attachment_entry_quota() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(None, 1024, 10).await;
+    let password_id = save_entry(&mut db).await;
+    let other_id = save_entry(&mut db).await;
+
+    db.save_attachment(attachment_for(password_id, "a"), body(&[b"123456"])).await.expect("Failed to save attachment");
+
+    let err = db
+        .save_attachment(attachment_for(password_id, "b"), body(&[b"12345"]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err.downcast_ref::<AttachmentError>(), Some(AttachmentError::QuotaExceeded(10))));
+
+    db.save_attachment(attachment_for(password_id, "c"), body(&[b"1234"])).await.expect("Quota should allow the remaining bytes");
+    db.save_attachment(attachment_for(other_id, "d"), body(&[b"12345"])).await.expect("Quota is per entry");
+}
+
+#[tokio::test]
+async fn test_attachment_requires_existing_entry() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(None, 1024, 4096).await;
+
+    let err = db
+        .save_attachment(attachment_for(Uuid::new_v4(), "orphan"), body(&[b"data"]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err.downcast_ref::<AttachmentError>(), Some(AttachmentError::PasswordNotFound(_))));
+
+    let err = db.open_attachment(Uuid::new_v4()).await.err().expect("Missing attachment should not open");
+    assert!(matches!(err.downcast_ref::<AttachmentError>(), Some(AttachmentError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_deleting_entry_removes_attachments() {
+    let _lock = DB_LOCK.lock().await;
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let mut db = get_test_database(Some(dir.path()), 1024, 4096).await;
+    let password_id = save_entry(&mut db).await;
+
+    let saved = db
+        .save_attachment(attachment_for(password_id, "id.png"), body(&[b"image"]))
+        .await
+        .expect("Failed to save attachment");
+    let path = dir.path().join(saved.id.to_string());
+    assert!(path.exists());
+
+    db.delete(password_id).await.expect("Failed to delete password");
+
+    assert!(!path.exists());
+    assert!(db.list_attachments(password_id).await.unwrap().is_empty());
+}
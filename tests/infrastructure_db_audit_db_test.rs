@@ -0,0 +1,169 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::audit::*;
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use chrono::{Duration, SubsecRound, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE audit_events RESTART IDENTITY")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+fn new_event(actor: &str, action: &str, entry_id: Option<Uuid>, status: i32) -> NewAuditEvent {
+    NewAuditEvent {
+        occurred_at: Utc::now().trunc_subsecs(6),
+        request_id: Uuid::new_v4().to_string(),
+        actor: actor.to_string(),
+        action: action.to_string(),
+        entry_id,
+        ip: Some("10.0.0.1".to_string()),
+        user_agent: Some("audit-test".to_string()),
+        status,
+        outcome: AuditOutcome::from_status(status as u16).as_str().to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_appended_events_form_a_chain() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+
+    let first = db.append_audit_event(new_event("alice", "GET /password/", None, 200)).await.unwrap();
+    let second = db.append_audit_event(new_event("bob", "POST /password/delete", Some(Uuid::new_v4()), 500)).await.unwrap();
+
+    assert_eq!(first.prev_hash, GENESIS_HASH);
+    assert_eq!(second.prev_hash, first.hash);
+    assert!(second.seq > first.seq);
+
+    let events = db.list_audit_chain().await.unwrap();
+    assert_eq!(events, vec![first, second]);
+    assert_eq!(verify_chain(&events), Ok(()));
+}
+
+#[tokio::test]
+async fn test_concurrent_appends_stay_chained() {
+    let _lock = DB_LOCK.lock().await;
+    get_test_database().await;
+    let config = app_config::load_config();
+    let mut db = Database::new(&config.test_db_url.clone(), 8, config).await.expect("Failed to create test DB");
+
+    let appends: Vec<_> = (0..32)
+        .map(|i| {
+            let mut db = db.clone();
+            tokio::spawn(async move {
+                let mut event = new_event(&format!("user-{}", i), "GET /password/", None, 200);
+                event.user_agent = Some("pw/1.0 (Zürich)".to_string());
+                db.append_audit_event(event).await.unwrap()
+            })
+        })
+        .collect();
+    for append in appends {
+        append.await.unwrap();
+    }
+
+    let events = db.list_audit_chain().await.unwrap();
+    assert_eq!(events.len(), 32);
+    assert_eq!(verify_chain(&events), Ok(()));
+}
+
+#[tokio::test]
+async fn test_query_filters_and_pagination() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+
+    let entry_id = Uuid::new_v4();
+    let start = Utc::now().trunc_subsecs(6) - Duration::seconds(1);
+    for _ in 0..3 {
+        db.append_audit_event(new_event("alice", "GET /password/", Some(entry_id), 200)).await.unwrap();
+    }
+    db.append_audit_event(new_event("bob", "POST /password/delete", Some(entry_id), 404)).await.unwrap();
+    db.append_audit_event(new_event("bob", "GET /status", None, 200)).await.unwrap();
+
+    let all = db.query_audit_events(&AuditFilter::default(), 1, 10).await.unwrap();
+    assert_eq!(all.total_count, 5);
+    assert!(all.items.windows(2).all(|pair| pair[0].seq > pair[1].seq));
+
+    let by_actor = AuditFilter { actor: Some("bob".to_string()), ..Default::default() };
+    assert_eq!(db.query_audit_events(&by_actor, 1, 10).await.unwrap().total_count, 2);
+
+    let by_entry = AuditFilter { entry_id: Some(entry_id), ..Default::default() };
+    assert_eq!(db.query_audit_events(&by_entry, 1, 10).await.unwrap().total_count, 4);
+
+    let failures = AuditFilter { outcome: Some(AuditOutcome::Failure), ..Default::default() };
+    let failed = db.query_audit_events(&failures, 1, 10).await.unwrap();
+    assert_eq!(failed.total_count, 1);
+    assert_eq!(failed.items[0].action, "POST /password/delete");
+
+    let by_action = AuditFilter { action: Some("GET /password/".to_string()), actor: Some("alice".to_string()), ..Default::default() };
+    let page = db.query_audit_events(&by_action, 2, 2).await.unwrap();
+    assert_eq!(page.total_count, 3);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.page, Some(2));
+
+    let by_request = AuditFilter { request_id: Some(failed.items[0].request_id.clone()), ..Default::default() };
+    assert_eq!(db.query_audit_events(&by_request, 1, 10).await.unwrap().items, failed.items);
+
+    let in_range = AuditFilter { after: Some(start), before: Some(Utc::now() + Duration::seconds(1)), ..Default::default() };
+    assert_eq!(db.query_audit_events(&in_range, 1, 10).await.unwrap().total_count, 5);
+    let before_start = AuditFilter { before: Some(start), ..Default::default() };
+    assert_eq!(db.query_audit_events(&before_start, 1, 10).await.unwrap().total_count, 0);
+}
+
+#[tokio::test]
+async fn test_events_cannot_be_updated_or_deleted() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+
+    let event = db.append_audit_event(new_event("alice", "GET /password/", None, 200)).await.unwrap();
+
+    let update = sqlx::query("UPDATE audit_events SET actor = 'mallory' WHERE seq = $1")
+        .bind(event.seq)
+        .execute(db.get_pool())
+        .await;
+    assert!(update.is_err());
+
+    let delete = sqlx::query("DELETE FROM audit_events WHERE seq = $1")
+        .bind(event.seq)
+        .execute(db.get_pool())
+        .await;
+    assert!(delete.is_err());
+
+    assert_eq!(db.list_audit_chain().await.unwrap(), vec![event]);
+}
+
+#[tokio::test]
+async fn test_tampering_is_detected() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+
+    let mut seqs = Vec::new();
+    for actor in ["alice", "bob", "carol"] {
+        seqs.push(db.append_audit_event(new_event(actor, "GET /password/", None, 200)).await.unwrap().seq);
+    }
+
+    let mut conn = db.get_connection().await.unwrap();
+    conn.execute("ALTER TABLE audit_events DISABLE TRIGGER audit_events_append_only").await.unwrap();
+    sqlx::query("UPDATE audit_events SET actor = 'mallory' WHERE seq = $1").bind(seqs[1]).execute(&mut *conn).await.unwrap();
+    drop(conn);
+    assert_eq!(verify_chain(&db.list_audit_chain().await.unwrap()), Err(AuditChainError::HashMismatch { seq: seqs[1] }));
+
+    let mut conn = db.get_connection().await.unwrap();
+    sqlx::query("DELETE FROM audit_events WHERE seq = $1").bind(seqs[1]).execute(&mut *conn).await.unwrap();
+    conn.execute("ALTER TABLE audit_events ENABLE TRIGGER audit_events_append_only").await.unwrap();
+    drop(conn);
+    assert_eq!(verify_chain(&db.list_audit_chain().await.unwrap()), Err(AuditChainError::BrokenLink { seq: seqs[2] }));
+}
@@ -0,0 +1,125 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::{backup::*, folder::Folder, password::Password, password_db::PasswordDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use chrono::{Duration, SubsecRound, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, password_tombstones, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+fn folder(name: &str, parent_id: Option<Uuid>) -> Folder {
+    let mut folder = Folder::new(Uuid::new_v4(), name.to_string(), parent_id);
+    folder.created_at = folder.created_at.trunc_subsecs(6);
+    folder.updated_at = folder.updated_at.trunc_subsecs(6);
+    folder
+}
+
+fn entry(service: &str, folder_id: Option<Uuid>) -> Password {
+    let mut password = Password::new(Uuid::new_v4(), service.to_string(), "nonce".to_string(), "cipher".to_string());
+    password.created_at = password.created_at.trunc_subsecs(6);
+    password.updated_at = password.updated_at.trunc_subsecs(6);
+    password.folder_id = folder_id;
+    password
+}
+
+async fn seed(db: &mut Database) -> (Vec<Folder>, Vec<Password>) {
+    let parent = folder("work", None);
+    let child = folder("servers", Some(parent.id));
+    db.create_folder(parent.clone()).await.unwrap();
+    db.create_folder(child.clone()).await.unwrap();
+
+    let mut tagged = entry("mail", Some(parent.id));
+    tagged.tags = vec!["personal".to_string()];
+    let entries = vec![tagged, entry("ssh", Some(child.id)), entry("bank", None)];
+    for password in &entries {
+        db.save(password.clone()).await.unwrap();
+    }
+
+    (vec![parent, child], entries)
+}
+
+async fn clear(db: &Database) {
+    let mut conn = db.get_connection().await.unwrap();
+    conn.execute("TRUNCATE TABLE passwords, password_tombstones, folders, tags CASCADE").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_export_and_restore_into_empty_vault() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (folders, entries) = seed(&mut db).await;
+
+    let backup = db.export_vault(Utc::now()).await.unwrap();
+    assert_eq!(backup.folders.len(), folders.len());
+    assert_eq!(backup.entries.len(), entries.len());
+
+    clear(&db).await;
+    let report = db.restore_vault(&backup, RestoreMode::PreserveIds).await.unwrap();
+    assert_eq!(report, RestoreReport { folders_created: 2, entries_created: 3, entries_updated: 0, entries_unchanged: 0 });
+
+    for password in &entries {
+        assert_eq!(db.get_by_id(password.id).await.unwrap(), *password);
+    }
+    let mut restored_folders = db.list_folders().await.unwrap();
+    restored_folders.sort_by_key(|folder| folder.name.clone());
+    assert_eq!(restored_folders.iter().map(|folder| folder.id).collect::<Vec<_>>(), vec![folders[1].id, folders[0].id]);
+}
+
+#[tokio::test]
+async fn test_restore_preserving_ids_fails_on_existing_entries() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (_, entries) = seed(&mut db).await;
+    let backup = db.export_vault(Utc::now()).await.unwrap();
+
+    let err = db.restore_vault(&backup, RestoreMode::PreserveIds).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<BackupError>(), Some(BackupError::FolderExists(_))));
+
+    let entries_only = VaultBackup { folders: Vec::new(), ..backup };
+    db.delete(entries[2].id).await.unwrap();
+    let err = db.restore_vault(&entries_only, RestoreMode::PreserveIds).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<BackupError>(), Some(BackupError::EntryExists(_))));
+    // Nothing is restored when the restore fails
+    assert!(db.get_by_id(entries[2].id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_restore_merging_keeps_newer_entries() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (_, entries) = seed(&mut db).await;
+    let mut backup = db.export_vault(Utc::now()).await.unwrap();
+
+    // The backup holds a newer copy of the first entry, the vault a newer copy of the second
+    let newer = backup.entries.iter_mut().find(|password| password.id == entries[0].id).unwrap();
+    newer.service = "mail (restored)".to_string();
+    newer.updated_at += Duration::minutes(5);
+    let mut edited = entries[1].clone();
+    edited.service = "ssh (edited)".to_string();
+    edited.updated_at += Duration::minutes(5);
+    db.update_batch(vec![edited.clone()], Default::default()).await.unwrap();
+    db.delete(entries[2].id).await.unwrap();
+
+    let report = db.restore_vault(&backup, RestoreMode::Merge).await.unwrap();
+    assert_eq!(report, RestoreReport { folders_created: 0, entries_created: 1, entries_updated: 1, entries_unchanged: 1 });
+
+    assert_eq!(db.get_by_id(entries[0].id).await.unwrap().service, "mail (restored)");
+    assert_eq!(db.get_by_id(entries[1].id).await.unwrap().service, "ssh (edited)");
+    assert_eq!(db.get_by_id(entries[2].id).await.unwrap(), entries[2]);
+}
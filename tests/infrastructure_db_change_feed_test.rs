@@ -0,0 +1,145 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::db::change_feed::*;
+use rust_password_server::bounded_context::infrastructure::http::configure_routes::configure_routes;
+use rust_password_server::bounded_context::domain::{change_feed::*, password::Password, password_db::PasswordDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use futures_util::StreamExt;
+use sqlx::Executor;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+const WAIT: Duration = Duration::from_secs(5);
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// A database with a running change listener publishing to the returned feed
+async fn get_test_feed() -> (Database, ChangeFeed) {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 3, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    let feed = ChangeFeed::new(16);
+    let listener = listen_for_changes(&database).await.expect("Failed to listen for changes");
+    tokio::spawn(forward_changes(listener, feed.clone()));
+
+    (database, feed)
+}
+
+fn entry(service: &str) -> Password {
+    Password::new(Uuid::new_v4(), service.to_string(), "nonce".to_string(), "cipher".to_string())
+}
+
+async fn next_change(receiver: &mut Receiver<FeedMessage>) -> EntryChange {
+    match tokio::time::timeout(WAIT, receiver.recv()).await.expect("No change received").unwrap() {
+        FeedMessage::Change(change) => change,
+        message => panic!("Expected a change, got {:?}", message),
+    }
+}
+
+async fn serve(database: Database, feed: ChangeFeed) -> String {
+    let app = configure_routes(database, None, feed);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
+    });
+
+    addr.to_string()
+}
+
+#[tokio::test]
+async fn test_changes_are_published_after_commit() {
+    let _lock = DB_LOCK.lock().await;
+    let (mut db, feed) = get_test_feed().await;
+    let mut receiver = feed.subscribe();
+
+    let password = entry("example");
+    db.save(password.clone()).await.unwrap();
+    db.record_use(password.id, chrono::Utc::now()).await.unwrap();
+    db.set_favorite(password.id, true).await.unwrap();
+    db.delete(password.id).await.unwrap();
+
+    for expected in [ChangeKind::Created, ChangeKind::Updated, ChangeKind::Deleted] {
+        let change = next_change(&mut receiver).await;
+        assert_eq!(change.event, expected);
+        assert_eq!(change.password_id, password.id);
+    }
+    assert!(receiver.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_lagging_subscribers_are_told_to_resync() {
+    let feed = ChangeFeed::new(1);
+    let mut receiver = feed.subscribe();
+
+    for _ in 0..3 {
+        feed.publish(FeedMessage::Resync);
+    }
+
+    assert!(matches!(receiver.recv().await, Err(tokio::sync::broadcast::error::RecvError::Lagged(_))));
+    assert_eq!(ChangeFeed::new(4).publish(FeedMessage::Resync), 0);
+}
+
+#[tokio::test]
+async fn test_server_sent_events_stream() {
+    let _lock = DB_LOCK.lock().await;
+    let (mut db, feed) = get_test_feed().await;
+    let addr = serve(db.clone(), feed.clone()).await;
+
+    let mut response = reqwest::get(format!("http://{}/events", addr)).await.unwrap();
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    let password = entry("example");
+    db.save(password.clone()).await.unwrap();
+
+    let mut received = String::new();
+    while !received.contains("\n\n") {
+        let chunk = tokio::time::timeout(WAIT, response.chunk()).await.expect("No event received").unwrap().unwrap();
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    assert!(received.starts_with("event: created\n"));
+    assert!(received.contains(&password.id.to_string()));
+
+    // Streams end when the server shuts down
+    feed.publish(FeedMessage::Closed);
+    let end = tokio::time::timeout(WAIT, async {
+        while response.chunk().await.unwrap().is_some() {}
+    })
+    .await;
+    assert!(end.is_ok());
+}
+
+#[tokio::test]
+async fn test_websocket_stream() {
+    let _lock = DB_LOCK.lock().await;
+    let (mut db, feed) = get_test_feed().await;
+    let addr = serve(db.clone(), feed.clone()).await;
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/events", addr)).await.unwrap();
+
+    let password = entry("example");
+    db.save(password.clone()).await.unwrap();
+
+    let message = tokio::time::timeout(WAIT, socket.next()).await.expect("No message received").unwrap().unwrap();
+    let change: EntryChange = serde_json::from_str(message.to_text().unwrap()).unwrap();
+    assert_eq!(change.event, ChangeKind::Created);
+    assert_eq!(change.password_id, password.id);
+
+    feed.publish(FeedMessage::Resync);
+    let message = tokio::time::timeout(WAIT, socket.next()).await.expect("No message received").unwrap().unwrap();
+    assert_eq!(message, Message::Text(r#"{"event":"resync"}"#.into()));
+
+    feed.publish(FeedMessage::Closed);
+    let end = tokio::time::timeout(WAIT, socket.next()).await.expect("Socket was not closed");
+    assert!(matches!(end, None | Some(Ok(Message::Close(_))) | Some(Err(_))));
+}
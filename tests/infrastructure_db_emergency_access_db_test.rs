@@ -0,0 +1,146 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::emergency_access::*;
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::token::generate_token;
+use chrono::{Duration, SubsecRound, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+use EmergencyAccessAction as Action;
+use EmergencyAccessStatus as Status;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE emergency_access CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+async fn invite(db: &mut Database, wait_hours: i32) -> (EmergencyAccess, String) {
+    let token = generate_token();
+    let access = EmergencyAccess::new(Uuid::new_v4(), "Alex".to_string(), &token, wait_hours);
+    db.create_emergency_access(access.clone()).await.expect("Failed to invite contact");
+    (access, token)
+}
+
+fn assert_error(err: Box<dyn std::error::Error>, expected: &str) {
+    let err = err.downcast_ref::<EmergencyAccessError>().expect("Expected an emergency access error");
+    let name = match err {
+        EmergencyAccessError::NotFound => "not_found",
+        EmergencyAccessError::InvalidTransition { .. } => "invalid_transition",
+        EmergencyAccessError::NotGranted => "not_granted",
+        _ => "other",
+    };
+    assert_eq!(name, expected);
+}
+
+#[tokio::test]
+async fn test_approved_request_grants_access() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (access, token) = invite(&mut db, 48).await;
+    let now = Utc::now();
+
+    assert_error(db.authorize_emergency_access(&token, now).await.unwrap_err(), "not_granted");
+
+    db.transition_emergency_access_by_token(&token, Action::Accept, now).await.expect("Failed to accept");
+    let requested = db.transition_emergency_access_by_token(&token, Action::Request, now).await.expect("Failed to request");
+    assert_eq!(requested.status, Status::Requested);
+    assert_eq!(requested.requested_at, Some(now));
+
+    let approved = db.transition_emergency_access(access.id, Action::Approve, now).await.expect("Failed to approve");
+    assert_eq!(approved.status, Status::Granted);
+
+    let authorized = db.authorize_emergency_access(&token, now).await.expect("Access should be granted");
+    assert_eq!(authorized.id, access.id);
+}
+
+#[tokio::test]
+async fn test_request_is_granted_after_waiting_period() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (access, token) = invite(&mut db, 24).await;
+    let requested_at = Utc::now().trunc_subsecs(6);
+
+    db.transition_emergency_access_by_token(&token, Action::Accept, requested_at).await.unwrap();
+    db.transition_emergency_access_by_token(&token, Action::Request, requested_at).await.unwrap();
+
+    assert_error(db.authorize_emergency_access(&token, requested_at + Duration::hours(23)).await.unwrap_err(), "not_granted");
+
+    let later = requested_at + Duration::hours(25);
+    db.authorize_emergency_access(&token, later).await.expect("Access should be granted after the wait");
+
+    // Once granted, the owner can no longer reject the request, only revoke access
+    assert_error(db.transition_emergency_access(access.id, Action::Reject, later).await.unwrap_err(), "invalid_transition");
+
+    let events = db.list_emergency_access_events(access.id).await.unwrap();
+    let actions: Vec<Action> = events.iter().map(|event| event.action).collect();
+    assert_eq!(actions, vec![Action::Invite, Action::Accept, Action::Request, Action::AutoApprove]);
+    assert_eq!(events[3].from_status, Some(Status::Requested));
+    assert_eq!(events[3].to_status, Status::Granted);
+    assert_eq!(events[3].occurred_at, requested_at + Duration::hours(24));
+}
+
+#[tokio::test]
+async fn test_rejected_request_restarts_the_wait() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (access, token) = invite(&mut db, 24).await;
+    let now = Utc::now();
+
+    db.transition_emergency_access_by_token(&token, Action::Accept, now).await.unwrap();
+    db.transition_emergency_access_by_token(&token, Action::Request, now).await.unwrap();
+
+    let rejected = db.transition_emergency_access(access.id, Action::Reject, now + Duration::hours(1)).await.expect("Failed to reject");
+    assert_eq!(rejected.status, Status::Accepted);
+    assert_eq!(rejected.requested_at, None);
+
+    assert_error(db.authorize_emergency_access(&token, now + Duration::hours(48)).await.unwrap_err(), "not_granted");
+
+    let listed = db.list_emergency_access(now + Duration::hours(48)).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].status, Status::Accepted);
+}
+
+#[tokio::test]
+async fn test_revoked_access_is_final() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (access, token) = invite(&mut db, 1).await;
+    let now = Utc::now();
+
+    db.transition_emergency_access_by_token(&token, Action::Accept, now).await.unwrap();
+    db.transition_emergency_access_by_token(&token, Action::Request, now).await.unwrap();
+    db.transition_emergency_access(access.id, Action::Approve, now).await.unwrap();
+
+    let revoked = db.transition_emergency_access(access.id, Action::Revoke, now).await.expect("Failed to revoke");
+    assert_eq!(revoked.status, Status::Revoked);
+
+    assert_error(db.authorize_emergency_access(&token, now).await.unwrap_err(), "not_granted");
+    assert_error(db.transition_emergency_access_by_token(&token, Action::Request, now).await.unwrap_err(), "invalid_transition");
+
+    let events = db.list_emergency_access_events(access.id).await.unwrap();
+    assert_eq!(events.len(), 5);
+    assert_eq!(events[4].to_status, Status::Revoked);
+}
+
+#[tokio::test]
+async fn test_unknown_grant() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let now = Utc::now();
+
+    assert_error(db.authorize_emergency_access(&generate_token(), now).await.unwrap_err(), "not_found");
+    assert_error(db.transition_emergency_access(Uuid::new_v4(), Action::Approve, now).await.unwrap_err(), "not_found");
+}
@@ -0,0 +1,124 @@
+use rust_password_server::bounded_context::infrastructure::db::hibp_index::*;
+use rust_password_server::bounded_context::domain::breach_db::BreachDb;
+use rust_password_server::bounded_context::utility::breach::{sha1_hex, split_hash};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("hibp-{}-{}", name, Uuid::new_v4()))
+}
+
+fn sorted_dataset() -> Vec<String> {
+    let mut lines: Vec<String> = ["password", "123456", "qwerty", "letmein", "hunter2"]
+        .iter()
+        .enumerate()
+        .map(|(i, pw)| format!("{}:{}", sha1_hex(pw), (i + 1) * 100))
+        .collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn test_sorted_file_lookup() {
+    let path = temp_path("sorted");
+    std::fs::write(&path, sorted_dataset().join("\r\n")).expect("Failed to write dataset");
+
+    let index = HibpIndex::build(&path).expect("Failed to build index");
+    assert_eq!(index.prefix_count(), 5);
+
+    let (prefix, suffix) = split_hash(&sha1_hex("password"));
+    let hashes = index.range(&prefix).expect("Lookup failed");
+
+    assert_eq!(hashes.len(), 1);
+    assert_eq!(hashes[0].suffix, suffix);
+    assert_eq!(hashes[0].count, 100);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_sorted_file_missing_prefix() {
+    let path = temp_path("missing");
+    std::fs::write(&path, sorted_dataset().join("\n")).expect("Failed to write dataset");
+
+    let index = HibpIndex::build(&path).expect("Failed to build index");
+    let (prefix, _) = split_hash(&sha1_hex("correct horse battery staple"));
+
+    assert!(index.range(&prefix).expect("Lookup failed").is_empty());
+    assert!(index.range("00000").expect("Lookup failed").is_empty());
+    assert!(index.range("FFFFF").expect("Lookup failed").is_empty());
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_sorted_file_lowercase_prefix() {
+    let path = temp_path("lowercase");
+    std::fs::write(&path, sorted_dataset().join("\n")).expect("Failed to write dataset");
+
+    let index = HibpIndex::build(&path).expect("Failed to build index");
+    let (prefix, _) = split_hash(&sha1_hex("qwerty"));
+
+    assert_eq!(index.range(&prefix.to_lowercase()).expect("Lookup failed").len(), 1);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_unsorted_file_rejected() {
+    let path = temp_path("unsorted");
+    let mut lines = sorted_dataset();
+    lines.reverse();
+    std::fs::write(&path, lines.join("\n")).expect("Failed to write dataset");
+
+    assert!(HibpIndex::build(&path).is_err());
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_malformed_file_rejected() {
+    let path = temp_path("malformed");
+    std::fs::write(&path, "not a hash line\n").expect("Failed to write dataset");
+
+    assert!(HibpIndex::build(&path).is_err());
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_range_directory_lookup() {
+    let dir = temp_path("dir");
+    std::fs::create_dir_all(&dir).expect("Failed to create dataset dir");
+
+    let (prefix, suffix) = split_hash(&sha1_hex("password"));
+    std::fs::write(
+        dir.join(format!("{}.txt", prefix)),
+        format!("{}:3861493\r\n{}:2\r\n", suffix, "0".repeat(35)),
+    )
+    .expect("Failed to write range file");
+    std::fs::write(dir.join("README.md"), "ignored").expect("Failed to write extra file");
+
+    let index = HibpIndex::build(&dir).expect("Failed to build index");
+    assert_eq!(index.prefix_count(), 1);
+
+    let hashes = index.range(&prefix).expect("Lookup failed");
+    assert_eq!(hashes.len(), 2);
+    assert!(hashes.iter().any(|h| h.suffix == suffix && h.count == 3861493));
+
+    assert!(index.range("00000").expect("Lookup failed").is_empty());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_invalid_prefix() {
+    let path = temp_path("invalid");
+    std::fs::write(&path, sorted_dataset().join("\n")).expect("Failed to write dataset");
+
+    let index = HibpIndex::build(&path).expect("Failed to build index");
+    assert!(index.range("ZZZZZ").is_err());
+    assert!(index.range("ABC").is_err());
+
+    std::fs::remove_file(&path).ok();
+}
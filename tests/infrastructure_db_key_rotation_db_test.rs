@@ -0,0 +1,68 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::{import::ImportedEntry, key_rotation::*, password_db::PasswordDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::encryption::{decrypt, generate_key};
+use chrono::{SubsecRound, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+async fn save(db: &mut Database, key: &str, service: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    let entry = ImportedEntry {
+        service: service.to_string(),
+        secret: format!("{} secret", service),
+        tags: vec!["rotated".to_string()],
+        ..Default::default()
+    };
+    db.save(entry.encrypt(key, None, id, None, Utc::now().trunc_subsecs(6))).await.unwrap();
+    id
+}
+
+#[tokio::test]
+async fn test_rotate_master_key() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let old_key = generate_key();
+    let new_key = generate_key();
+    let github = save(&mut db, &old_key, "GitHub").await;
+    save(&mut db, &old_key, "Jira").await;
+
+    assert_eq!(db.rotate_master_key(&old_key, &new_key).await.unwrap(), 2);
+
+    let rotated = db.get_by_id(github).await.unwrap();
+    assert_eq!(decrypt(&new_key, &rotated.nonce, &rotated.cipher), "GitHub secret");
+    assert_eq!(rotated.tags, vec!["rotated".to_string()]);
+}
+
+#[tokio::test]
+async fn test_rotate_master_key_is_all_or_nothing() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let old_key = generate_key();
+    let github = save(&mut db, &old_key, "GitHub").await;
+    let stranger = save(&mut db, &generate_key(), "Encrypted elsewhere").await;
+
+    let err = db.rotate_master_key(&old_key, &generate_key()).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<KeyRotationError>(), Some(KeyRotationError::DecryptionFailed(id)) if *id == stranger));
+
+    let unchanged = db.get_by_id(github).await.unwrap();
+    assert_eq!(decrypt(&old_key, &unchanged.nonce, &unchanged.cipher), "GitHub secret");
+}
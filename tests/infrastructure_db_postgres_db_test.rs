@@ -1,6 +1,6 @@
 use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
-use rust_password_server::bounded_context::domain::{password::Password, password_db::PasswordDb, password_db::SortBy};
-use sqlx::{query, Executor, PgPool};
+use rust_password_server::bounded_context::domain::{password::{Password, EncryptedText, CustomField}, password_db::PasswordDb, password_db::SortBy, password_db::EntryFilter, password_db::BatchMode, pagination::{Cursor, PageRequest}, fuzzy_search::rank_by_similarity, uri_match::{EntryUri, MatchStrategy}, item::{ItemData, ItemType}, folder::{Folder, FolderError}};
+use sqlx::Executor;
 use uuid::Uuid;
 use chrono::Utc;
 use tokio::sync::OnceCell;
@@ -20,22 +20,11 @@ async fn get_test_database() -> &'static tokio::sync::Mutex<Database> {
 async fn setup_db(database: &Database) {
     let mut conn = database.get_connection().await.expect("Failed to get DB connection");
 
-    conn.execute(
-        r#"
-        CREATE TABLE IF NOT EXISTS passwords (
-            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-            service TEXT NOT NULL,
-            nonce TEXT NOT NULL,
-            cipher TEXT NOT NULL,
-            created_at TIMESTAMPTZ DEFAULT NOW() NOT NULL,
-            updated_at TIMESTAMPTZ DEFAULT NOW() NOT NULL
-        )
-        "#,
-    )
-    .await
-    .expect("Failed to setup the database");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
 
-    conn.execute("TRUNCATE TABLE passwords CASCADE")
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
         .await
         .expect("Failed to clean test database");
 }
@@ -57,6 +46,7 @@ async fn test_save_and_get_password() {
         cipher: "test_cipher".to_string(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
+        ..Default::default()
     };
 
     database.save(test_password.clone()).await.expect("Failed to save password");
@@ -87,6 +77,7 @@ async fn test_get_by_id() {
         cipher: "test_cipher".to_string(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
+        ..Default::default()
     };
 
     database
@@ -126,6 +117,7 @@ async fn test_delete_password() {
         cipher: "test_cipher".to_string(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
+        ..Default::default()
     };
 
     database.save(test_password.clone()).await.expect("Failed to save password");
@@ -152,6 +144,7 @@ async fn test_search_by_service() {
             cipher: "c1".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            ..Default::default()
         },
         Password {
             id: Uuid::new_v4(),
@@ -160,6 +153,7 @@ async fn test_search_by_service() {
             cipher: "c2".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            ..Default::default()
         },
         Password {
             id: Uuid::new_v4(),
@@ -168,6 +162,7 @@ async fn test_search_by_service() {
             cipher: "c3".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            ..Default::default()
         },
     ];
 
@@ -177,9 +172,9 @@ async fn test_search_by_service() {
 
     // Test pagination
     let results = database
-        .search_by_service("mail", 1, 10)
+        .search_by_service("mail", &EntryFilter::default(), &PageRequest::offset(1, 10))
         .await
-        .expect("Search failed");
+        .expect("Search failed").items;
 
     assert_eq!(results.len(), 2);
     let services: Vec<String> = results.into_iter().map(|pw| pw.service).collect();
@@ -188,9 +183,9 @@ async fn test_search_by_service() {
 
     // Test exact match
     let exact_results = database
-        .search_by_service("GitHub Login", 1, 10)
+        .search_by_service("GitHub Login", &EntryFilter::default(), &PageRequest::offset(1, 10))
         .await
-        .expect("Search failed");
+        .expect("Search failed").items;
     assert_eq!(exact_results.len(), 1);
 }
 
@@ -208,6 +203,7 @@ async fn test_list_sorted_created_at_asc() {
             cipher: "c1".to_string(),
             created_at: now - Duration::hours(2),
             updated_at: now,
+            ..Default::default()
         },
         Password {
             id: Uuid::new_v4(),
@@ -216,6 +212,7 @@ async fn test_list_sorted_created_at_asc() {
             cipher: "c2".to_string(),
             created_at: now - Duration::hours(1),
             updated_at: now,
+            ..Default::default()
         },
         Password {
             id: Uuid::new_v4(),
@@ -224,6 +221,7 @@ async fn test_list_sorted_created_at_asc() {
             cipher: "c3".to_string(),
             created_at: now,
             updated_at: now,
+            ..Default::default()
         },
     ];
 
@@ -231,9 +229,9 @@ async fn test_list_sorted_created_at_asc() {
         database.save(pw.clone()).await.expect("Failed to save password");
     }
 
-    let results = database.list_sorted(&SortBy::CreatedAtAsc, 1, 10)
+    let results = database.list_sorted(&SortBy::CreatedAtAsc, &EntryFilter::default(), &PageRequest::offset(1, 10))
         .await
-        .expect("Sorting failed");
+        .expect("Sorting failed").items;
 
     assert_eq!(results.len(), 3);
     assert_eq!(results[0].service, "Oldest");
@@ -255,6 +253,7 @@ async fn test_list_sorted_updated_at_desc() {
             cipher: "c1".to_string(),
             created_at: now - Duration::hours(2),
             updated_at: now,
+            ..Default::default()
         },
         Password {
             id: Uuid::new_v4(),
@@ -263,6 +262,7 @@ async fn test_list_sorted_updated_at_desc() {
             cipher: "c2".to_string(),
             created_at: now - Duration::hours(3),
             updated_at: now - Duration::hours(1),
+            ..Default::default()
         },
     ];
 
@@ -270,9 +270,9 @@ async fn test_list_sorted_updated_at_desc() {
         database.save(pw.clone()).await.expect("Failed to save password");
     }
 
-    let results = database.list_sorted(&SortBy::UpdatedAtDesc, 1, 10)
+    let results = database.list_sorted(&SortBy::UpdatedAtDesc, &EntryFilter::default(), &PageRequest::offset(1, 10))
         .await
-        .expect("Sorting failed");
+        .expect("Sorting failed").items;
 
     assert_eq!(results.len(), 2);
     assert_eq!(results[0].service, "Updated Recently");
@@ -285,9 +285,9 @@ async fn test_empty_search_results() {
     setup_db(&database).await;
 
     let results = database
-        .search_by_service("nonexistent", 1, 10)
+        .search_by_service("nonexistent", &EntryFilter::default(), &PageRequest::offset(1, 10))
         .await
-        .expect("Search failed");
+        .expect("Search failed").items;
     
     assert!(results.is_empty());
 }
@@ -306,6 +306,7 @@ async fn test_all_sorting_variants() {
             cipher: "c1".to_string(),
             created_at: now - Duration::hours(2),
             updated_at: now - Duration::hours(1),
+            ..Default::default()
         },
         Password {
             id: Uuid::new_v4(),
@@ -314,6 +315,7 @@ async fn test_all_sorting_variants() {
             cipher: "c2".to_string(),
             created_at: now - Duration::hours(1),
             updated_at: now,
+            ..Default::default()
         },
     ];
 
@@ -329,9 +331,9 @@ async fn test_all_sorting_variants() {
     ];
 
     for (sort_by, expected_order) in test_cases {
-        let results = database.list_sorted(&sort_by, 1, 10)
+        let results = database.list_sorted(&sort_by, &EntryFilter::default(), &PageRequest::offset(1, 10))
             .await
-            .expect("Sorting failed");
+            .expect("Sorting failed").items;
         
         let services: Vec<&str> = results.iter().map(|pw| pw.service.as_str()).collect();
         assert_eq!(services, expected_order, "Failed for {:?}", sort_by);
@@ -351,20 +353,21 @@ async fn test_search_by_service_pagination() {
             cipher: format!("c{}", i),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            ..Default::default()
         };
         database.save(password).await.expect("Failed to save password");
     }
 
     let page1 = database
-        .search_by_service("Service", 1, 5)
+        .search_by_service("Service", &EntryFilter::default(), &PageRequest::offset(1, 5))
         .await
-        .expect("Search failed");
+        .expect("Search failed").items;
     assert_eq!(page1.len(), 5);
 
     let page2 = database
-        .search_by_service("Service", 2, 5)
+        .search_by_service("Service", &EntryFilter::default(), &PageRequest::offset(2, 5))
         .await
-        .expect("Search failed");
+        .expect("Search failed").items;
     assert_eq!(page2.len(), 5);
 
     let page1_services: Vec<String> = page1.into_iter().map(|pw| pw.service).collect();
@@ -374,8 +377,834 @@ async fn test_search_by_service_pagination() {
     }
 
     let page3 = database
-        .search_by_service("Service", 3, 5)
+        .search_by_service("Service", &EntryFilter::default(), &PageRequest::offset(3, 5))
         .await
-        .expect("Search failed");
+        .expect("Search failed").items;
     assert!(page3.is_empty());
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_save_fingerprint_and_strength() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let test_password = Password {
+        id: Uuid::new_v4(),
+        service: "test_service".to_string(),
+        nonce: "test_nonce".to_string(),
+        cipher: "test_cipher".to_string(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        fingerprint: Some("ab".repeat(32)),
+        strength: Some(2),
+        ..Default::default()
+    };
+
+    database.save(test_password.clone()).await.expect("Failed to save password");
+
+    let retrieved_password = database
+        .get_by_id(test_password.id)
+        .await
+        .expect("Failed to retrieve password");
+
+    assert_eq!(retrieved_password.fingerprint, test_password.fingerprint);
+    assert_eq!(retrieved_password.strength, Some(2));
+}
+
+#[tokio::test]
+async fn test_list_all() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let now = Utc::now();
+    for (service, age) in [("Recent", 1), ("Ancient", 400), ("Old", 100)] {
+        let password = Password {
+            id: Uuid::new_v4(),
+            service: service.to_string(),
+            nonce: "n".to_string(),
+            cipher: "c".to_string(),
+            created_at: now - Duration::days(age),
+            updated_at: now - Duration::days(age),
+            ..Default::default()
+        };
+        database.save(password).await.expect("Failed to save password");
+    }
+
+    let results = database.list_all().await.expect("Listing failed");
+
+    let services: Vec<&str> = results.iter().map(|pw| pw.service.as_str()).collect();
+    assert_eq!(services, vec!["Ancient", "Old", "Recent"]);
+}
+
+#[tokio::test]
+async fn test_save_rich_entry() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let test_password = Password {
+        id: Uuid::new_v4(),
+        service: "GitHub".to_string(),
+        nonce: "test_nonce".to_string(),
+        cipher: "test_cipher".to_string(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        username: Some("octocat".to_string()),
+        uris: vec![EntryUri::from("https://github.com/login"), EntryUri::new("https://gist.github.com", MatchStrategy::StartsWith)],
+        notes: Some(EncryptedText { nonce: "notes_nonce".to_string(), cipher: "notes_cipher".to_string() }),
+        totp: Some(EncryptedText { nonce: "totp_nonce".to_string(), cipher: "totp_cipher".to_string() }),
+        custom_fields: vec![
+            CustomField::Text { name: "Org".to_string(), value: "acme".to_string() },
+            CustomField::Hidden { name: "Recovery".to_string(), nonce: "n".to_string(), cipher: "c".to_string() },
+            CustomField::Boolean { name: "Shared".to_string(), value: true },
+        ],
+        ..Default::default()
+    };
+
+    database.save(test_password.clone()).await.expect("Failed to save password");
+
+    let retrieved_password = database
+        .get_by_id(test_password.id)
+        .await
+        .expect("Failed to retrieve password");
+
+    assert_eq!(retrieved_password.username, test_password.username);
+    assert_eq!(retrieved_password.uris, test_password.uris);
+    assert_eq!(retrieved_password.notes, test_password.notes);
+    assert_eq!(retrieved_password.totp, test_password.totp);
+    assert_eq!(retrieved_password.custom_fields, test_password.custom_fields);
+}
+
+#[tokio::test]
+async fn test_search_by_username_and_uri() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let passwords = vec![
+        Password {
+            id: Uuid::new_v4(),
+            service: "Code Hosting".to_string(),
+            nonce: "n1".to_string(),
+            cipher: "c1".to_string(),
+            username: Some("octocat".to_string()),
+            uris: vec![EntryUri::from("https://github.com/login")],
+            ..Default::default()
+        },
+        Password {
+            id: Uuid::new_v4(),
+            service: "Mail".to_string(),
+            nonce: "n2".to_string(),
+            cipher: "c2".to_string(),
+            username: Some("someone@example.com".to_string()),
+            ..Default::default()
+        },
+    ];
+
+    for pw in &passwords {
+        database.save(pw.clone()).await.expect("Failed to save password");
+    }
+
+    let by_username = database.search_by_service("octo", &EntryFilter::default(), &PageRequest::offset(1, 10)).await.expect("Search failed").items;
+    assert_eq!(by_username.len(), 1);
+    assert_eq!(by_username[0].service, "Code Hosting");
+
+    let by_uri = database.search_by_service("github.com", &EntryFilter::default(), &PageRequest::offset(1, 10)).await.expect("Search failed").items;
+    assert_eq!(by_uri.len(), 1);
+    assert_eq!(by_uri[0].service, "Code Hosting");
+
+    let by_email = database.search_by_service("example.com", &EntryFilter::default(), &PageRequest::offset(1, 10)).await.expect("Search failed").items;
+    assert_eq!(by_email.len(), 1);
+    assert_eq!(by_email[0].service, "Mail");
+}
+
+#[tokio::test]
+async fn test_save_typed_items() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let ssh_key = Password {
+        id: Uuid::new_v4(),
+        service: "Deploy Key".to_string(),
+        nonce: "n1".to_string(),
+        cipher: "c1".to_string(),
+        item: ItemData::SshKey {
+            public_key: "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAI deploy@ci".to_string(),
+            key_fingerprint: Some("SHA256:abc".to_string()),
+            passphrase: Some(EncryptedText { nonce: "pn".to_string(), cipher: "pc".to_string() }),
+        },
+        ..Default::default()
+    };
+
+    database.save(ssh_key.clone()).await.expect("Failed to save password");
+
+    let retrieved = database.get_by_id(ssh_key.id).await.expect("Failed to retrieve password");
+    assert_eq!(retrieved.item, ssh_key.item);
+}
+
+#[tokio::test]
+async fn test_item_type_filters() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let now = Utc::now();
+    let items = vec![
+        ("Work Login", ItemData::Login, 3),
+        ("Work Note", ItemData::SecureNote, 2),
+        ("Work Card", ItemData::Card { cardholder_name: None, expiry: None, security_code: None, brand: None, last_four: None }, 1),
+        ("Home Note", ItemData::SecureNote, 0),
+    ];
+
+    for (service, item, age) in items {
+        let password = Password {
+            id: Uuid::new_v4(),
+            service: service.to_string(),
+            nonce: "n".to_string(),
+            cipher: "c".to_string(),
+            created_at: now - Duration::hours(age),
+            updated_at: now - Duration::hours(age),
+            item,
+            ..Default::default()
+        };
+        database.save(password).await.expect("Failed to save password");
+    }
+
+    let notes = database
+        .search_by_service("Work", &EntryFilter { item_type: Some(ItemType::SecureNote), ..Default::default() }, &PageRequest::offset(1, 10))
+        .await
+        .expect("Search failed").items;
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].service, "Work Note");
+
+    let all_work = database.search_by_service("Work", &EntryFilter::default(), &PageRequest::offset(1, 10)).await.expect("Search failed").items;
+    assert_eq!(all_work.len(), 3);
+
+    let sorted_notes = database
+        .list_sorted(&SortBy::CreatedAtAsc, &EntryFilter { item_type: Some(ItemType::SecureNote), ..Default::default() }, &PageRequest::offset(1, 10))
+        .await
+        .expect("Sorting failed").items;
+    let services: Vec<&str> = sorted_notes.iter().map(|pw| pw.service.as_str()).collect();
+    assert_eq!(services, vec!["Work Note", "Home Note"]);
+
+    let cards = database
+        .list_sorted(&SortBy::CreatedAtAsc, &EntryFilter { item_type: Some(ItemType::Card), ..Default::default() }, &PageRequest::offset(1, 10))
+        .await
+        .expect("Sorting failed").items;
+    assert_eq!(cards.len(), 1);
+    assert_eq!(cards[0].item.item_type(), ItemType::Card);
+}
+
+fn entry(service: &str, folder_id: Option<Uuid>, tags: &[&str]) -> Password {
+    Password {
+        id: Uuid::new_v4(),
+        service: service.to_string(),
+        nonce: "n".to_string(),
+        cipher: "c".to_string(),
+        folder_id,
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_folder_lifecycle() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let work = Folder::new(Uuid::new_v4(), "Work".to_string(), None);
+    let infra = Folder::new(Uuid::new_v4(), "Infra".to_string(), Some(work.id));
+    database.create_folder(work.clone()).await.expect("Failed to create folder");
+    database.create_folder(infra.clone()).await.expect("Failed to create folder");
+
+    database.rename_folder(infra.id, "Infrastructure").await.expect("Failed to rename folder");
+    database.move_folder(infra.id, None).await.expect("Failed to move folder");
+
+    let folders = database.list_folders().await.expect("Failed to list folders");
+    assert_eq!(folders.len(), 2);
+    let infra_after = folders.iter().find(|f| f.id == infra.id).expect("Folder missing");
+    assert_eq!(infra_after.name, "Infrastructure");
+    assert_eq!(infra_after.parent_id, None);
+
+    let missing = database.rename_folder(Uuid::new_v4(), "Nope").await;
+    assert!(matches!(missing.unwrap_err().downcast_ref::<FolderError>(), Some(FolderError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_create_folder_with_missing_parent() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let orphan = Folder::new(Uuid::new_v4(), "Orphan".to_string(), Some(Uuid::new_v4()));
+    let result = database.create_folder(orphan).await;
+
+    assert!(matches!(result.unwrap_err().downcast_ref::<FolderError>(), Some(FolderError::ParentNotFound(_))));
+}
+
+#[tokio::test]
+async fn test_move_folder_rejects_cycles() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let root = Folder::new(Uuid::new_v4(), "Root".to_string(), None);
+    let child = Folder::new(Uuid::new_v4(), "Child".to_string(), Some(root.id));
+    let grandchild = Folder::new(Uuid::new_v4(), "Grandchild".to_string(), Some(child.id));
+    for folder in [&root, &child, &grandchild] {
+        database.create_folder(folder.clone()).await.expect("Failed to create folder");
+    }
+
+    let into_self = database.move_folder(root.id, Some(root.id)).await;
+    assert!(matches!(into_self.unwrap_err().downcast_ref::<FolderError>(), Some(FolderError::Cycle)));
+
+    let into_descendant = database.move_folder(root.id, Some(grandchild.id)).await;
+    assert!(matches!(into_descendant.unwrap_err().downcast_ref::<FolderError>(), Some(FolderError::Cycle)));
+
+    database.move_folder(grandchild.id, Some(root.id)).await.expect("Failed to move folder");
+}
+
+#[tokio::test]
+async fn test_concurrent_moves_cannot_create_a_cycle() {
+    // Both moves need a connection of their own, so this test uses a second pool
+    let _database = get_test_database().await.lock().await;
+    let config = app_config::load_config();
+    let pool = Database::new(&config.test_db_url.clone(), 2, config).await.expect("Failed to create test DB");
+    setup_db(&pool).await;
+
+    for _ in 0..20 {
+        let a = Folder::new(Uuid::new_v4(), "A".to_string(), None);
+        let b = Folder::new(Uuid::new_v4(), "B".to_string(), None);
+        let (mut first, mut second) = (pool.clone(), pool.clone());
+        first.create_folder(a.clone()).await.expect("Failed to create folder");
+        first.create_folder(b.clone()).await.expect("Failed to create folder");
+
+        let (a_under_b, b_under_a) = tokio::join!(first.move_folder(a.id, Some(b.id)), second.move_folder(b.id, Some(a.id)));
+        assert!(a_under_b.is_err() || b_under_a.is_err(), "Both moves succeeded");
+    }
+}
+
+#[tokio::test]
+async fn test_subfolder_filter_survives_a_cycle() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let a = Folder::new(Uuid::new_v4(), "A".to_string(), None);
+    let b = Folder::new(Uuid::new_v4(), "B".to_string(), Some(a.id));
+    database.create_folder(a.clone()).await.expect("Failed to create folder");
+    database.create_folder(b.clone()).await.expect("Failed to create folder");
+    database.save(entry("Service B", Some(b.id), &[])).await.expect("Failed to save password");
+    sqlx::query("UPDATE folders SET parent_id = $2 WHERE id = $1").bind(a.id).bind(b.id).execute(database.get_pool()).await.unwrap();
+
+    let nested = EntryFilter { folder_id: Some(a.id), include_subfolders: true, ..Default::default() };
+    let results = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        database.list_sorted(&SortBy::CreatedAtAsc, &nested, &PageRequest::offset(1, 10)),
+    )
+    .await
+    .expect("Query did not finish")
+    .expect("Sorting failed")
+    .items;
+    assert_eq!(results.len(), 1);
+}
+
+#[tokio::test]
+async fn test_delete_folder_cascades_and_unfiles_entries() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let root = Folder::new(Uuid::new_v4(), "Root".to_string(), None);
+    let child = Folder::new(Uuid::new_v4(), "Child".to_string(), Some(root.id));
+    database.create_folder(root.clone()).await.expect("Failed to create folder");
+    database.create_folder(child.clone()).await.expect("Failed to create folder");
+
+    let password = entry("Filed", Some(child.id), &[]);
+    database.save(password.clone()).await.expect("Failed to save password");
+
+    database.delete_folder(root.id).await.expect("Failed to delete folder");
+
+    assert!(database.list_folders().await.expect("Failed to list folders").is_empty());
+    let retrieved = database.get_by_id(password.id).await.expect("Failed to retrieve password");
+    assert_eq!(retrieved.folder_id, None);
+}
+
+#[tokio::test]
+async fn test_folder_filters() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let work = Folder::new(Uuid::new_v4(), "Work".to_string(), None);
+    let infra = Folder::new(Uuid::new_v4(), "Infra".to_string(), Some(work.id));
+    let aws = Folder::new(Uuid::new_v4(), "AWS".to_string(), Some(infra.id));
+    for folder in [&work, &infra, &aws] {
+        database.create_folder(folder.clone()).await.expect("Failed to create folder");
+    }
+
+    for password in [
+        entry("Service Work", Some(work.id), &[]),
+        entry("Service Infra", Some(infra.id), &[]),
+        entry("Service AWS", Some(aws.id), &[]),
+        entry("Service Unfiled", None, &[]),
+    ] {
+        database.save(password).await.expect("Failed to save password");
+    }
+
+    let direct = EntryFilter { folder_id: Some(work.id), ..Default::default() };
+    let results = database.search_by_service("Service", &direct, &PageRequest::offset(1, 10)).await.expect("Search failed").items;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].service, "Service Work");
+
+    let nested = EntryFilter { folder_id: Some(work.id), include_subfolders: true, ..Default::default() };
+    let results = database.list_sorted(&SortBy::CreatedAtAsc, &nested, &PageRequest::offset(1, 10)).await.expect("Sorting failed").items;
+    assert_eq!(results.len(), 3);
+
+    let subtree = EntryFilter { folder_id: Some(infra.id), include_subfolders: true, ..Default::default() };
+    let results = database.search_by_service("Service", &subtree, &PageRequest::offset(1, 10)).await.expect("Search failed").items;
+    let mut services: Vec<String> = results.into_iter().map(|pw| pw.service).collect();
+    services.sort();
+    assert_eq!(services, vec!["Service AWS", "Service Infra"]);
+}
+
+#[tokio::test]
+async fn test_set_folder() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let folder = Folder::new(Uuid::new_v4(), "Personal".to_string(), None);
+    database.create_folder(folder.clone()).await.expect("Failed to create folder");
+
+    let password = entry("Loose", None, &[]);
+    database.save(password.clone()).await.expect("Failed to save password");
+
+    database.set_folder(password.id, Some(folder.id)).await.expect("Failed to move password");
+    assert_eq!(database.get_by_id(password.id).await.unwrap().folder_id, Some(folder.id));
+
+    let missing_folder = database.set_folder(password.id, Some(Uuid::new_v4())).await;
+    assert!(missing_folder.unwrap_err().is::<FolderError>());
+
+    database.set_folder(password.id, None).await.expect("Failed to unfile password");
+    assert_eq!(database.get_by_id(password.id).await.unwrap().folder_id, None);
+
+    assert!(database.set_folder(Uuid::new_v4(), None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_tags() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let tagged = entry("Tagged", None, &["Work", "shared"]);
+    let other = entry("Other", None, &["work"]);
+    database.save(tagged.clone()).await.expect("Failed to save password");
+    database.save(other.clone()).await.expect("Failed to save password");
+
+    let retrieved = database.get_by_id(tagged.id).await.expect("Failed to retrieve password");
+    assert_eq!(retrieved.tags, vec!["shared", "work"]);
+
+    database.tag(other.id, "urgent").await.expect("Failed to tag password");
+    database.tag(other.id, "URGENT").await.expect("Tagging twice should be a no-op");
+    database.untag(tagged.id, "Shared").await.expect("Failed to untag password");
+    assert!(database.untag(tagged.id, "shared").await.is_err());
+    assert!(database.tag(Uuid::new_v4(), "urgent").await.is_err());
+
+    let tags = database.list_tags().await.expect("Failed to list tags");
+    let counts: Vec<(&str, i64)> = tags.iter().map(|t| (t.name.as_str(), t.count)).collect();
+    assert_eq!(counts, vec![("urgent", 1), ("work", 2)]);
+
+    let by_tag = EntryFilter { tag: Some("Urgent".to_string()), ..Default::default() };
+    let results = database.list_sorted(&SortBy::CreatedAtAsc, &by_tag, &PageRequest::offset(1, 10)).await.expect("Sorting failed").items;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].service, "Other");
+}
+
+#[tokio::test]
+async fn test_favorite_and_record_use() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let password = entry("Launcher", None, &[]);
+    database.save(password.clone()).await.expect("Failed to save password");
+
+    database.set_favorite(password.id, true).await.expect("Failed to favorite password");
+
+    let used_at = Utc::now() - Duration::minutes(5);
+    database.record_use(password.id, used_at).await.expect("Failed to record use");
+    database
+        .record_use(password.id, used_at - Duration::hours(1))
+        .await
+        .expect("Failed to record use");
+
+    let retrieved = database.get_by_id(password.id).await.expect("Failed to retrieve password");
+    assert!(retrieved.favorite);
+    assert_datetime_approx_eq(retrieved.last_used_at.expect("Use not recorded"), used_at, Duration::milliseconds(1));
+
+    assert!(database.set_favorite(Uuid::new_v4(), true).await.is_err());
+    assert!(database.record_use(Uuid::new_v4(), Utc::now()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_relevance_sorting_variants() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let now = Utc::now();
+    let passwords = vec![
+        Password { last_used_at: Some(now - Duration::hours(1)), ..entry("bravo", None, &[]) },
+        Password { favorite: true, last_used_at: Some(now - Duration::hours(3)), ..entry("Alpha", None, &[]) },
+        Password { last_used_at: None, ..entry("charlie", None, &[]) },
+        Password { favorite: true, last_used_at: Some(now - Duration::hours(2)), ..entry("Delta", None, &[]) },
+    ];
+
+    for pw in &passwords {
+        database.save(pw.clone()).await.expect("Failed to save password");
+    }
+
+    let test_cases = vec![
+        (SortBy::LastUsedDesc, vec!["bravo", "Delta", "Alpha", "charlie"]),
+        (SortBy::FavoritesFirst, vec!["Delta", "Alpha", "bravo", "charlie"]),
+        (SortBy::ServiceAsc, vec!["Alpha", "bravo", "charlie", "Delta"]),
+        (SortBy::ServiceDesc, vec!["Delta", "charlie", "bravo", "Alpha"]),
+    ];
+
+    for (sort_by, expected_order) in test_cases {
+        let results = database.list_sorted(&sort_by, &EntryFilter::default(), &PageRequest::offset(1, 10))
+            .await
+            .expect("Sorting failed").items;
+
+        let services: Vec<&str> = results.iter().map(|pw| pw.service.as_str()).collect();
+        assert_eq!(services, expected_order, "Failed for {:?}", sort_by);
+    }
+}
+
+#[tokio::test]
+async fn test_query_combines_conditions() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let now = Utc::now();
+    let passwords = vec![
+        Password {
+            username: Some("alice@work.com".to_string()),
+            favorite: true,
+            created_at: now - Duration::days(40),
+            updated_at: now - Duration::days(2),
+            ..entry("GitHub", None, &["work"])
+        },
+        Password {
+            username: Some("alice@home.com".to_string()),
+            created_at: now - Duration::days(40),
+            updated_at: now - Duration::days(30),
+            ..entry("GitLab", None, &["work"])
+        },
+        Password {
+            uris: vec![EntryUri::from("https://git.example.com")],
+            created_at: now - Duration::days(5),
+            updated_at: now - Duration::days(1),
+            ..entry("Bitbucket", None, &["personal"])
+        },
+    ];
+
+    for pw in &passwords {
+        database.save(pw.clone()).await.expect("Failed to save password");
+    }
+
+    let query = |filter: EntryFilter| (filter, SortBy::ServiceAsc);
+    let test_cases = vec![
+        (query(EntryFilter { search_term: Some("git".to_string()), ..Default::default() }), vec!["Bitbucket", "GitHub", "GitLab"]),
+        (query(EntryFilter { service: Some("git".to_string()), ..Default::default() }), vec!["GitHub", "GitLab"]),
+        (query(EntryFilter { username: Some("@WORK".to_string()), ..Default::default() }), vec!["GitHub"]),
+        (query(EntryFilter { uri: Some("example.com".to_string()), ..Default::default() }), vec!["Bitbucket"]),
+        (query(EntryFilter { favorite: Some(false), ..Default::default() }), vec!["Bitbucket", "GitLab"]),
+        (query(EntryFilter { created_before: Some(now - Duration::days(10)), ..Default::default() }), vec!["GitHub", "GitLab"]),
+        (
+            query(EntryFilter {
+                service: Some("git".to_string()),
+                tag: Some("work".to_string()),
+                updated_after: Some(now - Duration::days(7)),
+                updated_before: Some(now),
+                ..Default::default()
+            }),
+            vec!["GitHub"],
+        ),
+    ];
+
+    for ((filter, sort_by), expected) in test_cases {
+        let results = database.query(&filter, &sort_by, &PageRequest::offset(1, 10))
+            .await
+            .expect("Query failed").items;
+
+        let services: Vec<&str> = results.iter().map(|pw| pw.service.as_str()).collect();
+        assert_eq!(services, expected, "Failed for {:?}", filter);
+    }
+}
+
+#[tokio::test]
+async fn test_query_treats_wildcards_literally() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    for service in ["100% Cotton", "100 Cotton", "snake_case", "snakeXcase"] {
+        database.save(entry(service, None, &[])).await.expect("Failed to save password");
+    }
+
+    let percent = EntryFilter { service: Some("0%".to_string()), ..Default::default() };
+    let results = database.query(&percent, &SortBy::ServiceAsc, &PageRequest::offset(1, 10)).await.expect("Query failed").items;
+    assert_eq!(results.iter().map(|pw| pw.service.as_str()).collect::<Vec<_>>(), vec!["100% Cotton"]);
+
+    let underscore = EntryFilter { search_term: Some("e_c".to_string()), ..Default::default() };
+    let results = database.query(&underscore, &SortBy::ServiceAsc, &PageRequest::offset(1, 10)).await.expect("Query failed").items;
+    assert_eq!(results.iter().map(|pw| pw.service.as_str()).collect::<Vec<_>>(), vec!["snake_case"]);
+}
+
+#[tokio::test]
+async fn test_page_envelope() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    for i in 0..5 {
+        database.save(entry(&format!("Service {}", i), None, &[])).await.expect("Failed to save password");
+    }
+
+    let first = database.list_sorted(&SortBy::ServiceAsc, &EntryFilter::default(), &PageRequest::offset(1, 2))
+        .await
+        .expect("Sorting failed");
+    assert_eq!(first.total_count, 5);
+    assert_eq!(first.page, Some(1));
+    assert_eq!(first.page_size, 2);
+    assert_eq!(first.items.len(), 2);
+    assert!(first.next_cursor.is_some());
+
+    let last = database.list_sorted(&SortBy::ServiceAsc, &EntryFilter::default(), &PageRequest::offset(3, 2))
+        .await
+        .expect("Sorting failed");
+    assert_eq!(last.items.len(), 1);
+    assert_eq!(last.next_cursor, None);
+
+    let zero = database.list_sorted(&SortBy::ServiceAsc, &EntryFilter::default(), &PageRequest::offset(0, 2))
+        .await
+        .expect("Page 0 should not underflow");
+    assert_eq!(zero.items, first.items);
+}
+
+#[tokio::test]
+async fn test_cursor_pagination_walks_every_sort_option() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    // Shared timestamps, favorites and names force the id tiebreaker to decide the order
+    let now = Utc::now();
+    let timestamp = |hours: i64| DateTime::from_timestamp((now - Duration::hours(hours)).timestamp(), 0).unwrap();
+    for i in 0..7i64 {
+        let password = Password {
+            created_at: timestamp(i % 3),
+            updated_at: timestamp(i % 2),
+            favorite: i % 2 == 0,
+            last_used_at: if i % 3 == 0 { None } else { Some(timestamp(i % 4)) },
+            ..entry(if i % 2 == 0 { "Same" } else { "same" }, None, &[])
+        };
+        database.save(password).await.expect("Failed to save password");
+    }
+
+    let sort_options = vec![
+        SortBy::CreatedAtAsc,
+        SortBy::CreatedAtDesc,
+        SortBy::UpdatedAtAsc,
+        SortBy::UpdatedAtDesc,
+        SortBy::LastUsedDesc,
+        SortBy::FavoritesFirst,
+        SortBy::ServiceAsc,
+        SortBy::ServiceDesc,
+    ];
+
+    for sort_by in sort_options {
+        let expected: Vec<Uuid> = database.list_sorted(&sort_by, &EntryFilter::default(), &PageRequest::offset(1, 10))
+            .await
+            .expect("Sorting failed")
+            .items
+            .iter()
+            .map(|pw| pw.id)
+            .collect();
+
+        let mut walked = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = database.list_sorted(&sort_by, &EntryFilter::default(), &PageRequest::after(cursor, 3))
+                .await
+                .expect("Sorting failed");
+            assert_eq!(page.total_count, 7);
+            assert_eq!(page.page, None);
+            walked.extend(page.items.iter().map(|pw| pw.id));
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(Cursor::decode(&next, sort_by).expect("Invalid cursor")),
+                None => break,
+            }
+        }
+
+        assert_eq!(walked, expected, "Failed for {:?}", sort_by);
+    }
+}
+
+#[tokio::test]
+async fn test_cursor_is_stable_under_concurrent_changes() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    for service in ["b", "d", "f", "h"] {
+        database.save(entry(service, None, &[])).await.expect("Failed to save password");
+    }
+
+    let first = database.search_by_service("", &EntryFilter::default(), &PageRequest::after(None, 2))
+        .await
+        .expect("Search failed");
+    assert_eq!(first.items.iter().map(|pw| pw.service.as_str()).collect::<Vec<_>>(), vec!["b", "d"]);
+
+    // An insert before the cursor and the deletion of the cursor's entry shift offsets but not the cursor
+    database.save(entry("a", None, &[])).await.expect("Failed to save password");
+    database.delete(first.items[1].id).await.expect("Failed to delete password");
+
+    let cursor = Cursor::decode(first.next_cursor.as_deref().unwrap(), SortBy::ServiceAsc).expect("Invalid cursor");
+    let second = database.search_by_service("", &EntryFilter::default(), &PageRequest::after(Some(cursor), 2))
+        .await
+        .expect("Search failed");
+    assert_eq!(second.items.iter().map(|pw| pw.service.as_str()).collect::<Vec<_>>(), vec!["f", "h"]);
+    assert_eq!(second.next_cursor, None);
+}
+
+#[tokio::test]
+async fn test_fuzzy_search_ranks_by_similarity() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let passwords = vec![
+        entry("GitHub", None, &["work"]),
+        entry("GitHub Enterprise", None, &[]),
+        entry("GitLab", None, &[]),
+        entry("Netflix", None, &[]),
+    ];
+    for pw in &passwords {
+        database.save(pw.clone()).await.expect("Failed to save password");
+    }
+
+    let page = database.fuzzy_search("githbu", 0.2, &EntryFilter::default(), 1, 10)
+        .await
+        .expect("Fuzzy search failed");
+
+    let expected = rank_by_similarity(passwords.clone(), "githbu", 0.2);
+    assert_eq!(page.total_count, expected.len() as i64);
+    assert_eq!(
+        page.items.iter().map(|found| found.password.service.as_str()).collect::<Vec<_>>(),
+        expected.iter().map(|found| found.password.service.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(page.items[0].password.service, "GitHub");
+    for (found, scored) in page.items.iter().zip(&expected) {
+        assert!((found.score - scored.score).abs() < 1e-6, "Score differs for {}", found.password.service);
+    }
+
+    let strict = database.fuzzy_search("githbu", 0.9, &EntryFilter::default(), 1, 10)
+        .await
+        .expect("Fuzzy search failed");
+    assert!(strict.items.is_empty());
+
+    let tagged = EntryFilter { tag: Some("work".to_string()), ..Default::default() };
+    let filtered = database.fuzzy_search("git", 0.1, &tagged, 1, 10)
+        .await
+        .expect("Fuzzy search failed");
+    assert_eq!(filtered.items.iter().map(|found| found.password.service.as_str()).collect::<Vec<_>>(), vec!["GitHub"]);
+}
+
+#[tokio::test]
+async fn test_trigram_similarity_matches_pg_trgm() {
+    let database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+
+    let pairs = [
+        ("GitHub", "githbu"),
+        ("mail.google.com", "gmail"),
+        ("My Bank (Savings)", "bank savings"),
+        ("AWS_console", "aws console"),
+        ("user@example.com", "example"),
+        ("", "anything"),
+        ("a", "a"),
+    ];
+
+    for (a, b) in pairs {
+        let expected: f32 = sqlx::query_scalar("SELECT similarity($1, $2)")
+            .bind(a)
+            .bind(b)
+            .fetch_one(&mut *conn)
+            .await
+            .expect("Failed to compute similarity");
+
+        let actual = rust_password_server::bounded_context::utility::trigram::similarity(a, b);
+        assert!((actual - expected).abs() < 1e-6, "{} / {}: {} != {}", a, b, actual, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_save_batch_all_or_nothing_rolls_back() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let valid = Password { id: Uuid::new_v4(), service: "valid".to_string(), ..Default::default() };
+    let missing_folder = Password {
+        id: Uuid::new_v4(),
+        service: "missing_folder".to_string(),
+        folder_id: Some(Uuid::new_v4()),
+        ..Default::default()
+    };
+
+    let outcomes = database
+        .save_batch(vec![valid.clone(), missing_folder.clone()], BatchMode::AllOrNothing)
+        .await
+        .expect("Failed to run batch");
+
+    assert!(outcomes[0].is_ok());
+    assert!(outcomes[1].as_ref().unwrap_err().contains("Folder not found"));
+    assert!(database.get_by_id(valid.id).await.is_err());
+
+    let outcomes = database
+        .save_batch(vec![valid.clone(), missing_folder.clone()], BatchMode::BestEffort)
+        .await
+        .expect("Failed to run batch");
+
+    assert!(outcomes[0].is_ok());
+    assert!(outcomes[1].is_err());
+    assert_eq!(database.get_by_id(valid.id).await.expect("Valid entry was not saved").service, "valid");
+    assert!(database.get_by_id(missing_folder.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_update_and_delete_batch() {
+    let mut database = get_test_database().await.lock().await;
+    setup_db(&database).await;
+
+    let existing = Password { id: Uuid::new_v4(), service: "before".to_string(), tags: vec!["old".to_string()], ..Default::default() };
+    database.save(existing.clone()).await.expect("Failed to save password");
+    let missing = Uuid::new_v4();
+
+    let updated = Password { service: "after".to_string(), tags: vec!["new".to_string()], ..existing.clone() };
+    let outcomes = database
+        .update_batch(vec![updated, Password { id: missing, ..Default::default() }], BatchMode::BestEffort)
+        .await
+        .expect("Failed to run batch");
+
+    assert!(outcomes[0].is_ok());
+    assert_eq!(outcomes[1], Err(format!("Password not found: {}", missing)));
+    let stored = database.get_by_id(existing.id).await.expect("Failed to retrieve password");
+    assert_eq!(stored.service, "after");
+    assert_eq!(stored.tags, vec!["new".to_string()]);
+
+    let outcomes = database
+        .delete_batch(vec![existing.id, missing], BatchMode::AllOrNothing)
+        .await
+        .expect("Failed to run batch");
+
+    assert!(outcomes[0].is_ok());
+    assert!(outcomes[1].is_err());
+    assert!(database.get_by_id(existing.id).await.is_ok());
+
+    let outcomes = database
+        .delete_batch(vec![existing.id], BatchMode::AllOrNothing)
+        .await
+        .expect("Failed to run batch");
+
+    assert_eq!(outcomes, vec![Ok(())]);
+    assert!(database.get_by_id(existing.id).await.is_err());
+}
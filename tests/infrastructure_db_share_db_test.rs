@@ -0,0 +1,112 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::{password::EncryptedText, share::{Share, ShareDb, ShareError}};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::token::generate_token;
+use chrono::{Duration, Utc};
+use sqlx::Executor;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE shares")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+fn secret() -> EncryptedText {
+    EncryptedText { nonce: "share_nonce".to_string(), cipher: "share_cipher".to_string() }
+}
+
+fn assert_share_error(err: Box<dyn std::error::Error>, expected: ShareError) {
+    assert_eq!(
+        std::mem::discriminant(err.downcast_ref::<ShareError>().expect("Expected a share error")),
+        std::mem::discriminant(&expected)
+    );
+}
+
+#[tokio::test]
+async fn test_share_is_deleted_after_last_view() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let token = generate_token();
+    let expires_at = Utc::now() + Duration::hours(1);
+
+    db.create_share(Share::new(&token, secret(), 2, expires_at, None)).await.expect("Failed to create share");
+
+    let first = db.view_share(&token, None, Utc::now()).await.expect("First view failed");
+    assert_eq!(first.payload, secret());
+    assert_eq!(first.views_remaining, 1);
+
+    let second = db.view_share(&token, None, Utc::now()).await.expect("Second view failed");
+    assert_eq!(second.views_remaining, 0);
+
+    let err = db.view_share(&token, None, Utc::now()).await.unwrap_err();
+    assert_share_error(err, ShareError::NotFound);
+}
+
+#[tokio::test]
+async fn test_share_access_password() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let token = generate_token();
+    let expires_at = Utc::now() + Duration::hours(1);
+
+    db.create_share(Share::new(&token, secret(), 1, expires_at, Some("open sesame"))).await.expect("Failed to create share");
+
+    let err = db.view_share(&token, None, Utc::now()).await.unwrap_err();
+    assert_share_error(err, ShareError::PasswordRequired);
+
+    let err = db.view_share(&token, Some("guess"), Utc::now()).await.unwrap_err();
+    assert_share_error(err, ShareError::InvalidPassword);
+
+    // Failed attempts do not use up the only view
+    let viewed = db.view_share(&token, Some("open sesame"), Utc::now()).await.expect("View failed");
+    assert_eq!(viewed.payload, secret());
+}
+
+#[tokio::test]
+async fn test_share_expiry() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let expired = generate_token();
+    let live = generate_token();
+    let now = Utc::now();
+
+    db.create_share(Share::new(&expired, secret(), 5, now + Duration::minutes(1), None)).await.expect("Failed to create share");
+    db.create_share(Share::new(&live, secret(), 5, now + Duration::hours(1), None)).await.expect("Failed to create share");
+
+    let err = db.view_share(&expired, None, now + Duration::minutes(2)).await.unwrap_err();
+    assert_share_error(err, ShareError::NotFound);
+
+    assert_eq!(db.delete_expired_shares(now + Duration::minutes(2)).await.unwrap(), 0);
+    assert_eq!(db.delete_expired_shares(now + Duration::hours(2)).await.unwrap(), 1);
+
+    let err = db.view_share(&live, None, now).await.unwrap_err();
+    assert_share_error(err, ShareError::NotFound);
+}
+
+#[tokio::test]
+async fn test_delete_share() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let token = generate_token();
+
+    db.create_share(Share::new(&token, secret(), 3, Utc::now() + Duration::hours(1), None)).await.expect("Failed to create share");
+    db.delete_share(&token).await.expect("Failed to delete share");
+
+    let err = db.view_share(&token, None, Utc::now()).await.unwrap_err();
+    assert_share_error(err, ShareError::NotFound);
+
+    let err = db.delete_share(&token).await.unwrap_err();
+    assert_share_error(err, ShareError::NotFound);
+}
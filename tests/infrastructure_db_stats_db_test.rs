@@ -0,0 +1,61 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::{folder::Folder, item::ItemData, password::Password, password_db::PasswordDb, stats::StatsDb};
+use rust_password_server::bounded_context::domain::user::{ApiToken, User, UserDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::token::generate_token;
+use chrono::{Duration, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute(
+        "TRUNCATE TABLE passwords, folders, tags, attachments, shares, emergency_access, webhooks, audit_events, users, api_tokens CASCADE",
+    )
+    .await
+    .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+#[tokio::test]
+async fn test_vault_stats() {
+    let mut db = get_test_database().await;
+    let now = Utc::now();
+
+    let folder = Folder::new(Uuid::new_v4(), "Work".to_string(), None);
+    db.create_folder(folder.clone()).await.unwrap();
+    let mut login = Password::new(Uuid::new_v4(), "GitHub".to_string(), "nonce".to_string(), "cipher".to_string());
+    login.favorite = true;
+    login.folder_id = Some(folder.id);
+    login.tags = vec!["dev".to_string()];
+    let mut note = Password::new(Uuid::new_v4(), "Wifi".to_string(), "nonce".to_string(), "cipher".to_string());
+    note.item = ItemData::SecureNote;
+    for password in [login, note] {
+        db.save(password).await.unwrap();
+    }
+
+    let user = User::new("alice", now).unwrap();
+    db.create_user(user.clone()).await.unwrap();
+    db.create_token(ApiToken::new(user.id, &generate_token(), None, None, now)).await.unwrap();
+    db.create_token(ApiToken::new(user.id, &generate_token(), None, Some(now - Duration::days(1)), now)).await.unwrap();
+
+    let stats = db.vault_stats(now).await.unwrap();
+    assert_eq!(stats.entries, 2);
+    assert_eq!(stats.entries_by_type.get("login"), Some(&1));
+    assert_eq!(stats.entries_by_type.get("secure_note"), Some(&1));
+    assert_eq!(stats.favorites, 1);
+    assert_eq!(stats.folders, 1);
+    assert_eq!(stats.tags, 1);
+    assert_eq!(stats.attachments, 0);
+    assert_eq!(stats.attachment_bytes, 0);
+    assert_eq!(stats.users, 1);
+    assert_eq!(stats.active_api_tokens, 1);
+}
@@ -0,0 +1,167 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::{password::Password, password_db::PasswordDb, sync::*};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use chrono::{SubsecRound, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, password_tombstones, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+fn entry(service: &str) -> Password {
+    let mut password = Password::new(Uuid::new_v4(), service.to_string(), "nonce".to_string(), "cipher".to_string());
+    password.created_at = password.created_at.trunc_subsecs(6);
+    password.updated_at = password.updated_at.trunc_subsecs(6);
+    password
+}
+
+fn ids(changes: &SyncChanges) -> Vec<Uuid> {
+    changes.entries.iter().map(|entry| entry.id).collect()
+}
+
+fn token(changes: &SyncChanges) -> Option<SyncToken> {
+    SyncToken::decode(&changes.sync_token)
+}
+
+#[tokio::test]
+async fn test_changes_since_token() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+
+    let kept = entry("kept");
+    let changed = entry("changed");
+    let deleted = entry("deleted");
+    for password in [&kept, &changed, &deleted] {
+        db.save(password.clone()).await.unwrap();
+    }
+
+    let first = db.changes_since(None).await.unwrap();
+    assert_eq!(first.entries.len(), 3);
+    assert!(first.tombstones.is_empty());
+
+    let since = token(&first);
+    assert!(since.is_some());
+    let unchanged = db.changes_since(since).await.unwrap();
+    assert!(unchanged.entries.is_empty());
+    assert!(unchanged.tombstones.is_empty());
+
+    db.tag(changed.id, "work").await.unwrap();
+    db.delete(deleted.id).await.unwrap();
+    let created = entry("created");
+    db.save(created.clone()).await.unwrap();
+
+    let delta = db.changes_since(since).await.unwrap();
+    assert_eq!(ids(&delta), vec![changed.id, created.id]);
+    assert_eq!(delta.entries[0].tags, vec!["work".to_string()]);
+    assert_eq!(delta.tombstones.len(), 1);
+    assert_eq!(delta.tombstones[0].id, deleted.id);
+
+    // Recreating a deleted entry replaces its tombstone
+    db.save(deleted.clone()).await.unwrap();
+    let delta = db.changes_since(since).await.unwrap();
+    assert!(delta.tombstones.is_empty());
+    assert!(ids(&delta).contains(&deleted.id));
+}
+
+#[tokio::test]
+async fn test_push_applies_changes() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let now = Utc::now().trunc_subsecs(6);
+
+    let existing = entry("existing");
+    let removed = entry("removed");
+    db.save(existing.clone()).await.unwrap();
+    db.save(removed.clone()).await.unwrap();
+
+    let created = entry("created");
+    let mut edited = existing.clone();
+    edited.service = "edited".to_string();
+    edited.tags = vec!["personal".to_string()];
+
+    let result = db
+        .apply_changes(
+            vec![
+                SyncChange::Upsert { entry: Box::new(created.clone()), base_updated_at: None },
+                SyncChange::Upsert { entry: Box::new(edited.clone()), base_updated_at: Some(existing.updated_at) },
+                SyncChange::Delete { id: removed.id, base_updated_at: removed.updated_at },
+                SyncChange::Delete { id: Uuid::new_v4(), base_updated_at: now },
+            ],
+            now,
+        )
+        .await
+        .unwrap();
+
+    assert!(result.conflicts.is_empty());
+    assert_eq!(result.applied.len(), 4);
+    assert_eq!(result.applied[0], AppliedChange { id: created.id, updated_at: Some(now) });
+    assert_eq!(result.applied[2], AppliedChange { id: removed.id, updated_at: None });
+
+    let stored = db.get_by_id(existing.id).await.unwrap();
+    assert_eq!(stored.service, "edited");
+    assert_eq!(stored.tags, vec!["personal".to_string()]);
+    assert_eq!(stored.updated_at, now);
+    assert_eq!(stored.created_at, existing.created_at);
+    assert_eq!(db.get_by_id(created.id).await.unwrap().service, "created");
+    assert!(db.get_by_id(removed.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_push_reports_conflicts() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let now = Utc::now().trunc_subsecs(6);
+
+    let stale = entry("stale");
+    let gone = entry("gone");
+    db.save(stale.clone()).await.unwrap();
+    db.save(gone.clone()).await.unwrap();
+    db.delete(gone.id).await.unwrap();
+
+    // Someone else edits the entry after this client last synced
+    let mut newer = stale.clone();
+    newer.service = "newer".to_string();
+    let newer_at = now - chrono::Duration::seconds(10);
+    db.apply_changes(vec![SyncChange::Upsert { entry: Box::new(newer), base_updated_at: Some(stale.updated_at) }], newer_at)
+        .await
+        .unwrap();
+
+    let mut offline_edit = stale.clone();
+    offline_edit.service = "offline".to_string();
+    let result = db
+        .apply_changes(
+            vec![
+                SyncChange::Upsert { entry: Box::new(offline_edit), base_updated_at: Some(stale.updated_at) },
+                SyncChange::Delete { id: stale.id, base_updated_at: stale.updated_at },
+                SyncChange::Upsert { entry: Box::new(gone.clone()), base_updated_at: Some(gone.updated_at) },
+                SyncChange::Upsert { entry: Box::new(stale.clone()), base_updated_at: None },
+            ],
+            now,
+        )
+        .await
+        .unwrap();
+
+    assert!(result.applied.is_empty());
+    let reasons: Vec<ConflictReason> = result.conflicts.iter().map(|conflict| conflict.reason).collect();
+    assert_eq!(reasons, vec![ConflictReason::Modified, ConflictReason::Modified, ConflictReason::Deleted, ConflictReason::Exists]);
+    assert_eq!(result.conflicts[0].server_entry.as_ref().unwrap().service, "newer");
+    assert_eq!(result.conflicts[0].server_entry.as_ref().unwrap().updated_at, newer_at);
+    assert!(result.conflicts[2].server_entry.is_none());
+
+    assert_eq!(db.get_by_id(stale.id).await.unwrap().service, "newer");
+}
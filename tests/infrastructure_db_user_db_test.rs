@@ -0,0 +1,62 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::domain::user::*;
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::token::{generate_token, hash_token};
+use chrono::{Duration, SubsecRound, Utc};
+use sqlx::Executor;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE users, api_tokens CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+#[tokio::test]
+async fn test_create_and_get_user() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+
+    let user = User::new("alice", Utc::now().trunc_subsecs(6)).unwrap();
+    db.create_user(user.clone()).await.unwrap();
+    assert_eq!(db.get_user("alice").await.unwrap(), user);
+
+    let err = db.create_user(User::new("alice", Utc::now()).unwrap()).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<UserError>(), Some(UserError::UsernameTaken(_))));
+
+    let err = db.get_user("bob").await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<UserError>(), Some(UserError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_authenticate() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let now = Utc::now().trunc_subsecs(6);
+
+    let user = User::new("alice", now).unwrap();
+    db.create_user(user.clone()).await.unwrap();
+    let token = generate_token();
+    db.create_token(ApiToken::new(user.id, &token, Some("ci".to_string()), Some(now + Duration::days(1)), now)).await.unwrap();
+
+    assert_eq!(db.authenticate(&hash_token(&token), now).await.unwrap(), Some(user));
+    assert_eq!(db.authenticate(&hash_token(&generate_token()), now).await.unwrap(), None);
+    assert_eq!(db.authenticate(&hash_token(&token), now + Duration::days(1)).await.unwrap(), None);
+
+    let last_used_at: Option<chrono::DateTime<Utc>> = sqlx::query_scalar("SELECT last_used_at FROM api_tokens")
+        .fetch_one(db.get_pool())
+        .await
+        .unwrap();
+    assert_eq!(last_used_at, Some(now));
+}
@@ -0,0 +1,74 @@
+use rust_password_server::bounded_context::domain::export::{ExportedEntry, ExportedItem};
+use rust_password_server::bounded_context::infrastructure::export::dotenv::{variable_name, write_dotenv};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn entry(service: &str, secret: &str) -> ExportedEntry {
+    ExportedEntry {
+        id: Uuid::new_v4(),
+        service: service.to_string(),
+        username: None,
+        secret: secret.to_string(),
+        uris: Vec::new(),
+        notes: None,
+        totp: None,
+        fields: Vec::new(),
+        item: ExportedItem::Login,
+        folder: Vec::new(),
+        tags: Vec::new(),
+        favorite: false,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_variable_name() {
+    assert_eq!(variable_name("api.example.com"), "API_EXAMPLE_COM");
+    assert_eq!(variable_name("  Stripe -- live key "), "STRIPE_LIVE_KEY");
+    assert_eq!(variable_name("1password"), "_1PASSWORD");
+    assert_eq!(variable_name("🔑"), "SECRET");
+}
+
+#[test]
+fn test_write_dotenv() {
+    let data = write_dotenv(&[
+        entry("database_url", "postgres://user:p@ss$word@localhost/db"),
+        entry("Database URL", "it's \"quoted\"\nand multiline \\"),
+        entry("database-url", "third"),
+    ]);
+    let lines: Vec<&str> = std::str::from_utf8(&data).unwrap().lines().collect();
+
+    assert!(lines[0].starts_with('#'));
+    assert_eq!(lines[1], "DATABASE_URL='postgres://user:p@ss$word@localhost/db'");
+    assert_eq!(lines[2], r#"DATABASE_URL_2="it's \"quoted\"\nand multiline \\""#);
+    assert_eq!(lines[3], "DATABASE_URL_3='third'");
+    assert_eq!(lines.len(), 4);
+}
+
+#[test]
+fn test_write_dotenv_leaves_nothing_to_expand() {
+    let secrets = ["a'$HOME", "it's `whoami` and $(id)"];
+    let data = write_dotenv(&secrets.map(|secret| entry("token", secret)));
+    let text = std::str::from_utf8(&data).unwrap();
+
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[1], r#"TOKEN="a'\$HOME""#);
+    assert_eq!(lines[2], r#"TOKEN_2="it's "'`'"whoami"'`'" and \$(id)""#);
+
+    let parsed: Vec<(String, String)> = dotenvy::from_read_iter(text.as_bytes()).map(Result::unwrap).collect();
+    assert_eq!(parsed, vec![("TOKEN".to_string(), secrets[0].to_string()), ("TOKEN_2".to_string(), secrets[1].to_string())]);
+
+    #[cfg(unix)]
+    {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &data).unwrap();
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(r#". "$0" && printf '%s\n%s' "$TOKEN" "$TOKEN_2""#)
+            .arg(file.path())
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), secrets.join("\n"));
+    }
+}
@@ -0,0 +1,68 @@
+use rust_password_server::bounded_context::domain::export::{ExportedEntry, ExportedField, ExportedItem};
+use rust_password_server::bounded_context::infrastructure::export::keepass::write_keepass_csv;
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+fn entry(service: &str, secret: &str, item: ExportedItem) -> ExportedEntry {
+    let created_at = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+    ExportedEntry {
+        id: Uuid::new_v4(),
+        service: service.to_string(),
+        username: None,
+        secret: secret.to_string(),
+        uris: Vec::new(),
+        notes: None,
+        totp: None,
+        fields: Vec::new(),
+        item,
+        folder: Vec::new(),
+        tags: Vec::new(),
+        favorite: false,
+        created_at,
+        updated_at: created_at,
+    }
+}
+
+fn rows(data: &[u8]) -> Vec<Vec<String>> {
+    csv::Reader::from_reader(data)
+        .records()
+        .map(|record| record.unwrap().iter().map(str::to_string).collect())
+        .collect()
+}
+
+#[test]
+fn test_write_keepass_csv() {
+    let mut login = entry("GitHub", "hunter2, \"quoted\"", ExportedItem::Login);
+    login.username = Some("octocat".to_string());
+    login.uris = vec!["https://github.com".to_string(), "https://gist.github.com".to_string()];
+    login.notes = Some("work account".to_string());
+    login.totp = Some("otpauth://totp/GitHub?secret=JBSWY3DPEHPK3PXP".to_string());
+    login.folder = vec!["Work".to_string(), "Code".to_string()];
+    login.fields = vec![ExportedField::Hidden { name: "recovery".to_string(), value: "abcd".to_string() }];
+    login.tags = vec!["dev".to_string(), "sso".to_string()];
+    let note = entry("Wifi", "correct horse", ExportedItem::SecureNote);
+
+    let data = write_keepass_csv(&[login, note]);
+    let header = String::from_utf8(data.clone()).unwrap();
+    assert!(header.starts_with("\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\",\"TOTP\",\"Icon\",\"Last Modified\",\"Created\"\n"));
+
+    let rows = rows(&data);
+    assert_eq!(
+        rows[0],
+        vec![
+            "Root/Work/Code",
+            "GitHub",
+            "octocat",
+            "hunter2, \"quoted\"",
+            "https://github.com",
+            "work account\n\nURL: https://gist.github.com\nrecovery: abcd\nTags: dev, sso",
+            "otpauth://totp/GitHub?secret=JBSWY3DPEHPK3PXP",
+            "0",
+            "2024-01-02T03:04:05Z",
+            "2024-01-02T03:04:05Z",
+        ]
+    );
+    assert_eq!(rows[1][0], "Root");
+    assert_eq!(rows[1][3], "");
+    assert_eq!(rows[1][5], "correct horse");
+}
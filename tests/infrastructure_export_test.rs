@@ -0,0 +1,69 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::export::collect_export;
+use rust_password_server::bounded_context::domain::{export::ExportError, folder::Folder, import::ImportedEntry, password_db::PasswordDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::encryption::generate_key;
+use chrono::{SubsecRound, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+async fn save(database: &mut Database, key: &str, service: &str, folder_id: Option<Uuid>, tags: &[&str]) {
+    let entry = ImportedEntry {
+        service: service.to_string(),
+        secret: format!("{} secret", service),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        ..Default::default()
+    };
+    database.save(entry.encrypt(key, None, Uuid::new_v4(), folder_id, Utc::now().trunc_subsecs(6))).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_collect_export() {
+    let _lock = DB_LOCK.lock().await;
+    let mut database = get_test_database().await;
+    let key = generate_key();
+
+    let work = Folder::new(Uuid::new_v4(), "Work".to_string(), None);
+    let servers = Folder::new(Uuid::new_v4(), "Servers".to_string(), Some(work.id));
+    database.create_folder(work.clone()).await.unwrap();
+    database.create_folder(servers.clone()).await.unwrap();
+    save(&mut database, &key, "Jira", Some(work.id), &["ci"]).await;
+    save(&mut database, &key, "AWS", Some(servers.id), &[]).await;
+    save(&mut database, &key, "Bank", None, &["ci"]).await;
+
+    let everything = collect_export(&mut database, None, None, &key).await.unwrap();
+    let services: Vec<&str> = everything.iter().map(|entry| entry.service.as_str()).collect();
+    assert_eq!(services, vec!["Bank", "Jira", "AWS"]);
+    assert_eq!(everything[2].folder, vec!["Work", "Servers"]);
+    assert_eq!(everything[2].secret, "AWS secret");
+
+    let work_entries = collect_export(&mut database, Some("work"), None, &key).await.unwrap();
+    assert_eq!(work_entries.len(), 2);
+
+    let tagged = collect_export(&mut database, Some("Work"), Some(" CI "), &key).await.unwrap();
+    assert_eq!(tagged.iter().map(|entry| entry.service.as_str()).collect::<Vec<_>>(), vec!["Jira"]);
+
+    let err = collect_export(&mut database, Some("Work/Missing"), None, &key).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<ExportError>(), Some(ExportError::FolderNotFound(_))));
+
+    let err = collect_export(&mut database, None, None, &generate_key()).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<ExportError>(), Some(ExportError::DecryptionFailed(_))));
+}
@@ -0,0 +1,148 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::http::configure_routes::configure_routes;
+use rust_password_server::bounded_context::infrastructure::db::change_feed::ChangeFeed;
+use rust_password_server::bounded_context::domain::{audit::*, password::Password, password_db::PasswordDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use sqlx::Executor;
+use std::net::{IpAddr, Ipv4Addr};
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+async fn get_test_database(trusted_proxies: Vec<IpAddr>) -> Database {
+    let mut config = app_config::load_config();
+    config.trusted_proxies = trusted_proxies;
+    let database = Database::new(&config.test_db_url.clone(), 2, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE audit_events RESTART IDENTITY")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+async fn serve(database: Database) -> String {
+    let app = configure_routes(database, None, ChangeFeed::new(16));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
+    });
+
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_requests_are_recorded_in_the_audit_log() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(vec![LOCALHOST]).await;
+    let base = serve(db.clone()).await;
+    let client = reqwest::Client::new();
+
+    let read_id = Uuid::new_v4();
+    let response = client
+        .get(format!("{}/password?id={}", base, read_id))
+        .header("x-actor", "alice")
+        .header("x-request-id", "req-1")
+        .header("user-agent", "audit-client")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-request-id"], "req-1");
+
+    let deleted_id = Uuid::new_v4();
+    db.save(Password::new(deleted_id, "audited".to_string(), "nonce".to_string(), "cipher".to_string())).await.unwrap();
+    let response = client
+        .post(format!("{}/password/delete", base))
+        .header("x-forwarded-for", "198.51.100.1, 203.0.113.7")
+        .json(&serde_json::json!({ "id": deleted_id.to_string() }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let generated_request_id = response.headers()["x-request-id"].to_str().unwrap().to_string();
+
+    let events = db.list_audit_chain().await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(verify_chain(&events), Ok(()));
+
+    assert_eq!(events[0].actor, "alice");
+    assert_eq!(events[0].action, "GET /password");
+    assert_eq!(events[0].entry_id, Some(read_id));
+    assert_eq!(events[0].request_id, "req-1");
+    assert_eq!(events[0].ip.as_deref(), Some("127.0.0.1"));
+    assert_eq!(events[0].user_agent.as_deref(), Some("audit-client"));
+    assert_eq!(events[0].outcome, "failure");
+
+    assert_eq!(events[1].actor, "anonymous");
+    assert_eq!(events[1].action, "POST /password/delete");
+    assert_eq!(events[1].entry_id, Some(deleted_id));
+    assert_eq!(events[1].request_id, generated_request_id);
+    assert_eq!(events[1].ip.as_deref(), Some("203.0.113.7"));
+    assert_eq!(events[1].outcome, "success");
+
+    let page: serde_json::Value = client
+        .get(format!("{}/audit?actor=alice&page_size=5", base))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page["total_count"], 1);
+    assert_eq!(page["items"][0]["request_id"], "req-1");
+
+    let response = client.get(format!("{}/audit?page=0", base)).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_headers_from_untrusted_peers_are_ignored() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(Vec::new()).await;
+    let base = serve(db.clone()).await;
+
+    reqwest::Client::new()
+        .get(format!("{}/status", base))
+        .header("x-actor", "alice")
+        .header("x-forwarded-for", "203.0.113.7")
+        .send()
+        .await
+        .unwrap();
+
+    let events = db.list_audit_chain().await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].actor, "anonymous");
+    assert_eq!(events[0].ip.as_deref(), Some("127.0.0.1"));
+}
+
+#[tokio::test]
+async fn test_unrecorded_requests_fail() {
+    let _lock = DB_LOCK.lock().await;
+    let db = get_test_database(Vec::new()).await;
+    db.get_pool().execute("DROP SCHEMA IF EXISTS audit_unavailable CASCADE; CREATE SCHEMA audit_unavailable;").await.unwrap();
+
+    // Connections that look up tables in an empty schema cannot append to the audit log
+    let config = app_config::load_config();
+    let url = format!("{}?options=-c%20search_path%3Daudit_unavailable", config.test_db_url);
+    let unavailable = Database::new(&url, 1, config).await.expect("Failed to create test DB");
+    let base = serve(unavailable).await;
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/status", base))
+        .header("x-request-id", "req-unrecorded")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response.headers()["x-request-id"], "req-unrecorded");
+
+    db.get_pool().execute("DROP SCHEMA audit_unavailable CASCADE").await.unwrap();
+}
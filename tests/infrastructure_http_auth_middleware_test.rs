@@ -0,0 +1,113 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::http::configure_routes::configure_routes;
+use rust_password_server::bounded_context::infrastructure::db::change_feed::ChangeFeed;
+use rust_password_server::bounded_context::domain::{audit::AuditDb, user::{ApiToken, User, UserDb}, emergency_access::{EmergencyAccess, EmergencyAccessDb}};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::token::generate_token;
+use chrono::{Duration, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database(require_api_token: bool) -> Database {
+    let mut config = app_config::load_config();
+    config.require_api_token = require_api_token;
+    let database = Database::new(&config.test_db_url.clone(), 2, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE audit_events, users, api_tokens, emergency_access RESTART IDENTITY CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+async fn serve(database: Database) -> String {
+    let app = configure_routes(database, None, ChangeFeed::new(16));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
+    });
+
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_requests_require_an_api_token() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(true).await;
+    let now = Utc::now();
+    let user = User::new("alice", now).unwrap();
+    db.create_user(user.clone()).await.unwrap();
+    let token = generate_token();
+    let expired = generate_token();
+    db.create_token(ApiToken::new(user.id, &token, None, None, now)).await.unwrap();
+    db.create_token(ApiToken::new(user.id, &expired, None, Some(now - Duration::days(1)), now)).await.unwrap();
+
+    let base = serve(db.clone()).await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(format!("{}/tag", base)).header("x-actor", "mallory").send().await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    let response = client.get(format!("{}/tag", base)).bearer_auth(&expired).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    let response = client.get(format!("{}/tag", base)).bearer_auth(&token).header("x-actor", "mallory").send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client.get(format!("{}/status", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let actors: Vec<String> = db.list_audit_chain().await.unwrap().into_iter().map(|event| event.actor).collect();
+    assert_eq!(actors, vec!["anonymous", "anonymous", "alice", "anonymous"]);
+}
+
+#[tokio::test]
+async fn test_emergency_decisions_require_an_api_token() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database(false).await;
+    let now = Utc::now();
+    let user = User::new("owner", now).unwrap();
+    db.create_user(user.clone()).await.unwrap();
+    let api_token = generate_token();
+    db.create_token(ApiToken::new(user.id, &api_token, None, None, now)).await.unwrap();
+
+    let contact_token = generate_token();
+    let access = EmergencyAccess::new(Uuid::new_v4(), "alex@example.com".to_string(), &contact_token, 48);
+    db.create_emergency_access(access.clone()).await.unwrap();
+
+    let base = serve(db.clone()).await;
+    let client = reqwest::Client::new();
+    let contact_body = serde_json::json!({ "token": contact_token });
+    let owner_body = serde_json::json!({ "id": access.id });
+
+    let accepted: serde_json::Value = client.post(format!("{}/emergency/accept", base)).json(&contact_body).send().await.unwrap().json().await.unwrap();
+    assert_eq!(accepted["status"], "accepted");
+    assert!(accepted.get("id").is_none());
+
+    let requested: serde_json::Value = client.post(format!("{}/emergency/request", base)).json(&contact_body).send().await.unwrap().json().await.unwrap();
+    assert_eq!(requested["status"], "requested");
+    assert!(requested.get("id").is_none());
+    assert!(requested["grants_at"].is_string());
+
+    for action in ["approve", "reject", "revoke"] {
+        let response = client.post(format!("{}/emergency/{}", base, action)).json(&owner_body).send().await.unwrap();
+        assert_eq!(response.status(), 401);
+    }
+
+    let response = client.post(format!("{}/emergency/vault", base)).json(&contact_body).send().await.unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = client.post(format!("{}/emergency/approve", base)).bearer_auth(&api_token).json(&owner_body).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client.post(format!("{}/emergency/vault", base)).json(&contact_body).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::http::configure_routes::configure_routes;
+use rust_password_server::bounded_context::infrastructure::db::change_feed::ChangeFeed;
+use rust_password_server::bounded_context::domain::{password::Password, password_db::PasswordDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use sqlx::Executor;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_uploads_outlast_the_request_timeout() {
+    let mut config = app_config::load_config();
+    config.graceful_shutdown_time = 1;
+    config.attachment_upload_timeout = 30;
+    config.attachment_dir = None;
+    config.require_api_token = false;
+    let mut database = Database::new(&config.test_db_url.clone(), 2, config).await.expect("Failed to create test DB");
+    database.get_pool().execute(include_str!("../docker/init.sql")).await.expect("Failed to setup the database");
+
+    let password = Password::new(Uuid::new_v4(), "uploads".to_string(), "nonce".to_string(), "cipher".to_string());
+    database.save(password.clone()).await.unwrap();
+
+    let app = configure_routes(database, None, ChangeFeed::new(16));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
+    });
+
+    // The body arrives in two parts, further apart than the request timeout
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let head = format!(
+        "POST /attachment/upload?password_id={}&file_name=slow.txt HTTP/1.1\r\nHost: {}\r\nContent-Length: 10\r\nConnection: close\r\n\r\nfirst",
+        password.id, addr
+    );
+    stream.write_all(head.as_bytes()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    stream.write_all(b" half").await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "Unexpected response: {}", response);
+    assert!(response.contains("\"size\":10"));
+}
@@ -0,0 +1,95 @@
+use rust_password_server::bounded_context::infrastructure::import::bitwarden::parse_bitwarden_json;
+use rust_password_server::bounded_context::domain::import::{ImportError, ImportedField, ImportedItem};
+
+const EXPORT: &str = r#"{
+  "encrypted": false,
+  "folders": [
+    { "id": "f1", "name": "Work/Infra" }
+  ],
+  "items": [
+    {
+      "id": "i1", "folderId": "f1", "type": 1, "name": "AWS", "notes": "root account", "favorite": true,
+      "fields": [
+        { "name": "account id", "value": "123456789012", "type": 0 },
+        { "name": "mfa backup", "value": "abcd", "type": 1 },
+        { "name": "shared", "value": "true", "type": 2 },
+        { "name": "linked", "value": null, "type": 3, "linkedId": 100 }
+      ],
+      "login": { "uris": [{ "match": null, "uri": "https://aws.amazon.com" }], "username": "admin", "password": "s3cret", "totp": "JBSWY3DPEHPK3PXP" },
+      "creationDate": "2023-01-02T03:04:05.000Z", "revisionDate": "2023-02-03T04:05:06.000Z"
+    },
+    { "id": "i2", "folderId": null, "type": 2, "name": "Wifi", "notes": "guest password is on the fridge", "secureNote": { "type": 0 } },
+    {
+      "id": "i3", "type": 3, "name": "Visa",
+      "card": { "cardholderName": "Ada Lovelace", "brand": "Visa", "number": "4111111111111234", "expMonth": "4", "expYear": "2030", "code": "123" }
+    },
+    {
+      "id": "i4", "type": 4, "name": "Passport",
+      "identity": { "firstName": "Ada", "lastName": "Lovelace", "passportNumber": "X1234567", "city": "London", "country": "UK", "email": "ada@example.com" }
+    },
+    { "id": "i5", "type": 1, "name": "", "login": { "uris": [], "username": "nobody", "password": "x" } },
+    { "id": "i6", "type": 9, "name": "Future" }
+  ]
+}"#;
+
+#[test]
+fn test_parse_bitwarden_json() {
+    let parsed = parse_bitwarden_json(EXPORT.as_bytes()).expect("Failed to parse export");
+
+    assert_eq!(parsed.entries.len(), 4);
+    let login = &parsed.entries[0];
+    assert_eq!(login.record, 1);
+    assert_eq!(login.service, "AWS");
+    assert_eq!(login.username.as_deref(), Some("admin"));
+    assert_eq!(login.secret, "s3cret");
+    assert_eq!(login.uris, vec!["https://aws.amazon.com".to_string()]);
+    assert_eq!(login.notes.as_deref(), Some("root account"));
+    assert_eq!(login.totp.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+    assert_eq!(login.folder, vec!["Work".to_string(), "Infra".to_string()]);
+    assert!(login.favorite);
+    assert_eq!(login.fields.len(), 3);
+    assert_eq!(login.fields[1], ImportedField::Hidden { name: "mfa backup".to_string(), value: "abcd".to_string() });
+    assert_eq!(login.fields[2], ImportedField::Boolean { name: "shared".to_string(), value: true });
+    assert!(login.created_at.is_some() && login.updated_at > login.created_at);
+
+    let note = &parsed.entries[1];
+    assert_eq!(note.item, ImportedItem::SecureNote);
+    assert_eq!(note.secret, "guest password is on the fridge");
+    assert!(note.notes.is_none());
+
+    let card = &parsed.entries[2];
+    assert_eq!(card.secret, "4111111111111234");
+    assert_eq!(
+        card.item,
+        ImportedItem::Card {
+            cardholder_name: Some("Ada Lovelace".to_string()),
+            expiry: Some("04/2030".to_string()),
+            security_code: Some("123".to_string()),
+            brand: Some("Visa".to_string()),
+        }
+    );
+
+    let identity = &parsed.entries[3];
+    assert_eq!(identity.secret, "X1234567");
+    match &identity.item {
+        ImportedItem::Identity { full_name, address, email, .. } => {
+            assert_eq!(full_name.as_deref(), Some("Ada Lovelace"));
+            assert_eq!(address.as_deref(), Some("London, UK"));
+            assert_eq!(email.as_deref(), Some("ada@example.com"));
+        }
+        other => panic!("Expected an identity, got {:?}", other),
+    }
+
+    assert_eq!(parsed.skipped.len(), 2);
+    assert_eq!(parsed.skipped[0].record, 5);
+    assert_eq!(parsed.skipped[0].reason, "Missing name");
+    assert_eq!(parsed.skipped[1].record, 6);
+}
+
+#[test]
+fn test_parse_encrypted_bitwarden_json() {
+    let result = parse_bitwarden_json(br#"{ "encrypted": true, "items": [] }"#);
+
+    assert!(matches!(result, Err(ImportError::InvalidFile(_))));
+    assert!(matches!(parse_bitwarden_json(b"not json"), Err(ImportError::InvalidFile(_))));
+}
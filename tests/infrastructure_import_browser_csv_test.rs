@@ -0,0 +1,48 @@
+use rust_password_server::bounded_context::infrastructure::import::browser_csv::{parse_chrome_csv, parse_firefox_csv};
+
+#[test]
+fn test_parse_chrome_csv() {
+    let export = "name,url,username,password,note\n\
+        GitHub,https://github.com/login,octocat,hunter2,\n\
+        ,https://www.example.com/,me,pa55,\"multi\nline\"\n\
+        Empty,https://empty.example.com,me,,\n\
+        ,,,orphan,\n";
+
+    let parsed = parse_chrome_csv(export.as_bytes()).expect("Failed to parse export");
+
+    assert_eq!(parsed.entries.len(), 2);
+    assert_eq!(parsed.entries[0].record, 2);
+    assert_eq!(parsed.entries[0].service, "GitHub");
+    assert_eq!(parsed.entries[0].username.as_deref(), Some("octocat"));
+    assert_eq!(parsed.entries[0].secret, "hunter2");
+    assert!(parsed.entries[0].notes.is_none());
+    assert_eq!(parsed.entries[1].service, "example.com");
+    assert_eq!(parsed.entries[1].notes.as_deref(), Some("multi\nline"));
+
+    let skipped: Vec<_> = parsed.skipped.iter().map(|skipped| (skipped.record, skipped.reason.as_str())).collect();
+    assert_eq!(skipped, vec![(5, "Missing password"), (6, "Missing name and URL")]);
+}
+
+#[test]
+fn test_parse_firefox_csv() {
+    let export = "\u{feff}\"url\",\"username\",\"password\",\"httpRealm\",\"formActionOrigin\",\"guid\",\"timeCreated\",\"timeLastUsed\",\"timePasswordChanged\"\n\
+        \"https://accounts.example.com\",\"me\",\"secret\",,\"https://accounts.example.com\",\"{1}\",\"1672628645000\",\"1672628645000\",\"1675397106000\"\n\
+        \"chrome://FirefoxAccounts\",\"sync\",\"token\",,,\"{2}\",\"1672628645000\",\"1672628645000\",\"1672628645000\"\n";
+
+    let parsed = parse_firefox_csv(export.as_bytes()).expect("Failed to parse export");
+
+    assert_eq!(parsed.entries.len(), 1);
+    let entry = &parsed.entries[0];
+    assert_eq!(entry.service, "accounts.example.com");
+    assert_eq!(entry.secret, "secret");
+    assert_eq!(entry.created_at.map(|time| time.timestamp()), Some(1672628645));
+    assert_eq!(entry.updated_at.map(|time| time.timestamp()), Some(1675397106));
+    assert_eq!(parsed.skipped.len(), 1);
+    assert_eq!(parsed.skipped[0].record, 3);
+}
+
+#[test]
+fn test_parse_csv_without_expected_columns() {
+    assert!(parse_chrome_csv(b"title,secret\nfoo,bar\n").is_err());
+    assert!(parse_firefox_csv(b"name,username\nfoo,bar\n").is_err());
+}
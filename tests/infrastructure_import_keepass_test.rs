@@ -0,0 +1,97 @@
+use rust_password_server::bounded_context::infrastructure::import::keepass::parse_keepass_xml;
+use rust_password_server::bounded_context::domain::import::ImportedField;
+
+const EXPORT: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="yes"?>
+<KeePassFile>
+  <Meta>
+    <Generator>KeePass</Generator>
+    <RecycleBinUUID>cmVjeWNsZQ==</RecycleBinUUID>
+  </Meta>
+  <Root>
+    <Group>
+      <UUID>cm9vdA==</UUID>
+      <Name>Database</Name>
+      <Entry>
+        <UUID>ZW50cnkx</UUID>
+        <Tags>work;ops</Tags>
+        <Times>
+          <CreationTime>2023-01-02T03:04:05Z</CreationTime>
+          <LastModificationTime>2023-02-03T04:05:06Z</LastModificationTime>
+        </Times>
+        <String><Key>Title</Key><Value>Router</Value></String>
+        <String><Key>UserName</Key><Value>admin</Value></String>
+        <String><Key>Password</Key><Value ProtectInMemory="True">r0uter</Value></String>
+        <String><Key>URL</Key><Value>http://192.168.1.1</Value></String>
+        <String><Key>Notes</Key><Value>in the hallway</Value></String>
+        <String><Key>otp</Key><Value>otpauth://totp/router?secret=JBSWY3DPEHPK3PXP</Value></String>
+        <String><Key>Serial</Key><Value>SN-42</Value></String>
+        <String><Key>Recovery</Key><Value ProtectInMemory="True">abc-def</Value></String>
+        <History>
+          <Entry>
+            <String><Key>Title</Key><Value>Old router</Value></String>
+          </Entry>
+        </History>
+      </Entry>
+      <Group>
+        <UUID>ZW1haWw=</UUID>
+        <Name>Email</Name>
+        <Entry>
+          <String><Key>Title</Key><Value></Value></String>
+          <String><Key>UserName</Key><Value>me@example.com</Value></String>
+          <String><Key>Password</Key><Value>mail</Value></String>
+          <String><Key>URL</Key><Value>https://www.mail.example.com/login</Value></String>
+        </Entry>
+        <Entry>
+          <String><Key>Title</Key><Value></Value></String>
+          <String><Key>Password</Key><Value>orphan</Value></String>
+        </Entry>
+      </Group>
+      <Group>
+        <UUID>cmVjeWNsZQ==</UUID>
+        <Name>Recycle Bin</Name>
+        <Entry>
+          <String><Key>Title</Key><Value>Deleted</Value></String>
+        </Entry>
+      </Group>
+    </Group>
+  </Root>
+</KeePassFile>"#;
+
+#[test]
+fn test_parse_keepass_xml() {
+    let parsed = parse_keepass_xml(EXPORT.as_bytes()).expect("Failed to parse export");
+
+    assert_eq!(parsed.entries.len(), 2);
+    let router = &parsed.entries[0];
+    assert_eq!(router.record, 1);
+    assert_eq!(router.service, "Router");
+    assert_eq!(router.username.as_deref(), Some("admin"));
+    assert_eq!(router.secret, "r0uter");
+    assert_eq!(router.uris, vec!["http://192.168.1.1".to_string()]);
+    assert_eq!(router.notes.as_deref(), Some("in the hallway"));
+    assert!(router.totp.as_deref().is_some_and(|totp| totp.starts_with("otpauth://")));
+    assert_eq!(router.tags, vec!["work".to_string(), "ops".to_string()]);
+    assert!(router.folder.is_empty());
+    assert_eq!(
+        router.fields,
+        vec![
+            ImportedField::Text { name: "Serial".to_string(), value: "SN-42".to_string() },
+            ImportedField::Hidden { name: "Recovery".to_string(), value: "abc-def".to_string() },
+        ]
+    );
+    assert!(router.created_at.is_some() && router.updated_at > router.created_at);
+
+    let mail = &parsed.entries[1];
+    assert_eq!(mail.record, 2);
+    assert_eq!(mail.service, "mail.example.com");
+    assert_eq!(mail.folder, vec!["Email".to_string()]);
+
+    assert_eq!(parsed.skipped.len(), 1);
+    assert_eq!(parsed.skipped[0].record, 3);
+}
+
+#[test]
+fn test_parse_invalid_keepass_xml() {
+    assert!(parse_keepass_xml(b"<Database></Database>").is_err());
+    assert!(parse_keepass_xml(b"<KeePassFile><Root>").is_err());
+}
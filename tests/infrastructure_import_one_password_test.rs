@@ -0,0 +1,133 @@
+use rust_password_server::bounded_context::infrastructure::import::one_password::{parse_1pux, parse_1password_csv};
+use rust_password_server::bounded_context::domain::import::{ImportedField, ImportedItem};
+use std::io::Write;
+
+const EXPORT_DATA: &str = r#"{
+  "accounts": [{
+    "attrs": { "accountName": "Team" },
+    "vaults": [{
+      "attrs": { "uuid": "v1", "name": "Shared" },
+      "items": [
+        {
+          "uuid": "a", "favIndex": 1, "createdAt": 1672628645, "updatedAt": 1675397106, "state": "active", "categoryUuid": "001",
+          "details": {
+            "loginFields": [
+              { "value": "octocat", "name": "username", "fieldType": "T", "designation": "username" },
+              { "value": "hunter2", "name": "password", "fieldType": "P", "designation": "password" }
+            ],
+            "notesPlain": "main account",
+            "sections": [{ "title": "", "fields": [
+              { "title": "one-time password", "id": "TOTP_1", "value": { "totp": "otpauth://totp/github?secret=JBSWY3DPEHPK3PXP" } },
+              { "title": "recovery code", "id": "rc", "value": { "concealed": "1111-2222" } },
+              { "title": "team", "id": "t", "value": { "string": "platform" } }
+            ] }]
+          },
+          "overview": { "title": "GitHub", "url": "https://github.com", "urls": [{ "label": "", "url": "https://github.com" }], "tags": ["dev"] }
+        },
+        {
+          "uuid": "b", "favIndex": 0, "createdAt": 1672628645, "updatedAt": 1672628645, "state": "active", "categoryUuid": "002",
+          "details": { "sections": [{ "title": "", "fields": [
+            { "title": "cardholder name", "id": "cardholder", "value": { "string": "Ada Lovelace" } },
+            { "title": "type", "id": "type", "value": { "creditCardType": "visa" } },
+            { "title": "number", "id": "ccnum", "value": { "creditCardNumber": "4111111111111234" } },
+            { "title": "verification number", "id": "cvv", "value": { "concealed": "123" } },
+            { "title": "expiry date", "id": "expiry", "value": { "monthYear": 203004 } }
+          ] }] },
+          "overview": { "title": "Visa" }
+        },
+        {
+          "uuid": "c", "createdAt": 1672628645, "updatedAt": 1672628645, "state": "archived", "categoryUuid": "001",
+          "details": {}, "overview": { "title": "Old" }
+        },
+        {
+          "uuid": "d", "createdAt": 1672628645, "updatedAt": 1672628645, "state": "active", "categoryUuid": "110",
+          "details": { "notesPlain": "prod box", "sections": [{ "title": "", "fields": [
+            { "title": "hostname", "id": "h", "value": { "string": "db.internal" } }
+          ] }] },
+          "overview": { "title": "Database server" }
+        }
+      ]
+    }]
+  }]
+}"#;
+
+fn onepux(export_data: &str) -> Vec<u8> {
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    archive.start_file("export.attributes", zip::write::SimpleFileOptions::default()).unwrap();
+    archive.write_all(br#"{ "version": 3 }"#).unwrap();
+    archive.start_file("export.data", zip::write::SimpleFileOptions::default()).unwrap();
+    archive.write_all(export_data.as_bytes()).unwrap();
+
+    archive.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_parse_1pux() {
+    let parsed = parse_1pux(&onepux(EXPORT_DATA)).expect("Failed to parse export");
+
+    assert_eq!(parsed.entries.len(), 3);
+    let login = &parsed.entries[0];
+    assert_eq!(login.service, "GitHub");
+    assert_eq!(login.username.as_deref(), Some("octocat"));
+    assert_eq!(login.secret, "hunter2");
+    assert_eq!(login.uris, vec!["https://github.com".to_string()]);
+    assert_eq!(login.notes.as_deref(), Some("main account"));
+    assert!(login.totp.is_some());
+    assert_eq!(login.folder, vec!["Shared".to_string()]);
+    assert_eq!(login.tags, vec!["dev".to_string()]);
+    assert!(login.favorite);
+    assert_eq!(
+        login.fields,
+        vec![
+            ImportedField::Hidden { name: "recovery code".to_string(), value: "1111-2222".to_string() },
+            ImportedField::Text { name: "team".to_string(), value: "platform".to_string() },
+        ]
+    );
+    assert_eq!(login.created_at.map(|time| time.timestamp()), Some(1672628645));
+
+    let card = &parsed.entries[1];
+    assert_eq!(card.secret, "4111111111111234");
+    assert_eq!(
+        card.item,
+        ImportedItem::Card {
+            cardholder_name: Some("Ada Lovelace".to_string()),
+            expiry: Some("04/2030".to_string()),
+            security_code: Some("123".to_string()),
+            brand: Some("visa".to_string()),
+        }
+    );
+
+    let server = &parsed.entries[2];
+    assert_eq!(server.record, 4);
+    assert_eq!(server.item, ImportedItem::SecureNote);
+    assert_eq!(server.secret, "prod box");
+    assert_eq!(server.fields, vec![ImportedField::Text { name: "hostname".to_string(), value: "db.internal".to_string() }]);
+
+    assert_eq!(parsed.skipped.len(), 1);
+    assert_eq!(parsed.skipped[0].record, 3);
+    assert_eq!(parsed.skipped[0].reason, "Archived item");
+}
+
+#[test]
+fn test_parse_invalid_1pux() {
+    assert!(parse_1pux(b"not a zip").is_err());
+}
+
+#[test]
+fn test_parse_1password_csv() {
+    let export = "Title,Url,Username,Password,OTPAuth,Favorite,Archived,Tags,Notes\n\
+        GitHub,https://github.com,octocat,hunter2,,true,false,dev;work,\n\
+        Old,https://old.example.com,me,pw,,false,true,,\n\
+        Door code,,,,,false,false,,1234#\n";
+
+    let parsed = parse_1password_csv(export.as_bytes()).expect("Failed to parse export");
+
+    assert_eq!(parsed.entries.len(), 2);
+    assert_eq!(parsed.entries[0].service, "GitHub");
+    assert!(parsed.entries[0].favorite);
+    assert_eq!(parsed.entries[0].tags, vec!["dev".to_string(), "work".to_string()]);
+    assert_eq!(parsed.entries[1].item, ImportedItem::SecureNote);
+    assert_eq!(parsed.entries[1].secret, "1234#");
+    assert_eq!(parsed.skipped.len(), 1);
+    assert_eq!(parsed.skipped[0].record, 3);
+}
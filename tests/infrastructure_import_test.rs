@@ -0,0 +1,79 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::import::{import_export, parse_export};
+use rust_password_server::bounded_context::domain::{folder::Folder, import::ImportFormat, password::Password, password_db::PasswordDb};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::encryption::{decrypt, fingerprint, generate_key};
+use chrono::{SubsecRound, Utc};
+use sqlx::Executor;
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+const EXPORT: &str = r#"{
+  "encrypted": false,
+  "folders": [
+    { "id": "f1", "name": "Work/Infra" },
+    { "id": "f2", "name": "Work" }
+  ],
+  "items": [
+    { "type": 1, "name": "AWS", "folderId": "f1", "login": { "uris": [], "username": "admin", "password": "s3cret" } },
+    { "type": 1, "name": "Jira", "folderId": "f2", "login": { "uris": [], "username": "me", "password": "j1ra" } },
+    { "type": 1, "name": "aws", "folderId": "f2", "login": { "uris": [], "username": "Admin", "password": "other" } },
+    { "type": 1, "name": "GitHub", "login": { "uris": [], "username": "octocat", "password": "new" } },
+    { "type": 7, "name": "Unknown" }
+  ]
+}"#;
+
+#[tokio::test]
+async fn test_import_export() {
+    let _lock = DB_LOCK.lock().await;
+    let mut database = get_test_database().await;
+    let key = generate_key();
+
+    let work = Folder::new(Uuid::new_v4(), "work".to_string(), None);
+    database.create_folder(work.clone()).await.unwrap();
+    let mut existing = Password::new(Uuid::new_v4(), "GitHub".to_string(), "nonce".to_string(), "cipher".to_string());
+    existing.username = Some("octocat".to_string());
+    database.save(existing).await.unwrap();
+
+    let export = parse_export(ImportFormat::BitwardenJson, EXPORT.as_bytes()).expect("Failed to parse export");
+    let fingerprint_key = generate_key();
+    let report = import_export(&mut database, export, &key, Some(&fingerprint_key), Utc::now().trunc_subsecs(6))
+        .await
+        .expect("Failed to import export");
+
+    assert_eq!(report.imported, 2);
+    assert_eq!(report.folders_created, 1);
+    assert_eq!(report.duplicates.iter().map(|duplicate| duplicate.record).collect::<Vec<_>>(), vec![3, 4]);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].record, 5);
+
+    let folders = database.list_folders().await.unwrap();
+    let infra = folders.iter().find(|folder| folder.name == "Infra").expect("Folder was not created");
+    assert_eq!(infra.parent_id, Some(work.id));
+
+    let entries = database.list_all().await.unwrap();
+    let aws = entries.iter().find(|entry| entry.service == "AWS").expect("Entry was not imported");
+    assert_eq!(aws.folder_id, Some(infra.id));
+    assert_eq!(decrypt(&key, &aws.nonce, &aws.cipher), "s3cret");
+    assert_eq!(aws.fingerprint, Some(fingerprint(&fingerprint_key, "s3cret")));
+    assert_eq!(aws.strength, None);
+    let jira = entries.iter().find(|entry| entry.service == "Jira").expect("Entry was not imported");
+    assert_eq!(jira.folder_id, Some(work.id));
+}
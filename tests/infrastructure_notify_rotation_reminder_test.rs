@@ -0,0 +1,132 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::notify::{rotation_reminder::send_rotation_reminders, webhook_notifier::WebhookNotifier};
+use rust_password_server::bounded_context::domain::{password::Password, password_db::PasswordDb, rotation::{Notifier, RotationNotice}};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use async_trait::async_trait;
+use axum::{Json, Router, extract::State, routing::post};
+use chrono::{Duration, SubsecRound, Utc};
+use sqlx::Executor;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+/// Records every notice and fails while `fail` is set
+#[derive(Default)]
+struct RecordingNotifier {
+    notices: Mutex<Vec<RotationNotice>>,
+    fail: Mutex<bool>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, notices: &[RotationNotice]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if *self.fail.lock().unwrap() {
+            return Err("notifier unavailable".into());
+        }
+        self.notices.lock().unwrap().extend_from_slice(notices);
+        Ok(())
+    }
+}
+
+async fn save_due(db: &mut Database, service: &str, rotate_by: chrono::DateTime<Utc>) -> Password {
+    let mut password = Password::new(Uuid::new_v4(), service.to_string(), "nonce".to_string(), "cipher".to_string());
+    password.rotate_by = Some(rotate_by.trunc_subsecs(6));
+    db.save(password.clone()).await.expect("Failed to save password");
+    password
+}
+
+#[tokio::test]
+async fn test_each_due_date_is_notified_once() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let notifier = RecordingNotifier::default();
+    let now = Utc::now();
+
+    let due = save_due(&mut db, "due", now + Duration::days(2)).await;
+    save_due(&mut db, "later", now + Duration::days(60)).await;
+
+    let sent = send_rotation_reminders(&mut db, &notifier, now, Duration::days(14)).await.unwrap();
+    assert_eq!(sent, 1);
+    assert_eq!(notifier.notices.lock().unwrap()[0].password_id, due.id);
+
+    let sent = send_rotation_reminders(&mut db, &notifier, now + Duration::days(1), Duration::days(14)).await.unwrap();
+    assert_eq!(sent, 0);
+
+    // Further ahead the second entry enters the window as well
+    let sent = send_rotation_reminders(&mut db, &notifier, now + Duration::days(50), Duration::days(14)).await.unwrap();
+    assert_eq!(sent, 1);
+    assert_eq!(notifier.notices.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_failed_notifications_are_retried() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let notifier = RecordingNotifier::default();
+    let now = Utc::now();
+
+    save_due(&mut db, "overdue", now - Duration::days(1)).await;
+
+    *notifier.fail.lock().unwrap() = true;
+    assert!(send_rotation_reminders(&mut db, &notifier, now, Duration::days(14)).await.is_err());
+
+    *notifier.fail.lock().unwrap() = false;
+    let sent = send_rotation_reminders(&mut db, &notifier, now, Duration::days(14)).await.unwrap();
+    assert_eq!(sent, 1);
+    assert!(notifier.notices.lock().unwrap()[0].overdue);
+}
+
+#[tokio::test]
+async fn test_webhook_notifier_posts_notices() {
+    let received: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+
+    let app = Router::new()
+        .route("/hook", post(|State(received): State<Arc<Mutex<Vec<serde_json::Value>>>>, Json(body): Json<serde_json::Value>| async move {
+            received.lock().unwrap().push(body);
+        }))
+        .with_state(received.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let notice = RotationNotice {
+        password_id: Uuid::new_v4(),
+        service: "example".to_string(),
+        username: None,
+        due_at: Utc::now(),
+        overdue: true,
+    };
+
+    WebhookNotifier::new(format!("http://{}/hook", addr))
+        .notify(std::slice::from_ref(&notice))
+        .await
+        .expect("Webhook delivery failed");
+
+    {
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["event"], "rotation_due");
+        assert_eq!(received[0]["notices"][0]["password_id"], notice.password_id.to_string());
+    }
+
+    let err = WebhookNotifier::new(format!("http://{}/missing", addr)).notify(&[notice]).await;
+    assert!(err.is_err());
+}
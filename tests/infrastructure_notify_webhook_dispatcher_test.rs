@@ -0,0 +1,300 @@
+use rust_password_server::bounded_context::infrastructure::db::postgres_db::*;
+use rust_password_server::bounded_context::infrastructure::notify::webhook_dispatcher::*;
+use rust_password_server::bounded_context::domain::{password::Password, password_db::PasswordDb, webhook::*};
+use rust_password_server::bounded_context::infrastructure::config::app_config;
+use rust_password_server::bounded_context::utility::signature::verify_payload_signature;
+use axum::{Router, body::Bytes, extract::State, http::{HeaderMap, StatusCode}, routing::post};
+use chrono::{Duration, Utc};
+use sqlx::Executor;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+static DB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn get_test_database() -> Database {
+    let config = app_config::load_config();
+    let database = Database::new(&config.test_db_url.clone(), 1, config).await.expect("Failed to create test DB");
+
+    let mut conn = database.get_connection().await.expect("Failed to get DB connection");
+    conn.execute(include_str!("../docker/init.sql"))
+        .await
+        .expect("Failed to setup the database");
+    conn.execute("TRUNCATE TABLE webhooks, webhook_deliveries, passwords, folders, tags CASCADE")
+        .await
+        .expect("Failed to clean test database");
+    drop(conn);
+
+    database
+}
+
+/// A webhook receiver that records every request and answers with `status`
+#[derive(Clone)]
+struct Receiver {
+    requests: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+    status: Arc<Mutex<StatusCode>>,
+}
+
+async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    receiver.requests.lock().unwrap().push((headers, body));
+    *receiver.status.lock().unwrap()
+}
+
+async fn start_receiver() -> (Receiver, String) {
+    let receiver = Receiver { requests: Arc::default(), status: Arc::new(Mutex::new(StatusCode::OK)) };
+    let app = Router::new().route("/hook", post(receive)).with_state(receiver.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    (receiver, format!("http://{}/hook", addr))
+}
+
+async fn register(db: &mut Database, url: &str, events: &[WebhookEvent]) -> Webhook {
+    let webhook = Webhook::new(url.to_string(), "hook-secret".to_string(), events);
+    db.create_webhook(&webhook).await.expect("Failed to create webhook");
+    webhook
+}
+
+fn entry(service: &str) -> Password {
+    Password::new(Uuid::new_v4(), service.to_string(), "nonce".to_string(), "cipher".to_string())
+}
+
+async fn deliveries(db: &mut Database, webhook: &Webhook) -> Vec<WebhookDelivery> {
+    db.list_webhook_deliveries(Some(webhook.id), None, 1, 100).await.unwrap().items
+}
+
+#[tokio::test]
+async fn test_changes_are_delivered_signed() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (receiver, url) = start_receiver().await;
+    let webhook = register(&mut db, &url, &[WebhookEvent::Created, WebhookEvent::Deleted]).await;
+
+    let password = entry("example");
+    db.save(password.clone()).await.unwrap();
+    db.delete(password.id).await.unwrap();
+
+    let delivered = dispatch_webhooks(&mut db, &webhook_client(), Utc::now(), 8).await.unwrap();
+    assert_eq!(delivered, 2);
+
+    {
+        let requests = receiver.requests.lock().unwrap();
+        let events: Vec<&str> = requests.iter().map(|(headers, _)| headers[EVENT_HEADER].to_str().unwrap()).collect();
+        assert_eq!(events, vec!["created", "deleted"]);
+
+        for (headers, body) in requests.iter() {
+            let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+            let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+            assert!(verify_payload_signature(&webhook.secret, timestamp, body, signature));
+
+            let body: serde_json::Value = serde_json::from_slice(body).unwrap();
+            assert_eq!(body["id"], headers[DELIVERY_HEADER].to_str().unwrap());
+            assert_eq!(body["data"]["password_id"], password.id.to_string());
+            assert_eq!(body["data"]["service"], "example");
+        }
+    }
+
+    let history = deliveries(&mut db, &webhook).await;
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|delivery| delivery.status == "delivered" && delivery.attempts == 1));
+    assert!(history.iter().all(|delivery| delivery.last_response_status == Some(200) && delivery.delivered_at.is_some()));
+
+    assert_eq!(dispatch_webhooks(&mut db, &webhook_client(), Utc::now(), 8).await.unwrap(), 0);
+    assert_eq!(receiver.requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_only_subscribed_events_and_real_updates_are_queued() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (_receiver, url) = start_receiver().await;
+    let updates = register(&mut db, &url, &[WebhookEvent::Updated]).await;
+    let inactive = register(&mut db, &url, &[WebhookEvent::Updated]).await;
+    sqlx::query("UPDATE webhooks SET active = FALSE WHERE id = $1").bind(inactive.id).execute(db.get_pool()).await.unwrap();
+
+    let password = entry("example");
+    db.save(password.clone()).await.unwrap();
+    assert!(deliveries(&mut db, &updates).await.is_empty());
+
+    db.record_use(password.id, Utc::now()).await.unwrap();
+    assert!(deliveries(&mut db, &updates).await.is_empty());
+
+    db.set_favorite(password.id, true).await.unwrap();
+    let queued = deliveries(&mut db, &updates).await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].event, "updated");
+    assert_eq!(queued[0].status, "pending");
+
+    assert!(deliveries(&mut db, &inactive).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_setup_drops_the_old_webhook_trigger() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let webhook = register(&mut db, "http://127.0.0.1:9/hook", &[WebhookEvent::Created]).await;
+
+    // The trigger webhooks were first queued by, before the change feed took over
+    db.get_pool()
+        .execute(
+            r#"
+            CREATE OR REPLACE FUNCTION enqueue_password_webhooks() RETURNS TRIGGER AS $$
+            BEGIN
+                INSERT INTO webhook_deliveries (id, webhook_id, event, event_key, payload, next_attempt_at, created_at)
+                SELECT gen_random_uuid(), webhooks.id, 'created', gen_random_uuid()::TEXT,
+                    jsonb_build_object('password_id', NEW.id, 'service', NEW.service), NOW(), NOW()
+                FROM webhooks;
+                RETURN NULL;
+            END;
+            $$ LANGUAGE plpgsql;
+
+            CREATE TRIGGER passwords_webhooks
+                AFTER INSERT OR UPDATE OR DELETE ON passwords
+                FOR EACH ROW EXECUTE FUNCTION enqueue_password_webhooks();
+            "#,
+        )
+        .await
+        .unwrap();
+    db.get_pool().execute(include_str!("../docker/init.sql")).await.unwrap();
+
+    db.save(entry("example")).await.unwrap();
+    assert_eq!(deliveries(&mut db, &webhook).await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_back_off_and_give_up() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (receiver, url) = start_receiver().await;
+    let webhook = register(&mut db, &url, &[WebhookEvent::Created]).await;
+    *receiver.status.lock().unwrap() = StatusCode::INTERNAL_SERVER_ERROR;
+
+    db.save(entry("example")).await.unwrap();
+
+    let now = Utc::now();
+    assert_eq!(dispatch_webhooks(&mut db, &webhook_client(), now, 2).await.unwrap(), 0);
+    let delivery = deliveries(&mut db, &webhook).await.remove(0);
+    assert_eq!(delivery.status, "pending");
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.last_response_status, Some(500));
+    assert!(delivery.last_error.is_some());
+    assert_eq!(delivery.next_attempt_at.timestamp(), (now + Duration::seconds(RETRY_BASE_DELAY_SECONDS)).timestamp());
+
+    // Not retried before the backoff has passed
+    dispatch_webhooks(&mut db, &webhook_client(), now + Duration::seconds(1), 2).await.unwrap();
+    assert_eq!(receiver.requests.lock().unwrap().len(), 1);
+
+    dispatch_webhooks(&mut db, &webhook_client(), now + Duration::seconds(RETRY_BASE_DELAY_SECONDS), 2).await.unwrap();
+    let delivery = deliveries(&mut db, &webhook).await.remove(0);
+    assert_eq!(delivery.status, "failed");
+    assert_eq!(delivery.attempts, 2);
+    assert_eq!(receiver.requests.lock().unwrap().len(), 2);
+
+    let failed = db.list_webhook_deliveries(None, Some(DeliveryStatus::Failed), 1, 10).await.unwrap();
+    assert_eq!(failed.total_count, 1);
+}
+
+#[tokio::test]
+async fn test_expired_entries_are_delivered_once() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (receiver, url) = start_receiver().await;
+    register(&mut db, &url, &[WebhookEvent::Expired]).await;
+
+    let mut expired = entry("expired");
+    expired.rotate_by = Some(Utc::now() - Duration::days(1));
+    db.save(expired.clone()).await.unwrap();
+    let mut current = entry("current");
+    current.rotate_by = Some(Utc::now() + Duration::days(1));
+    db.save(current).await.unwrap();
+
+    enqueue_expired_entries(&mut db, None, Utc::now()).await.unwrap();
+    assert_eq!(dispatch_webhooks(&mut db, &webhook_client(), Utc::now(), 8).await.unwrap(), 1);
+    enqueue_expired_entries(&mut db, None, Utc::now()).await.unwrap();
+    assert_eq!(dispatch_webhooks(&mut db, &webhook_client(), Utc::now(), 8).await.unwrap(), 0);
+
+    let requests = receiver.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].1).unwrap();
+    assert_eq!(body["event"], "expired");
+    assert_eq!(body["data"]["password_id"], expired.id.to_string());
+}
+
+#[tokio::test]
+async fn test_expiry_checks_only_look_at_new_due_dates_and_changes() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let webhook = register(&mut db, "http://127.0.0.1:9/hook", &[WebhookEvent::Expired]).await;
+    let now = Utc::now();
+
+    let mut long_overdue = entry("long overdue");
+    long_overdue.rotate_by = Some(now - Duration::days(30));
+    db.save(long_overdue).await.unwrap();
+    let mut upcoming = entry("upcoming");
+    upcoming.rotate_by = Some(now + Duration::hours(1));
+    db.save(upcoming).await.unwrap();
+
+    let check = enqueue_expired_entries(&mut db, None, now).await.unwrap();
+    assert_eq!(deliveries(&mut db, &webhook).await.len(), 1);
+    sqlx::query("DELETE FROM webhook_deliveries").execute(db.get_pool()).await.unwrap();
+
+    // Entries that were already overdue are not looked at again
+    let check = enqueue_expired_entries(&mut db, Some(check), now + Duration::minutes(1)).await.unwrap();
+    assert!(deliveries(&mut db, &webhook).await.is_empty());
+
+    // A change that moves the due date into the past is picked up
+    let mut changed = entry("changed");
+    changed.rotation_interval_days = Some(1);
+    changed.updated_at = now - Duration::days(2);
+    db.save(changed.clone()).await.unwrap();
+    let check = enqueue_expired_entries(&mut db, Some(check), now + Duration::minutes(2)).await.unwrap();
+    let queued = deliveries(&mut db, &webhook).await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].payload["password_id"], changed.id.to_string());
+    sqlx::query("DELETE FROM webhook_deliveries").execute(db.get_pool()).await.unwrap();
+
+    // So is a due date passing
+    enqueue_expired_entries(&mut db, Some(check), now + Duration::hours(2)).await.unwrap();
+    let queued = deliveries(&mut db, &webhook).await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].payload["service"], "upcoming");
+}
+
+#[tokio::test]
+async fn test_private_hosts_and_redirects_are_not_followed() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let (receiver, url) = start_receiver().await;
+    let webhook = register(&mut db, &url.replace("127.0.0.1", "localhost"), &[WebhookEvent::Created]).await;
+
+    db.save(entry("example")).await.unwrap();
+    assert_eq!(dispatch_webhooks(&mut db, &webhook_client(), Utc::now(), 1).await.unwrap(), 0);
+    assert!(receiver.requests.lock().unwrap().is_empty());
+    let delivery = deliveries(&mut db, &webhook).await.remove(0);
+    assert_eq!(delivery.status, "failed");
+    assert!(delivery.last_response_status.is_none());
+
+    db.delete_webhook(webhook.id).await.unwrap();
+    let webhook = register(&mut db, &url, &[WebhookEvent::Created]).await;
+    *receiver.status.lock().unwrap() = StatusCode::TEMPORARY_REDIRECT;
+    db.save(entry("example")).await.unwrap();
+    assert_eq!(dispatch_webhooks(&mut db, &webhook_client(), Utc::now(), 1).await.unwrap(), 0);
+    assert_eq!(deliveries(&mut db, &webhook).await.remove(0).last_response_status, Some(307));
+}
+
+#[tokio::test]
+async fn test_deleting_a_webhook_drops_its_deliveries() {
+    let _lock = DB_LOCK.lock().await;
+    let mut db = get_test_database().await;
+    let webhook = register(&mut db, "http://127.0.0.1:9/hook", &[WebhookEvent::Created]).await;
+
+    db.save(entry("example")).await.unwrap();
+    assert_eq!(deliveries(&mut db, &webhook).await.len(), 1);
+
+    db.delete_webhook(webhook.id).await.unwrap();
+    assert!(db.list_webhooks().await.unwrap().is_empty());
+    assert_eq!(db.list_webhook_deliveries(None, None, 1, 10).await.unwrap().total_count, 0);
+
+    let err = db.delete_webhook(webhook.id).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<WebhookError>(), Some(WebhookError::NotFound(_))));
+}
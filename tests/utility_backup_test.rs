@@ -0,0 +1,50 @@
+use rust_password_server::bounded_context::utility::backup::*;
+
+#[test]
+fn test_seal_and_open_backup() {
+    let envelope = seal_backup("correct horse battery", b"vault contents");
+
+    assert_eq!(envelope.format, BACKUP_FORMAT);
+    assert_eq!(envelope.version, BACKUP_VERSION);
+    assert_eq!(open_backup("correct horse battery", &envelope), Some(b"vault contents".to_vec()));
+    assert_eq!(open_backup("wrong horse battery", &envelope), None);
+    let other = seal_backup("correct horse battery", b"vault contents");
+    assert_ne!(envelope.kdf.salt, other.kdf.salt);
+    assert_ne!(envelope.nonce, other.nonce);
+}
+
+#[test]
+fn test_open_backup_rejects_modified_envelope() {
+    let envelope = seal_backup("correct horse battery", b"vault contents");
+
+    let mut version = envelope.clone();
+    version.version += 1;
+    assert_eq!(open_backup("correct horse battery", &version), None);
+
+    let mut kdf = envelope.clone();
+    kdf.kdf.iterations += 1;
+    assert_eq!(open_backup("correct horse battery", &kdf), None);
+
+    let mut ciphertext = envelope.clone();
+    ciphertext.ciphertext.insert_str(0, "AAAA");
+    assert_eq!(open_backup("correct horse battery", &ciphertext), None);
+
+    let mut mac = envelope.clone();
+    mac.mac = "00".repeat(32);
+    assert_eq!(open_backup("correct horse battery", &mac), None);
+}
+
+#[test]
+fn test_open_backup_rejects_excessive_kdf_cost() {
+    let envelope = seal_backup("correct horse battery", b"vault contents");
+
+    for kdf in [
+        KdfParams { memory_kib: 4 * 1024 * 1024, ..envelope.kdf.clone() },
+        KdfParams { memory_kib: envelope.kdf.memory_kib * 4, ..envelope.kdf.clone() },
+        KdfParams { iterations: envelope.kdf.iterations * 4, ..envelope.kdf.clone() },
+        KdfParams { parallelism: 16, ..envelope.kdf.clone() },
+    ] {
+        let envelope = BackupEnvelope { kdf, ..envelope.clone() };
+        assert_eq!(open_backup("correct horse battery", &envelope), None);
+    }
+}
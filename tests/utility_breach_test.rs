@@ -0,0 +1,33 @@
+use rust_password_server::bounded_context::utility::breach::*;
+
+#[test]
+fn test_sha1_hex() {
+    assert_eq!(sha1_hex("password"), "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8");
+}
+
+#[test]
+fn test_split_hash() {
+    let (prefix, suffix) = split_hash("5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8");
+
+    assert_eq!(prefix, "5BAA6");
+    assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+}
+
+#[test]
+fn test_valid_hash_prefix() {
+    assert!(is_valid_hash_prefix("5BAA6"));
+    assert!(is_valid_hash_prefix("5baa6"));
+}
+
+#[test]
+fn test_invalid_hash_prefix() {
+    assert!(!is_valid_hash_prefix("5BAA"));
+    assert!(!is_valid_hash_prefix("5BAA61"));
+    assert!(!is_valid_hash_prefix("ZZZZZ"));
+}
+
+#[test]
+fn test_valid_sha1_hex() {
+    assert!(is_valid_sha1_hex(&sha1_hex("password")));
+    assert!(!is_valid_sha1_hex("5BAA6"));
+}
@@ -127,4 +127,69 @@ fn test_invalid_nonce_hex() {
 fn test_invalid_nonce_length() {
     let smaller_nonce = hex::encode(&[0u8; 11]);
     assert!(!is_valid_nonce(&smaller_nonce));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_fingerprint_is_deterministic() {
+    let fingerprint_key = generate_key();
+
+    let first = fingerprint(&fingerprint_key, "reused_password");
+    let second = fingerprint(&fingerprint_key, "reused_password");
+
+    assert_eq!(first, second);
+    assert!(is_valid_fingerprint(&first));
+}
+
+#[test]
+fn test_fingerprint_depends_on_key_and_password() {
+    let fingerprint_key = generate_key();
+    let other_key = generate_key();
+
+    let base = fingerprint(&fingerprint_key, "reused_password");
+
+    assert_ne!(base, fingerprint(&other_key, "reused_password"));
+    assert_ne!(base, fingerprint(&fingerprint_key, "other_password"));
+}
+
+#[test]
+fn test_fingerprint_known_vector() {
+    // RFC 4231 test case 2
+    let key = hex::encode("Jefe");
+    assert_eq!(
+        fingerprint(&key, "what do ya want for nothing?"),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
+
+#[test]
+fn test_invalid_fingerprint() {
+    assert!(!is_valid_fingerprint("zz"));
+    assert!(!is_valid_fingerprint(&hex::encode([0u8; 16])));
+}
+
+#[test]
+fn test_try_decrypt() {
+    let master_key = generate_key();
+    let (nonce, cipher_text) = encrypt(&master_key, "super_secure_password".to_string());
+
+    assert_eq!(try_decrypt(&master_key, &nonce, &cipher_text), Some("super_secure_password".to_string()));
+    assert_eq!(try_decrypt(&generate_key(), &nonce, &cipher_text), None);
+    assert_eq!(try_decrypt(&master_key, "not hex", &cipher_text), None);
+    assert_eq!(try_decrypt("short", &nonce, &cipher_text), None);
+}
+
+#[test]
+fn test_derive_master_key() {
+    use rust_password_server::bounded_context::utility::backup::KdfParams;
+
+    let kdf = KdfParams { memory_kib: 64, iterations: 1, parallelism: 1, salt: "00112233445566778899aabbccddeeff".to_string() };
+    let master_key = derive_master_key("correct horse battery", &kdf).unwrap();
+
+    assert!(is_valid_masterkey(&master_key));
+    assert_eq!(derive_master_key("correct horse battery", &kdf), Some(master_key.clone()));
+    assert_ne!(derive_master_key("wrong horse battery", &kdf), Some(master_key.clone()));
+    let other_salt = KdfParams { salt: "ffeeddccbbaa99887766554433221100".to_string(), ..kdf.clone() };
+    assert_ne!(derive_master_key("correct horse battery", &other_salt), Some(master_key));
+    assert_eq!(derive_master_key("correct horse battery", &KdfParams { salt: "not hex".to_string(), ..kdf.clone() }), None);
+    assert_eq!(derive_master_key("correct horse battery", &KdfParams { memory_kib: 0, ..kdf }), None);
+}
@@ -0,0 +1,129 @@
+use rust_password_server::bounded_context::utility::otp::*;
+use rust_password_server::bounded_context::utility::clock::FixedClock;
+use chrono::DateTime;
+use std::str::FromStr;
+
+fn clock(seconds: i64) -> FixedClock {
+    FixedClock(DateTime::from_timestamp(seconds, 0).unwrap())
+}
+
+// RFC 6238 appendix B seeds, one per algorithm
+const SHA1_SEED: &[u8] = b"12345678901234567890";
+const SHA256_SEED: &[u8] = b"12345678901234567890123456789012";
+const SHA512_SEED: &[u8] = b"1234567890123456789012345678901234567890123456789012345678901234";
+
+fn rfc6238(algorithm: OtpAlgorithm, secret: &[u8]) -> OtpAuth {
+    OtpAuth { algorithm, format: OtpFormat::Digits(8), ..OtpAuth::totp(secret.to_vec()) }
+}
+
+#[test]
+fn test_decode_base32() {
+    assert_eq!(decode_base32("JBSWY3DPEHPK3PXP"), Some(b"Hello!\xde\xad\xbe\xef".to_vec()));
+    assert_eq!(decode_base32("jbsw y3dp-ehpk 3pxp"), decode_base32("JBSWY3DPEHPK3PXP"));
+    assert_eq!(decode_base32("MZXW6==="), Some(b"foo".to_vec()));
+    assert_eq!(decode_base32("MZXW1"), None);
+}
+
+#[test]
+fn test_hotp_rfc4226_vectors() {
+    let hotp = OtpAuth::totp(SHA1_SEED.to_vec());
+    let expected = ["755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871", "520489"];
+
+    for (counter, code) in expected.iter().enumerate() {
+        assert_eq!(hotp.code_at(counter as u64).unwrap(), *code, "Failed for counter {}", counter);
+    }
+}
+
+#[test]
+fn test_totp_rfc6238_vectors() {
+    let test_cases = vec![
+        (59, "94287082", "46119246", "90693936"),
+        (1111111109, "07081804", "68084774", "25091201"),
+        (1234567890, "89005924", "91819424", "93441116"),
+        (20000000000, "65353130", "77737706", "47863826"),
+    ];
+
+    for (seconds, sha1, sha256, sha512) in test_cases {
+        assert_eq!(rfc6238(OtpAlgorithm::Sha1, SHA1_SEED).current_code(&clock(seconds)).unwrap().code, sha1);
+        assert_eq!(rfc6238(OtpAlgorithm::Sha256, SHA256_SEED).current_code(&clock(seconds)).unwrap().code, sha256);
+        assert_eq!(rfc6238(OtpAlgorithm::Sha512, SHA512_SEED).current_code(&clock(seconds)).unwrap().code, sha512);
+    }
+}
+
+#[test]
+fn test_totp_remaining_seconds() {
+    let totp = OtpAuth::totp(SHA1_SEED.to_vec());
+
+    assert_eq!(totp.current_code(&clock(60)).unwrap().remaining_seconds, Some(30));
+    assert_eq!(totp.current_code(&clock(89)).unwrap().remaining_seconds, Some(1));
+    assert_eq!(totp.current_code(&clock(60)).unwrap().code, totp.current_code(&clock(89)).unwrap().code);
+    assert_ne!(totp.current_code(&clock(89)).unwrap().code, totp.current_code(&clock(90)).unwrap().code);
+}
+
+#[test]
+fn test_invalid_generators_have_no_code() {
+    let too_long = OtpAuth { format: OtpFormat::Digits(10), ..OtpAuth::totp(SHA1_SEED.to_vec()) };
+    assert_eq!(too_long.code_at(0), Err(OtpError::InvalidDigits("10".to_string())));
+    assert_eq!(too_long.current_code(&clock(0)), Err(OtpError::InvalidDigits("10".to_string())));
+
+    let no_period = OtpAuth { kind: OtpKind::Totp { period: 0 }, ..OtpAuth::totp(SHA1_SEED.to_vec()) };
+    assert_eq!(no_period.current_code(&clock(59)), Err(OtpError::InvalidPeriod("0".to_string())));
+}
+
+#[test]
+fn test_steam_codes() {
+    let steam = OtpAuth::from_str("steam://JBSWY3DPEHPK3PXP").expect("Failed to parse Steam URI");
+    assert_eq!(steam.format, OtpFormat::Steam);
+
+    assert_eq!(steam.current_code(&clock(0)).unwrap().code, "VH8YJ");
+    assert_eq!(steam.current_code(&clock(1700000000)).unwrap().code, "2KM2P");
+
+    let encoder = OtpAuth::from_str("otpauth://totp/Steam:alice?secret=JBSWY3DPEHPK3PXP&encoder=steam").unwrap();
+    assert_eq!(encoder.current_code(&clock(0)).unwrap().code, "VH8YJ");
+}
+
+#[test]
+fn test_parse_otpauth_uri() {
+    let otp = OtpAuth::from_str(
+        "otpauth://totp/ACME%20Co:john.doe@email.com?secret=JBSWY3DPEHPK3PXP&algorithm=SHA256&digits=8&period=60",
+    )
+    .expect("Failed to parse URI");
+
+    assert_eq!(otp.kind, OtpKind::Totp { period: 60 });
+    assert_eq!(otp.algorithm, OtpAlgorithm::Sha256);
+    assert_eq!(otp.format, OtpFormat::Digits(8));
+    assert_eq!(otp.secret, decode_base32("JBSWY3DPEHPK3PXP").unwrap());
+    assert_eq!(otp.issuer.as_deref(), Some("ACME Co"));
+    assert_eq!(otp.account.as_deref(), Some("john.doe@email.com"));
+
+    let with_issuer = OtpAuth::from_str("otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&issuer=Example").unwrap();
+    assert_eq!(with_issuer.issuer.as_deref(), Some("Example"));
+    assert_eq!(with_issuer.format, OtpFormat::Digits(6));
+    assert_eq!(with_issuer.kind, OtpKind::Totp { period: 30 });
+
+    let hotp = OtpAuth::from_str("otpauth://hotp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&counter=1").unwrap();
+    assert_eq!(hotp.kind, OtpKind::Hotp { counter: 1 });
+    assert_eq!(hotp.current_code(&clock(0)).unwrap(), OtpCode { code: "287082".to_string(), remaining_seconds: None });
+
+    let bare = OtpAuth::from_str(" jbswy3dpehpk3pxp ").unwrap();
+    assert_eq!(bare, OtpAuth::totp(decode_base32("JBSWY3DPEHPK3PXP").unwrap()));
+}
+
+#[test]
+fn test_parse_invalid_otpauth_uri() {
+    let test_cases = vec![
+        ("otpauth://totp/alice", OtpError::MissingSecret),
+        ("otpauth://totp/alice?secret=not-base32!", OtpError::InvalidSecret),
+        ("otpauth://totp/alice?secret=JBSWY3DP&algorithm=MD5", OtpError::InvalidAlgorithm("MD5".to_string())),
+        ("otpauth://totp/alice?secret=JBSWY3DP&digits=4", OtpError::InvalidDigits("4".to_string())),
+        ("otpauth://totp/alice?secret=JBSWY3DP&period=0", OtpError::InvalidPeriod("0".to_string())),
+        ("otpauth://hotp/alice?secret=JBSWY3DP", OtpError::InvalidCounter),
+        ("otpauth://motp/alice?secret=JBSWY3DP", OtpError::UnsupportedType("motp".to_string())),
+        ("https://example.com/?secret=JBSWY3DP", OtpError::InvalidUri),
+        ("", OtpError::MissingSecret),
+    ];
+
+    for (input, expected) in test_cases {
+        assert_eq!(OtpAuth::from_str(input).unwrap_err(), expected, "Failed for {}", input);
+    }
+}
@@ -0,0 +1,11 @@
+use rust_password_server::bounded_context::utility::share::*;
+
+#[test]
+fn test_access_password() {
+    let hash = hash_access_password("correct horse");
+
+    assert!(verify_access_password("correct horse", &hash));
+    assert!(!verify_access_password("battery staple", &hash));
+    assert!(!verify_access_password("correct horse", "not a hash"));
+    assert_ne!(hash, hash_access_password("correct horse"));
+}
@@ -0,0 +1,23 @@
+use rust_password_server::bounded_context::utility::signature::*;
+
+#[test]
+fn test_sign_payload_is_hex_hmac_of_timestamp_and_body() {
+    let signature = sign_payload("secret", 1_700_000_000, b"{}");
+
+    assert!(signature.starts_with("sha256="));
+    assert_eq!(signature.len(), "sha256=".len() + 64);
+    assert_eq!(signature, sign_payload("secret", 1_700_000_000, b"{}"));
+}
+
+#[test]
+fn test_verify_payload_signature() {
+    let body = br#"{"event":"created"}"#;
+    let signature = sign_payload("secret", 1_700_000_000, body);
+
+    assert!(verify_payload_signature("secret", 1_700_000_000, body, &signature));
+    assert!(!verify_payload_signature("other", 1_700_000_000, body, &signature));
+    assert!(!verify_payload_signature("secret", 1_700_000_001, body, &signature));
+    assert!(!verify_payload_signature("secret", 1_700_000_000, br#"{"event":"deleted"}"#, &signature));
+    assert!(!verify_payload_signature("secret", 1_700_000_000, body, signature.trim_start_matches("sha256=")));
+    assert!(!verify_payload_signature("secret", 1_700_000_000, body, "sha256=not-hex"));
+}
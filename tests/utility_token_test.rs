@@ -0,0 +1,27 @@
+use rust_password_server::bounded_context::utility::token::*;
+
+#[test]
+fn test_generate_token() {
+    let token = generate_token();
+
+    assert_eq!(token.len(), 43);
+    assert!(is_valid_token(&token));
+    assert_ne!(token, generate_token());
+}
+
+#[test]
+fn test_is_valid_token() {
+    assert!(!is_valid_token(""));
+    assert!(!is_valid_token("not a token"));
+    assert!(!is_valid_token("AAAA"));
+    assert!(!is_valid_token(&format!("{}=", generate_token())));
+}
+
+#[test]
+fn test_hash_token() {
+    let token = generate_token();
+
+    assert_eq!(hash_token(&token), hash_token(&token));
+    assert_ne!(hash_token(&token), token);
+    assert_eq!(hash_token(&token).len(), 64);
+}
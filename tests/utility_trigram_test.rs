@@ -0,0 +1,32 @@
+use rust_password_server::bounded_context::utility::trigram::*;
+use std::collections::BTreeSet;
+
+#[test]
+fn test_trigrams_pad_each_word() {
+    let expected: BTreeSet<String> = ["  c", " ca", "cat", "at "].iter().map(|t| t.to_string()).collect();
+    assert_eq!(trigrams("Cat"), expected);
+
+    let words = trigrams("my-cat");
+    assert!(words.contains("  m") && words.contains("my ") && words.contains("cat"));
+    assert!(!words.contains("y-c"));
+}
+
+#[test]
+fn test_similarity() {
+    let test_cases = vec![
+        ("GitHub", "github", 1.0),
+        ("GitHub", "githbu", 0.4),
+        ("GitHub", "zzz", 0.0),
+        ("", "github", 0.0),
+        ("--", "", 0.0),
+    ];
+
+    for (a, b, expected) in test_cases {
+        assert!((similarity(a, b) - expected).abs() < 1e-6, "Failed for {} / {}", a, b);
+    }
+}
+
+#[test]
+fn test_similarity_is_symmetric() {
+    assert_eq!(similarity("gitlab.com", "GitHub"), similarity("GitHub", "gitlab.com"));
+}
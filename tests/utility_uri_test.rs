@@ -0,0 +1,70 @@
+use rust_password_server::bounded_context::utility::uri::*;
+
+#[test]
+fn test_normalize_uri() {
+    let test_cases = vec![
+        ("example.com", "https://example.com/"),
+        ("  HTTPS://Login.Example.COM/Path?q=1#section  ", "https://login.example.com/Path?q=1"),
+        ("http://example.com:80/", "http://example.com/"),
+        ("https://example.com:8443", "https://example.com:8443/"),
+        ("https://example.com./login", "https://example.com/login"),
+        ("https://bücher.de", "https://xn--bcher-kva.de/"),
+        ("androidapp://com.example.app", "androidapp://com.example.app"),
+    ];
+
+    for (input, expected) in test_cases {
+        let url = normalize_uri(input).expect("Failed to normalize URI");
+        assert_eq!(url.as_str(), expected, "Failed for {}", input);
+    }
+}
+
+#[test]
+fn test_normalize_invalid_uri() {
+    for input in ["", "   ", "https://", "not a url"] {
+        assert!(normalize_uri(input).is_none(), "Failed for {:?}", input);
+    }
+}
+
+#[test]
+fn test_registrable_domain() {
+    let test_cases = vec![
+        ("login.example.co.uk", Some("example.co.uk")),
+        ("example.com", Some("example.com")),
+        ("a.b.c.example.com", Some("example.com")),
+        ("alice.github.io", Some("alice.github.io")),
+        ("co.uk", None),
+        ("localhost", None),
+        ("192.168.0.1", None),
+        ("[::1]", None),
+    ];
+
+    for (host, expected) in test_cases {
+        assert_eq!(registrable_domain(host).as_deref(), expected, "Failed for {}", host);
+    }
+}
+
+#[test]
+fn test_is_public_address() {
+    let test_cases = vec![
+        ("93.184.216.34", true),
+        ("2606:2800:220:1:248:1893:25c8:1946", true),
+        ("127.0.0.1", false),
+        ("10.1.2.3", false),
+        ("172.16.0.1", false),
+        ("192.168.1.1", false),
+        ("169.254.169.254", false),
+        ("100.64.0.1", false),
+        ("0.0.0.0", false),
+        ("255.255.255.255", false),
+        ("::1", false),
+        ("::", false),
+        ("fd00::1", false),
+        ("fe80::1", false),
+        ("::ffff:127.0.0.1", false),
+        ("::ffff:93.184.216.34", true),
+    ];
+
+    for (input, expected) in test_cases {
+        assert_eq!(is_public_address(input.parse().unwrap()), expected, "Failed for {}", input);
+    }
+}